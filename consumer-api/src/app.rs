@@ -1,8 +1,19 @@
 use crate::{
-    endpoints::refetch_atoms::refetch_atoms, error::ApiError, openapi::ApiDoc, state::AppState,
+    endpoints::{
+        rebuild_relevance_scores::rebuild_relevance_scores, rebuild_rollups::rebuild_rollups,
+        refetch_atoms::refetch_atoms, requeue_atoms::requeue_atoms,
+        share_price_candles::share_price_candles,
+    },
+    error::ApiError,
+    openapi::ApiDoc,
+    profile_refresh::spawn_profile_refresh,
+    retry::spawn_resolution_retry,
+    rollup::spawn_rollups,
+    state::AppState,
     types::Env,
 };
 use axum::{
+    middleware,
     routing::{get, post},
     Router,
 };
@@ -12,12 +23,19 @@ use http::{
     Method,
 };
 use log::info;
-use std::time::Duration;
+use models::api_key::ApiKeyScope;
+use shared_utils::auth::require_scope;
+use std::{net::SocketAddr, time::Duration};
 use tokio::net::TcpListener;
 use tower_http::cors::CorsLayer;
 use utoipa::OpenApi;
 use utoipa_swagger_ui::SwaggerUi;
 
+/// The default time after which the profile of an account is refreshed
+const DEFAULT_PROFILE_TTL_SECS: u64 = 7 * 24 * 60 * 60;
+/// The default number of accounts refreshed at every interval
+const DEFAULT_PROFILE_REFRESH_BATCH_SIZE: i64 = 100;
+
 pub struct App {
     env: Env,
     app_state: AppState,
@@ -65,7 +83,7 @@ impl App {
     /// initialize the logger, and create the app state.
     pub async fn new() -> Result<Self, ApiError> {
         let env = Self::initialize().await?;
-        let app_state = AppState::new(&env).await;
+        let app_state = AppState::new(&env).await?;
         Ok(Self { env, app_state })
     }
 
@@ -73,7 +91,35 @@ impl App {
     fn router(&self) -> Router {
         let (prometheus_layer, metric_handle) = PrometheusMetricLayer::pair();
         Router::new()
-            .route("/refetch_atoms", post(refetch_atoms))
+            .route(
+                "/refetch_atoms",
+                post(refetch_atoms).route_layer(middleware::from_fn_with_state(
+                    self.app_state.auth.require(ApiKeyScope::Moderate),
+                    require_scope,
+                )),
+            )
+            .route(
+                "/requeue_atoms",
+                post(requeue_atoms).route_layer(middleware::from_fn_with_state(
+                    self.app_state.auth.require(ApiKeyScope::Moderate),
+                    require_scope,
+                )),
+            )
+            .route(
+                "/rebuild_relevance_scores",
+                post(rebuild_relevance_scores).route_layer(middleware::from_fn_with_state(
+                    self.app_state.auth.require(ApiKeyScope::Admin),
+                    require_scope,
+                )),
+            )
+            .route(
+                "/rebuild_rollups",
+                post(rebuild_rollups).route_layer(middleware::from_fn_with_state(
+                    self.app_state.auth.require(ApiKeyScope::Admin),
+                    require_scope,
+                )),
+            )
+            .route("/vaults/{id}/share_price_candles", get(share_price_candles))
             .route("/metrics", get(|| async move { metric_handle.render() }))
             .layer(prometheus_layer)
             .with_state(self.app_state.clone())
@@ -86,9 +132,46 @@ impl App {
             self.env.consumer_api_port.unwrap_or(3003)
         );
         let listener = self.build_listener().await?;
+        if let Some(interval) = self.env.resolution_retry_interval_secs {
+            info!(
+                "Retrying failed atom resolutions every {} seconds",
+                interval
+            );
+            spawn_resolution_retry(self.app_state.clone(), Duration::from_secs(interval));
+        }
+        if let Some(interval) = self.env.profile_refresh_interval_secs {
+            info!(
+                "Refreshing stale account profiles every {} seconds",
+                interval
+            );
+            spawn_profile_refresh(
+                self.app_state.clone(),
+                Duration::from_secs(interval),
+                Duration::from_secs(
+                    self.env
+                        .profile_ttl_secs
+                        .unwrap_or(DEFAULT_PROFILE_TTL_SECS),
+                ),
+                self.env
+                    .profile_refresh_batch_size
+                    .unwrap_or(DEFAULT_PROFILE_REFRESH_BATCH_SIZE),
+            );
+        }
+        if let Some(interval) = self.env.rollup_interval_secs {
+            info!(
+                "Rolling up deposits and redemptions every {} seconds",
+                interval
+            );
+            spawn_rollups(self.app_state.clone(), Duration::from_secs(interval));
+        }
         info!("Ready to receive requests");
-        axum::serve(listener, self.merge_layers())
-            .await
-            .map_err(ApiError::from)
+        // The peer address is used to rate limit clients by IP
+        axum::serve(
+            listener,
+            self.merge_layers()
+                .into_make_service_with_connect_info::<SocketAddr>(),
+        )
+        .await
+        .map_err(ApiError::from)
     }
 }
@@ -0,0 +1,59 @@
+use clap::Parser;
+use log::info;
+use models::counter_repair::repair_counters;
+use serde::Deserialize;
+use shared_utils::postgres::connect_to_db;
+use thiserror::Error;
+
+/// Recompute the derived counters (`predicate_object.triple_count` and
+/// `claim_count`, `vault.position_count` and the `stats` totals) from the
+/// base tables and print the drift of every counter. Without `--apply`, the
+/// drift is only counted from a read-only snapshot.
+#[derive(Parser, Debug)]
+struct RepairCountersArgs {
+    /// Repair the counters, locking the base tables against writes while it
+    /// runs
+    #[arg(long)]
+    apply: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct Env {
+    backend_schema: String,
+    indexer_database_url: String,
+}
+
+#[derive(Error, Debug)]
+enum RepairCountersError {
+    #[error(transparent)]
+    Env(#[from] envy::Error),
+    #[error(transparent)]
+    Model(#[from] models::error::ModelError),
+    #[error(transparent)]
+    SerdeJson(#[from] serde_json::Error),
+    #[error(transparent)]
+    SharedUtils(#[from] shared_utils::error::LibError),
+}
+
+#[tokio::main]
+async fn main() -> Result<(), RepairCountersError> {
+    let args = RepairCountersArgs::parse();
+    env_logger::init();
+    dotenvy::dotenv().ok();
+    let env = envy::from_env::<Env>()?;
+    let pg_pool = connect_to_db(&env.indexer_database_url).await?;
+
+    let dry_run = !args.apply;
+    let drifts = repair_counters(dry_run, &pg_pool, &env.backend_schema).await?;
+    for drift in drifts.iter().filter(|drift| drift.drifted_rows > 0) {
+        info!(
+            "{}.{} drifted in {} rows{}",
+            drift.table,
+            drift.column,
+            drift.drifted_rows,
+            if dry_run { " (dry run)" } else { "" }
+        );
+    }
+    println!("{}", serde_json::to_string_pretty(&drifts)?);
+    Ok(())
+}
@@ -1 +1,5 @@
+pub mod rebuild_relevance_scores;
+pub mod rebuild_rollups;
 pub mod refetch_atoms;
+pub mod requeue_atoms;
+pub mod share_price_candles;
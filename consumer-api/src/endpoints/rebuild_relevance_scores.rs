@@ -0,0 +1,46 @@
+use crate::{error::ApiError, state::AppState};
+use axum::{extract::State, Json};
+use axum_macros::debug_handler;
+use log::info;
+use models::relevance_score::RelevanceScore;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// The number of relevance scores written
+#[derive(Deserialize, Serialize, Default, Debug, ToSchema)]
+#[serde(rename_all = "PascalCase")]
+pub struct RebuildRelevanceScoresResponse {
+    pub scores: u64,
+}
+
+/// Rebuild the relevance scores of every atom and triple from the signals,
+/// with the half-life the decoded consumer applies signals with
+#[utoipa::path(
+    post,
+    path = "/rebuild_relevance_scores",
+    responses(
+        (status = 200, description = "Relevance scores rebuilt", body = RebuildRelevanceScoresResponse),
+        (status = 401, description = "Missing or invalid API key", body = String),
+        (status = 403, description = "The API key is missing the admin scope", body = String),
+        (status = 429, description = "Rate limit exceeded", body = String),
+        (status = 500, description = "Internal server error", body = String)
+    ),
+    security(("api_key" = [])),
+    tag = "relevance"
+)]
+#[debug_handler]
+pub async fn rebuild_relevance_scores(
+    State(state): State<AppState>,
+) -> Result<Json<RebuildRelevanceScoresResponse>, ApiError> {
+    info!(
+        "Rebuilding the relevance scores with a half-life of {} seconds",
+        state.relevance_half_life_secs
+    );
+    let scores = RelevanceScore::rebuild(
+        state.relevance_half_life_secs,
+        &state.pg_pool,
+        &state.backend_schema,
+    )
+    .await?;
+    Ok(Json(RebuildRelevanceScoresResponse { scores }))
+}
@@ -0,0 +1,74 @@
+use crate::{error::ApiError, state::AppState};
+use axum::{extract::State, Json};
+use axum_macros::debug_handler;
+use log::info;
+use models::rollup::{Rollup, RollupGranularity};
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+use utoipa::ToSchema;
+
+/// The granularity and the block timestamps of the rollups to rebuild
+#[derive(Deserialize, Serialize, Default, Debug, ToSchema)]
+#[serde(rename_all = "PascalCase")]
+pub struct RebuildRollupsRequest {
+    /// `Day` or `Week`, defaults to both
+    pub granularity: Option<String>,
+    /// The first block timestamp, defaults to the first event
+    pub from: Option<i64>,
+    /// The block timestamp the rebuild ends at, excluded, defaults to the last event
+    pub to: Option<i64>,
+}
+
+/// The number of rollups written
+#[derive(Deserialize, Serialize, Default, Debug, ToSchema)]
+#[serde(rename_all = "PascalCase")]
+pub struct RebuildRollupsResponse {
+    pub rollups: u64,
+}
+
+/// Rebuild the rollups of the buckets overlapping a range of block timestamps
+/// from the deposits and redemptions
+#[utoipa::path(
+    post,
+    path = "/rebuild_rollups",
+    request_body = inline(RebuildRollupsRequest),
+    responses(
+        (status = 200, description = "Rollups rebuilt", body = RebuildRollupsResponse),
+        (status = 400, description = "Invalid granularity or wrong format", body = String),
+        (status = 401, description = "Missing or invalid API key", body = String),
+        (status = 403, description = "The API key is missing the admin scope", body = String),
+        (status = 429, description = "Rate limit exceeded", body = String),
+        (status = 500, description = "Internal server error", body = String)
+    ),
+    security(("api_key" = [])),
+    tag = "rollups"
+)]
+#[debug_handler]
+pub async fn rebuild_rollups(
+    State(state): State<AppState>,
+    Json(json): Json<RebuildRollupsRequest>,
+) -> Result<Json<RebuildRollupsResponse>, ApiError> {
+    let granularities = match json.granularity.as_deref() {
+        Some(granularity) => vec![RollupGranularity::from_str(granularity)
+            .map_err(|_| ApiError::InvalidFilter(format!("Granularity: {granularity}")))?],
+        None => RollupGranularity::ALL.to_vec(),
+    };
+    let to = match json.to {
+        Some(to) => to,
+        None => {
+            match Rollup::find_last_event_timestamp(&state.pg_pool, &state.backend_schema).await? {
+                Some(timestamp) => timestamp + 1,
+                None => return Ok(Json(RebuildRollupsResponse::default())),
+            }
+        }
+    };
+    let from = json.from.unwrap_or(0);
+
+    let mut rollups = 0;
+    for granularity in granularities {
+        info!("Rebuilding {} rollups from {} to {}", granularity, from, to);
+        rollups +=
+            Rollup::rebuild(granularity, from, to, &state.pg_pool, &state.backend_schema).await?;
+    }
+    Ok(Json(RebuildRollupsResponse { rollups }))
+}
@@ -2,12 +2,14 @@ use crate::{error::ApiError, state::AppState};
 use axum::{extract::State, Json};
 use axum_macros::debug_handler;
 use log::info;
+use models::account::Account;
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 
 #[derive(Debug, Serialize, Deserialize)]
 pub enum ResolverMessageType {
     Atom(String),
+    Account(Account),
 }
 
 /// The response from the IPFS gateway
@@ -32,8 +34,12 @@ pub struct ResolverConsumerMessage {
     responses(
         (status = 200, description = "Atoms enqueued for re-fetching", body = String),
         (status = 400, description = "Invalid input or wrong format", body = String),
+        (status = 401, description = "Missing or invalid API key", body = String),
+        (status = 403, description = "The API key is missing the moderate scope", body = String),
+        (status = 429, description = "Rate limit exceeded", body = String),
         (status = 500, description = "Internal server error", body = String)
     ),
+    security(("api_key" = [])),
     tag = "images"
 )]
 #[debug_handler]
@@ -42,21 +48,29 @@ pub async fn refetch_atoms(
     Json(json): Json<RefetchAtomsRequest>,
 ) -> Result<Json<String>, ApiError> {
     info!("Enqueuing atoms to be refetched");
-    let queue_url = state.resolver_queue_url.clone();
     for atom_id in json.atom_ids {
-        let message = ResolverConsumerMessage {
-            message: ResolverMessageType::Atom(atom_id),
-        };
-        state
-            .sqs_client
-            .send_message()
-            .queue_url(queue_url.clone())
-            .message_body(serde_json::to_string(&message).map_err(ApiError::from)?)
-            .send()
-            .await
-            .map_err(ApiError::from)?;
-        info!("Message sent to SQS");
+        enqueue_atom(&state, atom_id).await?;
     }
 
     Ok(Json("Atoms enqueued for re-fetching".to_string()))
 }
+
+/// Sends an atom to the resolver consumer to be resolved again
+pub async fn enqueue_atom(state: &AppState, atom_id: String) -> Result<(), ApiError> {
+    enqueue(state, ResolverMessageType::Atom(atom_id)).await
+}
+
+/// Sends a message to the resolver consumer
+pub async fn enqueue(state: &AppState, message: ResolverMessageType) -> Result<(), ApiError> {
+    let message = ResolverConsumerMessage { message };
+    state
+        .sqs_client
+        .send_message()
+        .queue_url(state.resolver_queue_url.clone())
+        .message_body(serde_json::to_string(&message).map_err(ApiError::from)?)
+        .send()
+        .await
+        .map_err(ApiError::from)?;
+    info!("Message sent to SQS");
+    Ok(())
+}
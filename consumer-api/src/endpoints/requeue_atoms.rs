@@ -0,0 +1,104 @@
+use crate::{endpoints::refetch_atoms::enqueue_atom, error::ApiError, state::AppState};
+use axum::{extract::State, Json};
+use axum_macros::debug_handler;
+use log::info;
+use models::{
+    atom::{AtomResolvingStatus, AtomType},
+    atom_resolution_attempt::{
+        AtomResolutionAttempt, AtomResolutionFilter, ResolutionErrorCategory,
+    },
+};
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+use utoipa::ToSchema;
+
+/// The number of atoms enqueued when no limit is given
+const DEFAULT_LIMIT: i64 = 1000;
+/// The most atoms enqueued by a single request
+const MAX_LIMIT: i64 = 10000;
+
+/// The filters selecting the atoms to enqueue. Every filter is optional,
+/// and the ones that are set must all match.
+#[derive(Deserialize, Serialize, Default, Debug, ToSchema)]
+#[serde(rename_all = "PascalCase")]
+pub struct RequeueAtomsRequest {
+    /// The resolving status, e.g. `Failed`
+    pub status: Option<String>,
+    /// The atom type, e.g. `Unknown`
+    pub atom_type: Option<String>,
+    /// The category of the last resolution error, e.g. `GatewayTimeout`
+    pub error_category: Option<String>,
+    /// The first block the atoms were created at
+    pub from_block: Option<i64>,
+    /// The last block the atoms were created at
+    pub to_block: Option<i64>,
+    /// The most atoms to enqueue, defaults to 1000 and at most 10000
+    pub limit: Option<i64>,
+}
+
+/// The atoms that were enqueued
+#[derive(Deserialize, Serialize, Default, Debug, ToSchema)]
+#[serde(rename_all = "PascalCase")]
+pub struct RequeueAtomsResponse {
+    pub enqueued: usize,
+}
+
+impl RequeueAtomsRequest {
+    /// Parses the filters of the request, returning the invalid one if any
+    fn filter(&self) -> Result<AtomResolutionFilter, String> {
+        Ok(AtomResolutionFilter {
+            resolving_status: parse_filter::<AtomResolvingStatus>("Status", &self.status)?,
+            atom_type: parse_filter::<AtomType>("AtomType", &self.atom_type)?,
+            error_category: parse_filter::<ResolutionErrorCategory>(
+                "ErrorCategory",
+                &self.error_category,
+            )?,
+            from_block: self.from_block,
+            to_block: self.to_block,
+        })
+    }
+}
+
+/// Parses an optional filter, naming it in the error if it's invalid
+fn parse_filter<T: FromStr>(name: &str, value: &Option<String>) -> Result<Option<T>, String> {
+    value
+        .as_deref()
+        .map(|value| T::from_str(value).map_err(|_| format!("{name}: {value}")))
+        .transpose()
+}
+
+/// Enqueue the atoms matching the filters to be resolved again
+#[utoipa::path(
+    post,
+    path = "/requeue_atoms",
+    request_body = inline(RequeueAtomsRequest),
+    responses(
+        (status = 200, description = "Atoms enqueued for re-fetching", body = RequeueAtomsResponse),
+        (status = 400, description = "Invalid filter or wrong format", body = String),
+        (status = 401, description = "Missing or invalid API key", body = String),
+        (status = 403, description = "The API key is missing the moderate scope", body = String),
+        (status = 429, description = "Rate limit exceeded", body = String),
+        (status = 500, description = "Internal server error", body = String)
+    ),
+    security(("api_key" = [])),
+    tag = "atoms"
+)]
+#[debug_handler]
+pub async fn requeue_atoms(
+    State(state): State<AppState>,
+    Json(json): Json<RequeueAtomsRequest>,
+) -> Result<Json<RequeueAtomsResponse>, ApiError> {
+    let filter = json.filter().map_err(ApiError::InvalidFilter)?;
+    let limit = json.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+    let atom_ids =
+        AtomResolutionAttempt::find_atom_ids(&filter, limit, &state.pg_pool, &state.backend_schema)
+            .await?;
+    info!("Enqueuing {} atoms matching {:?}", atom_ids.len(), filter);
+    for atom_id in &atom_ids {
+        enqueue_atom(&state, atom_id.to_string()).await?;
+    }
+
+    Ok(Json(RequeueAtomsResponse {
+        enqueued: atom_ids.len(),
+    }))
+}
@@ -0,0 +1,94 @@
+use crate::{error::ApiError, state::AppState};
+use axum::{
+    extract::{Path, Query, State},
+    Json,
+};
+use axum_macros::debug_handler;
+use models::{
+    types::U256Wrapper,
+    vault::{SharePriceCandle, Vault},
+};
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+use utoipa::{IntoParams, ToSchema};
+
+/// The interval and the block timestamps of the candles
+#[derive(Deserialize, Serialize, Default, Debug, IntoParams)]
+#[serde(rename_all = "PascalCase")]
+#[into_params(parameter_in = Query)]
+pub struct SharePriceCandlesQuery {
+    /// The length of a candle in seconds
+    pub interval: i64,
+    /// The first block timestamp, defaults to the first share price
+    pub from: Option<i64>,
+    /// The block timestamp the candles end at, excluded, defaults to the last share price
+    pub to: Option<i64>,
+}
+
+/// The open, high, low and close share prices of a vault over an interval.
+/// Share prices are decimal strings.
+#[derive(Deserialize, Serialize, Default, Debug, ToSchema)]
+#[serde(rename_all = "PascalCase")]
+pub struct SharePriceCandleResponse {
+    pub bucket_start: i64,
+    pub open: String,
+    pub high: String,
+    pub low: String,
+    pub close: String,
+    pub sample_count: i64,
+}
+
+impl From<SharePriceCandle> for SharePriceCandleResponse {
+    fn from(candle: SharePriceCandle) -> Self {
+        Self {
+            bucket_start: candle.bucket_start,
+            open: candle.open.to_string(),
+            high: candle.high.to_string(),
+            low: candle.low.to_string(),
+            close: candle.close.to_string(),
+            sample_count: candle.sample_count,
+        }
+    }
+}
+
+/// Get the share price candles of a vault
+#[utoipa::path(
+    get,
+    path = "/vaults/{id}/share_price_candles",
+    params(
+        ("id" = String, Path, description = "The vault id"),
+        SharePriceCandlesQuery
+    ),
+    responses(
+        (status = 200, description = "The candles, oldest first", body = Vec<SharePriceCandleResponse>),
+        (status = 400, description = "Invalid vault id or interval", body = String),
+        (status = 500, description = "Internal server error", body = String)
+    ),
+    tag = "vaults"
+)]
+#[debug_handler]
+pub async fn share_price_candles(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Query(query): Query<SharePriceCandlesQuery>,
+) -> Result<Json<Vec<SharePriceCandleResponse>>, ApiError> {
+    let vault_id =
+        U256Wrapper::from_str(&id).map_err(|_| ApiError::InvalidFilter(format!("Id: {id}")))?;
+    if query.interval <= 0 {
+        return Err(ApiError::InvalidFilter(format!(
+            "Interval: {}",
+            query.interval
+        )));
+    }
+
+    let candles = Vault::share_price_candles(
+        &vault_id,
+        query.interval,
+        query.from.unwrap_or(0),
+        query.to.unwrap_or(i64::MAX),
+        &state.pg_pool,
+        &state.backend_schema,
+    )
+    .await?;
+    Ok(Json(candles.into_iter().map(Into::into).collect()))
+}
@@ -5,6 +5,9 @@ mod app;
 mod endpoints;
 mod error;
 mod openapi;
+mod profile_refresh;
+mod retry;
+mod rollup;
 mod state;
 mod types;
 
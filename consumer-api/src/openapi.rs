@@ -1,18 +1,56 @@
-use crate::endpoints::{self, refetch_atoms::RefetchAtomsRequest};
-use utoipa::OpenApi;
+use crate::endpoints::{
+    self,
+    rebuild_relevance_scores::RebuildRelevanceScoresResponse,
+    rebuild_rollups::{RebuildRollupsRequest, RebuildRollupsResponse},
+    refetch_atoms::RefetchAtomsRequest,
+    requeue_atoms::{RequeueAtomsRequest, RequeueAtomsResponse},
+    share_price_candles::SharePriceCandleResponse,
+};
+use utoipa::{
+    openapi::security::{HttpAuthScheme, HttpBuilder, SecurityScheme},
+    Modify, OpenApi,
+};
 
 #[derive(OpenApi)]
 #[openapi(
     paths(
+        endpoints::rebuild_relevance_scores::rebuild_relevance_scores,
+        endpoints::rebuild_rollups::rebuild_rollups,
         endpoints::refetch_atoms::refetch_atoms,
+        endpoints::requeue_atoms::requeue_atoms,
+        endpoints::share_price_candles::share_price_candles,
     ),
     components(
         schemas(
+            RebuildRelevanceScoresResponse,
+            RebuildRollupsRequest,
+            RebuildRollupsResponse,
             RefetchAtomsRequest,
+            RequeueAtomsRequest,
+            RequeueAtomsResponse,
+            SharePriceCandleResponse,
         )
     ),
+    modifiers(&ApiKeySecurity),
     tags(
-        (name = "atoms", description = "Atom re-fetch endpoints")
+        (name = "atoms", description = "Atom re-fetch endpoints"),
+        (name = "relevance", description = "Relevance score endpoints"),
+        (name = "rollups", description = "Daily and weekly rollup endpoints"),
+        (name = "vaults", description = "Vault share price endpoints")
     )
 )]
 pub struct ApiDoc;
+
+/// Registers the API key sent as a bearer token
+struct ApiKeySecurity;
+
+impl Modify for ApiKeySecurity {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        if let Some(components) = openapi.components.as_mut() {
+            components.add_security_scheme(
+                "api_key",
+                SecurityScheme::Http(HttpBuilder::new().scheme(HttpAuthScheme::Bearer).build()),
+            )
+        }
+    }
+}
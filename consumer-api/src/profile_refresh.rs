@@ -0,0 +1,45 @@
+use crate::{
+    endpoints::refetch_atoms::{enqueue, ResolverMessageType},
+    error::ApiError,
+    state::AppState,
+};
+use log::{info, warn};
+use models::account::Account;
+use std::time::Duration;
+
+/// Spawns the profile refresh job. Every `interval`, it enqueues up to
+/// `batch_size` accounts whose profile was resolved more than `ttl` ago to
+/// the resolver consumer, which updates the accounts whose ENS name or
+/// Basename changed.
+pub fn spawn_profile_refresh(state: AppState, interval: Duration, ttl: Duration, batch_size: i64) {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            ticker.tick().await;
+            match refresh_stale_profiles(&state, ttl, batch_size).await {
+                Ok(enqueued) => info!("Profile refresh enqueued {} accounts", enqueued),
+                Err(e) => warn!("Profile refresh failed: {}", e),
+            }
+        }
+    });
+}
+
+/// Claims a batch of accounts whose profile is stale and enqueues them
+async fn refresh_stale_profiles(
+    state: &AppState,
+    ttl: Duration,
+    batch_size: i64,
+) -> Result<usize, ApiError> {
+    let accounts = Account::claim_stale_profiles(
+        ttl.as_secs() as i64,
+        batch_size,
+        &state.pg_pool,
+        &state.backend_schema,
+    )
+    .await?;
+    let enqueued = accounts.len();
+    for account in accounts {
+        enqueue(state, ResolverMessageType::Account(account)).await?;
+    }
+    Ok(enqueued)
+}
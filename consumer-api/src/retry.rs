@@ -0,0 +1,57 @@
+use crate::{endpoints::refetch_atoms::enqueue_atom, error::ApiError, state::AppState};
+use log::{info, warn};
+use models::atom_resolution_attempt::AtomResolutionAttempt;
+use std::time::Duration;
+
+/// The number of atoms claimed from the database at once
+const PAGE_SIZE: i64 = 500;
+/// How long a claimed atom waits before being claimed again if it could not
+/// be enqueued
+const CLAIM_LEASE: Duration = Duration::from_secs(10 * 60);
+
+/// Spawns the resolution retry job. Every `interval`, it enqueues the failed
+/// atoms whose next retry is due to the resolver consumer, which schedules
+/// the next one if they fail again.
+pub fn spawn_resolution_retry(state: AppState, interval: Duration) {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            ticker.tick().await;
+            match retry_due_resolutions(&state).await {
+                Ok(enqueued) => info!("Resolution retry enqueued {} atoms", enqueued),
+                Err(e) => warn!("Resolution retry failed: {}", e),
+            }
+        }
+    });
+}
+
+/// Claims the atoms whose retry is due page by page and enqueues them. Atoms
+/// that fail to be enqueued keep their claim, so they are retried once it
+/// expires.
+async fn retry_due_resolutions(state: &AppState) -> Result<usize, ApiError> {
+    let lease = chrono::Duration::from_std(CLAIM_LEASE).unwrap_or_default();
+    let mut enqueued = 0;
+    loop {
+        let claimed = AtomResolutionAttempt::claim_due(
+            PAGE_SIZE,
+            lease,
+            &state.pg_pool,
+            &state.backend_schema,
+        )
+        .await?;
+        if claimed.is_empty() {
+            break;
+        }
+        for attempt in claimed {
+            if let Err(e) = enqueue_atom(state, attempt.atom_id.to_string()).await {
+                warn!("Failed to enqueue atom {}: {}", attempt.atom_id, e);
+                continue;
+            }
+            attempt
+                .mark_enqueued(&state.pg_pool, &state.backend_schema)
+                .await?;
+            enqueued += 1;
+        }
+    }
+    Ok(enqueued)
+}
@@ -0,0 +1,56 @@
+use crate::{error::ApiError, state::AppState};
+use log::{info, warn};
+use models::{
+    rollup::{Rollup, RollupCursor, RollupGranularity},
+    traits::SimpleCrud,
+};
+use std::time::Duration;
+
+/// Spawns the rollup job. Every `interval`, it recomputes the daily and
+/// weekly rollups of the buckets that received deposits or redemptions
+/// since its last run.
+pub fn spawn_rollups(state: AppState, interval: Duration) {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            ticker.tick().await;
+            for granularity in RollupGranularity::ALL {
+                match roll_up(&state, granularity).await {
+                    Ok(rollups) => info!("Rolled up {} {} buckets", rollups, granularity),
+                    Err(e) => warn!("{} rollup failed: {}", granularity, e),
+                }
+            }
+        }
+    });
+}
+
+/// Rebuilds the buckets from the one holding the cursor of a granularity to
+/// the last event, then moves the cursor to the last event. The bucket
+/// holding the cursor is rebuilt because it may have been partial.
+async fn roll_up(state: &AppState, granularity: RollupGranularity) -> Result<u64, ApiError> {
+    let Some(last_event_timestamp) =
+        Rollup::find_last_event_timestamp(&state.pg_pool, &state.backend_schema).await?
+    else {
+        return Ok(0);
+    };
+    let from_timestamp =
+        RollupCursor::find_by_id(granularity, &state.pg_pool, &state.backend_schema)
+            .await?
+            .map_or(0, |cursor| cursor.last_block_timestamp);
+
+    let rollups = Rollup::rebuild(
+        granularity,
+        from_timestamp,
+        last_event_timestamp + 1,
+        &state.pg_pool,
+        &state.backend_schema,
+    )
+    .await?;
+    RollupCursor::builder()
+        .granularity(granularity)
+        .last_block_timestamp(last_event_timestamp)
+        .build()
+        .upsert(&state.pg_pool, &state.backend_schema)
+        .await?;
+    Ok(rollups)
+}
@@ -1,19 +1,46 @@
-use crate::types::Env;
+use crate::{error::ApiError, types::Env};
 use aws_sdk_sqs::Client as AWSClient;
 use log::info;
+use models::relevance_score::DEFAULT_HALF_LIFE_SECS;
+use shared_utils::{
+    auth::{parse_trusted_proxies, ApiKeyAuth},
+    postgres::connect_to_db,
+};
+use sqlx::PgPool;
+
+/// The default number of requests per minute allowed from a single IP
+const DEFAULT_IP_RATE_LIMIT_PER_MINUTE: u32 = 120;
 
 #[derive(Clone)]
 pub struct AppState {
+    pub auth: ApiKeyAuth,
+    pub backend_schema: String,
+    pub pg_pool: PgPool,
+    pub relevance_half_life_secs: i64,
     pub sqs_client: AWSClient,
     pub resolver_queue_url: String,
 }
 
 impl AppState {
-    pub async fn new(env: &Env) -> Self {
-        Self {
+    pub async fn new(env: &Env) -> Result<Self, ApiError> {
+        let pg_pool = connect_to_db(&env.indexer_database_url).await?;
+        Ok(Self {
+            auth: ApiKeyAuth::new(
+                pg_pool.clone(),
+                env.api_key_schema.clone(),
+                env.admin_api_key.clone(),
+                env.ip_rate_limit_per_minute
+                    .unwrap_or(DEFAULT_IP_RATE_LIMIT_PER_MINUTE),
+                parse_trusted_proxies(env.trusted_proxies.as_deref())?,
+            ),
+            backend_schema: env.backend_schema.clone(),
+            pg_pool,
+            relevance_half_life_secs: env
+                .relevance_half_life_secs
+                .unwrap_or(DEFAULT_HALF_LIFE_SECS),
             sqs_client: Self::get_aws_client(&env.localstack_url).await,
             resolver_queue_url: env.resolver_queue_url.clone(),
-        }
+        })
     }
     /// This function returns an [`aws_sdk_sqs::Client`] based on the
     /// environment variables
@@ -2,7 +2,19 @@ use serde::Deserialize;
 
 #[derive(Deserialize)]
 pub struct Env {
+    pub admin_api_key: Option<String>,
+    pub api_key_schema: String,
+    pub backend_schema: String,
     pub consumer_api_port: Option<u16>,
+    pub indexer_database_url: String,
+    pub ip_rate_limit_per_minute: Option<u32>,
+    pub profile_refresh_batch_size: Option<i64>,
+    pub profile_refresh_interval_secs: Option<u64>,
+    pub profile_ttl_secs: Option<u64>,
+    pub relevance_half_life_secs: Option<i64>,
+    pub resolution_retry_interval_secs: Option<u64>,
     pub resolver_queue_url: String,
+    pub rollup_interval_secs: Option<u64>,
+    pub trusted_proxies: Option<String>,
     pub localstack_url: Option<String>,
 }
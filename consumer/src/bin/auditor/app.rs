@@ -0,0 +1,107 @@
+use crate::{
+    error::AuditorError,
+    Env,
+    EthMultiVault::{self, EthMultiVaultInstance},
+};
+use alloy::{
+    primitives::Address,
+    providers::{ProviderBuilder, RootProvider},
+    transports::http::Http,
+};
+use prometheus::{gather, Encoder, TextEncoder};
+use reqwest::Client;
+use shared_utils::postgres::connect_to_db;
+use sqlx::PgPool;
+use std::{convert::Infallible, str::FromStr, sync::Arc, time::Duration};
+use tracing::info;
+use tracing_subscriber::{layer::SubscriberExt, EnvFilter};
+use warp::Filter;
+
+/// Represents the auditor. It compares the indexed values to the Intuition
+/// contract at the same block.
+#[derive(Clone)]
+pub struct Auditor {
+    pub backend_schema: String,
+    pub base_client: Arc<EthMultiVaultInstance<Http<Client>, RootProvider<Http<Client>>>>,
+    /// The audited block, defaults to the last block processed by the indexer
+    pub block_number: Option<u64>,
+    /// The interval between two audits, or a single audit if not set
+    pub interval: Option<Duration>,
+    pub metrics_api_port: Option<u16>,
+    pub pg_pool: PgPool,
+    /// The number of random positions and vaults audited, or all of them if
+    /// not set
+    pub sample_size: Option<i64>,
+}
+
+impl Auditor {
+    /// This function reads the `.env` file, parses the environment variables
+    /// and builds the auditor with its database pool and contract client.
+    pub async fn new() -> Result<Self, AuditorError> {
+        Self::set_up_logging()?;
+        dotenvy::dotenv().ok();
+        info!("Parsing the environment variables");
+        let env = envy::from_env::<Env>()?;
+
+        let provider = ProviderBuilder::new().on_http(env.rpc_url_base.parse()?);
+        let base_client = Arc::new(EthMultiVault::new(
+            Address::from_str(&env.intuition_contract_address)
+                .map_err(|e| AuditorError::AddressParse(e.to_string()))?,
+            provider,
+        ));
+
+        Ok(Self {
+            backend_schema: env.backend_schema,
+            base_client,
+            block_number: env.audit_block_number,
+            interval: env.audit_interval_secs.map(Duration::from_secs),
+            metrics_api_port: env.auditor_metrics_api_port,
+            pg_pool: connect_to_db(&env.database_url).await?,
+            sample_size: env.audit_sample_size,
+        })
+    }
+
+    /// Set up the logging to STDOUT
+    fn set_up_logging() -> Result<(), AuditorError> {
+        let subscriber = tracing_subscriber::registry()
+            .with(
+                EnvFilter::from_default_env()
+                    .add_directive(tracing::Level::INFO.into())
+                    .add_directive("auditor=debug".parse().unwrap()),
+            )
+            .with(
+                tracing_subscriber::fmt::layer()
+                    .json()
+                    .with_file(true)
+                    .with_line_number(true)
+                    .with_target(true)
+                    .with_writer(std::io::stdout),
+            );
+        tracing::subscriber::set_global_default(subscriber)?;
+        Ok(())
+    }
+
+    /// Serve the metrics endpoint
+    async fn serve_metrics() -> Result<impl warp::Reply, Infallible> {
+        let encoder = TextEncoder::new();
+        let metric_families = gather();
+        let mut buffer = Vec::new();
+        encoder.encode(&metric_families, &mut buffer).unwrap();
+        Ok(warp::reply::with_header(
+            buffer,
+            "Content-Type",
+            encoder.format_type(),
+        ))
+    }
+
+    /// Spawn the warp server serving the metrics endpoint
+    pub fn spawn_metrics_server(&self) {
+        let metrics_route = warp::path!("metrics")
+            .and(warp::get())
+            .and_then(Self::serve_metrics);
+        let port = self.metrics_api_port.unwrap_or(3002);
+        tokio::spawn(async move {
+            warp::serve(metrics_route).run(([0, 0, 0, 0], port)).await;
+        });
+    }
+}
@@ -0,0 +1,473 @@
+use crate::{app::Auditor, error::AuditorError};
+use alloy::{
+    eips::BlockId,
+    primitives::{Address, U256},
+    providers::Provider,
+};
+use models::{
+    audit_finding::{AuditEntity, AuditFinding},
+    position::Position,
+    stats::Stats,
+    traits::SimpleCrud,
+    types::U256Wrapper,
+    vault::Vault,
+};
+use once_cell::sync::OnceCell;
+use prometheus::{register_int_gauge_vec, IntGaugeVec};
+use std::str::FromStr;
+use tracing::{info, warn};
+
+/// The number of rows read from the database at once in a full scan
+const PAGE_SIZE: i64 = 500;
+/// How many times a mismatch is checked again while the indexer moves on
+const MAX_RECHECKS: usize = 3;
+
+/// An audited field, with its indexed value and its value in the contract
+type AuditedField = (&'static str, U256Wrapper, U256);
+
+// Create a OnceCell to hold the gauge
+static AUDIT_MISMATCH_GAUGE: OnceCell<IntGaugeVec> = OnceCell::new();
+
+fn get_audit_mismatch_gauge() -> &'static IntGaugeVec {
+    AUDIT_MISMATCH_GAUGE.get_or_init(|| {
+        register_int_gauge_vec!(
+            "audit_mismatches",
+            "Number of indexed values that didn't match the contract at the last audit",
+            &["entity_type"]
+        )
+        .unwrap()
+    })
+}
+
+impl Auditor {
+    /// This function audits the indexer once, or at every interval if one is
+    /// set. Failed audits are logged and retried at the next interval.
+    pub async fn run_audits(&self) -> Result<(), AuditorError> {
+        let Some(interval) = self.interval else {
+            return self.audit().await;
+        };
+
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            ticker.tick().await;
+            if let Err(e) = self.audit().await {
+                warn!("Audit failed: {}", e);
+            }
+        }
+    }
+
+    /// This function compares the positions, the vaults and the contract
+    /// balance to the contract at the audited block, and records the
+    /// mismatches.
+    async fn audit(&self) -> Result<(), AuditorError> {
+        let block_number = self.audit_block_number().await?;
+        info!("Auditing the indexer at block {}", block_number);
+
+        let positions = self.audit_positions(block_number).await?;
+        let vaults = self.audit_vaults(block_number).await?;
+        let stats = self.audit_stats(block_number).await?;
+
+        let gauge = get_audit_mismatch_gauge();
+        gauge
+            .with_label_values(&[&AuditEntity::Position.to_string()])
+            .set(positions);
+        gauge
+            .with_label_values(&[&AuditEntity::Vault.to_string()])
+            .set(vaults);
+        gauge
+            .with_label_values(&[&AuditEntity::Stats.to_string()])
+            .set(stats);
+        info!(
+            "Audit at block {} found {} position, {} vault and {} stats mismatches",
+            block_number, positions, vaults, stats
+        );
+        Ok(())
+    }
+
+    /// Returns the configured block, or else the last block processed by the
+    /// indexer, so the indexed values are compared to the same state
+    async fn audit_block_number(&self) -> Result<u64, AuditorError> {
+        if let Some(block_number) = self.block_number {
+            return Ok(block_number);
+        }
+        let last_processed = Stats::find_by_id(0, &self.pg_pool, &self.backend_schema)
+            .await?
+            .and_then(|stats| stats.last_processed_block_number);
+        match last_processed {
+            Some(block_number) => Ok(block_number.0.saturating_to::<u64>()),
+            None => self
+                .base_client
+                .provider()
+                .get_block_number()
+                .await
+                .map_err(|e| AuditorError::NetworkError(e.to_string())),
+        }
+    }
+
+    /// This function compares the shares of the positions, a sample of them
+    /// or all of them page by page
+    pub(crate) async fn audit_positions(&self, block_number: u64) -> Result<i64, AuditorError> {
+        let mut mismatches = 0;
+        let mut after_id = String::new();
+        loop {
+            let positions = match self.sample_size {
+                Some(sample_size) => {
+                    Position::find_sample(sample_size, &self.pg_pool, &self.backend_schema).await?
+                }
+                None => {
+                    Position::find_page(&after_id, PAGE_SIZE, &self.pg_pool, &self.backend_schema)
+                        .await?
+                }
+            };
+            for position in &positions {
+                let fields = self.position_fields(position, block_number).await?;
+                mismatches += self
+                    .audit_entity(block_number, AuditEntity::Position, &position.id, fields)
+                    .await?;
+            }
+            match positions.last() {
+                Some(last) if self.sample_size.is_none() => after_id = last.id.clone(),
+                _ => break,
+            }
+        }
+        Ok(mismatches)
+    }
+
+    /// This function compares the total shares and the share price of the
+    /// vaults, a sample of them or all of them page by page
+    pub(crate) async fn audit_vaults(&self, block_number: u64) -> Result<i64, AuditorError> {
+        let mut mismatches = 0;
+        let mut after_id = U256Wrapper::default();
+        loop {
+            let vaults = match self.sample_size {
+                Some(sample_size) => {
+                    Vault::find_sample(sample_size, &self.pg_pool, &self.backend_schema).await?
+                }
+                None => {
+                    Vault::find_page(&after_id, PAGE_SIZE, &self.pg_pool, &self.backend_schema)
+                        .await?
+                }
+            };
+            for vault in &vaults {
+                let fields = self.vault_fields(vault, block_number).await?;
+                mismatches += self
+                    .audit_entity(
+                        block_number,
+                        AuditEntity::Vault,
+                        &vault.id.to_string(),
+                        fields,
+                    )
+                    .await?;
+            }
+            match vaults.last() {
+                Some(last) if self.sample_size.is_none() => after_id = last.id.clone(),
+                _ => break,
+            }
+        }
+        Ok(mismatches)
+    }
+
+    /// This function compares the contract balance of the stats
+    async fn audit_stats(&self, block_number: u64) -> Result<i64, AuditorError> {
+        let Some(stats) = Stats::find_by_id(0, &self.pg_pool, &self.backend_schema).await? else {
+            return Ok(0);
+        };
+        let fields = self.stats_fields(&stats, block_number).await?;
+        self.audit_entity(block_number, AuditEntity::Stats, "0", fields)
+            .await
+    }
+
+    /// Returns the audited fields of a position, with their indexed value and
+    /// their value at a block
+    async fn position_fields(
+        &self,
+        position: &Position,
+        block_number: u64,
+    ) -> Result<Vec<AuditedField>, AuditorError> {
+        let account = Address::from_str(&position.account_id)
+            .map_err(|e| AuditorError::AddressParse(e.to_string()))?;
+        let shares = self
+            .base_client
+            .getVaultStateForUser(position.vault_id.0, account)
+            .block(BlockId::number(block_number))
+            .call()
+            .await?
+            ._0;
+        Ok(vec![("shares", position.shares.clone(), shares)])
+    }
+
+    /// Returns the audited fields of a vault, with their indexed value and
+    /// their value at a block
+    async fn vault_fields(
+        &self,
+        vault: &Vault,
+        block_number: u64,
+    ) -> Result<Vec<AuditedField>, AuditorError> {
+        let block = BlockId::number(block_number);
+        let total_shares = self
+            .base_client
+            .vaults(vault.id.0)
+            .block(block)
+            .call()
+            .await?
+            .totalShares;
+        let share_price = self
+            .base_client
+            .currentSharePrice(vault.id.0)
+            .block(block)
+            .call()
+            .await?
+            ._0;
+        Ok(vec![
+            ("total_shares", vault.total_shares.clone(), total_shares),
+            (
+                "current_share_price",
+                vault.current_share_price.clone(),
+                share_price,
+            ),
+        ])
+    }
+
+    /// Returns the contract balance of the stats, with its indexed value and
+    /// its value at a block
+    async fn stats_fields(
+        &self,
+        stats: &Stats,
+        block_number: u64,
+    ) -> Result<Vec<AuditedField>, AuditorError> {
+        let Some(contract_balance) = stats.contract_balance.clone() else {
+            return Ok(Vec::new());
+        };
+        let balance = self
+            .base_client
+            .provider()
+            .get_balance(*self.base_client.address())
+            .block_id(BlockId::number(block_number))
+            .await
+            .map_err(|e| AuditorError::NetworkError(e.to_string()))?;
+        Ok(vec![("contract_balance", contract_balance, balance)])
+    }
+
+    /// Reads an entity again from the database, and returns its audited
+    /// fields at a block. An entity that no longer exists has none.
+    async fn find_entity_fields(
+        &self,
+        entity_type: AuditEntity,
+        entity_id: &str,
+        block_number: u64,
+    ) -> Result<Vec<AuditedField>, AuditorError> {
+        let (pool, schema) = (&self.pg_pool, self.backend_schema.as_str());
+        match entity_type {
+            AuditEntity::Position => {
+                match Position::find_by_id(entity_id.to_string(), pool, schema).await? {
+                    Some(position) => self.position_fields(&position, block_number).await,
+                    None => Ok(Vec::new()),
+                }
+            }
+            AuditEntity::Vault => {
+                match Vault::find_by_id(U256Wrapper::from_str(entity_id)?, pool, schema).await? {
+                    Some(vault) => self.vault_fields(&vault, block_number).await,
+                    None => Ok(Vec::new()),
+                }
+            }
+            AuditEntity::Stats => match Stats::find_by_id(0, pool, schema).await? {
+                Some(stats) => self.stats_fields(&stats, block_number).await,
+                None => Ok(Vec::new()),
+            },
+        }
+    }
+
+    /// Returns the last block processed by the indexer if it moved past a
+    /// block, so values indexed since then can be audited again. A
+    /// configured block is never moved past.
+    async fn processed_after(&self, block_number: u64) -> Result<Option<u64>, AuditorError> {
+        if self.block_number.is_some() {
+            return Ok(None);
+        }
+        Ok(Stats::find_by_id(0, &self.pg_pool, &self.backend_schema)
+            .await?
+            .and_then(|stats| stats.last_processed_block_number)
+            .map(|block_number| block_number.0.saturating_to::<u64>())
+            .filter(|last_processed| *last_processed > block_number))
+    }
+
+    /// Records the fields of an entity that don't match the contract, and
+    /// returns their number. The indexer keeps writing during the audit, so
+    /// a mismatch is checked again at the last processed block, with the
+    /// entity read again, as long as the indexer moves on.
+    async fn audit_entity(
+        &self,
+        block_number: u64,
+        entity_type: AuditEntity,
+        entity_id: &str,
+        fields: Vec<AuditedField>,
+    ) -> Result<i64, AuditorError> {
+        let (mut block_number, mut fields) = (block_number, fields);
+        for _ in 0..MAX_RECHECKS {
+            if fields
+                .iter()
+                .all(|(_, indexed_value, onchain_value)| indexed_value.0 == *onchain_value)
+            {
+                return Ok(0);
+            }
+            let Some(last_processed) = self.processed_after(block_number).await? else {
+                break;
+            };
+            block_number = last_processed;
+            fields = self
+                .find_entity_fields(entity_type, entity_id, block_number)
+                .await?;
+        }
+
+        let mut mismatches = 0;
+        for (field, indexed_value, onchain_value) in fields {
+            if self
+                .record_mismatch(
+                    block_number,
+                    entity_type,
+                    entity_id,
+                    field,
+                    &indexed_value,
+                    onchain_value,
+                )
+                .await?
+            {
+                mismatches += 1;
+            }
+        }
+        Ok(mismatches)
+    }
+
+    /// Records a finding if the indexed value doesn't match the contract, and
+    /// returns true if it didn't. A finding already recorded at the block is
+    /// not recorded again.
+    async fn record_mismatch(
+        &self,
+        block_number: u64,
+        entity_type: AuditEntity,
+        entity_id: &str,
+        field: &str,
+        indexed_value: &U256Wrapper,
+        onchain_value: U256,
+    ) -> Result<bool, AuditorError> {
+        if indexed_value.0 == onchain_value {
+            return Ok(false);
+        }
+        let finding = AuditFinding::builder()
+            .block_number(block_number as i64)
+            .entity_type(entity_type)
+            .entity_id(entity_id.to_string())
+            .field(field.to_string())
+            .indexed_value(indexed_value.clone())
+            .onchain_value(U256Wrapper::from(onchain_value))
+            .build()
+            .insert(&self.pg_pool, &self.backend_schema)
+            .await?;
+        if let Some(finding) = finding {
+            warn!("Audit finding: {:?}", finding);
+        }
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::EthMultiVault;
+    use alloy::providers::ProviderBuilder;
+    use sqlx::PgPool;
+    use std::sync::Arc;
+
+    /// The tables the audit reads and writes, copied into a throwaway schema
+    const AUDITED_TABLES: [&str; 4] = ["position", "vault", "stats", "audit_finding"];
+
+    /// Copies the audited tables of the devnet indexer into a throwaway
+    /// schema, leaving the findings out, so the audit doesn't write into the
+    /// indexer's schema
+    async fn create_audit_schema(pool: &PgPool, source_schema: &str, schema: &str) {
+        sqlx::query(&format!("CREATE SCHEMA {}", schema))
+            .execute(pool)
+            .await
+            .unwrap();
+        for table in AUDITED_TABLES {
+            sqlx::query(&format!(
+                "CREATE TABLE {schema}.{table} (LIKE {source_schema}.{table} INCLUDING ALL)"
+            ))
+            .execute(pool)
+            .await
+            .unwrap();
+            if table != "audit_finding" {
+                sqlx::query(&format!(
+                    "INSERT INTO {schema}.{table} SELECT * FROM {source_schema}.{table}"
+                ))
+                .execute(pool)
+                .await
+                .unwrap();
+            }
+        }
+    }
+
+    /// Audits the positions and the vaults at a block, and returns the
+    /// number of mismatches and the findings recorded at the block
+    async fn audit_block(
+        auditor: &Auditor,
+        block_number: u64,
+    ) -> Result<(i64, Vec<AuditFinding>), AuditorError> {
+        let mismatches = auditor.audit_positions(block_number).await?
+            + auditor.audit_vaults(block_number).await?;
+        let findings = AuditFinding::find_by_block_number(
+            block_number as i64,
+            &auditor.pg_pool,
+            &auditor.backend_schema,
+        )
+        .await?;
+        Ok((mismatches, findings))
+    }
+
+    // This test needs the database and the local devnet running, set with
+    // `DEVNET_DATABASE_URL`, `DEVNET_RPC_URL` and
+    // `DEVNET_INTUITION_CONTRACT_ADDRESS`. The indexed tables are read from
+    // `DEVNET_BACKEND_SCHEMA`, `public` by default.
+    #[tokio::test]
+    #[ignore]
+    async fn test_audit_devnet() {
+        let env = |name: &str| std::env::var(name).unwrap_or_else(|_| panic!("{name} is not set"));
+        let source_schema =
+            std::env::var("DEVNET_BACKEND_SCHEMA").unwrap_or_else(|_| "public".to_string());
+        let schema = format!("audit_test_{}", std::process::id());
+
+        let pg_pool = PgPool::connect(&env("DEVNET_DATABASE_URL"))
+            .await
+            .expect("Failed to create pg pool");
+        let provider = ProviderBuilder::new().on_http(env("DEVNET_RPC_URL").parse().unwrap());
+        let block_number = provider.get_block_number().await.unwrap();
+        create_audit_schema(&pg_pool, &source_schema, &schema).await;
+
+        let auditor = Auditor {
+            backend_schema: schema.clone(),
+            base_client: Arc::new(EthMultiVault::new(
+                Address::from_str(&env("DEVNET_INTUITION_CONTRACT_ADDRESS")).unwrap(),
+                provider,
+            )),
+            block_number: Some(block_number),
+            interval: None,
+            metrics_api_port: None,
+            pg_pool: pg_pool.clone(),
+            sample_size: None,
+        };
+        let first_audit = audit_block(&auditor, block_number).await;
+        let second_audit = audit_block(&auditor, block_number).await;
+
+        sqlx::query(&format!("DROP SCHEMA {} CASCADE", schema))
+            .execute(&pg_pool)
+            .await
+            .unwrap();
+
+        let (mismatches, findings) = first_audit.unwrap();
+        let (mismatches_again, findings_again) = second_audit.unwrap();
+        assert_eq!(findings.len() as i64, mismatches);
+        // Auditing the same block again doesn't record the findings twice
+        assert_eq!(mismatches_again, mismatches);
+        assert_eq!(findings_again, findings);
+    }
+}
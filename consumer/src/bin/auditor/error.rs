@@ -0,0 +1,22 @@
+use thiserror::Error;
+
+/// This enum represents the error types of the auditor
+#[derive(Error, Debug)]
+pub enum AuditorError {
+    #[error("Failed to parse address: {0}")]
+    AddressParse(String),
+    #[error(transparent)]
+    Alloy(#[from] alloy::contract::Error),
+    #[error(transparent)]
+    Envy(#[from] envy::Error),
+    #[error(transparent)]
+    ModelError(#[from] models::error::ModelError),
+    #[error("Network error: {0}")]
+    NetworkError(String),
+    #[error(transparent)]
+    SharedUtils(#[from] shared_utils::error::LibError),
+    #[error(transparent)]
+    Tracing(#[from] tracing::subscriber::SetGlobalDefaultError),
+    #[error(transparent)]
+    UrlParse(#[from] sqlx_core::url::ParseError),
+}
@@ -0,0 +1,40 @@
+#![allow(clippy::result_large_err)]
+use alloy::sol;
+use app::Auditor;
+use error::AuditorError;
+use serde::Deserialize;
+
+mod app;
+mod audit;
+mod error;
+
+// Codegen from ABI file to interact with the Intuition contract.
+sol!(
+    #[allow(missing_docs)]
+    #[sol(rpc)]
+    EthMultiVault,
+    "contracts/EthMultiVault.json"
+);
+
+/// The environment variables of the auditor
+#[derive(Clone, Debug, Deserialize)]
+pub struct Env {
+    pub audit_block_number: Option<u64>,
+    pub audit_interval_secs: Option<u64>,
+    pub audit_sample_size: Option<i64>,
+    pub auditor_metrics_api_port: Option<u16>,
+    pub backend_schema: String,
+    pub database_url: String,
+    pub intuition_contract_address: String,
+    pub rpc_url_base: String,
+}
+
+/// This is the main function of the auditor. It compares the indexed values
+/// to the Intuition contract, once or at every `AUDIT_INTERVAL_SECS`, and
+/// serves the mismatch gauges on the metrics endpoint.
+#[tokio::main]
+async fn main() -> Result<(), AuditorError> {
+    let auditor = Auditor::new().await?;
+    auditor.spawn_metrics_server();
+    auditor.run_audits().await
+}
@@ -5,21 +5,33 @@ use serde::Deserialize;
 
 #[derive(Clone, Deserialize, Debug, Default)]
 pub struct Env {
+    pub arweave_gateway_url: Option<String>,
+    pub basenames_registry_address: Option<String>,
+    pub chainlink_block_range: Option<u64>,
+    pub chainlink_eth_usd_feed_address: Option<String>,
+    pub chainlink_poll_interval_secs: Option<u64>,
+    pub chainlink_start_block: Option<u64>,
     pub consumer_metrics_api_port: Option<u16>,
     pub consumer_type: String,
     pub database_url: String,
     pub decoded_logs_queue_url: Option<String>,
     pub ens_contract_address: Option<String>,
+    pub image_guard_api_key: Option<String>,
     pub image_guard_url: Option<String>,
     pub indexing_source: Option<String>,
     pub intuition_contract_address: Option<String>,
+    pub ipfs_cache_dir: Option<String>,
+    pub ipfs_cache_max_size_mb: Option<u64>,
+    pub ipfs_cache_schema: Option<String>,
+    pub ipfs_gateway_race: Option<bool>,
     pub ipfs_gateway_url: Option<String>,
+    pub ipfs_gateways: Option<String>,
     pub ipfs_upload_queue_url: Option<String>,
-    pub ipfs_upload_url: Option<String>,
     pub localstack_url: Option<String>,
-    pub pinata_api_jwt: Option<String>,
-    pub pinata_gateway_token: Option<String>,
     pub raw_consumer_queue_url: Option<String>,
+    pub relevance_half_life_secs: Option<i64>,
+    pub resolution_retry_base_delay_secs: Option<u64>,
+    pub resolution_retry_max_attempts: Option<i32>,
     pub resolver_queue_url: Option<String>,
     pub rpc_url_base: Option<String>,
     pub rpc_url_mainnet: Option<String>,
@@ -91,10 +91,18 @@ pub enum ConsumerError {
     HexConversion(#[from] rustc_hex::FromHexError),
     #[error("Failed to resolve IPFS data: {0}")]
     Ipfs(String),
-    #[error("Invalid CAIP10")]
-    InvalidCaip10,
+    #[error("Invalid CAIP-2: {0}")]
+    InvalidCaip2(String),
+    #[error("Invalid CAIP-10: {0}")]
+    InvalidCaip10(String),
+    #[error("Invalid CAIP-19: {0}")]
+    InvalidCaip19(String),
+    #[error("Invalid DID: {0}")]
+    InvalidDid(String),
     #[error("Invalid JSON")]
     InvalidJson,
+    #[error("Invalid URI: {0}")]
+    InvalidUri(String),
     #[error("Failed to parse indexer source: {0}")]
     IndexerSourceParse(String),
     #[error("Label not found")]
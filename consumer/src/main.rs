@@ -32,12 +32,46 @@ sol!(
     }
 );
 
-// Codegen to interact with the ENSName contract.
+// Codegen to interact with the ENSName contract, the resolver of ENS names
+// and Basenames.
 sol! {
     #[allow(missing_docs)]
     #[sol(rpc)]
     interface ENSName {
         function name(bytes32 node) external view returns (string);
+        function addr(bytes32 node) external view returns (address);
+        function text(bytes32 node, string key) external view returns (string);
+    }
+}
+
+// Codegen to interact with the NFT contracts used as ENS avatars.
+sol! {
+    #[allow(missing_docs)]
+    #[sol(rpc)]
+    interface ERC721 {
+        function ownerOf(uint256 tokenId) external view returns (address);
+        function tokenURI(uint256 tokenId) external view returns (string);
+    }
+}
+
+sol! {
+    #[allow(missing_docs)]
+    #[sol(rpc)]
+    interface ERC1155 {
+        function balanceOf(address account, uint256 id) external view returns (uint256);
+        function uri(uint256 id) external view returns (string);
+    }
+}
+
+sol! {
+    #[allow(missing_docs)]
+    #[sol(rpc)]
+    interface ChainlinkAggregator {
+        event AnswerUpdated(int256 indexed current, uint256 indexed roundId, uint256 updatedAt);
+        function decimals() external view returns (uint8);
+        function phaseId() external view returns (uint16);
+        function phaseAggregators(uint16 phaseId) external view returns (address);
+        function latestRoundData() external view returns (uint80 roundId, int256 answer, uint256 startedAt, uint256 updatedAt, uint80 answeredInRound);
     }
 }
 
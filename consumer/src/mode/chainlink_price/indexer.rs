@@ -0,0 +1,237 @@
+use crate::{
+    error::ConsumerError,
+    mode::types::ChainlinkPriceConsumerContext,
+    ChainlinkAggregator::{AnswerUpdated, ChainlinkAggregatorInstance},
+};
+use alloy::{
+    eips::BlockId,
+    primitives::{Address, U256},
+    providers::{Provider, RootProvider},
+    rpc::types::{Filter, Log},
+    sol_types::SolEvent,
+    transports::http::Http,
+};
+use models::{chainlink_price::ChainlinkPrice, traits::SimpleCrud};
+use reqwest::Client;
+use std::collections::HashMap;
+use tracing::{info, warn};
+
+/// The proxy of the ETH/USD price feed on Base
+pub const ETH_USD_FEED_ADDRESS: &str = "0x71041dddad3595F9CEd3DcCFBe3D1F4b0a16Bb70";
+/// The default number of blocks scanned by a single `eth_getLogs` call
+pub const DEFAULT_BLOCK_RANGE: u64 = 2000;
+/// The default interval between two polls of the price feed, in seconds
+pub const DEFAULT_POLL_INTERVAL_SECS: u64 = 60;
+
+/// The proxy contract of a Chainlink price feed
+pub type PriceFeed = ChainlinkAggregatorInstance<Http<Client>, RootProvider<Http<Client>>>;
+
+/// Returns the round id of the feed proxy for a round of the aggregator of a
+/// phase. The proxy keeps the phase in the upper bits, so round ids stay
+/// unique when the aggregator is replaced.
+fn proxy_round_id(phase_id: u16, aggregator_round_id: U256) -> U256 {
+    (U256::from(phase_id) << 64) | aggregator_round_id
+}
+
+impl ChainlinkPriceConsumerContext {
+    /// This function indexes the answers of the price feed. It resumes from
+    /// the last observed block, or the start block, or else the latest round,
+    /// and polls the feed for new answers every poll interval.
+    pub async fn index_prices(&self) -> Result<(), ConsumerError> {
+        let decimals = self.feed.decimals().call().await?._0;
+        let last_observed = ChainlinkPrice::find_last_observed(&self.pg_pool, &self.backend_schema)
+            .await?
+            .and_then(|price| price.block_number);
+        let mut next_block = match (last_observed, self.start_block) {
+            (Some(block_number), _) => block_number as u64 + 1,
+            (None, Some(start_block)) => start_block,
+            (None, None) => self.store_latest_round(decimals).await? + 1,
+        };
+
+        info!("Indexing Chainlink prices from block {}", next_block);
+        let mut ticker = tokio::time::interval(self.poll_interval);
+        loop {
+            ticker.tick().await;
+            match self.index_new_rounds(next_block, decimals).await {
+                Ok(block_number) => next_block = block_number,
+                Err(e) => warn!(
+                    "Failed to index Chainlink prices from block {}: {}",
+                    next_block, e
+                ),
+            }
+        }
+    }
+
+    /// This function stores the latest round of the feed, observed at the
+    /// latest block, and returns the block.
+    async fn store_latest_round(&self, decimals: u8) -> Result<u64, ConsumerError> {
+        let block_number = self
+            .feed
+            .provider()
+            .get_block_number()
+            .await
+            .map_err(|e| ConsumerError::NetworkError(e.to_string()))?;
+        let round = self.feed.latestRoundData().call().await?;
+        ChainlinkPrice::builder()
+            .id(U256::from(round.roundId))
+            .usd(ChainlinkPrice::usd_from_answer(
+                &round.answer.to_string(),
+                decimals,
+            )?)
+            .block_number(block_number as i64)
+            .updated_at(round.updatedAt.saturating_to::<i64>())
+            .build()
+            .upsert(&self.pg_pool, &self.backend_schema)
+            .await?;
+        Ok(block_number)
+    }
+
+    /// This function stores the answers given from `from_block` to the
+    /// latest block, in batches of `block_range` blocks, and returns the
+    /// next block to scan. The answers of a phase are read from its
+    /// aggregator, only for the blocks where the phase was current.
+    async fn index_new_rounds(&self, from_block: u64, decimals: u8) -> Result<u64, ConsumerError> {
+        let provider = self.feed.provider();
+        let latest_block = provider
+            .get_block_number()
+            .await
+            .map_err(|e| ConsumerError::NetworkError(e.to_string()))?;
+        if from_block > latest_block {
+            return Ok(from_block);
+        }
+
+        let mut aggregators = HashMap::new();
+        let mut start_block = from_block;
+        while start_block <= latest_block {
+            let end_block = (start_block + self.block_range - 1).min(latest_block);
+            let mut phase_start = start_block;
+            while phase_start <= end_block {
+                let phase_id = self.phase_at(phase_start).await?;
+                let phase_end = self.phase_end(phase_id, phase_start, end_block).await?;
+                let aggregator = match aggregators.get(&phase_id) {
+                    Some(aggregator) => *aggregator,
+                    None => {
+                        let aggregator = self.feed.phaseAggregators(phase_id).call().await?._0;
+                        aggregators.insert(phase_id, aggregator);
+                        aggregator
+                    }
+                };
+                self.index_phase_rounds(aggregator, phase_id, phase_start, phase_end, decimals)
+                    .await?;
+                phase_start = phase_end + 1;
+            }
+            start_block = end_block + 1;
+        }
+
+        info!("Indexed Chainlink prices up to block {}", latest_block);
+        Ok(start_block)
+    }
+
+    /// This function stores the answers emitted by the aggregator of a phase
+    /// from `from_block` to `to_block`
+    async fn index_phase_rounds(
+        &self,
+        aggregator: Address,
+        phase_id: u16,
+        from_block: u64,
+        to_block: u64,
+        decimals: u8,
+    ) -> Result<(), ConsumerError> {
+        let filter = Filter::new()
+            .address(aggregator)
+            .event_signature(AnswerUpdated::SIGNATURE_HASH)
+            .from_block(from_block)
+            .to_block(to_block);
+        let logs = self
+            .feed
+            .provider()
+            .get_logs(&filter)
+            .await
+            .map_err(|e| ConsumerError::NetworkError(e.to_string()))?;
+        for log in logs {
+            self.store_round(&log, phase_id, decimals).await?;
+        }
+        Ok(())
+    }
+
+    /// This function returns the phase of the feed at a block
+    async fn phase_at(&self, block_number: u64) -> Result<u16, ConsumerError> {
+        Ok(self
+            .feed
+            .phaseId()
+            .block(BlockId::number(block_number))
+            .call()
+            .await?
+            ._0)
+    }
+
+    /// This function returns the last block from `from_block` to `to_block`
+    /// where the phase of the feed is `phase_id`, the phase being current at
+    /// `from_block`. Phases only increase, so the block is found by
+    /// bisection when the phase changes in the range.
+    async fn phase_end(
+        &self,
+        phase_id: u16,
+        from_block: u64,
+        to_block: u64,
+    ) -> Result<u64, ConsumerError> {
+        if self.phase_at(to_block).await? == phase_id {
+            return Ok(to_block);
+        }
+        let (mut low, mut high) = (from_block, to_block.saturating_sub(1));
+        while low < high {
+            let middle = low + (high - low).div_ceil(2);
+            if self.phase_at(middle).await? == phase_id {
+                low = middle;
+            } else {
+                high = middle - 1;
+            }
+        }
+        Ok(low)
+    }
+
+    /// This function stores the answer of an `AnswerUpdated` log
+    async fn store_round(
+        &self,
+        log: &Log,
+        phase_id: u16,
+        decimals: u8,
+    ) -> Result<(), ConsumerError> {
+        let block_number = log
+            .block_number
+            .ok_or_else(|| ConsumerError::LogDecodingError("Missing block number".to_string()))?;
+        let answer = log
+            .log_decode::<AnswerUpdated>()
+            .map_err(|e| ConsumerError::LogDecodingError(e.to_string()))?
+            .inner
+            .data;
+
+        let price = ChainlinkPrice::builder()
+            .id(proxy_round_id(phase_id, answer.roundId))
+            .usd(ChainlinkPrice::usd_from_answer(
+                &answer.current.to_string(),
+                decimals,
+            )?)
+            .block_number(block_number as i64)
+            .updated_at(answer.updatedAt.saturating_to::<i64>())
+            .build()
+            .upsert(&self.pg_pool, &self.backend_schema)
+            .await?;
+        info!("Stored Chainlink price: {:?}", price);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_proxy_round_id() {
+        // Round 1234 of phase 6 of the ETH/USD feed
+        assert_eq!(
+            proxy_round_id(6, U256::from(1234)),
+            U256::from(110680464442257310930u128)
+        );
+    }
+}
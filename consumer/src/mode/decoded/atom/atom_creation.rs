@@ -2,8 +2,10 @@ use crate::{
     error::ConsumerError,
     mode::{
         decoded::{
-            atom::atom_supported_types::get_supported_atom_metadata,
-            utils::{get_or_create_account, short_id, update_account_with_atom_id},
+            atom::atom_supported_types::{get_supported_atom_metadata, AtomMetadataStore},
+            utils::{
+                get_or_create_account, record_share_price, short_id, update_account_with_atom_id,
+            },
         },
         resolver::types::ResolveAtom,
         types::DecodedConsumerContext,
@@ -207,7 +209,7 @@ impl AtomCreated {
         // Handle the account or caip10 type
         let resolved_atom = ResolveAtom { atom: atom.clone() };
         supported_atom_metadata
-            .handle_account_or_caip10_type(&resolved_atom, decoded_consumer_context)
+            .handle_account_or_identifier_type(&resolved_atom, decoded_consumer_context)
             .await?;
 
         // Create the event
@@ -296,6 +298,13 @@ impl AtomCreated {
             &decoded_consumer_context.backend_schema,
         )
         .await?;
+        record_share_price(
+            self.vaultID,
+            current_share_price,
+            event,
+            decoded_consumer_context,
+        )
+        .await?;
 
         Ok((vault, atom))
     }
@@ -1,7 +1,10 @@
 use crate::{
     error::ConsumerError,
     mode::{
-        decoded::utils::{short_id, update_account_with_atom_id},
+        decoded::{
+            atom::caip::{self, AccountId, AssetId, ChainId},
+            utils::update_account_with_atom_id,
+        },
         resolver::{
             atom_resolver::{try_to_parse_json_or_text, try_to_resolve_schema_org_url},
             types::{ResolveAtom, ResolverConsumerMessage},
@@ -10,108 +13,58 @@ use crate::{
     },
 };
 use alloy::primitives::Address;
+use async_trait::async_trait;
 use models::{
     atom::{Atom, AtomResolvingStatus, AtomType},
     atom_value::AtomValue,
     caip10::Caip10,
+    caip19::Caip19,
+    caip2::Caip2,
+    did::Did,
     traits::SimpleCrud,
     types::U256Wrapper,
 };
-use serde::{Deserialize, Serialize};
+use shared_utils::atom_metadata::AtomMetadata;
 use sqlx::PgPool;
 use std::str::FromStr;
 use tracing::info;
-/// Represents the metadata for an atom
-#[derive(Debug, Serialize, Deserialize)]
-pub struct AtomMetadata {
-    pub label: String,
-    pub emoji: String,
-    pub atom_type: String,
-    pub image: Option<String>,
-}
-
-impl AtomMetadata {
-    /// Creates a new atom metadata for an address
-    pub fn address(address: &str, image: Option<String>) -> Self {
-        Self {
-            label: short_id(address),
-            emoji: "⛓️".to_string(),
-            atom_type: "Account".to_string(),
-            image,
-        }
-    }
-
-    /// Creates a new atom metadata for a book
-    pub fn book(name: String) -> Self {
-        Self {
-            label: name,
-            emoji: "📚".to_string(),
-            atom_type: "Book".to_string(),
-            image: None,
-        }
-    }
 
-    /// Creates a new atom metadata for a byte object
-    pub fn byte_object(image: Option<String>) -> Self {
-        Self {
-            label: "byte object".to_string(),
-            emoji: "🔢".to_string(),
-            atom_type: "ByteObject".to_string(),
-            image,
-        }
-    }
+/// The prefix of CAIP-2 chain id atoms
+const CAIP2_PREFIX: &str = "caip2:";
+/// The prefix of CAIP-10 account id atoms
+const CAIP10_PREFIX: &str = "caip10:";
+/// The prefix of CAIP-19 asset id atoms
+const CAIP19_PREFIX: &str = "caip19:";
 
-    /// Creates a new atom metadata for a caip10
-    pub fn caip10(caip10: String) -> Self {
-        Self {
-            label: caip10,
-            emoji: "🔗".to_string(),
-            atom_type: "Caip10".to_string(),
-            image: None,
-        }
-    }
-
-    /// Creates a new caip10
-    pub async fn create_caip10(
-        atom_id: U256Wrapper,
-        caip10: String,
+/// Stores what the metadata of an atom resolves to
+#[async_trait]
+pub trait AtomMetadataStore {
+    /// Stores the atom data in the database based on the atom type
+    async fn handle_account_or_identifier_type(
+        &self,
+        resolved_atom: &ResolveAtom,
         decoded_consumer_context: &DecodedConsumerContext,
-    ) -> Result<Caip10, ConsumerError> {
-        let caip10_parts = caip10.split(':').collect::<Vec<&str>>();
-        if caip10_parts.len() != 4 {
-            return Err(ConsumerError::InvalidCaip10);
-        }
-
-        let namespace = caip10_parts[1];
-        let chain_id = caip10_parts[2].parse::<i32>()?;
-        let account_address = caip10_parts[3];
+    ) -> Result<(), ConsumerError>;
 
-        Caip10::builder()
-            .id(atom_id)
-            .namespace(namespace)
-            .chain_id(chain_id)
-            .account_address(account_address)
-            .build()
-            .upsert(
-                &decoded_consumer_context.pg_pool,
-                &decoded_consumer_context.backend_schema,
-            )
-            .await
-            .map_err(ConsumerError::ModelError)
-    }
+    /// Creates an account and an atom value
+    async fn update_account_and_atom_value(
+        &self,
+        resolved_atom: &ResolveAtom,
+        decoded_consumer_context: &DecodedConsumerContext,
+    ) -> Result<(), ConsumerError>;
 
-    /// Creates a new atom metadata for a follow action
-    pub fn follow_action(image: Option<String>) -> Self {
-        Self {
-            label: "follow".to_string(),
-            emoji: "🔔".to_string(),
-            atom_type: "FollowAction".to_string(),
-            image,
-        }
-    }
+    /// Updates the atom metadata
+    async fn update_atom_metadata(
+        &self,
+        atom: &mut Atom,
+        pg_pool: &PgPool,
+        backend_schema: &str,
+    ) -> Result<AtomMetadata, ConsumerError>;
+}
 
-    /// Stores the atom data in the database based on the atom type
-    pub async fn handle_account_or_caip10_type(
+#[async_trait]
+impl AtomMetadataStore for AtomMetadata {
+    async fn handle_account_or_identifier_type(
         &self,
         resolved_atom: &ResolveAtom,
         decoded_consumer_context: &DecodedConsumerContext,
@@ -130,7 +83,46 @@ impl AtomMetadata {
                     "Creating caip10 for: {}",
                     resolved_atom.atom.data.clone().unwrap()
                 );
-                Self::create_caip10(
+                create_caip10(
+                    resolved_atom.atom.id.clone(),
+                    resolved_atom.atom.data.clone().unwrap(),
+                    decoded_consumer_context,
+                )
+                .await?;
+                Ok(())
+            }
+            AtomType::Caip19 => {
+                info!(
+                    "Creating caip19 for: {}",
+                    resolved_atom.atom.data.clone().unwrap()
+                );
+                create_caip19(
+                    resolved_atom.atom.id.clone(),
+                    resolved_atom.atom.data.clone().unwrap(),
+                    decoded_consumer_context,
+                )
+                .await?;
+                Ok(())
+            }
+            AtomType::Caip2 => {
+                info!(
+                    "Creating caip2 for: {}",
+                    resolved_atom.atom.data.clone().unwrap()
+                );
+                create_caip2(
+                    resolved_atom.atom.id.clone(),
+                    resolved_atom.atom.data.clone().unwrap(),
+                    decoded_consumer_context,
+                )
+                .await?;
+                Ok(())
+            }
+            AtomType::Did => {
+                info!(
+                    "Creating did for: {}",
+                    resolved_atom.atom.data.clone().unwrap()
+                );
+                create_did(
                     resolved_atom.atom.id.clone(),
                     resolved_atom.atom.data.clone().unwrap(),
                     decoded_consumer_context,
@@ -148,122 +140,7 @@ impl AtomMetadata {
         }
     }
 
-    /// Creates a new atom metadata for a json object
-    pub fn json_object(image: Option<String>) -> Self {
-        Self {
-            label: "json object".to_string(),
-            emoji: "📦".to_string(),
-            atom_type: "JsonObject".to_string(),
-            image,
-        }
-    }
-
-    /// Creates a new atom metadata for a keywords predicate
-    pub fn keywords_predicate(image: Option<String>) -> Self {
-        Self {
-            label: "has tag".to_string(),
-            emoji: "🏷️".to_string(),
-            atom_type: "Keywords".to_string(),
-            image,
-        }
-    }
-
-    /// Creates a new atom metadata for a like action
-    pub fn like_action(image: Option<String>) -> Self {
-        Self {
-            label: "like".to_string(),
-            emoji: "👍".to_string(),
-            atom_type: "LikeAction".to_string(),
-            image,
-        }
-    }
-
-    /// Creates a new atom metadata for an organization
-    pub fn organization(name: String, image: Option<String>) -> Self {
-        Self {
-            label: name,
-            emoji: "🏢".to_string(),
-            atom_type: "Organization".to_string(),
-            image,
-        }
-    }
-
-    /// Creates a new atom metadata for an organization predicate
-    pub fn organization_predicate(image: Option<String>) -> Self {
-        Self {
-            label: "is organization".to_string(),
-            emoji: "🏢".to_string(),
-            atom_type: "OrganizationPredicate".to_string(),
-            image,
-        }
-    }
-
-    /// Creates a new atom metadata for a person
-    pub fn person(name: String, image: Option<String>) -> Self {
-        Self {
-            label: name,
-            emoji: "👤".to_string(),
-            atom_type: "Person".to_string(),
-            image,
-        }
-    }
-
-    /// Creates a new atom metadata for a person predicate
-    pub fn person_predicate(image: Option<String>) -> Self {
-        Self {
-            label: "is person".to_string(),
-            emoji: "👤".to_string(),
-            atom_type: "PersonPredicate".to_string(),
-            image,
-        }
-    }
-
-    /// Creates a new atom metadata for a text object
-    pub fn text_object(name: Option<String>) -> Self {
-        Self {
-            label: name
-                .unwrap_or("text object".to_string())
-                .chars()
-                .take(256)
-                .collect(),
-            emoji: "📝".to_string(),
-            atom_type: "TextObject".to_string(),
-            image: None,
-        }
-    }
-
-    /// Creates a new atom metadata for a thing
-    pub fn thing(name: String, image: Option<String>) -> Self {
-        Self {
-            label: name,
-            emoji: "🧩".to_string(),
-            atom_type: "Thing".to_string(),
-            image,
-        }
-    }
-
-    /// Creates a new atom metadata for a thing predicate
-    pub fn thing_predicate(image: Option<String>) -> Self {
-        Self {
-            label: "is thing".to_string(),
-            emoji: "🧩".to_string(),
-            atom_type: "ThingPredicate".to_string(),
-            image,
-        }
-    }
-
-    /// Returns an unknown atom metadata
-    pub fn unknown() -> Self {
-        Self {
-            label: "Unknown".to_string(),
-            emoji: "❓".to_string(),
-            atom_type: "Unknown".to_string(),
-            image: None,
-        }
-    }
-
-    /// Creates an account and an atom value
-    pub async fn update_account_and_atom_value(
+    async fn update_account_and_atom_value(
         &self,
         resolved_atom: &ResolveAtom,
         decoded_consumer_context: &DecodedConsumerContext,
@@ -310,8 +187,7 @@ impl AtomMetadata {
         Ok(())
     }
 
-    /// Updates the atom metadata
-    pub async fn update_atom_metadata(
+    async fn update_atom_metadata(
         &self,
         atom: &mut Atom,
         pg_pool: &PgPool,
@@ -331,6 +207,154 @@ impl AtomMetadata {
     }
 }
 
+/// Creates a new caip10
+pub async fn create_caip10(
+    atom_id: U256Wrapper,
+    caip10: String,
+    decoded_consumer_context: &DecodedConsumerContext,
+) -> Result<Caip10, ConsumerError> {
+    let account_id = caip10
+        .strip_prefix(CAIP10_PREFIX)
+        .ok_or_else(|| ConsumerError::InvalidCaip10(caip10.clone()))?
+        .parse::<AccountId>()?;
+
+    // Only `eip155` chains have a numeric chain id
+    let chain_id = (account_id.chain_id.namespace == "eip155")
+        .then(|| account_id.chain_id.reference.parse::<i32>().ok())
+        .flatten();
+
+    let caip10 = Caip10::builder()
+        .id(atom_id.clone())
+        .namespace(account_id.chain_id.namespace)
+        .chain_id(chain_id)
+        .chain_reference(account_id.chain_id.reference)
+        .account_address(account_id.address)
+        .build()
+        .upsert(
+            &decoded_consumer_context.pg_pool,
+            &decoded_consumer_context.backend_schema,
+        )
+        .await?;
+
+    AtomValue::builder()
+        .id(atom_id)
+        .caip10_id(caip10.id.clone())
+        .build()
+        .upsert(
+            &decoded_consumer_context.pg_pool,
+            &decoded_consumer_context.backend_schema,
+        )
+        .await?;
+
+    Ok(caip10)
+}
+
+/// Creates a new caip19
+pub async fn create_caip19(
+    atom_id: U256Wrapper,
+    caip19: String,
+    decoded_consumer_context: &DecodedConsumerContext,
+) -> Result<Caip19, ConsumerError> {
+    let asset_id = caip19
+        .strip_prefix(CAIP19_PREFIX)
+        .ok_or_else(|| ConsumerError::InvalidCaip19(caip19.clone()))?
+        .parse::<AssetId>()?;
+
+    let caip19 = Caip19::builder()
+        .id(atom_id.clone())
+        .namespace(asset_id.chain_id.namespace)
+        .chain_reference(asset_id.chain_id.reference)
+        .asset_namespace(asset_id.asset_namespace)
+        .asset_reference(asset_id.asset_reference)
+        .token_id(asset_id.token_id)
+        .build()
+        .upsert(
+            &decoded_consumer_context.pg_pool,
+            &decoded_consumer_context.backend_schema,
+        )
+        .await?;
+
+    AtomValue::builder()
+        .id(atom_id)
+        .caip19_id(caip19.id.clone())
+        .build()
+        .upsert(
+            &decoded_consumer_context.pg_pool,
+            &decoded_consumer_context.backend_schema,
+        )
+        .await?;
+
+    Ok(caip19)
+}
+
+/// Creates a new caip2
+pub async fn create_caip2(
+    atom_id: U256Wrapper,
+    caip2: String,
+    decoded_consumer_context: &DecodedConsumerContext,
+) -> Result<Caip2, ConsumerError> {
+    let chain_id = caip2
+        .strip_prefix(CAIP2_PREFIX)
+        .ok_or_else(|| ConsumerError::InvalidCaip2(caip2.clone()))?
+        .parse::<ChainId>()?;
+
+    let caip2 = Caip2::builder()
+        .id(atom_id.clone())
+        .namespace(chain_id.namespace)
+        .reference(chain_id.reference)
+        .build()
+        .upsert(
+            &decoded_consumer_context.pg_pool,
+            &decoded_consumer_context.backend_schema,
+        )
+        .await?;
+
+    AtomValue::builder()
+        .id(atom_id)
+        .caip2_id(caip2.id.clone())
+        .build()
+        .upsert(
+            &decoded_consumer_context.pg_pool,
+            &decoded_consumer_context.backend_schema,
+        )
+        .await?;
+
+    Ok(caip2)
+}
+
+/// Creates a new did
+pub async fn create_did(
+    atom_id: U256Wrapper,
+    did: String,
+    decoded_consumer_context: &DecodedConsumerContext,
+) -> Result<Did, ConsumerError> {
+    let parsed = did.parse::<caip::Did>()?;
+
+    let did = Did::builder()
+        .id(atom_id.clone())
+        .did(parsed.to_string())
+        .method(parsed.method)
+        .identifier(parsed.identifier)
+        .build()
+        .upsert(
+            &decoded_consumer_context.pg_pool,
+            &decoded_consumer_context.backend_schema,
+        )
+        .await?;
+
+    AtomValue::builder()
+        .id(atom_id)
+        .did_id(did.id.clone())
+        .build()
+        .upsert(
+            &decoded_consumer_context.pg_pool,
+            &decoded_consumer_context.backend_schema,
+        )
+        .await?;
+
+    Ok(did)
+}
+
 /// Validates if a string is a valid Ethereum address
 ///
 /// # Arguments
@@ -355,27 +379,40 @@ pub fn is_valid_address(address: &str) -> Result<bool, ConsumerError> {
 /// * `bool` - True if valid CAIP10, false otherwise
 ///
 /// A `caip10` looks like: `caip10:eip155:8453:0x71041dddad3595F9CEd3DcCFBe3D1F4b0a16Bb70`
-/// where the first part is the `caip10` prefix, the second part is the `eip155` namespace,
-/// the third part is the `chain_id` and the last part is the `address`.
+/// where the first part is the `caip10` prefix, followed by the CAIP-2 chain id
+/// (`namespace:reference`) and an account address valid for that namespace.
 pub fn is_valid_caip10(caip10: &str) -> Result<bool, ConsumerError> {
-    // Check if the string starts with "caip10:"
-    if !caip10.starts_with("caip10:") {
-        return Ok(false);
-    }
+    Ok(caip10
+        .strip_prefix(CAIP10_PREFIX)
+        .is_some_and(|account_id| account_id.parse::<AccountId>().is_ok()))
+}
 
-    // Check if the string has at least 4 parts
-    let parts = caip10.split(':').collect::<Vec<&str>>();
-    if parts.len() < 4 {
-        return Ok(false);
-    }
+/// Validates if a string is a valid CAIP19
+///
+/// A `caip19` looks like:
+/// `caip19:eip155:1/erc721:0x06012c8cf97BEaD5deAe237070F9587f8E7A266d/771769`
+/// where the `caip19` prefix is followed by the CAIP-2 chain id, the asset
+/// namespace and reference, and an optional token id.
+pub fn is_valid_caip19(caip19: &str) -> Result<bool, ConsumerError> {
+    Ok(caip19
+        .strip_prefix(CAIP19_PREFIX)
+        .is_some_and(|asset_id| asset_id.parse::<AssetId>().is_ok()))
+}
 
-    // Check if the last part is a valid Ethereum address
-    let address = parts.last().unwrap();
-    if !is_valid_address(address)? {
-        return Ok(false);
-    }
+/// Validates if a string is a valid CAIP2
+///
+/// A `caip2` looks like: `caip2:eip155:8453`, the `caip2` prefix followed by
+/// the namespace and the chain reference.
+pub fn is_valid_caip2(caip2: &str) -> Result<bool, ConsumerError> {
+    Ok(caip2
+        .strip_prefix(CAIP2_PREFIX)
+        .is_some_and(|chain_id| chain_id.parse::<ChainId>().is_ok()))
+}
 
-    Ok(true)
+/// Validates if a string is a DID of one of the supported methods
+/// (`did:pkh`, `did:web` or `did:key`)
+pub fn is_valid_did(did: &str) -> Result<bool, ConsumerError> {
+    Ok(did.parse::<caip::Did>().is_ok())
 }
 
 /// Gets the metadata for a supported atom type based on the atom data.
@@ -387,9 +424,9 @@ pub fn is_valid_caip10(caip10: &str) -> Result<bool, ConsumerError> {
 /// 2. The atom data is an address. This is also one of the "happy paths",
 ///    since we can directly map it to an account and dont need to resolve
 ///    anything.
-/// 3. The atom data is a CAIP10. This is also one of the "happy paths",
-///    since we can directly map it to an account and dont need to resolve
-///    anything.
+/// 3. The atom data is a CAIP10, CAIP2 or CAIP19 id, or a DID. These are
+///    also "happy paths", since we only need to validate them for their
+///    namespace or method and dont need to resolve anything.
 /// 4. The atom data is an IPFS URI. We need to fetch the data from IPFS
 ///    and then resolve it. Keep in mind that if we are parsing an IPFS URI,
 ///    we need to fetch the data from IPFS and then parse it as JSON.
@@ -422,6 +459,18 @@ pub async fn get_supported_atom_metadata(
         // As we dont need to resolve anything, we can mark the atom as resolved
         atom.resolving_status = AtomResolvingStatus::Resolved;
         Ok(AtomMetadata::caip10(decoded_atom_data.to_string()))
+    } else if is_valid_caip2(decoded_atom_data)? {
+        info!("Atom data is a CAIP2, returning chain metadata...");
+        atom.resolving_status = AtomResolvingStatus::Resolved;
+        Ok(AtomMetadata::caip2(decoded_atom_data.to_string()))
+    } else if is_valid_caip19(decoded_atom_data)? {
+        info!("Atom data is a CAIP19, returning asset metadata...");
+        atom.resolving_status = AtomResolvingStatus::Resolved;
+        Ok(AtomMetadata::caip19(decoded_atom_data.to_string()))
+    } else if is_valid_did(decoded_atom_data)? {
+        info!("Atom data is a DID, returning did metadata...");
+        atom.resolving_status = AtomResolvingStatus::Resolved;
+        Ok(AtomMetadata::did(decoded_atom_data.to_string()))
     } else {
         info!("Atom data is not an address, verifying if it's an IPFS URI...");
         // 4. Now we need to enqueue the message to be processed by the resolver
@@ -473,6 +522,38 @@ mod tests {
         assert!(!is_valid_caip10("caip10:eip155:1:not_an_address")?);
         assert!(!is_valid_caip10("")?);
 
+        // Other namespaces are validated for their own address format
+        assert!(is_valid_caip10(
+            "caip10:solana:5eykt4UsFv8P8NJdTREpY1vzqKqZKvdp:7S3P4HxJpyyigGzodYwHtCxZyUQe9JiBMHyRWXArAaKv"
+        )?);
+        assert!(is_valid_caip10(
+            "caip10:cosmos:cosmoshub-4:cosmos1t2uflqwqe0fsj0shcfkrvpukewcw40yjj6hdc0"
+        )?);
+        // Bad EIP-55 checksum
+        assert!(!is_valid_caip10(
+            "caip10:eip155:8453:0x71041dddad3595F9CEd3DcCFBe3D1F4b0a16bb70"
+        )?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_is_valid_caip2_caip19_and_did() -> Result<(), ConsumerError> {
+        assert!(is_valid_caip2("caip2:eip155:8453")?);
+        assert!(!is_valid_caip2("eip155:8453")?);
+        assert!(!is_valid_caip2("caip2:eip155:base")?);
+
+        assert!(is_valid_caip19(
+            "caip19:eip155:1/erc20:0x6B175474E89094C44Da98b954EedeAC495271d0F"
+        )?);
+        assert!(!is_valid_caip19("caip19:eip155:1/erc20")?);
+
+        assert!(is_valid_did(
+            "did:pkh:eip155:1:0x71041dddad3595F9CEd3DcCFBe3D1F4b0a16Bb70"
+        )?);
+        assert!(is_valid_did("did:web:example.com")?);
+        assert!(!is_valid_did("did:example:123")?);
+
         Ok(())
     }
 }
@@ -0,0 +1,439 @@
+use crate::error::ConsumerError;
+use alloy::primitives::Address;
+use std::{fmt, str::FromStr};
+
+/// The characters of base58, as used by Solana and Bitcoin
+const BASE58_ALPHABET: &str = "123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+/// The characters of the data part of bech32 addresses
+const BECH32_ALPHABET: &str = "qpzry9x8gf2tvdw0s3jn54khce6mua7l";
+/// The DID methods we resolve
+const SUPPORTED_DID_METHODS: [&str; 3] = ["key", "pkh", "web"];
+
+/// A CAIP-2 chain id, e.g. `eip155:1` or
+/// `solana:5eykt4UsFv8P8NJdTREpY1vzqKqZKvdp`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChainId {
+    pub namespace: String,
+    pub reference: String,
+}
+
+impl FromStr for ChainId {
+    type Err = ConsumerError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = |reason: &str| ConsumerError::InvalidCaip2(format!("{}: {}", s, reason));
+        let (namespace, reference) = s
+            .split_once(':')
+            .ok_or_else(|| invalid("expected <namespace>:<reference>"))?;
+
+        if !matches_charset(namespace, 3, 8, |c| {
+            c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-'
+        }) {
+            return Err(invalid("the namespace must be 3 to 8 of [-a-z0-9]"));
+        }
+        if !matches_charset(reference, 1, 32, |c| {
+            c.is_ascii_alphanumeric() || c == '-' || c == '_'
+        }) {
+            return Err(invalid("the reference must be 1 to 32 of [-_a-zA-Z0-9]"));
+        }
+
+        match namespace {
+            "eip155" if reference.parse::<u64>().is_err() || reference.starts_with('0') => {
+                return Err(invalid("eip155 references are decimal chain ids"));
+            }
+            "solana" if reference.len() != 32 || !is_base58(reference) => {
+                return Err(invalid(
+                    "solana references are the first 32 characters of the base58 genesis hash",
+                ));
+            }
+            "bip122"
+                if reference.len() != 32
+                    || !reference
+                        .chars()
+                        .all(|c| c.is_ascii_digit() || ('a'..='f').contains(&c)) =>
+            {
+                return Err(invalid(
+                    "bip122 references are the first 32 characters of the hex genesis hash",
+                ));
+            }
+            _ => {}
+        }
+
+        Ok(Self {
+            namespace: namespace.to_string(),
+            reference: reference.to_string(),
+        })
+    }
+}
+
+impl fmt::Display for ChainId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.namespace, self.reference)
+    }
+}
+
+/// A CAIP-10 account id, e.g. `eip155:1:0xab16a96D359eC26a11e2C2b3d8f8B8942d5Bfcdb`
+/// or `cosmos:cosmoshub-4:cosmos1t2uflqwqe0fsj0shcfkrvpukewcw40yjj6hdc0`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AccountId {
+    pub chain_id: ChainId,
+    pub address: String,
+}
+
+impl FromStr for AccountId {
+    type Err = ConsumerError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = |reason: &str| ConsumerError::InvalidCaip10(format!("{}: {}", s, reason));
+        let (chain_id, address) = s
+            .rsplit_once(':')
+            .ok_or_else(|| invalid("expected <namespace>:<reference>:<address>"))?;
+        let chain_id = chain_id
+            .parse::<ChainId>()
+            .map_err(|e| invalid(&e.to_string()))?;
+
+        if !matches_charset(address, 1, 128, is_caip_identifier_char) {
+            return Err(invalid("the address must be 1 to 128 of [-.%a-zA-Z0-9]"));
+        }
+        match chain_id.namespace.as_str() {
+            "eip155" => validate_evm_address(address).map_err(invalid)?,
+            "solana" if !(32..=44).contains(&address.len()) || !is_base58(address) => {
+                return Err(invalid("solana addresses are base58 public keys"));
+            }
+            "cosmos" if !is_bech32(address) => {
+                return Err(invalid("cosmos addresses are bech32 encoded"));
+            }
+            "bip122"
+                if !is_bech32(address)
+                    && (!(25..=35).contains(&address.len()) || !is_base58(address)) =>
+            {
+                return Err(invalid("bip122 addresses are base58 or bech32 encoded"));
+            }
+            _ => {}
+        }
+
+        Ok(Self {
+            chain_id,
+            address: address.to_string(),
+        })
+    }
+}
+
+impl fmt::Display for AccountId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.chain_id, self.address)
+    }
+}
+
+/// A CAIP-19 asset id, e.g. `eip155:1/erc20:0x6B175474E89094C44Da98b954EedeAC495271d0F`
+/// or `eip155:1/erc721:0x06012c8cf97BEaD5deAe237070F9587f8E7A266d/771769`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AssetId {
+    pub chain_id: ChainId,
+    pub asset_namespace: String,
+    pub asset_reference: String,
+    pub token_id: Option<String>,
+}
+
+impl FromStr for AssetId {
+    type Err = ConsumerError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = |reason: &str| ConsumerError::InvalidCaip19(format!("{}: {}", s, reason));
+        let mut parts = s.splitn(3, '/');
+        let chain_id = parts
+            .next()
+            .unwrap_or_default()
+            .parse::<ChainId>()
+            .map_err(|e| invalid(&e.to_string()))?;
+        let (asset_namespace, asset_reference) = parts
+            .next()
+            .and_then(|asset| asset.split_once(':'))
+            .ok_or_else(|| invalid("expected <chain id>/<asset namespace>:<asset reference>"))?;
+        let token_id = parts.next();
+
+        if !matches_charset(asset_namespace, 3, 8, |c| {
+            c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-'
+        }) {
+            return Err(invalid("the asset namespace must be 3 to 8 of [-a-z0-9]"));
+        }
+        if !matches_charset(asset_reference, 1, 128, is_caip_identifier_char) {
+            return Err(invalid(
+                "the asset reference must be 1 to 128 of [-.%a-zA-Z0-9]",
+            ));
+        }
+        if token_id.is_some_and(|id| !matches_charset(id, 1, 78, is_caip_identifier_char)) {
+            return Err(invalid("the token id must be 1 to 78 of [-.%a-zA-Z0-9]"));
+        }
+
+        match (chain_id.namespace.as_str(), asset_namespace) {
+            ("eip155", "erc20") => {
+                validate_evm_address(asset_reference).map_err(invalid)?;
+                if token_id.is_some() {
+                    return Err(invalid("erc20 assets have no token id"));
+                }
+            }
+            ("eip155", "erc721" | "erc1155") => {
+                validate_evm_address(asset_reference).map_err(invalid)?;
+                if token_id.is_some_and(|id| !id.chars().all(|c| c.is_ascii_digit())) {
+                    return Err(invalid("token ids are decimal integers"));
+                }
+            }
+            (_, "slip44") if !asset_reference.chars().all(|c| c.is_ascii_digit()) => {
+                return Err(invalid("slip44 references are decimal coin types"));
+            }
+            _ => {}
+        }
+
+        Ok(Self {
+            chain_id,
+            asset_namespace: asset_namespace.to_string(),
+            asset_reference: asset_reference.to_string(),
+            token_id: token_id.map(str::to_string),
+        })
+    }
+}
+
+/// A DID of one of the supported methods: `did:pkh` with a CAIP-10 account
+/// id, `did:web` with a domain name and `did:key` with a base58btc multibase
+/// public key
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Did {
+    pub method: String,
+    pub identifier: String,
+}
+
+impl FromStr for Did {
+    type Err = ConsumerError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = |reason: &str| ConsumerError::InvalidDid(format!("{}: {}", s, reason));
+        let (method, identifier) = s
+            .strip_prefix("did:")
+            .and_then(|did| did.split_once(':'))
+            .ok_or_else(|| invalid("expected did:<method>:<identifier>"))?;
+
+        match method {
+            "pkh" => {
+                identifier
+                    .parse::<AccountId>()
+                    .map_err(|e| invalid(&e.to_string()))?;
+            }
+            "web" => validate_did_web(identifier).map_err(invalid)?,
+            "key" => {
+                let key = identifier
+                    .strip_prefix('z')
+                    .ok_or_else(|| invalid("did:key identifiers are base58btc multibase keys"))?;
+                if key.is_empty() || !is_base58(key) {
+                    return Err(invalid("did:key identifiers are base58btc multibase keys"));
+                }
+            }
+            _ => {
+                return Err(invalid(&format!(
+                    "unsupported method, expected one of {}",
+                    SUPPORTED_DID_METHODS.join(", ")
+                )));
+            }
+        }
+
+        Ok(Self {
+            method: method.to_string(),
+            identifier: identifier.to_string(),
+        })
+    }
+}
+
+impl fmt::Display for Did {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "did:{}:{}", self.method, self.identifier)
+    }
+}
+
+/// Checks an EVM address, and its EIP-55 checksum if it's mixed-case
+fn validate_evm_address(address: &str) -> Result<(), &'static str> {
+    let hex = address
+        .strip_prefix("0x")
+        .ok_or("EVM addresses start with 0x")?;
+    if hex.len() != 40 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err("EVM addresses are 20 hex encoded bytes");
+    }
+    let mixed_case =
+        hex.chars().any(|c| c.is_ascii_lowercase()) && hex.chars().any(|c| c.is_ascii_uppercase());
+    if mixed_case && Address::parse_checksummed(address, None).is_err() {
+        return Err("invalid EIP-55 checksum");
+    }
+    Ok(())
+}
+
+/// Checks a `did:web` identifier: a domain name, with an optional
+/// percent-encoded port, followed by optional `:` separated path segments
+fn validate_did_web(identifier: &str) -> Result<(), &'static str> {
+    let mut segments = identifier.split(':');
+    let host = segments.next().unwrap_or_default();
+    let domain = host.split("%3A").next().unwrap_or_default();
+    let valid_domain = domain.split('.').count() > 1
+        && domain.split('.').all(|label| {
+            !label.is_empty()
+                && !label.starts_with('-')
+                && !label.ends_with('-')
+                && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+        });
+    if !valid_domain && domain != "localhost" {
+        return Err("did:web identifiers start with a domain name");
+    }
+    if !segments.all(|segment| {
+        !segment.is_empty()
+            && segment
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || "-._~%".contains(c))
+    }) {
+        return Err("did:web path segments must be URL safe");
+    }
+    Ok(())
+}
+
+/// Returns whether a string has between `min` and `max` characters, all
+/// accepted by `accept`
+fn matches_charset(s: &str, min: usize, max: usize, accept: impl Fn(char) -> bool) -> bool {
+    (min..=max).contains(&s.len()) && s.chars().all(accept)
+}
+
+/// The characters CAIP allows in addresses, asset references and token ids
+fn is_caip_identifier_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '-' || c == '.' || c == '%'
+}
+
+fn is_base58(s: &str) -> bool {
+    s.chars().all(|c| BASE58_ALPHABET.contains(c))
+}
+
+/// Returns whether a string looks like a bech32 address: a human readable
+/// part, the `1` separator, and data in the bech32 alphabet. The checksum
+/// isn't verified.
+fn is_bech32(s: &str) -> bool {
+    match s.rsplit_once('1') {
+        Some((hrp, data)) => {
+            (8..=90).contains(&s.len())
+                && !hrp.is_empty()
+                && hrp
+                    .chars()
+                    .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit())
+                && data.len() >= 6
+                && data.chars().all(|c| BECH32_ALPHABET.contains(c))
+        }
+        None => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_chain_id() {
+        let chain_id = "eip155:8453".parse::<ChainId>().unwrap();
+        assert_eq!(chain_id.namespace, "eip155");
+        assert_eq!(chain_id.reference, "8453");
+        assert!("solana:5eykt4UsFv8P8NJdTREpY1vzqKqZKvdp"
+            .parse::<ChainId>()
+            .is_ok());
+        assert!("cosmos:cosmoshub-4".parse::<ChainId>().is_ok());
+        assert!("bip122:000000000019d6689c085ae165831e93"
+            .parse::<ChainId>()
+            .is_ok());
+
+        assert!("eip155".parse::<ChainId>().is_err());
+        assert!("eip155:base".parse::<ChainId>().is_err());
+        assert!("EIP155:1".parse::<ChainId>().is_err());
+        assert!("solana:not-base58".parse::<ChainId>().is_err());
+    }
+
+    #[test]
+    fn test_parse_account_id() {
+        let account = "eip155:1:0xab16a96D359eC26a11e2C2b3d8f8B8942d5Bfcdb"
+            .parse::<AccountId>()
+            .unwrap();
+        assert_eq!(account.chain_id.reference, "1");
+        assert_eq!(
+            account.address,
+            "0xab16a96D359eC26a11e2C2b3d8f8B8942d5Bfcdb"
+        );
+        // Single-case addresses have no checksum
+        assert!("eip155:1:0xab16a96d359ec26a11e2c2b3d8f8b8942d5bfcdb"
+            .parse::<AccountId>()
+            .is_ok());
+        assert!(
+            "solana:5eykt4UsFv8P8NJdTREpY1vzqKqZKvdp:7S3P4HxJpyyigGzodYwHtCxZyUQe9JiBMHyRWXArAaKv"
+                .parse::<AccountId>()
+                .is_ok()
+        );
+        assert!(
+            "cosmos:cosmoshub-4:cosmos1t2uflqwqe0fsj0shcfkrvpukewcw40yjj6hdc0"
+                .parse::<AccountId>()
+                .is_ok()
+        );
+
+        // Bad EIP-55 checksum
+        assert!("eip155:1:0xAb16a96D359eC26a11e2C2b3d8f8B8942d5Bfcdb"
+            .parse::<AccountId>()
+            .is_err());
+        assert!("eip155:1:0x123".parse::<AccountId>().is_err());
+        assert!("cosmos:cosmoshub-4:not_bech32"
+            .parse::<AccountId>()
+            .is_err());
+    }
+
+    #[test]
+    fn test_parse_asset_id() {
+        let asset = "eip155:1/erc721:0x06012c8cf97BEaD5deAe237070F9587f8E7A266d/771769"
+            .parse::<AssetId>()
+            .unwrap();
+        assert_eq!(asset.asset_namespace, "erc721");
+        assert_eq!(asset.token_id.as_deref(), Some("771769"));
+        assert!("eip155:1/erc20:0x6B175474E89094C44Da98b954EedeAC495271d0F"
+            .parse::<AssetId>()
+            .is_ok());
+        assert!("bip122:000000000019d6689c085ae165831e93/slip44:0"
+            .parse::<AssetId>()
+            .is_ok());
+
+        assert!(
+            "eip155:1/erc20:0x6B175474E89094C44Da98b954EedeAC495271d0F/1"
+                .parse::<AssetId>()
+                .is_err()
+        );
+        assert!(
+            "eip155:1/erc1155:0x06012c8cf97BEaD5deAe237070F9587f8E7A266d/abc"
+                .parse::<AssetId>()
+                .is_err()
+        );
+        assert!("eip155:1".parse::<AssetId>().is_err());
+    }
+
+    #[test]
+    fn test_parse_did() {
+        let did = "did:pkh:eip155:1:0xab16a96D359eC26a11e2C2b3d8f8B8942d5Bfcdb"
+            .parse::<Did>()
+            .unwrap();
+        assert_eq!(did.method, "pkh");
+        assert_eq!(
+            did.to_string(),
+            "did:pkh:eip155:1:0xab16a96D359eC26a11e2C2b3d8f8B8942d5Bfcdb"
+        );
+        assert!("did:web:example.com".parse::<Did>().is_ok());
+        assert!("did:web:localhost%3A8443:users:alice"
+            .parse::<Did>()
+            .is_ok());
+        assert!("did:key:z6MkhaXgBZDvotDkL5257faiztiGiC2QtKLGpbnnEGta2doK"
+            .parse::<Did>()
+            .is_ok());
+
+        assert!("did:web:not a domain".parse::<Did>().is_err());
+        assert!("did:key:6MkhaXgBZDvotDkL5257faiztiGiC2QtKLGpbnnEGta2doK"
+            .parse::<Did>()
+            .is_err());
+        assert!("did:ethr:0xab16a96D359eC26a11e2C2b3d8f8B8942d5Bfcdb"
+            .parse::<Did>()
+            .is_err());
+    }
+}
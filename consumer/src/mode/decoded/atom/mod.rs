@@ -1,2 +1,3 @@
 pub mod atom_creation;
 pub mod atom_supported_types;
+pub mod caip;
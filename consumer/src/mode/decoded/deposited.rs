@@ -1,4 +1,7 @@
-use super::utils::get_absolute_triple_id;
+use super::utils::{
+    atom_deposit_fraction_amount, atom_deposit_fraction_shares, get_absolute_triple_id, record_fee,
+    record_share_price, update_relevance_score, update_triple_consensus,
+};
 use crate::{
     mode::{decoded::utils::get_or_create_account, types::DecodedConsumerContext},
     schemas::types::DecodedMessage,
@@ -11,7 +14,9 @@ use models::{
     claim::Claim,
     deposit::Deposit,
     event::{Event, EventType},
+    fee_breakdown::FeeType,
     position::Position,
+    position_cost_basis::PositionCostBasis,
     predicate_object::PredicateObject,
     signal::Signal,
     traits::SimpleCrud,
@@ -123,6 +128,109 @@ impl Deposited {
             .map_err(ConsumerError::ModelError)
     }
 
+    /// This function records the fees paid by the deposit: the entry fee
+    /// kept by the vault and, for triple deposits, the atom deposit fraction
+    /// split equally between the atom vaults of the triple. The fraction of a
+    /// triple creation is a flat amount instead, and its deposit is emitted
+    /// before the triple is created, so it isn't recorded.
+    async fn record_fees(
+        &self,
+        event: &DecodedMessage,
+        decoded_consumer_context: &DecodedConsumerContext,
+    ) -> Result<(), ConsumerError> {
+        let payer_id = self.sender.to_string().to_lowercase();
+        record_fee(
+            FeeType::Entry,
+            Some(self.vaultId),
+            &payer_id,
+            self.entryFee,
+            event,
+            decoded_consumer_context,
+        )
+        .await?;
+
+        if !self.isTriple {
+            return Ok(());
+        }
+        let Some(triple) = Triple::find_by_id(
+            U256Wrapper::from(get_absolute_triple_id(self.vaultId)),
+            &decoded_consumer_context.pg_pool,
+            &decoded_consumer_context.backend_schema,
+        )
+        .await?
+        else {
+            return Ok(());
+        };
+        let (fraction, denominator) = decoded_consumer_context
+            .fetch_atom_deposit_fraction_config(event.block_number)
+            .await?;
+        let amount = atom_deposit_fraction_amount(
+            self.senderAssetsAfterTotalFees + self.entryFee,
+            fraction,
+            denominator,
+        );
+        for (atom_id, share) in atom_deposit_fraction_shares(
+            [
+                triple.subject_id.0,
+                triple.predicate_id.0,
+                triple.object_id.0,
+            ],
+            amount,
+        ) {
+            record_fee(
+                FeeType::AtomDepositFraction,
+                Some(atom_id),
+                &payer_id,
+                share,
+                event,
+                decoded_consumer_context,
+            )
+            .await?;
+        }
+        Ok(())
+    }
+
+    /// This function adds the deposit to the cost-basis ledger of the
+    /// receiver's position
+    async fn update_cost_basis(
+        &self,
+        event: &DecodedMessage,
+        decoded_consumer_context: &DecodedConsumerContext,
+    ) -> Result<(), ConsumerError> {
+        let position_id = self.format_position_id();
+        let mut ledger = match PositionCostBasis::find_by_id(
+            position_id.clone(),
+            &decoded_consumer_context.pg_pool,
+            &decoded_consumer_context.backend_schema,
+        )
+        .await?
+        {
+            Some(ledger) => ledger,
+            None => PositionCostBasis::builder()
+                .id(position_id)
+                .account_id(self.receiver.to_string())
+                .vault_id(self.vaultId)
+                .build(),
+        };
+
+        if ledger.apply_deposit(
+            self.senderAssetsAfterTotalFees,
+            self.receiverTotalSharesInVault,
+            event.block_number,
+            event.log_index,
+        ) {
+            ledger
+                .upsert(
+                    &decoded_consumer_context.pg_pool,
+                    &decoded_consumer_context.backend_schema,
+                )
+                .await?;
+        } else {
+            info!("Deposit already applied to the cost basis of {}", ledger.id);
+        }
+        Ok(())
+    }
+
     /// This function creates an `Event` for the `Deposited` event
     async fn create_event(
         &self,
@@ -342,10 +450,24 @@ impl Deposited {
             current_share_price,
             event,
         ))?;
+        record_share_price(
+            self.vaultId,
+            current_share_price,
+            event,
+            decoded_consumer_context,
+        )
+        .await?;
 
         // Create deposit record
         let deposit = self.create_deposit(event, decoded_consumer_context).await?;
 
+        // Classify the fees paid by the deposit
+        self.record_fees(event, decoded_consumer_context).await?;
+
+        // Update the cost basis of the position
+        self.update_cost_basis(event, decoded_consumer_context)
+            .await?;
+
         // Handle position and related entities
         self.handle_position_and_claims(decoded_consumer_context, &vault)
             .await?;
@@ -357,6 +479,18 @@ impl Deposited {
         // Create signal
         self.create_signal(decoded_consumer_context, event, &vault)
             .await?;
+        if self.senderAssetsAfterTotalFees > U256::ZERO {
+            update_relevance_score(
+                &vault,
+                &U256Wrapper::from(self.senderAssetsAfterTotalFees).to_big_decimal()?,
+                event,
+                decoded_consumer_context,
+            )
+            .await?;
+        }
+
+        // Update the consensus of the triple
+        update_triple_consensus(&vault, decoded_consumer_context).await?;
 
         Ok(())
     }
@@ -5,13 +5,14 @@ use crate::{
 use models::{
     account::{Account, AccountType},
     event::{Event, EventType},
+    fee_breakdown::FeeType,
     fee_transfer::FeeTransfer,
     traits::SimpleCrud,
     types::U256Wrapper,
 };
 use tracing::info;
 
-use super::utils::short_id;
+use super::utils::{record_fee, short_id};
 
 impl FeesTransferred {
     /// This function creates an `Event` for the `FeesTransferred` event
@@ -119,6 +120,17 @@ impl FeesTransferred {
         )
         .await?;
 
+        // Classify the fee
+        record_fee(
+            FeeType::Protocol,
+            None,
+            &sender_account.id,
+            self.amount,
+            event,
+            decoded_consumer_context,
+        )
+        .await?;
+
         // Create the event
         self.create_event(decoded_consumer_context, event).await?;
         Ok(())
@@ -1,4 +1,7 @@
-use super::utils::get_or_create_account;
+use super::utils::{
+    get_or_create_account, record_fee, record_share_price, update_relevance_score,
+    update_triple_consensus,
+};
 use crate::{
     error::ConsumerError, mode::types::DecodedConsumerContext, schemas::types::DecodedMessage,
     EthMultiVault::Redeemed,
@@ -8,7 +11,9 @@ use models::{
     account::Account,
     claim::Claim,
     event::{Event, EventType},
+    fee_breakdown::FeeType,
     position::Position,
+    position_cost_basis::PositionCostBasis,
     predicate_object::PredicateObject,
     redemption::Redemption,
     signal::Signal,
@@ -96,6 +101,54 @@ impl Redeemed {
             .map_err(ConsumerError::ModelError)
     }
 
+    /// This function removes the redeemed shares from the cost-basis ledger
+    /// of the sender's position and realizes their profit or loss
+    async fn update_cost_basis(
+        &self,
+        decoded_consumer_context: &DecodedConsumerContext,
+        sender_account: &Account,
+        event: &DecodedMessage,
+    ) -> Result<(), ConsumerError> {
+        let position_id = format!("{}-{}", self.vaultId, sender_account.id.to_lowercase());
+        let mut ledger = match PositionCostBasis::find_by_id(
+            position_id.clone(),
+            &decoded_consumer_context.pg_pool,
+            &decoded_consumer_context.backend_schema,
+        )
+        .await?
+        {
+            Some(ledger) => ledger,
+            // The deposits of the position were indexed before the ledger
+            // existed, so its cost is unknown
+            None => PositionCostBasis::builder()
+                .id(position_id)
+                .account_id(sender_account.id.clone())
+                .vault_id(self.vaultId)
+                .build(),
+        };
+
+        if ledger.apply_redemption(
+            self.assetsForReceiver,
+            self.sharesRedeemedBySender,
+            self.senderTotalSharesInVault,
+            event.block_number,
+            event.log_index,
+        )? {
+            ledger
+                .upsert(
+                    &decoded_consumer_context.pg_pool,
+                    &decoded_consumer_context.backend_schema,
+                )
+                .await?;
+        } else {
+            info!(
+                "Redemption already applied to the cost basis of {}",
+                ledger.id
+            );
+        }
+        Ok(())
+    }
+
     /// This function creates a `Signal` for the `Redeemed` event
     async fn create_signal(
         &self,
@@ -232,11 +285,29 @@ impl Redeemed {
             event,
         )
         .await?;
+        self.update_cost_basis(decoded_consumer_context, &sender_account, event)
+            .await?;
+        record_fee(
+            FeeType::Exit,
+            Some(self.vaultId),
+            &sender_account.id,
+            self.exitFee,
+            event,
+            decoded_consumer_context,
+        )
+        .await?;
 
         // 3. Get vault and current share price
         let current_share_price = decoded_consumer_context
             .fetch_current_share_price(self.vaultId, event.block_number)
             .await?;
+        record_share_price(
+            self.vaultId,
+            current_share_price,
+            event,
+            decoded_consumer_context,
+        )
+        .await?;
 
         // When the redemption fully depletes the sender's shares:
         if self.senderTotalSharesInVault == Uint::from(0) {
@@ -272,6 +343,18 @@ impl Redeemed {
             .await?;
         self.create_signal(decoded_consumer_context, event, &vault)
             .await?;
+        if self.assetsForReceiver > U256::ZERO {
+            update_relevance_score(
+                &vault,
+                &-U256Wrapper::from(self.assetsForReceiver).to_big_decimal()?,
+                event,
+                decoded_consumer_context,
+            )
+            .await?;
+        }
+
+        // Update the consensus of the triple
+        update_triple_consensus(&vault, decoded_consumer_context).await?;
 
         Ok(())
     }
@@ -14,13 +14,14 @@ use models::{
     predicate_object::PredicateObject,
     traits::SimpleCrud,
     triple::Triple,
+    triple_consensus::TripleConsensus,
     types::U256Wrapper,
     vault::Vault,
 };
 use std::str::FromStr;
 use tracing::info;
 
-use super::utils::short_id;
+use super::utils::{record_share_price, short_id};
 
 impl TripleCreated {
     /// This function checks if the subject atom is an account and if the predicate and object atoms are a person or organization.
@@ -400,6 +401,16 @@ impl TripleCreated {
         self.update_positions(decoded_consumer_context, &triple, event.block_number)
             .await?;
 
+        // The creation deposit was handled before the triple existed, so its
+        // consensus is computed here
+        let consensus = TripleConsensus::refresh(
+            &triple.id,
+            &decoded_consumer_context.pg_pool,
+            &decoded_consumer_context.backend_schema,
+        )
+        .await?;
+        info!("Updated triple consensus: {:?}", consensus);
+
         // Create the event
         self.create_event(event, decoded_consumer_context).await?;
         Ok(())
@@ -636,6 +647,20 @@ impl TripleCreated {
             event.block_number,
         )
         .await?;
+        record_share_price(
+            self.vaultID,
+            vault_current_share_price,
+            event,
+            decoded_consumer_context,
+        )
+        .await?;
+        record_share_price(
+            counter_vault_id,
+            counter_vault_current_share_price,
+            event,
+            decoded_consumer_context,
+        )
+        .await?;
 
         Ok(triple)
     }
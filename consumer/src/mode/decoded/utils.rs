@@ -1,20 +1,23 @@
 use crate::{
     error::ConsumerError,
     mode::{resolver::types::ResolverConsumerMessage, types::DecodedConsumerContext},
+    schemas::types::DecodedMessage,
 };
 use alloy::primitives::U256;
 use models::{
     account::{Account, AccountType},
+    fee_breakdown::{FeeBreakdown, FeeType},
+    relevance_score::{RelevanceEntity, RelevanceScore},
+    share_price_history::SharePriceHistory,
     traits::SimpleCrud,
+    triple_consensus::TripleConsensus,
     types::U256Wrapper,
+    vault::Vault,
 };
+pub use shared_utils::atom_metadata::short_id;
+use sqlx::types::BigDecimal;
 use tracing::info;
 
-/// Shortens an address string by taking first 6 and last 4 chars
-pub fn short_id(address: &str) -> String {
-    format!("{}...{}", &address[..6], &address[address.len() - 4..])
-}
-
 /// Returns the absolute triple ID for a given vault ID by determining if it's a counter vault
 /// and adjusting the ID accordingly
 pub fn get_absolute_triple_id(vault_id: U256) -> U256 {
@@ -117,3 +120,187 @@ pub async fn update_account_with_atom_id(
         .await?;
     Ok(account)
 }
+
+/// Records the share price of a vault observed by an event in the share price
+/// history. The vault must already exist.
+pub async fn record_share_price(
+    vault_id: U256,
+    share_price: U256,
+    event: &DecodedMessage,
+    decoded_consumer_context: &DecodedConsumerContext,
+) -> Result<SharePriceHistory, ConsumerError> {
+    SharePriceHistory::builder()
+        .vault_id(vault_id)
+        .block_number(event.block_number)
+        .log_index(event.log_index)
+        .block_timestamp(event.block_timestamp)
+        .share_price(share_price)
+        .build()
+        .upsert(
+            &decoded_consumer_context.pg_pool,
+            &decoded_consumer_context.backend_schema,
+        )
+        .await
+        .map_err(ConsumerError::ModelError)
+}
+
+/// Recomputes the consensus of the triple of a vault, if the vault belongs to
+/// a triple. It must run after the vault and the positions are updated.
+pub async fn update_triple_consensus(
+    vault: &Vault,
+    decoded_consumer_context: &DecodedConsumerContext,
+) -> Result<(), ConsumerError> {
+    if let Some(triple_id) = &vault.triple_id {
+        let consensus = TripleConsensus::refresh(
+            triple_id,
+            &decoded_consumer_context.pg_pool,
+            &decoded_consumer_context.backend_schema,
+        )
+        .await?;
+        info!("Updated triple consensus: {:?}", consensus);
+    }
+    Ok(())
+}
+
+/// Adds a signal delta to the relevance score of the atom or triple of a
+/// vault. It must run after the signal is stored. Redemptions pass the
+/// redeemed assets as a negative delta.
+pub async fn update_relevance_score(
+    vault: &Vault,
+    delta: &BigDecimal,
+    event: &DecodedMessage,
+    decoded_consumer_context: &DecodedConsumerContext,
+) -> Result<(), ConsumerError> {
+    let (entity_type, entity_id) = match (&vault.atom_id, &vault.triple_id) {
+        (Some(atom_id), _) => (RelevanceEntity::Atom, atom_id),
+        (None, Some(triple_id)) => (RelevanceEntity::Triple, triple_id),
+        (None, None) => return Ok(()),
+    };
+    match RelevanceScore::apply_signal(
+        entity_type,
+        entity_id,
+        delta,
+        event.block_timestamp,
+        event.block_number,
+        event.log_index,
+        decoded_consumer_context.relevance_half_life_secs,
+        &decoded_consumer_context.pg_pool,
+        &decoded_consumer_context.backend_schema,
+    )
+    .await?
+    {
+        Some(score) => info!("Updated relevance score: {:?}", score),
+        None => info!(
+            "Signal already applied to the relevance score of {}",
+            entity_id
+        ),
+    }
+    Ok(())
+}
+
+/// Records a fee paid in an event. Zero fees, e.g. the entry fee of the first
+/// deposit in a vault, aren't recorded.
+pub async fn record_fee(
+    fee_type: FeeType,
+    vault_id: Option<U256>,
+    payer_id: &str,
+    amount: U256,
+    event: &DecodedMessage,
+    decoded_consumer_context: &DecodedConsumerContext,
+) -> Result<(), ConsumerError> {
+    if amount.is_zero() {
+        return Ok(());
+    }
+    let vault_id = vault_id.map(U256Wrapper::from);
+    let fee = FeeBreakdown::builder()
+        .id(FeeBreakdown::format_id(
+            &DecodedMessage::event_id(event),
+            fee_type,
+            vault_id.as_ref(),
+        ))
+        .fee_type(fee_type)
+        .payer_id(payer_id.to_string())
+        .amount(amount)
+        .block_number(U256Wrapper::try_from(event.block_number)?)
+        .block_timestamp(event.block_timestamp)
+        .transaction_hash(event.transaction_hash.clone());
+    match vault_id {
+        Some(vault_id) => fee.vault_id(vault_id).build(),
+        None => fee.build(),
+    }
+    .upsert(
+        &decoded_consumer_context.pg_pool,
+        &decoded_consumer_context.backend_schema,
+    )
+    .await?;
+    Ok(())
+}
+
+/// Returns the atom deposit fraction taken from a triple deposit, given the
+/// assets that went into the triple vault (the assets after fees plus the
+/// entry fee). The contract takes `fraction / denominator` of the deposit
+/// before the entry fee, so the deposit is `assets * denominator /
+/// (denominator - fraction)`.
+pub fn atom_deposit_fraction_amount(assets: U256, fraction: U256, denominator: U256) -> U256 {
+    if fraction.is_zero() || denominator <= fraction {
+        return U256::ZERO;
+    }
+    assets * fraction / (denominator - fraction)
+}
+
+/// Splits the atom deposit fraction of a triple deposit between its atoms,
+/// summing the shares of an atom the triple repeats (e.g. when the subject is
+/// also the object) so each atom gets a single fee record.
+pub fn atom_deposit_fraction_shares(atom_ids: [U256; 3], amount: U256) -> Vec<(U256, U256)> {
+    let amount_per_atom = amount / U256::from(3);
+    let mut shares: Vec<(U256, U256)> = Vec::with_capacity(atom_ids.len());
+    for atom_id in atom_ids {
+        match shares.iter_mut().find(|(id, _)| *id == atom_id) {
+            Some((_, share)) => *share += amount_per_atom,
+            None => shares.push((atom_id, amount_per_atom)),
+        }
+    }
+    shares
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_atom_deposit_fraction_amount() {
+        let denominator = U256::from(10_000);
+        // A 10% fraction of a 1000 wei deposit leaves 900 wei for the triple
+        assert_eq!(
+            atom_deposit_fraction_amount(U256::from(900), U256::from(1_000), denominator),
+            U256::from(100)
+        );
+        assert_eq!(
+            atom_deposit_fraction_amount(U256::from(900), U256::ZERO, denominator),
+            U256::ZERO
+        );
+        assert_eq!(
+            atom_deposit_fraction_amount(U256::from(900), denominator, denominator),
+            U256::ZERO
+        );
+    }
+
+    #[test]
+    fn test_atom_deposit_fraction_shares() {
+        let (subject, predicate, object) = (U256::from(1), U256::from(2), U256::from(3));
+        assert_eq!(
+            atom_deposit_fraction_shares([subject, predicate, object], U256::from(300)),
+            vec![
+                (subject, U256::from(100)),
+                (predicate, U256::from(100)),
+                (object, U256::from(100))
+            ]
+        );
+        // A triple pointing its object back at its subject records one fee
+        // for that atom with both shares
+        assert_eq!(
+            atom_deposit_fraction_shares([subject, predicate, subject], U256::from(300)),
+            vec![(subject, U256::from(200)), (predicate, U256::from(100))]
+        );
+    }
+}
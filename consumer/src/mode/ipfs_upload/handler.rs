@@ -15,6 +15,7 @@ impl IpfsUploadMessage {
                 self.image.clone(),
                 ipfs_upload_consumer_context.reqwest_client.clone(),
                 ipfs_upload_consumer_context.image_guard_url.clone(),
+                ipfs_upload_consumer_context.image_guard_api_key.clone(),
             )
             .await;
             if let Err(e) = image_upload {
@@ -1,3 +1,4 @@
+pub mod chainlink_price;
 pub mod decoded;
 pub mod ipfs_upload;
 pub mod raw;
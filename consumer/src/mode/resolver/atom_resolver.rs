@@ -1,7 +1,7 @@
 use crate::{
     error::ConsumerError,
     mode::{
-        decoded::atom::atom_supported_types::AtomMetadata,
+        resolver::web_page::{OEmbed, PageMetadata},
         types::{AtomUpdater, ResolverConsumerContext},
     },
 };
@@ -11,50 +11,48 @@ use models::{
     atom_value::AtomValue,
     book::Book,
     byte_object::ByteObject,
+    creative_work::CreativeWork,
     json_object::JsonObject,
     organization::Organization,
     person::Person,
+    place::Place,
+    product::Product,
+    schema_event::SchemaEvent,
+    software_application::SoftwareApplication,
     text_object::TextObject,
     thing::Thing,
     traits::SimpleCrud,
+    web_page::WebPage,
+    web_site::WebSite,
 };
-use reqwest::Response;
+use reqwest::Url;
 use serde_json::Value;
+use shared_utils::{
+    atom_metadata::AtomMetadata,
+    json_ld::{self, JsonLdNode},
+    types::{FetchedContent, SCHEMA_ORG_CONTEXTS},
+};
 use std::str::FromStr;
 use tracing::{info, warn};
 
-/// Supported schema.org contexts
-pub const SCHEMA_ORG_CONTEXTS: [&str; 4] = [
-    "https://schema.org",
-    "https://schema.org/",
-    "http://schema.org",
-    "http://schema.org/",
-];
+/// The prefixes of the URIs resolved to the document they point to. `https://`
+/// URIs are only resolved if they point to a JSON document or a web page,
+/// other links are kept as text.
+const RESOLVED_URI_PREFIXES: [&str; 4] = ["ipfs://", "ipns://", "ar://", "data:"];
 
-/// Resolves an IPFS URI
-pub async fn try_to_resolve_ipfs_uri(
+/// Resolves an atom URI (`ipfs://`, `ipns://`, `ar://`, `https://` or
+/// `data:`) to the document it points to. Returns `None` if the atom data is
+/// not a supported URI or is an `https://` link that is kept as text.
+pub async fn try_to_resolve_uri(
     atom_data: &str,
     resolver_consumer_context: &ResolverConsumerContext,
-) -> Result<Option<Response>, ConsumerError> {
-    // Handle IPFS URIs
-    warn!("Trying to resolve IPFS URI: {}", atom_data);
-    if let Some(ipfs_hash) = atom_data.strip_prefix("ipfs://") {
-        if let Ok(ipfs_data) = resolver_consumer_context
-            .ipfs_resolver
-            .fetch_from_ipfs(ipfs_hash)
-            .await
-        {
-            // At this point we dont know what type of data is contained in the response,
-            // we just know that the response is valid
-            Ok(Some(ipfs_data))
-        } else {
-            warn!("Failed to fetch IPFS data, atom data: {}", atom_data);
-            Ok(None)
-        }
-    } else {
-        warn!("Atom data is not an IPFS URI: {}", atom_data);
-        Ok(None)
-    }
+) -> Result<Option<FetchedContent>, ConsumerError> {
+    info!("Trying to resolve URI: {}", atom_data);
+    // At this point we dont know what type of data is contained in the document
+    resolver_consumer_context
+        .uri_resolver
+        .resolve(atom_data)
+        .await
 }
 
 /// This function tries to resolve a schema.org URL from the atom data
@@ -121,6 +119,71 @@ async fn try_to_resolve_schema_org_properties(
                     create_book_atom_value(atom, &book, consumer_context).await?;
                     Ok(AtomMetadata::book(book.name.unwrap_or_default()))
                 }
+                AtomType::CreativeWork => {
+                    let creative_work = create_creative_work_from_obj(atom, obj)
+                        .upsert(consumer_context.pool(), consumer_context.backend_schema())
+                        .await?;
+                    create_creative_work_atom_value(atom, &creative_work, consumer_context).await?;
+                    Ok(AtomMetadata::creative_work(
+                        creative_work.name.unwrap_or_default(),
+                        creative_work.image.clone(),
+                    ))
+                }
+                AtomType::Event => {
+                    let event = create_schema_event_from_obj(atom, obj)
+                        .upsert(consumer_context.pool(), consumer_context.backend_schema())
+                        .await?;
+                    create_schema_event_atom_value(atom, &event, consumer_context).await?;
+                    Ok(AtomMetadata::event(
+                        event.name.unwrap_or_default(),
+                        event.image.clone(),
+                    ))
+                }
+                AtomType::Place => {
+                    let place = create_place_from_obj(atom, obj)
+                        .upsert(consumer_context.pool(), consumer_context.backend_schema())
+                        .await?;
+                    create_place_atom_value(atom, &place, consumer_context).await?;
+                    Ok(AtomMetadata::place(
+                        place.name.unwrap_or_default(),
+                        place.image.clone(),
+                    ))
+                }
+                AtomType::Product => {
+                    let product = create_product_from_obj(atom, obj)
+                        .upsert(consumer_context.pool(), consumer_context.backend_schema())
+                        .await?;
+                    create_product_atom_value(atom, &product, consumer_context).await?;
+                    Ok(AtomMetadata::product(
+                        product.name.unwrap_or_default(),
+                        product.image.clone(),
+                    ))
+                }
+                AtomType::SoftwareApplication => {
+                    let software_application = create_software_application_from_obj(atom, obj)
+                        .upsert(consumer_context.pool(), consumer_context.backend_schema())
+                        .await?;
+                    create_software_application_atom_value(
+                        atom,
+                        &software_application,
+                        consumer_context,
+                    )
+                    .await?;
+                    Ok(AtomMetadata::software_application(
+                        software_application.name.unwrap_or_default(),
+                        software_application.image.clone(),
+                    ))
+                }
+                AtomType::WebSite => {
+                    let web_site = create_web_site_from_obj(atom, obj)
+                        .upsert(consumer_context.pool(), consumer_context.backend_schema())
+                        .await?;
+                    create_web_site_atom_value(atom, &web_site, consumer_context).await?;
+                    Ok(AtomMetadata::web_site(
+                        web_site.name.unwrap_or_default(),
+                        web_site.image.clone(),
+                    ))
+                }
                 _ => {
                     warn!("Unsupported schema.org type: {}", obj_type);
                     Ok(AtomMetadata::unknown())
@@ -296,6 +359,131 @@ pub fn create_book_from_obj(atom: &Atom, obj: &Value) -> Book {
         .build()
 }
 
+/// Returns a string property of a schema.org object, or an empty string. A
+/// nested object, e.g. the `Person` of an `author` or the `Place` of a
+/// `location`, is read as its `name`.
+fn get_string_property(obj: &Value, key: &str) -> String {
+    obj.get(key)
+        .and_then(|value| {
+            value
+                .as_str()
+                .or_else(|| value.get("name").and_then(|name| name.as_str()))
+        })
+        .map(|string_value| string_value.to_string())
+        .unwrap_or_default()
+}
+
+/// Creates a CreativeWork from a schema.org object
+pub fn create_creative_work_from_obj(atom: &Atom, obj: &Value) -> CreativeWork {
+    CreativeWork::builder()
+        .id(atom.id.clone())
+        .name(get_string_property(obj, "name"))
+        .description(get_string_property(obj, "description"))
+        .image(get_string_property(obj, "image"))
+        .url(get_string_property(obj, "url"))
+        .author(get_string_property(obj, "author"))
+        .date_published(get_string_property(obj, "datePublished"))
+        .build()
+}
+
+/// Creates a SchemaEvent from a schema.org `Event` object
+pub fn create_schema_event_from_obj(atom: &Atom, obj: &Value) -> SchemaEvent {
+    SchemaEvent::builder()
+        .id(atom.id.clone())
+        .name(get_string_property(obj, "name"))
+        .description(get_string_property(obj, "description"))
+        .image(get_string_property(obj, "image"))
+        .url(get_string_property(obj, "url"))
+        .start_date(get_string_property(obj, "startDate"))
+        .end_date(get_string_property(obj, "endDate"))
+        .location(get_string_property(obj, "location"))
+        .build()
+}
+
+/// Creates a Place from a schema.org object
+pub fn create_place_from_obj(atom: &Atom, obj: &Value) -> Place {
+    Place::builder()
+        .id(atom.id.clone())
+        .name(get_string_property(obj, "name"))
+        .description(get_string_property(obj, "description"))
+        .image(get_string_property(obj, "image"))
+        .url(get_string_property(obj, "url"))
+        .address(get_string_property(obj, "address"))
+        .latitude(get_coordinate(obj, "latitude"))
+        .longitude(get_coordinate(obj, "longitude"))
+        .build()
+}
+
+/// Creates a Product from a schema.org object
+pub fn create_product_from_obj(atom: &Atom, obj: &Value) -> Product {
+    Product::builder()
+        .id(atom.id.clone())
+        .name(get_string_property(obj, "name"))
+        .description(get_string_property(obj, "description"))
+        .image(get_string_property(obj, "image"))
+        .url(get_string_property(obj, "url"))
+        .brand(get_string_property(obj, "brand"))
+        .sku(get_string_property(obj, "sku"))
+        .build()
+}
+
+/// Creates a SoftwareApplication from a schema.org object
+pub fn create_software_application_from_obj(atom: &Atom, obj: &Value) -> SoftwareApplication {
+    SoftwareApplication::builder()
+        .id(atom.id.clone())
+        .name(get_string_property(obj, "name"))
+        .description(get_string_property(obj, "description"))
+        .image(get_string_property(obj, "image"))
+        .url(get_string_property(obj, "url"))
+        .application_category(get_string_property(obj, "applicationCategory"))
+        .operating_system(get_string_property(obj, "operatingSystem"))
+        .build()
+}
+
+/// Creates a WebSite from a schema.org object
+pub fn create_web_site_from_obj(atom: &Atom, obj: &Value) -> WebSite {
+    WebSite::builder()
+        .id(atom.id.clone())
+        .name(get_string_property(obj, "name"))
+        .description(get_string_property(obj, "description"))
+        .image(get_string_property(obj, "image"))
+        .url(get_string_property(obj, "url"))
+        .build()
+}
+
+/// Returns a coordinate of a schema.org `Place`, set either on the place or on
+/// its `geo` coordinates
+fn get_coordinate(obj: &Value, key: &str) -> Option<f64> {
+    obj.get(key)
+        .or_else(|| obj.get("geo").and_then(|geo| geo.get(key)))
+        .and_then(|value| value.as_f64())
+}
+
+/// Handles JSON-LD. The first node with a supported schema.org type is
+/// resolved, falling back to the first node with any schema.org type. Other
+/// vocabularies are kept as a JsonObject, with their types expanded to IRIs.
+async fn handle_json_ld(
+    consumer_context: &impl AtomUpdater,
+    atom: &Atom,
+    json: &Value,
+    nodes: &[JsonLdNode],
+) -> Result<AtomMetadata, ConsumerError> {
+    match json_ld::schema_org_node(nodes) {
+        Some((node, schema_org_type)) => {
+            handle_schema_org_json(
+                consumer_context,
+                atom,
+                &node.to_schema_org_object(schema_org_type),
+            )
+            .await
+        }
+        None => {
+            info!("No schema.org type found in JSON-LD, returning it as JsonObject");
+            store_json_object(consumer_context, atom, &json_ld::with_expanded_types(json)).await
+        }
+    }
+}
+
 /// Handles schema.org JSON
 async fn handle_schema_org_json(
     consumer_context: &impl AtomUpdater,
@@ -316,6 +504,15 @@ async fn handle_regular_json(
         "No @context found in JSON: {:?}, returning it as JsonObject",
         json
     );
+    store_json_object(consumer_context, atom, json).await
+}
+
+/// Stores JSON as a JsonObject
+async fn store_json_object(
+    consumer_context: &impl AtomUpdater,
+    atom: &Atom,
+    json: &Value,
+) -> Result<AtomMetadata, ConsumerError> {
     let json_object = create_json_object_from_obj(atom, json)
         .upsert(consumer_context.pool(), consumer_context.backend_schema())
         .await?;
@@ -346,15 +543,77 @@ pub async fn handle_binary_data(
     }
 }
 
+/// Handles a web page. Its OpenGraph, Twitter card and HTML metadata are
+/// stored as a WebPage, completed with its oEmbed response if the page
+/// advertises one. The preview image becomes the image of the atom.
+pub async fn handle_web_page(
+    resolver_consumer_context: &ResolverConsumerContext,
+    atom: &Atom,
+    url: &str,
+    content: &FetchedContent,
+) -> Result<AtomMetadata, ConsumerError> {
+    info!("Data is a web page, extracting its metadata");
+    let page_url = Url::parse(url).map_err(|_| ConsumerError::InvalidUri(url.to_string()))?;
+    let mut metadata = PageMetadata::parse(&String::from_utf8_lossy(&content.data), &page_url);
+
+    if metadata.is_incomplete() {
+        if let Some(oembed_url) = metadata.oembed_url.clone() {
+            match fetch_oembed(resolver_consumer_context, &oembed_url).await {
+                Ok(oembed) => metadata.merge_oembed(oembed),
+                Err(e) => warn!("Failed to fetch oEmbed {}: {}", oembed_url, e),
+            }
+        }
+    }
+
+    let web_page = WebPage::builder()
+        .id(atom.id.clone())
+        .url(url)
+        .canonical_url(metadata.canonical_url.unwrap_or_else(|| url.to_string()))
+        .title(metadata.title)
+        .description(metadata.description)
+        .image(metadata.image)
+        .site_name(metadata.site_name)
+        .favicon(metadata.favicon)
+        .build()
+        .upsert(
+            resolver_consumer_context.pool(),
+            resolver_consumer_context.backend_schema(),
+        )
+        .await?;
+    create_web_page_atom_value(atom, &web_page, resolver_consumer_context).await?;
+
+    let label = web_page
+        .title
+        .clone()
+        .or_else(|| web_page.site_name.clone())
+        .unwrap_or_else(|| web_page.url.clone());
+    Ok(AtomMetadata::url(label, web_page.image))
+}
+
+/// Fetches the oEmbed response of a web page
+async fn fetch_oembed(
+    resolver_consumer_context: &ResolverConsumerContext,
+    oembed_url: &str,
+) -> Result<OEmbed, ConsumerError> {
+    let content = resolver_consumer_context
+        .safe_fetcher
+        .fetch(oembed_url)
+        .await?;
+    Ok(serde_json::from_slice(&content.data)?)
+}
+
 /// Handles text data
 async fn handle_text_data(
     consumer_context: &impl AtomUpdater,
     atom: &Atom,
     atom_data: &str,
 ) -> Result<AtomMetadata, ConsumerError> {
-    // We need to ignore the case where the atom data is an IPFS URI, as we already handled it
-    // in the previous step
-    if atom_data.starts_with("ipfs://") {
+    // We need to ignore the case where the atom data is an URI the resolver fetches, as we
+    // already handled it in the previous step
+    if RESOLVED_URI_PREFIXES
+        .iter()
+        .any(|prefix| atom_data.starts_with(prefix))
+    {
         return Ok(AtomMetadata::unknown());
     }
 
@@ -373,11 +632,9 @@ pub async fn try_to_parse_json_or_text(
     consumer_context: &impl AtomUpdater,
 ) -> Result<AtomMetadata, ConsumerError> {
     if let Ok(json) = serde_json::from_str::<Value>(atom_data) {
-        match json.get("@context").and_then(|c| c.as_str()) {
-            Some(ctx_str) if SCHEMA_ORG_CONTEXTS.contains(&ctx_str) => {
-                handle_schema_org_json(consumer_context, atom, &json).await
-            }
-            _ => handle_regular_json(consumer_context, atom, &json).await,
+        match json_ld::normalize(&json) {
+            Some(nodes) => handle_json_ld(consumer_context, atom, &json, &nodes).await,
+            None => handle_regular_json(consumer_context, atom, &json).await,
         }
     } else {
         handle_text_data(consumer_context, atom, atom_data).await
@@ -488,3 +745,128 @@ pub async fn create_book_atom_value(
         .await?;
     Ok(())
 }
+
+/// Creates an atom value for a creative work
+pub async fn create_creative_work_atom_value(
+    atom: &Atom,
+    creative_work: &CreativeWork,
+    consumer_context: &impl AtomUpdater,
+) -> Result<(), ConsumerError> {
+    AtomValue::builder()
+        .id(atom.id.clone())
+        .creative_work_id(creative_work.id.clone())
+        .build()
+        .upsert(consumer_context.pool(), consumer_context.backend_schema())
+        .await?;
+    Ok(())
+}
+
+/// Creates an atom value for an event
+pub async fn create_schema_event_atom_value(
+    atom: &Atom,
+    event: &SchemaEvent,
+    consumer_context: &impl AtomUpdater,
+) -> Result<(), ConsumerError> {
+    AtomValue::builder()
+        .id(atom.id.clone())
+        .schema_event_id(event.id.clone())
+        .build()
+        .upsert(consumer_context.pool(), consumer_context.backend_schema())
+        .await?;
+    Ok(())
+}
+
+/// Creates an atom value for a place
+pub async fn create_place_atom_value(
+    atom: &Atom,
+    place: &Place,
+    consumer_context: &impl AtomUpdater,
+) -> Result<(), ConsumerError> {
+    AtomValue::builder()
+        .id(atom.id.clone())
+        .place_id(place.id.clone())
+        .build()
+        .upsert(consumer_context.pool(), consumer_context.backend_schema())
+        .await?;
+    Ok(())
+}
+
+/// Creates an atom value for a product
+pub async fn create_product_atom_value(
+    atom: &Atom,
+    product: &Product,
+    consumer_context: &impl AtomUpdater,
+) -> Result<(), ConsumerError> {
+    AtomValue::builder()
+        .id(atom.id.clone())
+        .product_id(product.id.clone())
+        .build()
+        .upsert(consumer_context.pool(), consumer_context.backend_schema())
+        .await?;
+    Ok(())
+}
+
+/// Creates an atom value for a software application
+pub async fn create_software_application_atom_value(
+    atom: &Atom,
+    software_application: &SoftwareApplication,
+    consumer_context: &impl AtomUpdater,
+) -> Result<(), ConsumerError> {
+    AtomValue::builder()
+        .id(atom.id.clone())
+        .software_application_id(software_application.id.clone())
+        .build()
+        .upsert(consumer_context.pool(), consumer_context.backend_schema())
+        .await?;
+    Ok(())
+}
+
+/// Creates an atom value for a website
+pub async fn create_web_site_atom_value(
+    atom: &Atom,
+    web_site: &WebSite,
+    consumer_context: &impl AtomUpdater,
+) -> Result<(), ConsumerError> {
+    AtomValue::builder()
+        .id(atom.id.clone())
+        .web_site_id(web_site.id.clone())
+        .build()
+        .upsert(consumer_context.pool(), consumer_context.backend_schema())
+        .await?;
+    Ok(())
+}
+
+/// Creates an atom value for a web page
+pub async fn create_web_page_atom_value(
+    atom: &Atom,
+    web_page: &WebPage,
+    consumer_context: &impl AtomUpdater,
+) -> Result<(), ConsumerError> {
+    AtomValue::builder()
+        .id(atom.id.clone())
+        .web_page_id(web_page.id.clone())
+        .build()
+        .upsert(consumer_context.pool(), consumer_context.backend_schema())
+        .await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_get_string_property() {
+        let obj = json!({
+            "@type": "Event",
+            "name": "Denver meetup",
+            "location": {"@type": "Place", "name": "Denver"},
+            "organizer": {"@type": "Organization"}
+        });
+        assert_eq!(get_string_property(&obj, "name"), "Denver meetup");
+        assert_eq!(get_string_property(&obj, "location"), "Denver");
+        assert_eq!(get_string_property(&obj, "organizer"), "");
+        assert_eq!(get_string_property(&obj, "url"), "");
+    }
+}
@@ -1,22 +1,172 @@
 use crate::{
     error::ConsumerError,
-    mode::{ipfs_upload::types::IpfsUploadMessage, types::ResolverConsumerContext},
+    mode::{decoded::atom::caip::AssetId, types::ResolverConsumerContext},
     ENSName::ENSNameInstance,
     ENSRegistry::ENSRegistryInstance,
+    ERC1155::ERC1155Instance,
+    ERC721::ERC721Instance,
 };
 use alloy::{
-    primitives::{keccak256, Address, FixedBytes},
+    primitives::{keccak256, Address, FixedBytes, U256},
     providers::RootProvider,
     transports::http::Http,
 };
 use reqwest::Client;
-use tracing::info;
+use std::str::FromStr;
+use tracing::{info, warn};
 
-/// This struct represents the ENS name and avatar for an address.
-#[derive(Clone, Debug)]
+/// The registry of Basenames on Base
+pub const BASENAMES_REGISTRY_ADDRESS: &str = "0xB94704422c2a1E396835A571837Aa5AE53285a95";
+/// The reverse records of ENS names on mainnet are stored under this name
+const ENS_REVERSE_NAME: &str = "addr.reverse";
+/// The reverse records of Basenames are stored under the ENSIP-11 coin type
+/// of Base, `0x80000000 | 8453`
+const BASENAMES_REVERSE_NAME: &str = "80002105.reverse";
+/// The chain id of mainnet
+const MAINNET_CHAIN_ID: u64 = 1;
+/// The chain id of Base
+const BASE_CHAIN_ID: u64 = 8453;
+
+/// The registry contract of a name service
+pub type Registry = ENSRegistryInstance<Http<Client>, RootProvider<Http<Client>>>;
+
+/// This struct represents the ENS name or Basename of an address, with the
+/// text records of its profile.
+#[derive(Clone, Debug, Default)]
 pub struct Ens {
     pub name: Option<String>,
     pub image: Option<String>,
+    pub description: Option<String>,
+    pub url: Option<String>,
+    pub twitter: Option<String>,
+    pub github: Option<String>,
+}
+
+/// A name service resolving names with the ENS contracts, on the chain of
+/// its registry
+struct NameService<'a> {
+    registry: &'a Registry,
+    reverse_name: &'static str,
+    chain_id: u64,
+}
+
+impl NameService<'_> {
+    /// This function gets the resolver address of a node, if any.
+    async fn get_resolver_address(&self, node: &[u8]) -> Result<Option<Address>, ConsumerError> {
+        let resolver_address = self
+            .registry
+            .resolver(FixedBytes::from_slice(node))
+            .call()
+            .await?
+            ._0;
+        Ok((resolver_address != Address::ZERO).then_some(resolver_address))
+    }
+
+    /// This function gets the name of an address and the address of its
+    /// resolver. The reverse record is set by the address itself, so the
+    /// name is only returned if its forward record points back to the address.
+    async fn get_verified_name(
+        &self,
+        address: Address,
+    ) -> Result<Option<(String, Address)>, ConsumerError> {
+        let reverse_node = Ens::namehash(&Ens::prepare_name(address, self.reverse_name));
+        let Some(reverse_resolver) = self.get_resolver_address(&reverse_node).await? else {
+            info!("No resolver found for {} in {}", address, self.reverse_name);
+            return Ok(None);
+        };
+        let name = ENSNameInstance::new(reverse_resolver, self.registry.provider())
+            .name(FixedBytes::from_slice(&reverse_node))
+            .call()
+            .await?
+            ._0;
+        if name.is_empty() {
+            return Ok(None);
+        }
+
+        let node = Ens::namehash(&name);
+        let Some(resolver_address) = self.get_resolver_address(&node).await? else {
+            warn!(
+                "No resolver found for {}, ignoring the name of {}",
+                name, address
+            );
+            return Ok(None);
+        };
+        let resolved_address = ENSNameInstance::new(resolver_address, self.registry.provider())
+            .addr(FixedBytes::from_slice(&node))
+            .call()
+            .await?
+            ._0;
+        if resolved_address != address {
+            warn!(
+                "{} resolves to {}, ignoring the reverse record of {}",
+                name, resolved_address, address
+            );
+            return Ok(None);
+        }
+
+        info!("Resolved name of {}: {}", address, name);
+        Ok(Some((name, resolver_address)))
+    }
+
+    /// This function gets a text record of a name. Resolvers are not
+    /// required to support text records, so errors are ignored.
+    async fn get_text(&self, resolver_address: Address, node: &[u8], key: &str) -> Option<String> {
+        ENSNameInstance::new(resolver_address, self.registry.provider())
+            .text(FixedBytes::from_slice(node), key.to_string())
+            .call()
+            .await
+            .ok()
+            .map(|text| text._0.trim().to_string())
+            .filter(|text| !text.is_empty())
+    }
+
+    /// This function gets the image URL of an NFT avatar, e.g.
+    /// `eip155:1/erc721:0xb47e3cd837dDF8e4c57F05d70Ab865de6e193BBB/1`. The
+    /// NFT must be on the chain of the name service and owned by the address.
+    async fn get_nft_image(
+        &self,
+        avatar: &str,
+        address: Address,
+        consumer_context: &ResolverConsumerContext,
+    ) -> Result<Option<String>, ConsumerError> {
+        let asset = AssetId::from_str(avatar)?;
+        let (Some(token_id), true) = (
+            asset.token_id.as_deref(),
+            asset.chain_id.reference == self.chain_id.to_string(),
+        ) else {
+            return Ok(None);
+        };
+        let contract = Address::from_str(&asset.asset_reference)
+            .map_err(|e| ConsumerError::AddressParse(e.to_string()))?;
+        let token_id = U256::from_str(token_id)?;
+
+        let uri = match asset.asset_namespace.as_str() {
+            "erc721" => {
+                let nft = ERC721Instance::new(contract, self.registry.provider());
+                if nft.ownerOf(token_id).call().await?._0 != address {
+                    return Ok(None);
+                }
+                nft.tokenURI(token_id).call().await?._0
+            }
+            "erc1155" => {
+                let nft = ERC1155Instance::new(contract, self.registry.provider());
+                if nft.balanceOf(address, token_id).call().await?._0.is_zero() {
+                    return Ok(None);
+                }
+                Ens::erc1155_uri(&nft.uri(token_id).call().await?._0, token_id)
+            }
+            _ => return Ok(None),
+        };
+
+        let Some(metadata) = consumer_context.uri_resolver.resolve(uri.trim()).await? else {
+            return Ok(None);
+        };
+        let metadata: serde_json::Value = serde_json::from_slice(&metadata.data)?;
+        Ok(["image", "image_url"]
+            .iter()
+            .find_map(|key| metadata.get(key).and_then(|image| image.as_str()))
+            .map(str::to_string))
+    }
 }
 
 impl Ens {
@@ -33,94 +183,111 @@ impl Ens {
         hash
     }
 
-    /// This function gets the ENS name and avatar for an address.
+    /// This function gets the ENS name or, failing that, the Basename of an
+    /// address, with its avatar and text records.
     pub async fn get_ens(
         address: Address,
         consumer_context: &ResolverConsumerContext,
     ) -> Result<Ens, ConsumerError> {
-        let name = Self::get_ens_name(address, &consumer_context.mainnet_client).await?;
-        let mut image = None;
-        if let Some(name_str) = &name {
-            image = Self::get_ens_avatar(name_str, consumer_context).await?;
+        let mut name_services = vec![NameService {
+            registry: &consumer_context.mainnet_client,
+            reverse_name: ENS_REVERSE_NAME,
+            chain_id: MAINNET_CHAIN_ID,
+        }];
+        if let Some(basenames_client) = &consumer_context.basenames_client {
+            name_services.push(NameService {
+                registry: basenames_client,
+                reverse_name: BASENAMES_REVERSE_NAME,
+                chain_id: BASE_CHAIN_ID,
+            });
         }
-        Ok(Ens { name, image })
-    }
 
-    /// Gets the ENS avatar URL for a given name
-    async fn get_ens_avatar(
-        name: &str,
-        consumer_context: &ResolverConsumerContext,
-    ) -> Result<Option<String>, ConsumerError> {
-        let url = format!("https://metadata.ens.domains/mainnet/avatar/{}", name);
-        match reqwest::get(&url).await {
-            Ok(response) => {
-                if response.status() == 200 {
-                    info!("Sending image to IPFS upload consumer: {}", url);
-                    consumer_context
-                        .client
-                        .send_message(
-                            serde_json::to_string(&IpfsUploadMessage { image: url.clone() })?,
-                            None,
-                        )
-                        .await?;
-                    Ok(Some(url))
-                } else {
-                    Ok(None)
-                }
+        for name_service in name_services {
+            if let Some((name, resolver_address)) = name_service.get_verified_name(address).await? {
+                return Ok(Self::get_profile(
+                    name_service,
+                    address,
+                    name,
+                    resolver_address,
+                    consumer_context,
+                )
+                .await);
             }
-            Err(_) => Ok(None),
         }
+        Ok(Ens::default())
     }
 
-    /// This function gets the ENS name for an address.
-    pub async fn get_ens_name(
+    /// This function gets the avatar and the text records of a verified name
+    async fn get_profile(
+        name_service: NameService<'_>,
         address: Address,
-        mainnet_client: &ENSRegistryInstance<Http<Client>, RootProvider<Http<Client>>>,
-    ) -> Result<Option<String>, ConsumerError> {
-        info!("Getting ENS name for {}", address);
-        let address_hash = Self::namehash(&Self::prepare_name(address));
-        let resolver_address =
-            Self::get_resolver_address(address, &address_hash, mainnet_client).await?;
-
-        if resolver_address != Address::ZERO {
-            let alloy_contract = ENSNameInstance::new(resolver_address, mainnet_client.provider());
-            let name = alloy_contract
-                .name(FixedBytes::from_slice(address_hash.as_slice()))
-                .call()
-                .await?
-                ._0;
-            info!("ResolvedENS name: {:?}", name);
-            Ok(Some(name))
-        } else {
-            Ok(None)
+        name: String,
+        resolver_address: Address,
+        consumer_context: &ResolverConsumerContext,
+    ) -> Ens {
+        let node = Self::namehash(&name);
+        let text = |key| name_service.get_text(resolver_address, &node, key);
+
+        let image = match text("avatar").await {
+            Some(avatar) => {
+                Self::get_ens_avatar(&name_service, &avatar, address, consumer_context).await
+            }
+            None => None,
+        };
+
+        Ens {
+            image,
+            description: text("description").await,
+            url: text("url").await,
+            twitter: text("com.twitter").await,
+            github: text("com.github").await,
+            name: Some(name),
         }
     }
 
-    /// This function gets the resolver address for an address hash.
-    async fn get_resolver_address(
+    /// Gets the image URL of an avatar record, either the URL of the image or
+    /// an NFT.
+    async fn get_ens_avatar(
+        name_service: &NameService<'_>,
+        avatar: &str,
         address: Address,
-        address_hash: &[u8],
-        mainnet_client: &ENSRegistryInstance<Http<Client>, RootProvider<Http<Client>>>,
-    ) -> Result<Address, ConsumerError> {
-        let resolver_address = mainnet_client
-            .resolver(FixedBytes::from_slice(address_hash))
-            .call()
-            .await?
-            ._0;
-
-        if resolver_address == Address::ZERO {
-            info!("No resolver found for {}", address);
+        consumer_context: &ResolverConsumerContext,
+    ) -> Option<String> {
+        let url = if avatar.starts_with("eip155:") {
+            name_service
+                .get_nft_image(avatar, address, consumer_context)
+                .await
+                .unwrap_or_else(|e| {
+                    warn!("Failed to resolve the NFT avatar {}: {}", avatar, e);
+                    None
+                })?
         } else {
-            info!("Resolver found for {}: {}", address, resolver_address);
+            avatar.to_string()
+        };
+        if !Self::is_supported_avatar_url(&url) {
+            info!("Unsupported avatar URL: {}", url);
+            return None;
         }
+        Some(url)
+    }
+
+    /// Returns true if an avatar URL can be fetched by the IPFS upload consumer
+    fn is_supported_avatar_url(url: &str) -> bool {
+        ["https://", "ipfs://", "ar://", "data:image/"]
+            .iter()
+            .any(|prefix| url.starts_with(prefix))
+    }
 
-        Ok(resolver_address)
+    /// This function expands the `{id}` placeholder of an ERC-1155 metadata
+    /// URI, the token id as 64 lowercase hex characters.
+    fn erc1155_uri(uri: &str, token_id: U256) -> String {
+        uri.replace("{id}", &format!("{:064x}", token_id))
     }
 
-    /// This function prepares the name for the ENS resolver.
-    fn prepare_name(address: Address) -> String {
+    /// This function prepares the name of the reverse record of an address.
+    fn prepare_name(address: Address, reverse_name: &str) -> String {
         let addr_str = address.to_string().to_lowercase();
-        format!("{}.addr.reverse", addr_str.trim_start_matches("0x"))
+        format!("{}.{}", addr_str.trim_start_matches("0x"), reverse_name)
     }
 }
 
@@ -145,4 +312,36 @@ mod tests {
         let alice_eth_hash = "787192fc5378cc32aa956ddfdedbf26b24e8d78e40109add0eea2c1a012c3dec";
         assert_eq!(hex::encode(Ens::namehash("alice.eth")), alice_eth_hash);
     }
+
+    #[test]
+    fn test_prepare_name() {
+        let address = Address::from_str("0xd8dA6BF26964aF9D7eEd9e03E53415D37aA96045").unwrap();
+        assert_eq!(
+            Ens::prepare_name(address, ENS_REVERSE_NAME),
+            "d8da6bf26964af9d7eed9e03e53415d37aa96045.addr.reverse"
+        );
+        assert_eq!(
+            Ens::prepare_name(address, BASENAMES_REVERSE_NAME),
+            "d8da6bf26964af9d7eed9e03e53415d37aa96045.80002105.reverse"
+        );
+    }
+
+    #[test]
+    fn test_avatar_urls() {
+        assert!(Ens::is_supported_avatar_url(
+            "https://example.com/avatar.png"
+        ));
+        assert!(Ens::is_supported_avatar_url(
+            "ipfs://QmX1KZ4Eund4v93j6C3xk83gfq3wtwfzZG2tepNzqNuvGh"
+        ));
+        assert!(!Ens::is_supported_avatar_url(
+            "http://example.com/avatar.png"
+        ));
+        assert!(!Ens::is_supported_avatar_url("javascript:alert(1)"));
+
+        assert_eq!(
+            Ens::erc1155_uri("https://example.com/{id}.json", U256::from(314)),
+            "https://example.com/000000000000000000000000000000000000000000000000000000000000013a.json"
+        );
+    }
 }
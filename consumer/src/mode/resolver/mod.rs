@@ -1,3 +1,6 @@
 pub mod atom_resolver;
 pub mod ens_resolver;
+pub mod retry;
 pub mod types;
+pub mod uri_resolver;
+pub mod web_page;
@@ -0,0 +1,208 @@
+use crate::{error::ConsumerError, mode::types::ResolverConsumerContext};
+use chrono::{DateTime, Utc};
+use models::{
+    atom_resolution_attempt::{AtomResolutionAttempt, ResolutionErrorCategory},
+    traits::SimpleCrud,
+    types::U256Wrapper,
+};
+use shared_utils::error::LibError;
+use std::time::Duration;
+use tracing::info;
+
+/// How many times the resolution of an atom is attempted by default
+pub const DEFAULT_MAX_ATTEMPTS: i32 = 5;
+/// The delay before the first retry by default, doubled after every attempt
+pub const DEFAULT_BASE_DELAY: Duration = Duration::from_secs(60);
+/// The longest delay between two attempts
+const MAX_DELAY: Duration = Duration::from_secs(24 * 60 * 60);
+/// The longest error message recorded
+const MAX_ERROR_LENGTH: usize = 1000;
+
+/// When failed resolutions are retried
+#[derive(Clone, Debug)]
+pub struct RetryPolicy {
+    pub max_attempts: i32,
+    pub base_delay: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: DEFAULT_MAX_ATTEMPTS,
+            base_delay: DEFAULT_BASE_DELAY,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Returns when to retry a resolution that failed `attempts` times, or
+    /// `None` if the error is not retryable or the attempts are exhausted
+    pub fn next_retry_at(
+        &self,
+        attempts: i32,
+        category: ResolutionErrorCategory,
+        now: DateTime<Utc>,
+    ) -> Option<DateTime<Utc>> {
+        if !category.is_retryable() || attempts >= self.max_attempts {
+            return None;
+        }
+        let exponent = attempts.saturating_sub(1).clamp(0, 31) as u32;
+        let delay = self
+            .base_delay
+            .saturating_mul(2_u32.saturating_pow(exponent))
+            .min(MAX_DELAY);
+        Some(now + chrono::Duration::from_std(delay).ok()?)
+    }
+}
+
+/// Returns the category of an error met resolving an atom, or `None` for
+/// errors of the indexer itself, e.g. of the database, that are not about
+/// the atom and are returned to the consumer loop instead
+pub fn resolution_error_category(e: &ConsumerError) -> Option<ResolutionErrorCategory> {
+    match e {
+        ConsumerError::TimeoutError(_) => Some(ResolutionErrorCategory::GatewayTimeout),
+        ConsumerError::Reqwest(e) if e.is_timeout() => {
+            Some(ResolutionErrorCategory::GatewayTimeout)
+        }
+        ConsumerError::Ipfs(_)
+        | ConsumerError::NetworkError(_)
+        | ConsumerError::FailedToGetBytes
+        | ConsumerError::Reqwest(_) => Some(ResolutionErrorCategory::NetworkError),
+        ConsumerError::InvalidUri(_) => Some(ResolutionErrorCategory::InvalidContent),
+        ConsumerError::ByteObjectError(_)
+        | ConsumerError::InvalidCaip2(_)
+        | ConsumerError::InvalidCaip10(_)
+        | ConsumerError::InvalidCaip19(_)
+        | ConsumerError::InvalidDid(_)
+        | ConsumerError::InvalidJson
+        | ConsumerError::SerdeJson(_)
+        | ConsumerError::Utf8(_) => Some(ResolutionErrorCategory::ParseError),
+        ConsumerError::AtomDataNotFound | ConsumerError::Strum(_) => {
+            Some(ResolutionErrorCategory::UnsupportedType)
+        }
+        ConsumerError::SharedUtils(e) => lib_error_category(e),
+        ConsumerError::ModelError(_)
+        | ConsumerError::SqlError(_)
+        | ConsumerError::PostgresConnectError(_)
+        | ConsumerError::AtomNotFound
+        | ConsumerError::AWSSendMessage(_) => None,
+        _ => Some(ResolutionErrorCategory::Other),
+    }
+}
+
+/// Returns the category of an error met fetching a document
+fn lib_error_category(e: &LibError) -> Option<ResolutionErrorCategory> {
+    match e {
+        LibError::TimeoutError(_) => Some(ResolutionErrorCategory::GatewayTimeout),
+        LibError::Reqwest(e) if e.is_timeout() => Some(ResolutionErrorCategory::GatewayTimeout),
+        LibError::NetworkError(_) | LibError::Reqwest(_) | LibError::ResourceNotFoundError(_) => {
+            Some(ResolutionErrorCategory::NetworkError)
+        }
+        LibError::ContentTooLarge(_)
+        | LibError::ForbiddenUrl(_)
+        | LibError::IntegrityError(_)
+        | LibError::InvalidCid(_) => Some(ResolutionErrorCategory::InvalidContent),
+        LibError::SerdeJson(_) => Some(ResolutionErrorCategory::ParseError),
+        LibError::Model(_) | LibError::PostgresConnectError(_) => None,
+        _ => Some(ResolutionErrorCategory::Other),
+    }
+}
+
+/// Records a failed resolution of an atom, scheduling its next attempt
+pub async fn record_resolution_failure(
+    resolver_consumer_context: &ResolverConsumerContext,
+    atom_id: &U256Wrapper,
+    category: ResolutionErrorCategory,
+    error: &str,
+) -> Result<AtomResolutionAttempt, ConsumerError> {
+    let pool = &resolver_consumer_context.pg_pool;
+    let schema = &resolver_consumer_context
+        .server_initialize
+        .env
+        .backend_schema;
+
+    let attempts = AtomResolutionAttempt::find_by_id(atom_id.clone(), pool, schema)
+        .await?
+        .map_or(0, |previous| previous.attempts)
+        + 1;
+    let now = Utc::now();
+    let next_retry_at = resolver_consumer_context
+        .retry_policy
+        .next_retry_at(attempts, category, now);
+    info!(
+        "Resolution of atom {} failed ({}, attempt {}), next retry: {:?}",
+        atom_id, category, attempts, next_retry_at
+    );
+
+    Ok(AtomResolutionAttempt::builder()
+        .atom_id(atom_id.clone())
+        .attempts(attempts)
+        .last_error_category(category)
+        .last_error(error.chars().take(MAX_ERROR_LENGTH).collect::<String>())
+        .last_attempt_at(now)
+        .next_retry_at(next_retry_at)
+        .build()
+        .upsert(pool, schema)
+        .await?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_next_retry_at() {
+        let policy = RetryPolicy::default();
+        let now = Utc::now();
+
+        assert_eq!(
+            policy.next_retry_at(1, ResolutionErrorCategory::GatewayTimeout, now),
+            Some(now + chrono::Duration::seconds(60))
+        );
+        assert_eq!(
+            policy.next_retry_at(3, ResolutionErrorCategory::NetworkError, now),
+            Some(now + chrono::Duration::seconds(240))
+        );
+        // Attempts are exhausted
+        assert_eq!(
+            policy.next_retry_at(5, ResolutionErrorCategory::GatewayTimeout, now),
+            None
+        );
+        // Retrying wouldn't help
+        assert_eq!(
+            policy.next_retry_at(1, ResolutionErrorCategory::ParseError, now),
+            None
+        );
+
+        let policy = RetryPolicy {
+            max_attempts: 100,
+            ..Default::default()
+        };
+        assert_eq!(
+            policy.next_retry_at(40, ResolutionErrorCategory::Other, now),
+            Some(now + chrono::Duration::from_std(MAX_DELAY).unwrap())
+        );
+    }
+
+    #[test]
+    fn test_resolution_error_category() {
+        assert_eq!(
+            resolution_error_category(&ConsumerError::TimeoutError("QmHash".to_string())),
+            Some(ResolutionErrorCategory::GatewayTimeout)
+        );
+        assert_eq!(
+            resolution_error_category(&ConsumerError::SharedUtils(LibError::ForbiddenUrl(
+                "https://127.0.0.1".to_string()
+            ))),
+            Some(ResolutionErrorCategory::InvalidContent)
+        );
+        assert_eq!(
+            resolution_error_category(&ConsumerError::InvalidJson),
+            Some(ResolutionErrorCategory::ParseError)
+        );
+        assert_eq!(
+            resolution_error_category(&ConsumerError::AtomNotFound),
+            None
+        );
+    }
+}
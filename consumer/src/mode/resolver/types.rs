@@ -1,13 +1,15 @@
 use crate::{
     error::ConsumerError,
     mode::{
-        decoded::atom::atom_supported_types::AtomMetadata,
+        decoded::{atom::atom_supported_types::AtomMetadataStore, utils::short_id},
         ipfs_upload::types::IpfsUploadMessage,
         resolver::{
             atom_resolver::{
-                handle_binary_data, try_to_parse_json_or_text, try_to_resolve_ipfs_uri,
+                handle_binary_data, handle_web_page, try_to_parse_json_or_text, try_to_resolve_uri,
             },
             ens_resolver::Ens,
+            retry::{record_resolution_failure, resolution_error_category},
+            web_page::is_html,
         },
         types::ResolverConsumerContext,
     },
@@ -15,11 +17,15 @@ use crate::{
 use alloy::primitives::Address;
 use models::{
     account::Account,
+    account_profile_change::AccountProfileChange,
     atom::{Atom, AtomType},
+    atom_resolution_attempt::{AtomResolutionAttempt, ResolutionErrorCategory},
     traits::SimpleCrud,
     types::U256Wrapper,
 };
+use reqwest::Url;
 use serde::{Deserialize, Serialize};
+use shared_utils::atom_metadata::AtomMetadata;
 use std::str::FromStr;
 use tracing::info;
 
@@ -59,80 +65,144 @@ impl ResolverMessageType {
             }
             ResolverMessageType::Account(account) => {
                 info!("Processing a resolved account: {account:?}");
-                self.process_account(resolver_consumer_context, &mut account.clone())
+                self.process_account(resolver_consumer_context, account)
                     .await
             }
         }
     }
 
-    /// This function processes an account message type
+    /// This function processes an account message type. The profile of the
+    /// account is resolved from its ENS name or Basename, and the account is
+    /// only updated when its profile changed. Accounts whose name was removed
+    /// get back their short address as label.
     async fn process_account(
         &self,
         resolver_consumer_context: &ResolverConsumerContext,
-        account: &mut Account,
+        account: &Account,
     ) -> Result<(), ConsumerError> {
+        let pool = &resolver_consumer_context.pg_pool;
+        let schema = &resolver_consumer_context
+            .server_initialize
+            .env
+            .backend_schema;
+        // The message may be older than the stored account, e.g. when the
+        // atom of the account was created in the meantime
+        let account = Account::find_by_id(account.id.clone(), pool, schema)
+            .await?
+            .ok_or(ConsumerError::AccountNotFound)?;
+
         let ens = Ens::get_ens(Address::from_str(&account.id)?, resolver_consumer_context).await?;
-        if let Some(_name) = ens.name.clone() {
-            info!("ENS for account: {:?}", ens);
-            // We need to update the account metadata
-            self.update_account_metadata(
-                resolver_consumer_context,
-                account.id.clone(),
-                ens.clone(),
-            )
-            .await?;
-            // We also need to update the atom
-            if let Some(atom_id) = account.atom_id.clone() {
-                self.update_atom_metadata(resolver_consumer_context, &atom_id, ens)
+        info!("ENS for account: {:?}", ens);
+        let profile = Account {
+            label: ens.name.unwrap_or_else(|| short_id(&account.id)),
+            image: ens.image,
+            description: ens.description,
+            url: ens.url,
+            twitter: ens.twitter,
+            github: ens.github,
+            ..account.clone()
+        };
+
+        let changes = Self::profile_changes(&account, &profile);
+        if changes.is_empty() {
+            info!("Profile of account {} is unchanged", account.id);
+        } else {
+            info!("Profile of account {} changed: {:?}", account.id, changes);
+            profile.upsert(pool, schema).await?;
+            for (field, previous_value, value) in changes {
+                AccountProfileChange::builder()
+                    .account_id(account.id.clone())
+                    .field(field)
+                    .previous_value(previous_value)
+                    .value(value)
+                    .build()
+                    .insert(pool, schema)
                     .await?;
-            } else {
-                // We deal with the case where the account atom_id was not set
-                // when the account was created. In this case, we need to query the DB
-                // to find the atom_id, as this update happens in another consumer
-                info!(
-                    "No atom found for account: {:?}, querying the DB...",
-                    account
-                );
-                let account = Account::find_by_id(
-                    account.id.clone(),
-                    &resolver_consumer_context.pg_pool,
-                    &resolver_consumer_context
-                        .server_initialize
-                        .env
-                        .backend_schema,
-                )
-                .await?
-                .ok_or(ConsumerError::AccountNotFound)?;
-                if let Some(atom_id) = account.atom_id {
-                    self.update_atom_metadata(resolver_consumer_context, &atom_id, ens)
+            }
+
+            if profile.image != account.image {
+                if let Some(image) = profile.image.clone() {
+                    self.handle_atom_image(resolver_consumer_context, image)
+                        .await?;
+                }
+            }
+            if profile.label != account.label || profile.image != account.image {
+                if let Some(atom_id) = &profile.atom_id {
+                    self.update_atom_metadata(resolver_consumer_context, atom_id, &profile)
                         .await?;
-                } else {
-                    info!("No atom found for account: {:?}", account);
                 }
             }
-        } else {
-            info!("No ENS found for account: {:?}", account);
         }
+
+        Account::mark_profile_resolved(&account.id, pool, schema).await?;
         Ok(())
     }
 
-    /// This function processes an atom message type
+    /// This function returns the fields of the profile of an account that
+    /// changed, with their previous and new values
+    fn profile_changes(
+        account: &Account,
+        profile: &Account,
+    ) -> Vec<(&'static str, Option<String>, Option<String>)> {
+        [
+            (
+                "label",
+                Some(account.label.clone()),
+                Some(profile.label.clone()),
+            ),
+            ("image", account.image.clone(), profile.image.clone()),
+            (
+                "description",
+                account.description.clone(),
+                profile.description.clone(),
+            ),
+            ("url", account.url.clone(), profile.url.clone()),
+            ("twitter", account.twitter.clone(), profile.twitter.clone()),
+            ("github", account.github.clone(), profile.github.clone()),
+        ]
+        .into_iter()
+        .filter(|(_, previous_value, value)| previous_value != value)
+        .collect()
+    }
+
+    /// This function processes an atom message type. Atoms that can't be
+    /// resolved are marked as failed, and their failure is recorded so
+    /// the resolution can be retried later.
     async fn process_atom(
         &self,
         resolver_consumer_context: &ResolverConsumerContext,
         atom_id: &str,
     ) -> Result<(), ConsumerError> {
-        let metadata = self
+        match self
             .resolve_and_parse_atom_data(resolver_consumer_context, atom_id)
-            .await?;
-
-        // If the atom type is not unknown, we handle the new atom type that was resolved
-        if AtomType::from_str(&metadata.atom_type)? != AtomType::Unknown {
-            self.handle_known_atom_type(resolver_consumer_context, atom_id, metadata)
-                .await?;
-        } else {
-            self.mark_atom_as_failed(resolver_consumer_context, &U256Wrapper::from_str(atom_id)?)
+            .await
+        {
+            // If the atom type is not unknown, we handle the new atom type that was resolved
+            Ok(metadata) if AtomType::from_str(&metadata.atom_type)? != AtomType::Unknown => {
+                self.handle_known_atom_type(resolver_consumer_context, atom_id, metadata)
+                    .await?;
+            }
+            Ok(_) => {
+                self.mark_atom_as_failed(
+                    resolver_consumer_context,
+                    &U256Wrapper::from_str(atom_id)?,
+                    ResolutionErrorCategory::UnsupportedType,
+                    "The atom data is not of a supported type",
+                )
                 .await?;
+            }
+            Err(e) => match resolution_error_category(&e) {
+                Some(category) => {
+                    self.mark_atom_as_failed(
+                        resolver_consumer_context,
+                        &U256Wrapper::from_str(atom_id)?,
+                        category,
+                        &e.to_string(),
+                    )
+                    .await?;
+                }
+                None => return Err(e),
+            },
         }
         Ok(())
     }
@@ -154,51 +224,39 @@ impl ResolverMessageType {
         .await?
         .ok_or(ConsumerError::AtomDataNotFound)?;
 
-        // We check if the atom data is an IPFS URI and if it is, we fetch the data from the IPFS node
-        let data = try_to_resolve_ipfs_uri(
-            &atom.clone().data.ok_or(ConsumerError::AtomDataNotFound)?,
-            resolver_consumer_context,
-        )
-        .await?;
+        // We check if the atom data is an URI and if it is, we fetch the document it points to
+        let atom_data = atom.clone().data.ok_or(ConsumerError::AtomDataNotFound)?;
+        let data = try_to_resolve_uri(&atom_data, resolver_consumer_context).await?;
         // let text = data.text().await;
         // let bytes = data.bytes().await;
 
-        // This is the case where we receive a response from the IPFS node, but we dont know yet
-        // if the response is a JSON or a binary file.
+        // This is the case where we fetched the document, but we dont know yet
+        // if it is a JSON or a binary file.
         if let Some(data) = data {
-            info!("Atom data is an IPFS URI and we have a response from the IPFS node");
-            // First we try to decode the response as bytes
-            match data.bytes().await {
-                Ok(bytes) => {
-                    // Try to convert bytes to text
-                    match String::from_utf8(bytes.to_vec()) {
-                        Ok(text) => {
-                            info!("Trying to get text from {}", text);
-                            let data = text.replace('\u{feff}', "");
-                            try_to_parse_json_or_text(&data, &atom, resolver_consumer_context).await
-                        }
-                        Err(_) => {
-                            info!("Failed to parse as text, trying to parse atom data as Binary");
-                            handle_binary_data(resolver_consumer_context, &atom, bytes).await
-                        }
-                    }
+            info!("Atom data is an URI and we fetched the document it points to");
+            // Web pages are only fetched for `https://` links, and are stored
+            // with the metadata they declare
+            let url = atom_data.trim();
+            if is_html(&data) && Url::parse(url).is_ok_and(|url| url.scheme() == "https") {
+                return handle_web_page(resolver_consumer_context, &atom, url, &data).await;
+            }
+            let bytes = data.data;
+            // Try to convert bytes to text
+            match String::from_utf8(bytes.to_vec()) {
+                Ok(text) => {
+                    info!("Trying to get text from {}", text);
+                    let data = text.replace('\u{feff}', "");
+                    try_to_parse_json_or_text(&data, &atom, resolver_consumer_context).await
                 }
-                Err(e) => {
-                    info!("Failed to get bytes from IPFS response: {e}");
-                    Err(ConsumerError::FailedToGetBytes)
+                Err(_) => {
+                    info!("Failed to parse as text, trying to parse atom data as Binary");
+                    handle_binary_data(resolver_consumer_context, &atom, bytes).await
                 }
             }
-        // This is the case where the atom data is not an IPFS URI, so we try to parse it as JSON
+        // This is the case where the atom data is not an URI, so we try to parse it as JSON
         } else {
-            info!(
-                "No IPFS URI found or IPFS URI is not valid, trying to parse atom data as JSON or text..."
-            );
-            try_to_parse_json_or_text(
-                &atom.clone().data.ok_or(ConsumerError::AtomDataNotFound)?,
-                &atom,
-                resolver_consumer_context,
-            )
-            .await
+            info!("No URI found or URI is not valid, trying to parse atom data as JSON or text...");
+            try_to_parse_json_or_text(&atom_data, &atom, resolver_consumer_context).await
         }
     }
 
@@ -226,6 +284,16 @@ impl ResolverMessageType {
                 .backend_schema,
         )
         .await?;
+        // A later failure starts its backoff from the first attempt again
+        AtomResolutionAttempt::delete(
+            &atom.id,
+            &resolver_consumer_context.pg_pool,
+            &resolver_consumer_context
+                .server_initialize
+                .env
+                .backend_schema,
+        )
+        .await?;
 
         info!("Updated atom metadata: {atom:?}");
         Ok(())
@@ -277,11 +345,13 @@ impl ResolverMessageType {
             .await
     }
 
-    /// This function marks the atom as failed
+    /// This function marks the atom as failed and records the failure
     async fn mark_atom_as_failed(
         &self,
         resolver_consumer_context: &ResolverConsumerContext,
         atom_id: &U256Wrapper,
+        category: ResolutionErrorCategory,
+        error: &str,
     ) -> Result<(), ConsumerError> {
         let atom = Atom::find_by_id(
             atom_id.clone(),
@@ -300,47 +370,17 @@ impl ResolverMessageType {
                 .env
                 .backend_schema,
         )
-        .await
-        .map_err(ConsumerError::from)
-    }
-
-    /// This function updates the account metadata
-    async fn update_account_metadata(
-        &self,
-        resolver_consumer_context: &ResolverConsumerContext,
-        account_id: String,
-        ens: Ens,
-    ) -> Result<(), ConsumerError> {
-        let mut account = Account::find_by_id(
-            account_id,
-            &resolver_consumer_context.pg_pool,
-            &resolver_consumer_context
-                .server_initialize
-                .env
-                .backend_schema,
-        )
-        .await?
-        .ok_or(ConsumerError::AccountNotFound)?;
-        account.label = ens.name.ok_or(ConsumerError::LabelNotFound)?;
-        account.image = ens.image;
-        account
-            .upsert(
-                &resolver_consumer_context.pg_pool,
-                &resolver_consumer_context
-                    .server_initialize
-                    .env
-                    .backend_schema,
-            )
-            .await?;
+        .await?;
+        record_resolution_failure(resolver_consumer_context, atom_id, category, error).await?;
         Ok(())
     }
 
-    /// This function updates the atom metadata
+    /// This function updates the label and image of the atom of an account
     async fn update_atom_metadata(
         &self,
         resolver_consumer_context: &ResolverConsumerContext,
         atom_id: &U256Wrapper,
-        ens: Ens,
+        account: &Account,
     ) -> Result<(), ConsumerError> {
         let mut atom = Atom::find_by_id(
             atom_id.clone(),
@@ -352,8 +392,8 @@ impl ResolverMessageType {
         )
         .await?
         .ok_or(ConsumerError::AtomNotFound)?;
-        atom.label = ens.name;
-        atom.image = ens.image;
+        atom.label = Some(account.label.clone());
+        atom.image = account.image.clone();
         atom.upsert(
             &resolver_consumer_context.pg_pool,
             &resolver_consumer_context
@@ -0,0 +1,320 @@
+use crate::{error::ConsumerError, mode::resolver::web_page::is_html};
+use async_trait::async_trait;
+use base64::{engine::general_purpose::STANDARD, Engine};
+use serde_json::Value;
+use shared_utils::{
+    content_cache::ContentCache, ipfs::IPFSResolver, safe_fetch::SafeFetcher, types::FetchedContent,
+};
+use std::{collections::HashMap, sync::Arc};
+use tracing::{info, warn};
+
+/// The default Arweave gateway
+pub const ARWEAVE_GATEWAY_URL: &str = "https://arweave.net";
+/// The media type of data URIs that don't specify one
+const DEFAULT_DATA_URI_MEDIA_TYPE: &str = "text/plain;charset=US-ASCII";
+
+/// Fetches the document an atom URI points to
+#[async_trait]
+pub trait UriHandler: Send + Sync {
+    /// Resolves an URI with the scheme of the handler
+    async fn resolve(&self, uri: &str) -> Result<FetchedContent, ConsumerError>;
+
+    /// Returns true if an URI the handler failed to resolve is kept as text
+    /// instead of failing the resolution of the atom
+    fn keeps_failures_as_text(&self) -> bool {
+        false
+    }
+}
+
+/// Resolves atom URIs with the handler registered for their scheme
+#[derive(Clone, Default)]
+pub struct UriResolver {
+    handlers: HashMap<String, Arc<dyn UriHandler>>,
+}
+
+impl UriResolver {
+    /// Registers the handler of a scheme, replacing any previous one
+    pub fn register(mut self, scheme: &str, handler: impl UriHandler + 'static) -> Self {
+        self.handlers
+            .insert(scheme.to_ascii_lowercase(), Arc::new(handler));
+        self
+    }
+
+    /// Creates a resolver for the `ipfs://`, `ipns://`, `ar://`, `https://`
+    /// and `data:` URIs
+    pub fn with_default_handlers(
+        ipfs_resolver: IPFSResolver,
+        content_cache: Option<ContentCache>,
+        arweave_gateway_url: String,
+    ) -> Result<Self, ConsumerError> {
+        let fetcher = SafeFetcher::from_ipfs_resolver(&ipfs_resolver)?;
+        Ok(Self::default()
+            .register(
+                "ipfs",
+                IpfsHandler {
+                    ipfs_resolver: ipfs_resolver.clone(),
+                    content_cache,
+                },
+            )
+            .register("ipns", IpnsHandler { ipfs_resolver })
+            .register(
+                "ar",
+                ArweaveHandler {
+                    fetcher: fetcher.clone(),
+                    gateway_url: arweave_gateway_url,
+                },
+            )
+            .register("https", HttpsHandler { fetcher })
+            .register("data", DataUriHandler))
+    }
+
+    /// Returns the handler registered for the scheme of an URI
+    fn handler(&self, uri: &str) -> Option<&Arc<dyn UriHandler>> {
+        let (scheme, _) = uri.split_once(':')?;
+        self.handlers.get(&scheme.to_ascii_lowercase())
+    }
+
+    /// Resolves an URI. Returns `None` if no handler is registered for its
+    /// scheme, in which case the URI is treated as inline data, and so are
+    /// the failures of handlers keeping them as text.
+    pub async fn resolve(&self, uri: &str) -> Result<Option<FetchedContent>, ConsumerError> {
+        let Some(handler) = self.handler(uri.trim()) else {
+            return Ok(None);
+        };
+        match handler.resolve(uri.trim()).await {
+            Ok(content) => Ok(Some(content)),
+            Err(e) if handler.keeps_failures_as_text() => {
+                warn!("Failed to resolve {}, keeping it as text: {}", uri, e);
+                Ok(None)
+            }
+            Err(e) => {
+                warn!("Failed to resolve {}: {}", uri, e);
+                Err(e)
+            }
+        }
+    }
+}
+
+/// Returns the part of an URI after `<scheme>://`
+fn strip_scheme<'a>(uri: &'a str, scheme: &str) -> Result<&'a str, ConsumerError> {
+    uri.get(..scheme.len() + 3)
+        .filter(|prefix| prefix.eq_ignore_ascii_case(&format!("{}://", scheme)))
+        .map(|_| &uri[scheme.len() + 3..])
+        .filter(|rest| !rest.is_empty())
+        .ok_or_else(|| ConsumerError::InvalidUri(uri.to_string()))
+}
+
+/// Resolves `ipfs://<cid>[/<path>]` URIs, reading from the content cache
+/// first. CIDs are immutable, so cached content never needs to be fetched
+/// again.
+struct IpfsHandler {
+    ipfs_resolver: IPFSResolver,
+    content_cache: Option<ContentCache>,
+}
+
+#[async_trait]
+impl UriHandler for IpfsHandler {
+    async fn resolve(&self, uri: &str) -> Result<FetchedContent, ConsumerError> {
+        let cid = strip_scheme(uri, "ipfs")?;
+
+        if let Some(cache) = &self.content_cache {
+            match cache.get(cid).await {
+                Ok(Some(cached)) => {
+                    info!("Resolved {} from the content cache", cid);
+                    return Ok(cached);
+                }
+                Ok(None) => {}
+                Err(e) => warn!("Failed to read {} from the content cache: {}", cid, e),
+            }
+        }
+
+        let content = self.ipfs_resolver.fetch_from_ipfs(cid).await?;
+        if let Some(cache) = &self.content_cache {
+            if let Err(e) = cache.put(cid, &content).await {
+                warn!("Failed to cache {}: {}", cid, e);
+            }
+        }
+        Ok(content)
+    }
+}
+
+/// Resolves `ipns://<name>[/<path>]` URIs through the IPFS gateways. IPNS
+/// names are mutable, so their content is not cached.
+struct IpnsHandler {
+    ipfs_resolver: IPFSResolver,
+}
+
+#[async_trait]
+impl UriHandler for IpnsHandler {
+    async fn resolve(&self, uri: &str) -> Result<FetchedContent, ConsumerError> {
+        let name = strip_scheme(uri, "ipns")?;
+        Ok(self.ipfs_resolver.fetch_from_ipns(name).await?)
+    }
+}
+
+/// Resolves `ar://<transaction id>[/<path>]` URIs through an Arweave gateway
+struct ArweaveHandler {
+    fetcher: SafeFetcher,
+    gateway_url: String,
+}
+
+#[async_trait]
+impl UriHandler for ArweaveHandler {
+    async fn resolve(&self, uri: &str) -> Result<FetchedContent, ConsumerError> {
+        let path = strip_scheme(uri, "ar")?;
+        // Transaction ids are 32 bytes, base64url encoded
+        let tx_id = path.split('/').next().unwrap_or_default();
+        if tx_id.len() != 43
+            || !tx_id
+                .bytes()
+                .all(|b| b.is_ascii_alphanumeric() || b == b'-' || b == b'_')
+        {
+            return Err(ConsumerError::InvalidUri(uri.to_string()));
+        }
+        let url = format!("{}/{}", self.gateway_url.trim_end_matches('/'), path);
+        Ok(self.fetcher.fetch(&url).await?)
+    }
+}
+
+/// Resolves `https://` URIs pointing to a JSON document or a web page, only
+/// reaching public addresses. Links to anything else, e.g. images, are kept
+/// as text atoms.
+struct HttpsHandler {
+    fetcher: SafeFetcher,
+}
+
+#[async_trait]
+impl UriHandler for HttpsHandler {
+    async fn resolve(&self, uri: &str) -> Result<FetchedContent, ConsumerError> {
+        let content = self.fetcher.fetch(uri).await?;
+        let body = content
+            .data
+            .strip_prefix(b"\xef\xbb\xbf")
+            .unwrap_or(&content.data);
+        if !is_html(&content) && serde_json::from_slice::<Value>(body).is_err() {
+            return Err(ConsumerError::InvalidJson);
+        }
+        Ok(content)
+    }
+
+    fn keeps_failures_as_text(&self) -> bool {
+        true
+    }
+}
+
+/// Decodes `data:[<media type>][;base64],<data>` URIs
+struct DataUriHandler;
+
+#[async_trait]
+impl UriHandler for DataUriHandler {
+    async fn resolve(&self, uri: &str) -> Result<FetchedContent, ConsumerError> {
+        parse_data_uri(uri).ok_or_else(|| ConsumerError::InvalidUri(uri.to_string()))
+    }
+}
+
+/// Parses a data URI, as defined by RFC 2397
+fn parse_data_uri(uri: &str) -> Option<FetchedContent> {
+    let (scheme, rest) = uri.split_once(':')?;
+    if !scheme.eq_ignore_ascii_case("data") {
+        return None;
+    }
+    let (meta, data) = rest.split_once(',')?;
+
+    let (media_type, is_base64) = match meta.rsplit_once(';') {
+        Some((media_type, param)) if param.eq_ignore_ascii_case("base64") => (media_type, true),
+        _ => (meta, false),
+    };
+    let data = if is_base64 {
+        let data = percent_decode(data)?
+            .into_iter()
+            .filter(|b| !b.is_ascii_whitespace())
+            .collect::<Vec<_>>();
+        STANDARD.decode(data).ok()?
+    } else {
+        percent_decode(data)?
+    };
+
+    let content_type = match media_type {
+        "" => DEFAULT_DATA_URI_MEDIA_TYPE.to_string(),
+        media_type if media_type.starts_with(';') => format!("text/plain{}", media_type),
+        media_type => media_type.to_string(),
+    };
+    Some(FetchedContent {
+        data: data.into(),
+        content_type: Some(content_type),
+    })
+}
+
+/// Decodes the `%XX` escapes of an URI component
+fn percent_decode(value: &str) -> Option<Vec<u8>> {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            let hex = std::str::from_utf8(bytes.get(i + 1..i + 3)?).ok()?;
+            decoded.push(u8::from_str_radix(hex, 16).ok()?);
+            i += 3;
+        } else {
+            decoded.push(bytes[i]);
+            i += 1;
+        }
+    }
+    Some(decoded)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_data_uri() {
+        let content =
+            parse_data_uri("data:application/json;base64,eyJuYW1lIjoiQWxpY2UifQ==").unwrap();
+        assert_eq!(content.content_type.as_deref(), Some("application/json"));
+        assert_eq!(&content.data[..], br#"{"name":"Alice"}"#);
+
+        let content = parse_data_uri("data:,Hello%2C%20World").unwrap();
+        assert_eq!(
+            content.content_type.as_deref(),
+            Some(DEFAULT_DATA_URI_MEDIA_TYPE)
+        );
+        assert_eq!(&content.data[..], b"Hello, World");
+
+        let content = parse_data_uri("data:;charset=utf-8,caf%C3%A9").unwrap();
+        assert_eq!(
+            content.content_type.as_deref(),
+            Some("text/plain;charset=utf-8")
+        );
+        assert_eq!(&content.data[..], "café".as_bytes());
+
+        assert!(parse_data_uri("data:text/plain;base64,not base64!").is_none());
+        assert!(parse_data_uri("data:no-comma").is_none());
+        assert!(parse_data_uri("data:,bad%zz").is_none());
+    }
+
+    #[test]
+    fn test_strip_scheme() {
+        assert_eq!(
+            strip_scheme("ipfs://Qm/a.json", "ipfs").unwrap(),
+            "Qm/a.json"
+        );
+        assert_eq!(strip_scheme("AR://tx", "ar").unwrap(), "tx");
+        assert!(strip_scheme("ipfs://", "ipfs").is_err());
+        assert!(strip_scheme("ipfs:Qm", "ipfs").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_unknown_scheme_is_not_resolved() {
+        let resolver = UriResolver::default().register("data", DataUriHandler);
+        assert!(resolver
+            .resolve("ftp://example.com/atom.json")
+            .await
+            .unwrap()
+            .is_none());
+        assert!(resolver.resolve("just some text").await.unwrap().is_none());
+        assert!(resolver.resolve("data:,atom").await.unwrap().is_some());
+        // Data URIs can't be resolved again, so they fail the atom
+        assert!(resolver.resolve("data:no-comma").await.is_err());
+    }
+}
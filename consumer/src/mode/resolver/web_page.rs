@@ -0,0 +1,368 @@
+use regex::Regex;
+use reqwest::Url;
+use serde::Deserialize;
+use shared_utils::types::FetchedContent;
+use std::{collections::HashMap, sync::LazyLock};
+
+/// The media type of the oEmbed documents we read
+const OEMBED_JSON_TYPE: &str = "application/json+oembed";
+/// How many bytes of a page are scanned for metadata. Pages declare it in
+/// their `<head>`, so there is no need to go through the whole body.
+const MAX_SCANNED_BYTES: usize = 512 << 10;
+
+/// Matches `<meta>` and `<link>` tags, capturing their name and attributes
+static TAG_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?is)<(meta|link)\b([^>]*)>").expect("the tag regex is valid"));
+/// Matches quoted and unquoted attributes of a tag
+static ATTRIBUTE_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"(?s)([a-zA-Z_:.-]+)\s*=\s*(?:"([^"]*)"|'([^']*)'|([^\s"'>]+))"#)
+        .expect("the attribute regex is valid")
+});
+/// Matches the `<title>` of a page
+static TITLE_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?is)<title\b[^>]*>(.*?)</title>").expect("the title regex is valid")
+});
+
+/// The metadata a page declares about itself, with OpenGraph properties
+/// taking precedence over Twitter cards and plain HTML
+#[derive(Debug, Default, PartialEq)]
+pub struct PageMetadata {
+    pub canonical_url: Option<String>,
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub image: Option<String>,
+    pub site_name: Option<String>,
+    pub favicon: Option<String>,
+    /// The oEmbed endpoint of the page, if it advertises one
+    pub oembed_url: Option<String>,
+}
+
+/// The fields of an oEmbed response we use to fill in missing metadata
+#[derive(Debug, Deserialize)]
+pub struct OEmbed {
+    pub title: Option<String>,
+    pub thumbnail_url: Option<String>,
+    pub provider_name: Option<String>,
+}
+
+impl PageMetadata {
+    /// Extracts the metadata of an HTML page. Relative URLs are resolved
+    /// against the URL of the page, and only `http(s)` URLs are kept.
+    pub fn parse(html: &str, page_url: &Url) -> Self {
+        let head = head_of(html);
+        let mut meta = HashMap::new();
+        let mut canonical_url = None;
+        let mut favicon = None;
+        let mut oembed_url = None;
+
+        for tag in TAG_REGEX.captures_iter(head) {
+            let attributes = parse_attributes(&tag[2]);
+            if tag[1].eq_ignore_ascii_case("meta") {
+                let (Some(key), Some(content)) = (
+                    attributes
+                        .get("property")
+                        .or_else(|| attributes.get("name")),
+                    attributes.get("content"),
+                ) else {
+                    continue;
+                };
+                meta.entry(key.to_ascii_lowercase())
+                    .or_insert_with(|| content.clone());
+                continue;
+            }
+
+            let (Some(rel), Some(href)) = (attributes.get("rel"), attributes.get("href")) else {
+                continue;
+            };
+            let rel = rel.to_ascii_lowercase();
+            let rels = rel.split_ascii_whitespace().collect::<Vec<_>>();
+            if rels.contains(&"canonical") {
+                canonical_url = canonical_url.or_else(|| absolute_url(page_url, href));
+            } else if rels.contains(&"icon") || rels.contains(&"apple-touch-icon") {
+                favicon = favicon.or_else(|| absolute_url(page_url, href));
+            } else if rels.contains(&"alternate")
+                && attributes
+                    .get("type")
+                    .is_some_and(|t| t.eq_ignore_ascii_case(OEMBED_JSON_TYPE))
+            {
+                oembed_url = oembed_url.or_else(|| absolute_url(page_url, href));
+            }
+        }
+
+        let first = |keys: &[&str]| {
+            keys.iter()
+                .filter_map(|key| meta.get(*key))
+                .map(|value| clean_text(value))
+                .find(|value| !value.is_empty())
+        };
+        let html_title = TITLE_REGEX
+            .captures(head)
+            .map(|title| clean_text(&title[1]))
+            .filter(|title| !title.is_empty());
+
+        Self {
+            canonical_url: canonical_url
+                .or_else(|| first(&["og:url"]).and_then(|url| absolute_url(page_url, &url))),
+            title: first(&["og:title", "twitter:title"]).or(html_title),
+            description: first(&["og:description", "twitter:description", "description"]),
+            // The first image with an http(s) URL is kept
+            image: [
+                "og:image:secure_url",
+                "og:image",
+                "og:image:url",
+                "twitter:image",
+                "twitter:image:src",
+            ]
+            .iter()
+            .filter_map(|key| meta.get(*key))
+            .find_map(|image| absolute_url(page_url, image)),
+            site_name: first(&["og:site_name", "application-name"]),
+            favicon: favicon.or_else(|| page_url.join("/favicon.ico").ok().map(String::from)),
+            oembed_url,
+        }
+    }
+
+    /// Fills the fields the page didn't declare with its oEmbed response
+    pub fn merge_oembed(&mut self, oembed: OEmbed) {
+        let non_empty = |value: Option<String>| {
+            value
+                .map(|value| clean_text(&value))
+                .filter(|value| !value.is_empty())
+        };
+        self.title = self.title.take().or_else(|| non_empty(oembed.title));
+        self.image = self.image.take().or_else(|| {
+            non_empty(oembed.thumbnail_url)
+                .and_then(|image| Url::parse(&image).ok())
+                .filter(|image| matches!(image.scheme(), "http" | "https"))
+                .map(String::from)
+        });
+        self.site_name = self
+            .site_name
+            .take()
+            .or_else(|| non_empty(oembed.provider_name));
+    }
+
+    /// Returns true if the metadata is missing something oEmbed could provide
+    pub fn is_incomplete(&self) -> bool {
+        self.title.is_none() || self.image.is_none() || self.site_name.is_none()
+    }
+}
+
+/// Returns true if the fetched document is an HTML page
+pub fn is_html(content: &FetchedContent) -> bool {
+    match content.content_type.as_deref() {
+        Some(content_type) => {
+            let media_type = content_type
+                .split(';')
+                .next()
+                .unwrap_or_default()
+                .trim()
+                .to_ascii_lowercase();
+            media_type == "text/html" || media_type == "application/xhtml+xml"
+        }
+        None => {
+            let start = String::from_utf8_lossy(&content.data[..content.data.len().min(1024)])
+                .trim_start()
+                .to_ascii_lowercase();
+            start.starts_with("<!doctype html") || start.starts_with("<html")
+        }
+    }
+}
+
+/// Returns the part of a page where metadata is declared
+fn head_of(html: &str) -> &str {
+    let mut end = html.len().min(MAX_SCANNED_BYTES);
+    while !html.is_char_boundary(end) {
+        end -= 1;
+    }
+    let html = &html[..end];
+    let lowercase = html.to_ascii_lowercase();
+    match lowercase
+        .find("</head>")
+        .or_else(|| lowercase.find("<body"))
+    {
+        Some(end) => &html[..end],
+        None => html,
+    }
+}
+
+/// Parses the attributes of a tag, with lowercase names
+fn parse_attributes(attributes: &str) -> HashMap<String, String> {
+    ATTRIBUTE_REGEX
+        .captures_iter(attributes)
+        .filter_map(|attribute| {
+            let value = attribute
+                .get(2)
+                .or_else(|| attribute.get(3))
+                .or_else(|| attribute.get(4))?;
+            Some((
+                attribute[1].to_ascii_lowercase(),
+                decode_entities(value.as_str()),
+            ))
+        })
+        .collect()
+}
+
+/// Resolves an URL against the URL of the page, keeping only `http(s)` URLs
+fn absolute_url(page_url: &Url, url: &str) -> Option<String> {
+    page_url
+        .join(url.trim())
+        .ok()
+        .filter(|url| matches!(url.scheme(), "http" | "https"))
+        .map(String::from)
+}
+
+/// Decodes the entities of the text of a page and collapses its whitespace
+fn clean_text(text: &str) -> String {
+    decode_entities(text)
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Decodes the named entities common in page metadata and numeric character
+/// references. Unknown entities are left as is.
+fn decode_entities(text: &str) -> String {
+    let mut decoded = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('&') {
+        decoded.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let entity = rest[1..]
+            .find(';')
+            .filter(|end| *end <= 10)
+            .map(|end| &rest[1..end + 1]);
+        let character = entity.and_then(|entity| match entity {
+            "amp" => Some('&'),
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            "nbsp" => Some(' '),
+            _ => entity
+                .strip_prefix("#x")
+                .or_else(|| entity.strip_prefix("#X"))
+                .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+                .or_else(|| entity.strip_prefix('#').and_then(|dec| dec.parse().ok()))
+                .and_then(char::from_u32),
+        });
+        match (entity, character) {
+            (Some(entity), Some(character)) => {
+                decoded.push(character);
+                rest = &rest[entity.len() + 2..];
+            }
+            _ => {
+                decoded.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    decoded.push_str(rest);
+    decoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PAGE: &str = r#"<!DOCTYPE html>
+<html>
+<head>
+  <title>Fallback &amp; Title</title>
+  <meta name="description" content="Plain description">
+  <meta property="og:title" content="  The   Post &#8212; Blog ">
+  <meta property="og:image" content="/images/preview.png">
+  <meta name='twitter:image' content='https://cdn.example.com/card.png'>
+  <meta property="og:site_name" content="Example">
+  <link rel="canonical" href="https://example.com/post">
+  <link rel="shortcut icon" href="/static/icon.png">
+  <link rel="alternate" type="application/json+oembed" href="https://example.com/oembed?url=post">
+</head>
+<body><meta property="og:title" content="Not in the head"></body>
+</html>"#;
+
+    #[test]
+    fn test_parse_page_metadata() {
+        let url = Url::parse("https://example.com/post?utm_source=feed").unwrap();
+        let metadata = PageMetadata::parse(PAGE, &url);
+
+        assert_eq!(
+            metadata,
+            PageMetadata {
+                canonical_url: Some("https://example.com/post".to_string()),
+                title: Some("The Post — Blog".to_string()),
+                description: Some("Plain description".to_string()),
+                image: Some("https://example.com/images/preview.png".to_string()),
+                site_name: Some("Example".to_string()),
+                favicon: Some("https://example.com/static/icon.png".to_string()),
+                oembed_url: Some("https://example.com/oembed?url=post".to_string()),
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_page_metadata_fallbacks() {
+        let url = Url::parse("https://example.com/a/b").unwrap();
+        let metadata = PageMetadata::parse(
+            "<html><head><title>Only a title</title>\
+             <meta name=twitter:image content=card.png>\
+             <meta property=\"og:image\" content=\"javascript:alert(1)\"></head></html>",
+            &url,
+        );
+
+        assert_eq!(metadata.canonical_url, None);
+        assert_eq!(metadata.title, Some("Only a title".to_string()));
+        // Non http(s) images are skipped
+        assert_eq!(
+            metadata.image,
+            Some("https://example.com/a/card.png".to_string())
+        );
+        assert_eq!(
+            metadata.favicon,
+            Some("https://example.com/favicon.ico".to_string())
+        );
+        assert!(metadata.is_incomplete());
+    }
+
+    #[test]
+    fn test_merge_oembed() {
+        let mut metadata = PageMetadata {
+            title: Some("Declared title".to_string()),
+            ..Default::default()
+        };
+        metadata.merge_oembed(OEmbed {
+            title: Some("oEmbed title".to_string()),
+            thumbnail_url: Some("https://i.example.com/thumb.jpg".to_string()),
+            provider_name: Some("Example Video".to_string()),
+        });
+
+        assert_eq!(metadata.title, Some("Declared title".to_string()));
+        assert_eq!(
+            metadata.image,
+            Some("https://i.example.com/thumb.jpg".to_string())
+        );
+        assert_eq!(metadata.site_name, Some("Example Video".to_string()));
+        assert!(!metadata.is_incomplete());
+    }
+
+    #[test]
+    fn test_is_html() {
+        let content = |content_type: Option<&str>, data: &'static str| FetchedContent {
+            data: data.into(),
+            content_type: content_type.map(str::to_string),
+        };
+
+        assert!(is_html(&content(Some("text/html; charset=utf-8"), "")));
+        assert!(!is_html(&content(Some("application/json"), "{}")));
+        assert!(is_html(&content(None, "\n<!DOCTYPE html><html></html>")));
+        assert!(!is_html(&content(None, "{\"name\": \"Alice\"}")));
+    }
+
+    #[test]
+    fn test_decode_entities() {
+        assert_eq!(
+            decode_entities("Tom &amp; Jerry&#39;s &#x1F600; &unknown; & more"),
+            "Tom & Jerry's 😀 &unknown; & more"
+        );
+    }
+}
@@ -5,6 +5,7 @@ use crate::{
     error::ConsumerError,
     schemas::types::DecodedMessage,
     traits::BasicConsumer,
+    ChainlinkAggregator,
     ENSRegistry::{self, ENSRegistryInstance},
     EthMultiVault::{self, EthMultiVaultEvents, EthMultiVaultInstance},
 };
@@ -14,17 +15,35 @@ use alloy::{
     providers::{Provider, ProviderBuilder, RootProvider},
     transports::http::Http,
 };
-use models::{stats::Stats, types::U256Wrapper};
+use models::{relevance_score::DEFAULT_HALF_LIFE_SECS, stats::Stats, types::U256Wrapper};
 use once_cell::sync::OnceCell;
 use prometheus::{register_histogram_vec, HistogramVec};
 use reqwest::Client;
-use shared_utils::{ipfs::IPFSResolver, postgres::connect_to_db};
+use shared_utils::{
+    content_cache::ContentCache, ipfs::IPFSResolver, ipfs_gateway::GatewayPool,
+    postgres::connect_to_db, safe_fetch::SafeFetcher,
+};
 use sqlx::PgPool;
-use std::{str::FromStr, sync::Arc};
+use std::{
+    path::PathBuf,
+    str::FromStr,
+    sync::{Arc, Mutex},
+};
 use tokio::time::{sleep, Duration};
 use tracing::{debug, info, warn};
 
-use super::{ipfs_upload::types::IpfsUploadMessage, resolver::types::ResolverConsumerMessage};
+use super::{
+    chainlink_price::indexer::{
+        PriceFeed, DEFAULT_BLOCK_RANGE, DEFAULT_POLL_INTERVAL_SECS, ETH_USD_FEED_ADDRESS,
+    },
+    ipfs_upload::types::IpfsUploadMessage,
+    resolver::{
+        ens_resolver::{Registry, BASENAMES_REGISTRY_ADDRESS},
+        retry::{RetryPolicy, DEFAULT_BASE_DELAY, DEFAULT_MAX_ATTEMPTS},
+        types::ResolverConsumerMessage,
+        uri_resolver::{UriResolver, ARWEAVE_GATEWAY_URL},
+    },
+};
 
 pub trait AtomUpdater {
     fn pool(&self) -> &PgPool;
@@ -50,12 +69,29 @@ fn get_event_processing_histogram() -> &'static HistogramVec {
 /// to be performing different actions
 #[derive(Clone)]
 pub enum ConsumerMode {
+    ChainlinkPrice(ChainlinkPriceConsumerContext),
     Decoded(DecodedConsumerContext),
     Raw(RawConsumerContext),
     Resolver(ResolverConsumerContext),
     IpfsUpload(IpfsUploadConsumerContext),
 }
 
+/// Represents the Chainlink price consumer context. It polls the price feed
+/// instead of consuming a queue.
+#[derive(Clone)]
+pub struct ChainlinkPriceConsumerContext {
+    pub backend_schema: String,
+    pub block_range: u64,
+    pub feed: Arc<PriceFeed>,
+    pub pg_pool: PgPool,
+    pub poll_interval: Duration,
+    pub start_block: Option<u64>,
+}
+
+/// The atom deposit fraction and fee denominator, with the block they were
+/// fetched at
+type AtomDepositFractionConfigCache = Arc<Mutex<Option<(i64, (U256, U256))>>>;
+
 /// Represents the decoded consumer context
 #[derive(Clone)]
 pub struct DecodedConsumerContext {
@@ -63,6 +99,11 @@ pub struct DecodedConsumerContext {
     pub base_client: Arc<EthMultiVaultInstance<Http<Client>, RootProvider<Http<Client>>>>,
     pub pg_pool: PgPool,
     pub backend_schema: String,
+    pub relevance_half_life_secs: i64,
+    /// The atom deposit fraction config of the last block it was fetched at.
+    /// The contract emits no event when an admin changes it, so the block is
+    /// the invalidation key.
+    pub atom_deposit_fraction_config: AtomDepositFractionConfigCache,
 }
 
 impl DecodedConsumerContext {
@@ -224,6 +265,51 @@ impl DecodedConsumerContext {
         .await
     }
 
+    /// This function fetches the atom deposit fraction of triple deposits and
+    /// the fee denominator at a block
+    pub async fn fetch_atom_deposit_fraction_config(
+        &self,
+        block_number: i64,
+    ) -> Result<(U256, U256), ConsumerError> {
+        let cached = *self
+            .atom_deposit_fraction_config
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        if let Some((cached_block, config)) = cached {
+            if cached_block == block_number {
+                return Ok(config);
+            }
+        }
+
+        let config = self
+            .retry_with_backoff(|| async {
+                let block = BlockId::from_str(&block_number.to_string())?;
+                let triple_config = self.base_client.tripleConfig().block(block).call().await;
+                let general_config = self.base_client.generalConfig().block(block).call().await;
+                match (&triple_config, &general_config) {
+                    (Ok(triple_config), Ok(general_config)) => {
+                        info!("Triple config: {:?}", triple_config);
+                        Ok((
+                            triple_config.atomDepositFractionForTriple,
+                            general_config.feeDenominator,
+                        ))
+                    }
+                    _ => {
+                        warn!("Response: {:?} {:?}", triple_config, general_config);
+                        warn!("Error fetching the atom deposit fraction config");
+                        Err(ConsumerError::MaxRetriesExceeded)
+                    }
+                }
+            })
+            .await?;
+
+        *self
+            .atom_deposit_fraction_config
+            .lock()
+            .unwrap_or_else(|e| e.into_inner()) = Some((block_number, config));
+        Ok(config)
+    }
+
     /// This function fetches the contract balance at a specific block.
     pub async fn fetch_contract_balance_at_block(
         &self,
@@ -270,6 +356,7 @@ impl AtomUpdater for DecodedConsumerContext {
 #[derive(Clone)]
 pub struct IpfsUploadConsumerContext {
     pub client: Arc<dyn BasicConsumer>,
+    pub image_guard_api_key: Option<String>,
     pub image_guard_url: String,
     pub ipfs_resolver: IPFSResolver,
     pub pg_pool: PgPool,
@@ -289,13 +376,17 @@ pub struct RawConsumerContext {
 /// Represents the resolver consumer context
 #[derive(Clone)]
 pub struct ResolverConsumerContext {
+    pub basenames_client: Option<Arc<Registry>>,
     pub client: Arc<dyn BasicConsumer>,
     pub image_guard_url: String,
     pub ipfs_resolver: IPFSResolver,
-    pub mainnet_client: Arc<ENSRegistryInstance<Http<Client>, RootProvider<Http<Client>>>>,
+    pub mainnet_client: Arc<Registry>,
     pub pg_pool: PgPool,
     pub reqwest_client: reqwest::Client,
+    pub retry_policy: RetryPolicy,
+    pub safe_fetcher: SafeFetcher,
     pub server_initialize: ServerInitialize,
+    pub uri_resolver: UriResolver,
 }
 
 impl AtomUpdater for ResolverConsumerContext {
@@ -352,6 +443,50 @@ impl ConsumerMode {
         Ok(alloy_contract)
     }
 
+    /// This function creates a Chainlink price consumer, indexing the
+    /// ETH/USD feed on Base
+    async fn create_chainlink_price_consumer(
+        data: ServerInitialize,
+        pg_pool: PgPool,
+    ) -> Result<ConsumerMode, ConsumerError> {
+        let provider = ProviderBuilder::new().on_http(
+            data.env
+                .rpc_url_base
+                .as_deref()
+                .unwrap_or_else(|| panic!("RPC URL base mainnet is not set"))
+                .parse()?,
+        );
+        let feed = ChainlinkAggregator::new(
+            Address::from_str(
+                data.env
+                    .chainlink_eth_usd_feed_address
+                    .as_deref()
+                    .unwrap_or(ETH_USD_FEED_ADDRESS),
+            )
+            .map_err(|e| ConsumerError::AddressParse(e.to_string()))?,
+            provider,
+        );
+
+        Ok(ConsumerMode::ChainlinkPrice(
+            ChainlinkPriceConsumerContext {
+                backend_schema: data.env.backend_schema.clone(),
+                block_range: data
+                    .env
+                    .chainlink_block_range
+                    .unwrap_or(DEFAULT_BLOCK_RANGE)
+                    .max(1),
+                feed: Arc::new(feed),
+                pg_pool,
+                poll_interval: Duration::from_secs(
+                    data.env
+                        .chainlink_poll_interval_secs
+                        .unwrap_or(DEFAULT_POLL_INTERVAL_SECS),
+                ),
+                start_block: data.env.chainlink_start_block,
+            },
+        ))
+    }
+
     /// This function creates a decoded consumer
     async fn create_decoded_consumer(
         data: ServerInitialize,
@@ -387,6 +522,11 @@ impl ConsumerMode {
             client,
             pg_pool,
             backend_schema: data.env.backend_schema.clone(),
+            relevance_half_life_secs: data
+                .env
+                .relevance_half_life_secs
+                .unwrap_or(DEFAULT_HALF_LIFE_SECS),
+            atom_deposit_fraction_config: Arc::new(Mutex::new(None)),
         }))
     }
 
@@ -399,34 +539,48 @@ impl ConsumerMode {
             .unwrap_or_else(|| panic!("Image guard URL is not set")))
     }
 
-    /// This function creates a ipfs resolver
+    /// This function creates the cache of the content fetched from IPFS, if
+    /// its schema is configured
+    fn create_content_cache(data: &ServerInitialize, pg_pool: &PgPool) -> Option<ContentCache> {
+        let schema = data.env.ipfs_cache_schema.clone()?;
+        Some(
+            ContentCache::builder()
+                .pg_pool(pg_pool.clone())
+                .schema(schema)
+                .directory(data.env.ipfs_cache_dir.clone().map(PathBuf::from))
+                .max_size_bytes(data.env.ipfs_cache_max_size_mb.map(|mb| mb * 1024 * 1024))
+                .build(),
+        )
+    }
+
+    /// Builds the policy used to retry failed atom resolutions
+    fn create_retry_policy(data: &ServerInitialize) -> RetryPolicy {
+        RetryPolicy {
+            max_attempts: data
+                .env
+                .resolution_retry_max_attempts
+                .unwrap_or(DEFAULT_MAX_ATTEMPTS),
+            base_delay: data
+                .env
+                .resolution_retry_base_delay_secs
+                .map_or(DEFAULT_BASE_DELAY, Duration::from_secs),
+        }
+    }
+
+    /// This function creates a ipfs resolver. Consumers only fetch from IPFS,
+    /// so no pinning backend is configured.
     async fn create_ipfs_resolver(data: ServerInitialize) -> Result<IPFSResolver, ConsumerError> {
         Ok(IPFSResolver::builder()
             .http_client(Client::new())
-            .ipfs_upload_url(
-                data.env
-                    .ipfs_upload_url
-                    .clone()
-                    .unwrap_or_else(|| panic!("IPFS upload URL is not set")),
-            )
-            .ipfs_fetch_url(
-                data.env
+            .gateways(GatewayPool::from_config(
+                data.env.ipfs_gateways.as_deref(),
+                &data
+                    .env
                     .ipfs_gateway_url
                     .clone()
                     .unwrap_or_else(|| panic!("IPFS gateway URL is not set")),
-            )
-            .pinata_jwt(
-                data.env
-                    .pinata_api_jwt
-                    .clone()
-                    .unwrap_or_else(|| panic!("Pinata API JWT is not set")),
-            )
-            .pinata_gateway_token(
-                data.env
-                    .pinata_gateway_token
-                    .clone()
-                    .unwrap_or_else(|| panic!("Pinata gateway token is not set")),
-            )
+                data.env.ipfs_gateway_race.unwrap_or(false),
+            )?)
             .build())
     }
 
@@ -455,6 +609,7 @@ impl ConsumerMode {
         let reqwest_client = reqwest::Client::new();
         Ok(ConsumerMode::IpfsUpload(IpfsUploadConsumerContext {
             client,
+            image_guard_api_key: data.env.image_guard_api_key.clone(),
             image_guard_url,
             ipfs_resolver,
             pg_pool,
@@ -517,6 +672,22 @@ impl ConsumerMode {
                 .ens_contract_address
                 .unwrap_or_else(|| panic!("ENS contract address is not set")),
         )?);
+        // Basenames are resolved when a Base RPC is configured
+        let basenames_client = data
+            .env
+            .rpc_url_base
+            .as_deref()
+            .map(|rpc_url| {
+                Self::build_ens_client(
+                    rpc_url,
+                    data.env
+                        .basenames_registry_address
+                        .as_deref()
+                        .unwrap_or(BASENAMES_REGISTRY_ADDRESS),
+                )
+            })
+            .transpose()?
+            .map(Arc::new);
 
         let client = Self::build_client(
             data.clone(),
@@ -532,18 +703,32 @@ impl ConsumerMode {
         .await?;
 
         let ipfs_resolver = Self::create_ipfs_resolver(data.clone()).await?;
+        let safe_fetcher = SafeFetcher::from_ipfs_resolver(&ipfs_resolver)?;
+        let uri_resolver = UriResolver::with_default_handlers(
+            ipfs_resolver.clone(),
+            Self::create_content_cache(&data, &pg_pool),
+            data.env
+                .arweave_gateway_url
+                .clone()
+                .unwrap_or_else(|| ARWEAVE_GATEWAY_URL.to_string()),
+        )?;
 
         let image_guard_url = Self::create_image_guard(data.clone()).await?;
 
         let reqwest_client = reqwest::Client::new();
+        let retry_policy = Self::create_retry_policy(&data);
         Ok(ConsumerMode::Resolver(ResolverConsumerContext {
+            basenames_client,
             client,
             image_guard_url,
             ipfs_resolver,
             mainnet_client,
             pg_pool,
             reqwest_client,
+            retry_policy,
+            safe_fetcher,
             server_initialize: data,
+            uri_resolver,
         }))
     }
 
@@ -561,6 +746,9 @@ impl ConsumerMode {
             "IpfsUpload" | "ipfs-upload" | "IPFS_UPLOAD" => {
                 Self::create_ipfs_upload_consumer(data, pg_pool).await
             }
+            "ChainlinkPrice" | "chainlink-price" | "CHAINLINK_PRICE" => {
+                Self::create_chainlink_price_consumer(data, pg_pool).await
+            }
             _ => Err(ConsumerError::UnsuportedMode),
         }
     }
@@ -569,6 +757,8 @@ impl ConsumerMode {
     /// is running on.
     pub async fn process_message(&self, message: String) -> Result<(), ConsumerError> {
         match self {
+            // The Chainlink price consumer doesn't consume a queue
+            ConsumerMode::ChainlinkPrice(_) => Err(ConsumerError::UnsuportedMode),
             ConsumerMode::Raw(raw_consumer_context) => {
                 self.raw_message_store_and_relay(message, raw_consumer_context)
                     .await
@@ -592,6 +782,9 @@ impl ConsumerMode {
     /// is running on.
     pub async fn process_messages(&self) -> Result<(), ConsumerError> {
         match self {
+            ConsumerMode::ChainlinkPrice(chainlink_price_consumer_context) => {
+                chainlink_price_consumer_context.index_prices().await
+            }
             ConsumerMode::Raw(raw_consumer_context) => {
                 raw_consumer_context
                     .client
@@ -0,0 +1,28 @@
+ALTER TABLE atom_value
+  DROP COLUMN creative_work_id,
+  DROP COLUMN schema_event_id,
+  DROP COLUMN place_id,
+  DROP COLUMN product_id,
+  DROP COLUMN software_application_id,
+  DROP COLUMN web_site_id;
+
+DROP TABLE creative_work;
+DROP TABLE schema_event;
+DROP TABLE place;
+DROP TABLE product;
+DROP TABLE software_application;
+DROP TABLE web_site;
+
+-- Unfortunately, PostgreSQL doesn't allow removing enum values directly. The down migration requires recreating the type.
+CREATE TYPE atom_type_new AS ENUM (
+  'Unknown', 'Account', 'Thing', 'ThingPredicate', 'Person', 'PersonPredicate',
+  'Organization', 'OrganizationPredicate', 'Book', 'LikeAction', 'FollowAction', 'Keywords', 'Caip10',
+  'JsonObject', 'TextObject', 'ByteObject'
+);
+
+ALTER TABLE atom
+  ALTER COLUMN type TYPE atom_type_new
+  USING (type::text::atom_type_new);
+
+DROP TYPE atom_type;
+ALTER TYPE atom_type_new RENAME TO atom_type;
@@ -0,0 +1,95 @@
+-- Add CreativeWork
+
+ALTER TYPE atom_type ADD VALUE 'CreativeWork';
+
+CREATE TABLE creative_work (
+  id NUMERIC(78, 0) PRIMARY KEY NOT NULL,
+  name TEXT,
+  description TEXT,
+  image TEXT,
+  url TEXT,
+  author TEXT,
+  date_published TEXT
+);
+
+ALTER TABLE atom_value ADD COLUMN creative_work_id NUMERIC(78, 0) REFERENCES creative_work(id);
+
+-- Add Event. The table is prefixed since `event` holds the chain events
+
+ALTER TYPE atom_type ADD VALUE 'Event';
+
+CREATE TABLE schema_event (
+  id NUMERIC(78, 0) PRIMARY KEY NOT NULL,
+  name TEXT,
+  description TEXT,
+  image TEXT,
+  url TEXT,
+  start_date TEXT,
+  end_date TEXT,
+  location TEXT
+);
+
+ALTER TABLE atom_value ADD COLUMN schema_event_id NUMERIC(78, 0) REFERENCES schema_event(id);
+
+-- Add Place
+
+ALTER TYPE atom_type ADD VALUE 'Place';
+
+CREATE TABLE place (
+  id NUMERIC(78, 0) PRIMARY KEY NOT NULL,
+  name TEXT,
+  description TEXT,
+  image TEXT,
+  url TEXT,
+  address TEXT,
+  latitude DOUBLE PRECISION,
+  longitude DOUBLE PRECISION
+);
+
+ALTER TABLE atom_value ADD COLUMN place_id NUMERIC(78, 0) REFERENCES place(id);
+
+-- Add Product
+
+ALTER TYPE atom_type ADD VALUE 'Product';
+
+CREATE TABLE product (
+  id NUMERIC(78, 0) PRIMARY KEY NOT NULL,
+  name TEXT,
+  description TEXT,
+  image TEXT,
+  url TEXT,
+  brand TEXT,
+  sku TEXT
+);
+
+ALTER TABLE atom_value ADD COLUMN product_id NUMERIC(78, 0) REFERENCES product(id);
+
+-- Add SoftwareApplication
+
+ALTER TYPE atom_type ADD VALUE 'SoftwareApplication';
+
+CREATE TABLE software_application (
+  id NUMERIC(78, 0) PRIMARY KEY NOT NULL,
+  name TEXT,
+  description TEXT,
+  image TEXT,
+  url TEXT,
+  application_category TEXT,
+  operating_system TEXT
+);
+
+ALTER TABLE atom_value ADD COLUMN software_application_id NUMERIC(78, 0) REFERENCES software_application(id);
+
+-- Add WebSite
+
+ALTER TYPE atom_type ADD VALUE 'WebSite';
+
+CREATE TABLE web_site (
+  id NUMERIC(78, 0) PRIMARY KEY NOT NULL,
+  name TEXT,
+  description TEXT,
+  image TEXT,
+  url TEXT
+);
+
+ALTER TABLE atom_value ADD COLUMN web_site_id NUMERIC(78, 0) REFERENCES web_site(id);
@@ -0,0 +1,28 @@
+ALTER TABLE atom_value
+  DROP COLUMN caip2_id,
+  DROP COLUMN caip19_id,
+  DROP COLUMN did_id;
+
+DROP TABLE caip2;
+DROP TABLE caip19;
+DROP TABLE did;
+
+-- Accounts without a numeric chain id can't be kept once chain_id is required again
+DELETE FROM caip10 WHERE chain_id IS NULL;
+ALTER TABLE caip10 DROP COLUMN chain_reference;
+ALTER TABLE caip10 ALTER COLUMN chain_id SET NOT NULL;
+
+-- Unfortunately, PostgreSQL doesn't allow removing enum values directly. The down migration requires recreating the type.
+CREATE TYPE atom_type_new AS ENUM (
+  'Unknown', 'Account', 'Thing', 'ThingPredicate', 'Person', 'PersonPredicate',
+  'Organization', 'OrganizationPredicate', 'Book', 'LikeAction', 'FollowAction', 'Keywords', 'Caip10',
+  'JsonObject', 'TextObject', 'ByteObject', 'CreativeWork', 'Event', 'Place', 'Product',
+  'SoftwareApplication', 'WebSite'
+);
+
+ALTER TABLE atom
+  ALTER COLUMN type TYPE atom_type_new
+  USING (type::text::atom_type_new);
+
+DROP TYPE atom_type;
+ALTER TYPE atom_type_new RENAME TO atom_type;
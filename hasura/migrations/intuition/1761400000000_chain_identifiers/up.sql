@@ -0,0 +1,47 @@
+-- CAIP-10 accounts of non-eip155 chains have no numeric chain id, so the
+-- CAIP-2 reference is stored as is
+
+ALTER TABLE caip10 ALTER COLUMN chain_id DROP NOT NULL;
+ALTER TABLE caip10 ADD COLUMN chain_reference TEXT;
+UPDATE caip10 SET chain_reference = chain_id::text;
+ALTER TABLE caip10 ALTER COLUMN chain_reference SET NOT NULL;
+
+-- Add Caip2
+
+ALTER TYPE atom_type ADD VALUE 'Caip2';
+
+CREATE TABLE caip2 (
+  id NUMERIC(78, 0) PRIMARY KEY NOT NULL,
+  namespace TEXT NOT NULL,
+  reference TEXT NOT NULL
+);
+
+ALTER TABLE atom_value ADD COLUMN caip2_id NUMERIC(78, 0) REFERENCES caip2(id);
+
+-- Add Caip19
+
+ALTER TYPE atom_type ADD VALUE 'Caip19';
+
+CREATE TABLE caip19 (
+  id NUMERIC(78, 0) PRIMARY KEY NOT NULL,
+  namespace TEXT NOT NULL,
+  chain_reference TEXT NOT NULL,
+  asset_namespace TEXT NOT NULL,
+  asset_reference TEXT NOT NULL,
+  token_id TEXT
+);
+
+ALTER TABLE atom_value ADD COLUMN caip19_id NUMERIC(78, 0) REFERENCES caip19(id);
+
+-- Add Did
+
+ALTER TYPE atom_type ADD VALUE 'Did';
+
+CREATE TABLE did (
+  id NUMERIC(78, 0) PRIMARY KEY NOT NULL,
+  did TEXT NOT NULL,
+  method TEXT NOT NULL,
+  identifier TEXT NOT NULL
+);
+
+ALTER TABLE atom_value ADD COLUMN did_id NUMERIC(78, 0) REFERENCES did(id);
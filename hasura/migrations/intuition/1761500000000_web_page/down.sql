@@ -0,0 +1,18 @@
+ALTER TABLE atom_value DROP COLUMN web_page_id;
+
+DROP TABLE web_page;
+
+-- Unfortunately, PostgreSQL doesn't allow removing enum values directly. The down migration requires recreating the type.
+CREATE TYPE atom_type_new AS ENUM (
+  'Unknown', 'Account', 'Thing', 'ThingPredicate', 'Person', 'PersonPredicate',
+  'Organization', 'OrganizationPredicate', 'Book', 'LikeAction', 'FollowAction', 'Keywords', 'Caip10',
+  'JsonObject', 'TextObject', 'ByteObject', 'CreativeWork', 'Event', 'Place', 'Product',
+  'SoftwareApplication', 'WebSite', 'Caip2', 'Caip19', 'Did'
+);
+
+ALTER TABLE atom
+  ALTER COLUMN type TYPE atom_type_new
+  USING (type::text::atom_type_new);
+
+DROP TYPE atom_type;
+ALTER TYPE atom_type_new RENAME TO atom_type;
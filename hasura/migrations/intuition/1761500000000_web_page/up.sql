@@ -0,0 +1,14 @@
+ALTER TYPE atom_type ADD VALUE 'Url';
+
+CREATE TABLE web_page (
+  id NUMERIC(78, 0) PRIMARY KEY NOT NULL,
+  url TEXT NOT NULL,
+  canonical_url TEXT NOT NULL,
+  title TEXT,
+  description TEXT,
+  image TEXT,
+  site_name TEXT,
+  favicon TEXT
+);
+
+ALTER TABLE atom_value ADD COLUMN web_page_id NUMERIC(78, 0) REFERENCES web_page(id);
@@ -0,0 +1,2 @@
+DROP TABLE atom_resolution_attempt;
+DROP TYPE resolution_error_category;
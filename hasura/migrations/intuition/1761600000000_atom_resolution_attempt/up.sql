@@ -0,0 +1,15 @@
+CREATE TYPE resolution_error_category AS ENUM (
+  'GatewayTimeout', 'NetworkError', 'InvalidContent', 'ParseError', 'UnsupportedType', 'Other'
+);
+
+CREATE TABLE atom_resolution_attempt (
+  atom_id NUMERIC(78, 0) PRIMARY KEY NOT NULL REFERENCES atom(id),
+  attempts INTEGER NOT NULL,
+  last_error_category resolution_error_category NOT NULL,
+  last_error TEXT NOT NULL,
+  last_attempt_at TIMESTAMPTZ NOT NULL,
+  next_retry_at TIMESTAMPTZ
+);
+
+CREATE INDEX idx_atom_resolution_attempt_next_retry_at ON atom_resolution_attempt(next_retry_at)
+  WHERE next_retry_at IS NOT NULL;
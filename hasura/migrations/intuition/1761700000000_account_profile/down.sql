@@ -0,0 +1,5 @@
+ALTER TABLE account
+  DROP COLUMN description,
+  DROP COLUMN url,
+  DROP COLUMN twitter,
+  DROP COLUMN github;
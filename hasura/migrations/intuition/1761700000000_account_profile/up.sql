@@ -0,0 +1,5 @@
+ALTER TABLE account
+  ADD COLUMN description TEXT,
+  ADD COLUMN url TEXT,
+  ADD COLUMN twitter TEXT,
+  ADD COLUMN github TEXT;
@@ -0,0 +1,8 @@
+DROP TABLE account_profile_change;
+DROP FUNCTION account_profile_change_append_only();
+
+DROP INDEX idx_account_profile_resolved_at;
+
+ALTER TABLE account
+  DROP COLUMN profile_resolved_at,
+  DROP COLUMN profile_refresh_enqueued_at;
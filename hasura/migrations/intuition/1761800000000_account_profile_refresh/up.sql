@@ -0,0 +1,29 @@
+ALTER TABLE account
+  ADD COLUMN profile_resolved_at TIMESTAMPTZ,
+  ADD COLUMN profile_refresh_enqueued_at TIMESTAMPTZ;
+
+CREATE INDEX idx_account_profile_resolved_at ON account(profile_resolved_at NULLS FIRST);
+
+-- Every change of the profile of an account is recorded here, rows are never
+-- updated or deleted
+CREATE TABLE account_profile_change (
+  id BIGSERIAL PRIMARY KEY NOT NULL,
+  account_id TEXT NOT NULL REFERENCES account(id),
+  field TEXT NOT NULL,
+  previous_value TEXT,
+  value TEXT,
+  changed_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE INDEX idx_account_profile_change_account_id ON account_profile_change(account_id);
+
+CREATE OR REPLACE FUNCTION account_profile_change_append_only()
+RETURNS TRIGGER AS $$
+BEGIN
+  RAISE EXCEPTION 'account_profile_change is append-only';
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER account_profile_change_append_only
+BEFORE UPDATE OR DELETE ON account_profile_change
+FOR EACH ROW EXECUTE FUNCTION account_profile_change_append_only();
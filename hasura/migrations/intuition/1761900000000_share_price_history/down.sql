@@ -0,0 +1,3 @@
+DROP FUNCTION IF EXISTS share_price_candles;
+DROP TABLE share_price_candle;
+DROP TABLE share_price_history;
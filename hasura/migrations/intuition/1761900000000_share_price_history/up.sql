@@ -0,0 +1,45 @@
+-- Every share price of a vault observed by an event, keyed by the event
+CREATE TABLE share_price_history (
+  vault_id NUMERIC(78, 0) NOT NULL REFERENCES vault(id),
+  block_number BIGINT NOT NULL,
+  log_index BIGINT NOT NULL,
+  block_timestamp BIGINT NOT NULL,
+  share_price NUMERIC(78, 0) NOT NULL,
+  PRIMARY KEY (vault_id, block_number, log_index)
+);
+
+CREATE INDEX idx_share_price_history_vault_timestamp ON share_price_history(vault_id, block_timestamp);
+
+-- The return type of share_price_candles, it has no rows
+CREATE TABLE share_price_candle (
+  vault_id NUMERIC(78, 0) NOT NULL,
+  bucket_start BIGINT NOT NULL,
+  open NUMERIC(78, 0) NOT NULL,
+  high NUMERIC(78, 0) NOT NULL,
+  low NUMERIC(78, 0) NOT NULL,
+  close NUMERIC(78, 0) NOT NULL,
+  sample_count BIGINT NOT NULL,
+  PRIMARY KEY (vault_id, bucket_start)
+);
+
+-- The open, high, low and close share prices of a vault over intervals of
+-- interval_secs, between two block timestamps (the end is excluded)
+CREATE FUNCTION share_price_candles(vault numeric, interval_secs bigint, from_timestamp bigint, to_timestamp bigint) RETURNS SETOF share_price_candle
+    LANGUAGE sql STABLE
+    AS $$
+SELECT
+  vault AS vault_id,
+  (block_timestamp / interval_secs) * interval_secs AS bucket_start,
+  (array_agg(share_price ORDER BY block_number, log_index))[1] AS open,
+  MAX(share_price) AS high,
+  MIN(share_price) AS low,
+  (array_agg(share_price ORDER BY block_number DESC, log_index DESC))[1] AS close,
+  COUNT(*) AS sample_count
+FROM share_price_history
+WHERE share_price_history.vault_id = vault
+  AND interval_secs > 0
+  AND block_timestamp >= from_timestamp
+  AND block_timestamp < to_timestamp
+GROUP BY 2
+ORDER BY 2;
+$$;
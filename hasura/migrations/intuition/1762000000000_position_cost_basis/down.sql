@@ -0,0 +1,2 @@
+DROP FUNCTION IF EXISTS position_cost_basis_unrealized_pnl;
+DROP TABLE position_cost_basis;
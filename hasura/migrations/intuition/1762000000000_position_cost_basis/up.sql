@@ -0,0 +1,102 @@
+-- The cost-basis ledger of a position, it has the same id as the position and
+-- is kept when the position is fully redeemed
+CREATE TABLE position_cost_basis (
+  id TEXT PRIMARY KEY NOT NULL,
+  account_id TEXT REFERENCES account(id) NOT NULL,
+  vault_id NUMERIC(78, 0) REFERENCES vault(id) NOT NULL,
+  shares NUMERIC(78, 0) NOT NULL,
+  total_assets_in NUMERIC(78, 0) NOT NULL,
+  total_assets_out NUMERIC(78, 0) NOT NULL,
+  cost_basis NUMERIC(78, 0) NOT NULL,
+  average_entry_price NUMERIC(78, 0) NOT NULL,
+  -- Signed, a loss is negative
+  realized_pnl NUMERIC(78, 0) NOT NULL,
+  last_block_number BIGINT NOT NULL,
+  last_log_index BIGINT NOT NULL
+);
+
+CREATE INDEX idx_position_cost_basis_account_id ON position_cost_basis(account_id);
+CREATE INDEX idx_position_cost_basis_vault_id ON position_cost_basis(vault_id);
+CREATE INDEX idx_position_cost_basis_realized_pnl ON position_cost_basis(realized_pnl);
+
+-- The profit and loss of the remaining shares at the current share price of
+-- the vault, share prices are the assets of 10^18 shares
+CREATE FUNCTION position_cost_basis_unrealized_pnl(ledger position_cost_basis) RETURNS numeric
+    LANGUAGE sql STABLE
+    AS $$
+SELECT TRUNC(ledger.shares * vault.current_share_price / 1000000000000000000) - ledger.cost_basis
+FROM vault
+WHERE vault.id = ledger.vault_id;
+$$;
+
+-- Backfill the ledgers from the deposits and redemptions indexed before the
+-- table existed, replaying them in order. Event ids are
+-- `<transaction hash>-<log index>`.
+DO $$
+DECLARE
+  e RECORD;
+  ledger position_cost_basis%ROWTYPE;
+  cost_removed NUMERIC(78, 0);
+BEGIN
+  FOR e IN
+    SELECT vault_id || '-' || LOWER(receiver_id) AS id,
+           LOWER(receiver_id) AS account_id,
+           vault_id,
+           sender_assets_after_total_fees AS assets,
+           0 AS shares_redeemed,
+           receiver_total_shares_in_vault AS shares_after,
+           block_number::BIGINT AS block_number,
+           split_part(id, '-', 2)::BIGINT AS log_index
+    FROM deposit
+    UNION ALL
+    SELECT vault_id || '-' || LOWER(sender_id),
+           LOWER(sender_id),
+           vault_id,
+           assets_for_receiver,
+           shares_redeemed_by_sender,
+           sender_total_shares_in_vault,
+           block_number::BIGINT,
+           split_part(id, '-', 2)::BIGINT
+    FROM redemption
+    ORDER BY block_number, log_index
+  LOOP
+    SELECT * INTO ledger FROM position_cost_basis WHERE id = e.id;
+    IF NOT FOUND THEN
+      ledger := ROW(e.id, e.account_id, e.vault_id, 0, 0, 0, 0, 0, 0, 0, 0);
+    END IF;
+
+    IF e.shares_redeemed = 0 THEN
+      ledger.total_assets_in := ledger.total_assets_in + e.assets;
+      ledger.cost_basis := ledger.cost_basis + e.assets;
+    ELSE
+      IF e.shares_after = 0 THEN
+        cost_removed := ledger.cost_basis;
+      ELSE
+        cost_removed := div(ledger.cost_basis * e.shares_redeemed, e.shares_after + e.shares_redeemed);
+      END IF;
+      ledger.total_assets_out := ledger.total_assets_out + e.assets;
+      ledger.realized_pnl := ledger.realized_pnl + e.assets - cost_removed;
+      ledger.cost_basis := ledger.cost_basis - cost_removed;
+    END IF;
+
+    ledger.shares := e.shares_after;
+    ledger.average_entry_price := CASE
+      WHEN e.shares_after = 0 THEN 0
+      ELSE div(ledger.cost_basis * 1000000000000000000, e.shares_after)
+    END;
+    ledger.last_block_number := e.block_number;
+    ledger.last_log_index := e.log_index;
+
+    INSERT INTO position_cost_basis VALUES (ledger.*)
+    ON CONFLICT (id) DO UPDATE SET
+      shares = EXCLUDED.shares,
+      total_assets_in = EXCLUDED.total_assets_in,
+      total_assets_out = EXCLUDED.total_assets_out,
+      cost_basis = EXCLUDED.cost_basis,
+      average_entry_price = EXCLUDED.average_entry_price,
+      realized_pnl = EXCLUDED.realized_pnl,
+      last_block_number = EXCLUDED.last_block_number,
+      last_log_index = EXCLUDED.last_log_index;
+  END LOOP;
+END;
+$$;
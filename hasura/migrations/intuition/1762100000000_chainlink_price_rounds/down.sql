@@ -0,0 +1,13 @@
+DROP FUNCTION IF EXISTS signal_delta_usd;
+DROP FUNCTION IF EXISTS event_usd_value;
+DROP FUNCTION IF EXISTS stats_contract_balance_usd;
+DROP FUNCTION IF EXISTS stats_total_fees_usd;
+DROP FUNCTION IF EXISTS wei_to_usd;
+DROP FUNCTION IF EXISTS eth_usd_price_at;
+
+DROP INDEX idx_chainlink_price_updated_at;
+DROP INDEX idx_chainlink_price_block_number;
+
+ALTER TABLE chainlink_price
+  DROP COLUMN updated_at,
+  DROP COLUMN block_number;
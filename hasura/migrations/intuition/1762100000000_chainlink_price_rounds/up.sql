@@ -0,0 +1,59 @@
+-- The id of a price is the round id of the feed proxy. The block number is the
+-- block the answer was observed at, and updated_at the timestamp of the answer.
+ALTER TABLE chainlink_price
+  ADD COLUMN block_number BIGINT,
+  ADD COLUMN updated_at BIGINT;
+
+CREATE INDEX idx_chainlink_price_block_number ON chainlink_price(block_number);
+CREATE INDEX idx_chainlink_price_updated_at ON chainlink_price(updated_at);
+
+-- The ETH/USD price at a block timestamp, the last answer given at or before it
+CREATE FUNCTION eth_usd_price_at(ts bigint) RETURNS float8
+    LANGUAGE sql STABLE
+    AS $$
+SELECT usd
+FROM chainlink_price
+WHERE updated_at <= ts AND usd IS NOT NULL
+ORDER BY updated_at DESC
+LIMIT 1;
+$$;
+
+-- The value in dollars of an amount of wei at a block timestamp
+CREATE FUNCTION wei_to_usd(wei numeric, ts bigint) RETURNS float8
+    LANGUAGE sql STABLE
+    AS $$
+SELECT (wei / 1000000000000000000 * eth_usd_price_at(ts)::numeric)::float8;
+$$;
+
+CREATE FUNCTION stats_total_fees_usd(stats_row stats) RETURNS float8
+    LANGUAGE sql STABLE
+    AS $$
+SELECT wei_to_usd(stats_row.total_fees, stats_row.last_processed_block_timestamp);
+$$;
+
+CREATE FUNCTION stats_contract_balance_usd(stats_row stats) RETURNS float8
+    LANGUAGE sql STABLE
+    AS $$
+SELECT wei_to_usd(stats_row.contract_balance, stats_row.last_processed_block_timestamp);
+$$;
+
+-- The value in dollars of the assets deposited, redeemed or transferred as fees
+-- by an event
+CREATE FUNCTION event_usd_value(event_row event) RETURNS float8
+    LANGUAGE sql STABLE
+    AS $$
+SELECT wei_to_usd(
+  COALESCE(
+    (SELECT sender_assets_after_total_fees FROM deposit WHERE deposit.id = event_row.deposit_id),
+    (SELECT assets_for_receiver FROM redemption WHERE redemption.id = event_row.redemption_id),
+    (SELECT amount FROM fee_transfer WHERE fee_transfer.id = event_row.fee_transfer_id)
+  ),
+  event_row.block_timestamp
+);
+$$;
+
+CREATE FUNCTION signal_delta_usd(signal_row signal) RETURNS float8
+    LANGUAGE sql STABLE
+    AS $$
+SELECT wei_to_usd(signal_row.delta, signal_row.block_timestamp);
+$$;
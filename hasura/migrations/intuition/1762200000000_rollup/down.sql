@@ -0,0 +1,4 @@
+DROP TABLE rollup_cursor;
+DROP TABLE rollup;
+DROP TYPE rollup_entity;
+DROP TYPE rollup_granularity;
@@ -0,0 +1,29 @@
+CREATE TYPE rollup_granularity AS ENUM ('Day', 'Week');
+CREATE TYPE rollup_entity AS ENUM ('Global', 'Atom', 'Triple');
+
+-- The activity of the protocol, an atom or a triple over a day or a week,
+-- computed from the deposits and redemptions by the rollup worker of the
+-- consumer-api. Global rollups have an entity_id of 0.
+CREATE TABLE rollup (
+  granularity rollup_granularity NOT NULL,
+  entity_type rollup_entity NOT NULL,
+  entity_id NUMERIC(78, 0) NOT NULL,
+  bucket_start BIGINT NOT NULL,
+  deposit_count BIGINT NOT NULL,
+  deposit_assets NUMERIC(78, 0) NOT NULL,
+  redemption_count BIGINT NOT NULL,
+  redemption_assets NUMERIC(78, 0) NOT NULL,
+  unique_depositors BIGINT NOT NULL,
+  net_signal NUMERIC NOT NULL,
+  fees NUMERIC(78, 0) NOT NULL,
+  position_count BIGINT NOT NULL,
+  PRIMARY KEY (granularity, entity_type, entity_id, bucket_start)
+);
+
+CREATE INDEX idx_rollup_granularity_bucket_start ON rollup(granularity, bucket_start);
+
+-- The last block timestamp rolled up for each granularity
+CREATE TABLE rollup_cursor (
+  granularity rollup_granularity PRIMARY KEY NOT NULL,
+  last_block_timestamp BIGINT NOT NULL
+);
@@ -0,0 +1 @@
+DROP TABLE triple_consensus;
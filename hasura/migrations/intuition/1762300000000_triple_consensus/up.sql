@@ -0,0 +1,40 @@
+-- How much the network agrees with a triple, from its vault (for) and its
+-- counter vault (against), updated by the decoded consumer
+CREATE TABLE triple_consensus (
+  id NUMERIC(78, 0) PRIMARY KEY NOT NULL REFERENCES triple(id),
+  assets_for NUMERIC(78, 0) NOT NULL,
+  assets_against NUMERIC(78, 0) NOT NULL,
+  shares_for NUMERIC(78, 0) NOT NULL,
+  shares_against NUMERIC(78, 0) NOT NULL,
+  supporters BIGINT NOT NULL,
+  opponents BIGINT NOT NULL,
+  -- From -1 to 1, weighted by assets
+  agreement_score DOUBLE PRECISION NOT NULL
+);
+
+CREATE INDEX idx_triple_consensus_agreement_score ON triple_consensus(agreement_score);
+
+-- Backfill the consensus of the triples indexed before the table existed,
+-- share prices are the assets of 10^18 shares
+INSERT INTO triple_consensus (
+  id, assets_for, assets_against, shares_for, shares_against, supporters, opponents,
+  agreement_score
+)
+SELECT id, assets_for, assets_against, shares_for, shares_against, supporters, opponents,
+  CASE WHEN assets_for + assets_against = 0 THEN 0
+    ELSE ((assets_for - assets_against) / (assets_for + assets_against))::float8
+  END
+FROM (
+  SELECT t.id,
+    TRUNC(v.total_shares * v.current_share_price / 1000000000000000000) AS assets_for,
+    TRUNC(cv.total_shares * cv.current_share_price / 1000000000000000000) AS assets_against,
+    v.total_shares AS shares_for,
+    cv.total_shares AS shares_against,
+    (SELECT COUNT(*) FROM position p
+      WHERE p.vault_id = t.vault_id AND p.shares > 0) AS supporters,
+    (SELECT COUNT(*) FROM position p
+      WHERE p.vault_id = t.counter_vault_id AND p.shares > 0) AS opponents
+  FROM triple t
+  JOIN vault v ON v.id = t.vault_id
+  JOIN vault cv ON cv.id = t.counter_vault_id
+) figures;
@@ -0,0 +1,2 @@
+DROP TABLE audit_finding;
+DROP TYPE audit_entity;
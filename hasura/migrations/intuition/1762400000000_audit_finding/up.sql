@@ -0,0 +1,19 @@
+CREATE TYPE audit_entity AS ENUM ('Position', 'Vault', 'Stats');
+
+-- The values of the indexer that didn't match the contract at the audited
+-- block, recorded by the auditor consumer. Rows are never updated, and a value
+-- is only recorded once per block.
+CREATE TABLE audit_finding (
+  id BIGSERIAL PRIMARY KEY NOT NULL,
+  block_number BIGINT NOT NULL,
+  entity_type audit_entity NOT NULL,
+  entity_id TEXT NOT NULL,
+  field TEXT NOT NULL,
+  indexed_value NUMERIC(78, 0) NOT NULL,
+  onchain_value NUMERIC(78, 0) NOT NULL,
+  found_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE UNIQUE INDEX idx_audit_finding_block_entity_field
+  ON audit_finding(block_number, entity_type, entity_id, field);
+CREATE INDEX idx_audit_finding_entity ON audit_finding(entity_type, entity_id);
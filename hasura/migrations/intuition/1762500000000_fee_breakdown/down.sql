@@ -0,0 +1,4 @@
+DROP VIEW account_fees_paid;
+DROP VIEW atom_fee_earnings;
+DROP TABLE fee_breakdown;
+DROP TYPE fee_type;
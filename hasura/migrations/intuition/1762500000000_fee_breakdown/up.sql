@@ -0,0 +1,42 @@
+CREATE TYPE fee_type AS ENUM ('Protocol', 'Entry', 'Exit', 'AtomDepositFraction');
+
+-- Every fee paid in an event, classified by type. The vault is the vault
+-- receiving the fee, and is NULL for the fees transferred to the protocol.
+CREATE TABLE fee_breakdown (
+  id TEXT PRIMARY KEY NOT NULL,
+  fee_type fee_type NOT NULL,
+  vault_id NUMERIC(78, 0),
+  payer_id TEXT NOT NULL REFERENCES account(id),
+  amount NUMERIC(78, 0) NOT NULL,
+  block_number NUMERIC(78, 0) NOT NULL,
+  block_timestamp BIGINT NOT NULL,
+  transaction_hash TEXT NOT NULL
+);
+
+CREATE INDEX idx_fee_breakdown_fee_type ON fee_breakdown(fee_type);
+CREATE INDEX idx_fee_breakdown_vault ON fee_breakdown(vault_id);
+CREATE INDEX idx_fee_breakdown_payer ON fee_breakdown(payer_id);
+CREATE INDEX idx_fee_breakdown_block_number ON fee_breakdown(block_number);
+
+-- The fees each atom earned from the deposits into the triples it is part of
+CREATE VIEW atom_fee_earnings AS
+SELECT
+  v.atom_id,
+  SUM(f.amount) AS triple_deposit_fees,
+  COUNT(*) AS fee_count
+FROM fee_breakdown f
+JOIN vault v ON v.id = f.vault_id
+WHERE f.fee_type = 'AtomDepositFraction' AND v.atom_id IS NOT NULL
+GROUP BY v.atom_id;
+
+-- The fees each account paid, by fee type
+CREATE VIEW account_fees_paid AS
+SELECT
+  payer_id AS account_id,
+  COALESCE(SUM(amount) FILTER (WHERE fee_type = 'Protocol'), 0) AS protocol_fees,
+  COALESCE(SUM(amount) FILTER (WHERE fee_type = 'Entry'), 0) AS entry_fees,
+  COALESCE(SUM(amount) FILTER (WHERE fee_type = 'Exit'), 0) AS exit_fees,
+  COALESCE(SUM(amount) FILTER (WHERE fee_type = 'AtomDepositFraction'), 0) AS atom_deposit_fractions,
+  SUM(amount) AS total_fees
+FROM fee_breakdown
+GROUP BY payer_id;
@@ -0,0 +1,4 @@
+DROP VIEW current_relevance_score;
+DROP TABLE relevance_score;
+DROP FUNCTION relevance_decay;
+DROP TYPE relevance_entity;
@@ -0,0 +1,42 @@
+CREATE TYPE relevance_entity AS ENUM ('Atom', 'Triple');
+
+-- Decays a score over a number of seconds. Past 64 half-lives the score no
+-- longer counts, which also keeps the scores away from the float underflow.
+CREATE FUNCTION relevance_decay(score float8, elapsed_secs bigint, half_life_secs bigint)
+RETURNS float8 AS $$
+  SELECT CASE
+    WHEN GREATEST(elapsed_secs, 0)::float8 / half_life_secs > 64 THEN 0
+    ELSE score * power(0.5, GREATEST(elapsed_secs, 0)::float8 / half_life_secs)
+  END
+$$ LANGUAGE sql IMMUTABLE;
+
+-- The time-decayed signal of the atoms and triples, as of their last signal.
+-- It's kept by the decoded consumer and rebuilt by the consumer API. The block
+-- and log index of the last signal applied keep replayed signals out.
+CREATE TABLE relevance_score (
+  entity_type relevance_entity NOT NULL,
+  entity_id NUMERIC(78, 0) NOT NULL,
+  signal_score DOUBLE PRECISION NOT NULL,
+  unique_accounts BIGINT NOT NULL,
+  half_life_secs BIGINT NOT NULL,
+  last_signal_timestamp BIGINT NOT NULL,
+  last_block_number BIGINT NOT NULL,
+  last_log_index BIGINT NOT NULL,
+  PRIMARY KEY (entity_type, entity_id)
+);
+
+-- The scores decayed to now, and the relevance used to rank the trending
+-- atoms and triples: the decayed score weighted by the log of the accounts
+CREATE VIEW current_relevance_score AS
+SELECT
+  r.*,
+  d.current_signal_score,
+  d.current_signal_score * ln(1 + r.unique_accounts) AS relevance
+FROM relevance_score r,
+LATERAL (
+  SELECT relevance_decay(
+    r.signal_score,
+    EXTRACT(EPOCH FROM now())::bigint - r.last_signal_timestamp,
+    r.half_life_secs
+  ) AS current_signal_score
+) d;
@@ -1,14 +1,26 @@
 use crate::{
     endpoints::{
-        upload_image::upload_image, upload_image_from_url::upload_image_from_url,
+        admin::api_keys::{issue_api_key, list_api_keys, revoke_api_key},
+        moderation::{
+            audit_log::audit_log,
+            list_images::list_images,
+            reclassify_image::reclassify_image,
+            review_image::review_image,
+            thresholds::{list_thresholds, update_threshold},
+        },
+        serve_image::serve_image,
+        upload_image::upload_image,
+        upload_image_from_url::upload_image_from_url,
         upload_json_to_ipfs::upload_json_to_jpfs,
     },
     error::ApiError,
     openapi::ApiDoc,
+    reconcile::spawn_pin_reconciliation,
     state::AppState,
     types::Env,
 };
 use axum::{
+    middleware,
     routing::{get, post},
     Router,
 };
@@ -18,7 +30,9 @@ use http::{
     Method,
 };
 use log::info;
-use std::time::Duration;
+use models::api_key::ApiKeyScope;
+use shared_utils::auth::require_scope;
+use std::{net::SocketAddr, time::Duration};
 use tokio::net::TcpListener;
 use tower_http::cors::CorsLayer;
 use utoipa::OpenApi;
@@ -85,17 +99,58 @@ impl App {
     /// initialize the logger, and create the app state.
     pub async fn new() -> Result<Self, ApiError> {
         let env = Self::initialize().await?;
-        let app_state = AppState::new(&env).await;
+        let app_state = AppState::new(&env).await?;
         Ok(Self { env, app_state })
     }
 
-    /// Create the router for the application.
-    fn router(&self) -> Router {
-        let (prometheus_layer, metric_handle) = PrometheusMetricLayer::pair();
+    /// Create the router for the upload endpoints. Every route requires an
+    /// API key with the upload scope.
+    fn upload_router(&self) -> Router<AppState> {
         Router::new()
             .route("/upload", post(upload_image))
             .route("/upload_image_from_url", post(upload_image_from_url))
             .route("/upload_json_to_ipfs", post(upload_json_to_jpfs))
+            .route_layer(middleware::from_fn_with_state(
+                self.app_state.auth.require(ApiKeyScope::Upload),
+                require_scope,
+            ))
+    }
+
+    /// Create the router for the moderation endpoints. Every route requires
+    /// an API key with the moderate scope.
+    fn moderation_router(&self) -> Router<AppState> {
+        Router::new()
+            .route("/images", get(list_images))
+            .route("/images/review", post(review_image))
+            .route("/images/reclassify", post(reclassify_image))
+            .route("/images/audit", get(audit_log))
+            .route("/thresholds", get(list_thresholds).post(update_threshold))
+            .route_layer(middleware::from_fn_with_state(
+                self.app_state.auth.require(ApiKeyScope::Moderate),
+                require_scope,
+            ))
+    }
+
+    /// Create the router for the admin endpoints. Every route requires an
+    /// API key with the admin scope.
+    fn admin_router(&self) -> Router<AppState> {
+        Router::new()
+            .route("/api_keys", get(list_api_keys).post(issue_api_key))
+            .route("/api_keys/{id}/revoke", post(revoke_api_key))
+            .route_layer(middleware::from_fn_with_state(
+                self.app_state.auth.require(ApiKeyScope::Admin),
+                require_scope,
+            ))
+    }
+
+    /// Create the router for the application.
+    fn router(&self) -> Router {
+        let (prometheus_layer, metric_handle) = PrometheusMetricLayer::pair();
+        Router::new()
+            .merge(self.upload_router())
+            .route("/images/{key}", get(serve_image))
+            .nest("/moderation", self.moderation_router())
+            .nest("/admin", self.admin_router())
             .route("/metrics", get(|| async move { metric_handle.render() }))
             .layer(prometheus_layer)
             .with_state(self.app_state.clone())
@@ -108,9 +163,18 @@ impl App {
             self.env.classification_api_port
         );
         let listener = self.build_listener().await?;
+        if let Some(interval) = self.env.pin_reconcile_interval_secs {
+            info!("Reconciling pins every {} seconds", interval);
+            spawn_pin_reconciliation(self.app_state.clone(), Duration::from_secs(interval));
+        }
         info!("Ready to receive requests");
-        axum::serve(listener, self.merge_layers())
-            .await
-            .map_err(ApiError::from)
+        // The peer address is used to rate limit clients by IP
+        axum::serve(
+            listener,
+            self.merge_layers()
+                .into_make_service_with_connect_info::<SocketAddr>(),
+        )
+        .await
+        .map_err(ApiError::from)
     }
 }
@@ -0,0 +1,540 @@
+use models::atom::AtomType;
+use reqwest::Url;
+use serde::Serialize;
+use serde_json::{Map, Value};
+use shared_utils::{
+    atom_metadata::AtomMetadata,
+    json_ld::{self, JsonLdNode},
+};
+use std::str::FromStr;
+use utoipa::ToSchema;
+
+/// The maximum size of a JSON atom once serialized, in bytes
+pub const MAX_JSON_SIZE: usize = 100_000;
+/// The maximum length of a string field, in characters
+pub const MAX_STRING_LENGTH: usize = 10_000;
+/// The URI schemes accepted in the `url` field
+const URL_SCHEMES: [&str; 2] = ["http", "https"];
+/// The URI schemes accepted in the `image` field
+const IMAGE_SCHEMES: [&str; 4] = ["http", "https", "ipfs", "data"];
+/// The fields that can be a nested object, e.g. the `Person` of an `author`,
+/// which the resolver reads as its `name`
+const NESTED_FIELDS: [&str; 4] = ["author", "location", "brand", "address"];
+
+/// A schema.org type supported by the resolver, with the fields it reads
+struct SupportedType {
+    atom_type: AtomType,
+    required: &'static [&'static str],
+    optional: &'static [&'static str],
+    numbers: &'static [&'static str],
+}
+
+/// The schema.org types the resolver turns into atoms. The fields are the ones
+/// read by the `create_*_from_obj` functions of the resolver. They are strings,
+/// except for the `numbers`.
+static SUPPORTED_TYPES: [SupportedType; 10] = [
+    SupportedType {
+        atom_type: AtomType::Thing,
+        required: &["name"],
+        optional: &["description", "image", "url"],
+        numbers: &[],
+    },
+    SupportedType {
+        atom_type: AtomType::Person,
+        required: &["name"],
+        optional: &["identifier", "description", "image", "url", "email"],
+        numbers: &[],
+    },
+    SupportedType {
+        atom_type: AtomType::Organization,
+        required: &["name"],
+        optional: &["description", "image", "url"],
+        numbers: &[],
+    },
+    SupportedType {
+        atom_type: AtomType::Book,
+        required: &["name"],
+        optional: &["description", "genre", "url"],
+        numbers: &[],
+    },
+    SupportedType {
+        atom_type: AtomType::CreativeWork,
+        required: &["name"],
+        optional: &["description", "image", "url", "author", "datePublished"],
+        numbers: &[],
+    },
+    SupportedType {
+        atom_type: AtomType::Event,
+        required: &["name"],
+        optional: &[
+            "description",
+            "image",
+            "url",
+            "startDate",
+            "endDate",
+            "location",
+        ],
+        numbers: &[],
+    },
+    SupportedType {
+        atom_type: AtomType::Place,
+        required: &["name"],
+        optional: &["description", "image", "url", "address"],
+        numbers: &["latitude", "longitude"],
+    },
+    SupportedType {
+        atom_type: AtomType::Product,
+        required: &["name"],
+        optional: &["description", "image", "url", "brand", "sku"],
+        numbers: &[],
+    },
+    SupportedType {
+        atom_type: AtomType::SoftwareApplication,
+        required: &["name"],
+        optional: &[
+            "description",
+            "image",
+            "url",
+            "applicationCategory",
+            "operatingSystem",
+        ],
+        numbers: &[],
+    },
+    SupportedType {
+        atom_type: AtomType::WebSite,
+        required: &["name"],
+        optional: &["description", "image", "url"],
+        numbers: &[],
+    },
+];
+
+/// A validation error of a JSON atom
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct ValidationError {
+    /// The field that failed validation, `$` for the document itself
+    pub field: String,
+    pub message: String,
+}
+
+impl ValidationError {
+    fn new(field: &str, message: impl Into<String>) -> Self {
+        Self {
+            field: field.to_string(),
+            message: message.into(),
+        }
+    }
+}
+
+/// The validation errors of a JSON atom, returned with a `400`
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct ValidationErrors {
+    pub errors: Vec<ValidationError>,
+}
+
+/// Validates a JSON atom before it's pinned. The document is read the way
+/// the resolver reads it: a JSON-LD document is resolved from its first node
+/// with a schema.org type, which needs a supported type and its required
+/// fields, and any other object is stored as a `JsonObject`. Returns the
+/// metadata the resolver would derive, or every validation error found.
+pub fn validate_json_atom(json: &Value) -> Result<AtomMetadata, ValidationErrors> {
+    let size = serde_json::to_vec(json)
+        .map(|bytes| bytes.len())
+        .unwrap_or(0);
+    if size > MAX_JSON_SIZE {
+        return Err(ValidationErrors {
+            errors: vec![ValidationError::new(
+                "$",
+                format!("document is {} bytes, the limit is {}", size, MAX_JSON_SIZE),
+            )],
+        });
+    }
+
+    let obj = json.as_object().ok_or_else(|| ValidationErrors {
+        errors: vec![ValidationError::new("$", "document must be a JSON object")],
+    })?;
+
+    let mut errors = Vec::new();
+    let metadata = match json_ld::normalize(json) {
+        None => {
+            if obj.contains_key("@type") {
+                errors.push(ValidationError::new(
+                    "@type",
+                    "`@type` requires a schema.org `@context`",
+                ));
+            }
+            AtomMetadata::json_object(None)
+        }
+        Some(nodes) => match json_ld::schema_org_node(&nodes) {
+            Some((node, schema_org_type)) => match validate_type(schema_org_type, &mut errors) {
+                Some(supported) => {
+                    let object = node.to_schema_org_object(schema_org_type);
+                    let object = object.as_object().cloned().unwrap_or_default();
+                    validate_fields(&object, supported, &mut errors);
+                    let name = object.get("name").and_then(Value::as_str);
+                    let image = object
+                        .get("image")
+                        .and_then(Value::as_str)
+                        .filter(|image| !image.is_empty());
+                    AtomMetadata::schema_org(
+                        &supported.atom_type,
+                        name.unwrap_or_default().to_string(),
+                        image.map(str::to_string),
+                    )
+                }
+                None => AtomMetadata::json_object(None),
+            },
+            None => {
+                validate_untyped_nodes(&nodes, &mut errors);
+                AtomMetadata::json_object(None)
+            }
+        },
+    };
+
+    if errors.is_empty() {
+        Ok(metadata)
+    } else {
+        Err(ValidationErrors { errors })
+    }
+}
+
+/// Checks the nodes of a JSON-LD document without a schema.org type. They
+/// are stored as a `JsonObject`, so they need a type from another vocabulary
+/// that the `@context` defines.
+fn validate_untyped_nodes(nodes: &[JsonLdNode], errors: &mut Vec<ValidationError>) {
+    if nodes.is_empty() {
+        errors.push(ValidationError::new("@type", "is required"));
+        return;
+    }
+    // Types that can't be expanded are kept as written, without a scheme
+    if let Some(undefined) = nodes
+        .iter()
+        .flat_map(|node| &node.types)
+        .find(|t| !t.contains(':'))
+    {
+        errors.push(ValidationError::new(
+            "@type",
+            format!("type `{}` isn't defined by the `@context`", undefined),
+        ));
+    }
+}
+
+/// Checks that a schema.org type is supported and returns it
+fn validate_type(
+    schema_org_type: &str,
+    errors: &mut Vec<ValidationError>,
+) -> Option<&'static SupportedType> {
+    let supported = AtomType::from_str(schema_org_type)
+        .ok()
+        .and_then(|atom_type| SUPPORTED_TYPES.iter().find(|t| t.atom_type == atom_type));
+    if supported.is_none() {
+        let names = SUPPORTED_TYPES
+            .iter()
+            .map(|t| t.atom_type.to_string())
+            .collect::<Vec<_>>();
+        errors.push(ValidationError::new(
+            "@type",
+            format!(
+                "unsupported type `{}`, expected one of {}",
+                schema_org_type,
+                names.join(", ")
+            ),
+        ));
+    }
+    supported
+}
+
+/// Checks the fields read by the resolver for a supported type
+fn validate_fields(
+    obj: &Map<String, Value>,
+    supported: &SupportedType,
+    errors: &mut Vec<ValidationError>,
+) {
+    for field in supported.required {
+        match obj.get(*field).and_then(Value::as_str) {
+            Some(value) if !value.trim().is_empty() => {}
+            Some(_) => errors.push(ValidationError::new(field, "must not be empty")),
+            None if obj.contains_key(*field) => {}
+            None => errors.push(ValidationError::new(field, "is required")),
+        }
+    }
+
+    for field in supported.required.iter().chain(supported.optional) {
+        let Some(value) = obj.get(*field) else {
+            continue;
+        };
+        let value = match value.as_str() {
+            Some(value) => value,
+            None if NESTED_FIELDS.contains(field) => {
+                match value.get("name").and_then(Value::as_str) {
+                    Some(name) => name,
+                    None => {
+                        errors.push(ValidationError::new(
+                            field,
+                            "must be a string or an object with a `name`",
+                        ));
+                        continue;
+                    }
+                }
+            }
+            None => {
+                errors.push(ValidationError::new(field, "must be a string"));
+                continue;
+            }
+        };
+        // An empty optional field is the same as a missing one
+        if value.is_empty() {
+            continue;
+        }
+        if value.chars().count() > MAX_STRING_LENGTH {
+            errors.push(ValidationError::new(
+                field,
+                format!("must be at most {} characters", MAX_STRING_LENGTH),
+            ));
+            continue;
+        }
+        if let Err(message) = validate_format(field, value) {
+            errors.push(ValidationError::new(field, message));
+        }
+    }
+
+    for field in supported.numbers {
+        match obj.get(*field) {
+            Some(value) if !value.is_number() => {
+                errors.push(ValidationError::new(field, "must be a number"))
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Checks the format of the URL, image and email fields
+fn validate_format(field: &str, value: &str) -> Result<(), String> {
+    match field {
+        "url" => validate_uri(value, &URL_SCHEMES),
+        "image" => {
+            validate_uri(value, &IMAGE_SCHEMES)?;
+            if value.starts_with("data:") && !value.starts_with("data:image/") {
+                return Err("data URIs must contain an image".to_string());
+            }
+            Ok(())
+        }
+        "email" => match value.split_once('@') {
+            Some((user, domain)) if !user.is_empty() && domain.contains('.') => Ok(()),
+            _ => Err("must be an email address".to_string()),
+        },
+        _ => Ok(()),
+    }
+}
+
+/// Checks that a value is an absolute URI with one of the schemes
+fn validate_uri(value: &str, schemes: &[&str]) -> Result<(), String> {
+    let url = Url::parse(value).map_err(|e| format!("must be a valid URI: {}", e))?;
+    if !schemes.contains(&url.scheme()) {
+        return Err(format!(
+            "unsupported scheme `{}`, expected one of {}",
+            url.scheme(),
+            schemes.join(", ")
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_validate_schema_org_atom() {
+        let metadata = validate_json_atom(&json!({
+            "@context": "https://schema.org/",
+            "@type": "Person",
+            "name": "Alice",
+            "image": "ipfs://QmcqqAoEQLAP84ptTY1VjL7UoXMbGQ8sjyAPHXog8Ynbrt",
+            "url": "https://example.com",
+            "email": "alice@example.com"
+        }))
+        .unwrap();
+
+        assert_eq!(metadata.label, "Alice");
+        assert_eq!(metadata.atom_type, "Person");
+        assert_eq!(
+            metadata.image.as_deref(),
+            Some("ipfs://QmcqqAoEQLAP84ptTY1VjL7UoXMbGQ8sjyAPHXog8Ynbrt")
+        );
+    }
+
+    #[test]
+    fn test_validate_plain_json_atom() {
+        let metadata = validate_json_atom(&json!({"foo": "bar"})).unwrap();
+        assert_eq!(metadata.atom_type, "JsonObject");
+
+        let errors = validate_json_atom(&json!({"@type": "Thing"}))
+            .unwrap_err()
+            .errors;
+        assert_eq!(errors[0].field, "@type");
+        assert!(validate_json_atom(&json!(["not", "an", "object"])).is_err());
+    }
+
+    #[test]
+    fn test_validate_collects_errors() {
+        let errors = validate_json_atom(&json!({
+            "@context": "https://schema.org",
+            "@type": "Thing",
+            "description": 42,
+            "image": "data:text/html,<script></script>",
+            "url": "ftp://example.com"
+        }))
+        .unwrap_err()
+        .errors;
+
+        let fields = errors.iter().map(|e| e.field.as_str()).collect::<Vec<_>>();
+        assert_eq!(fields, vec!["name", "description", "image", "url"]);
+    }
+
+    #[test]
+    fn test_validate_context_and_type() {
+        let errors = validate_json_atom(&json!({
+            "@context": "https://example.com",
+            "@type": "Recipe",
+            "name": "Pancakes"
+        }))
+        .unwrap_err()
+        .errors;
+
+        assert_eq!(
+            errors,
+            vec![ValidationError::new(
+                "@type",
+                "type `Recipe` isn't defined by the `@context`"
+            )]
+        );
+
+        let errors = validate_json_atom(&json!({
+            "@context": "https://schema.org",
+            "@type": "Recipe",
+            "name": "Pancakes"
+        }))
+        .unwrap_err()
+        .errors;
+        assert_eq!(errors[0].field, "@type");
+
+        // Other vocabularies are stored as a JsonObject
+        let metadata = validate_json_atom(&json!({
+            "@context": {"@vocab": "https://example.com/vocab#"},
+            "@type": "Badge",
+            "name": "Early adopter"
+        }))
+        .unwrap();
+        assert_eq!(metadata.atom_type, "JsonObject");
+    }
+
+    #[test]
+    fn test_validate_json_ld_atom() {
+        let metadata = validate_json_atom(&json!({
+            "@context": ["https://schema.org", {"ex": "https://example.com/vocab#"}],
+            "@type": ["ex:Member", "Person"],
+            "name": {"@value": "Alice"},
+            "image": {"@id": "ipfs://QmcqqAoEQLAP84ptTY1VjL7UoXMbGQ8sjyAPHXog8Ynbrt"}
+        }))
+        .unwrap();
+        assert_eq!(metadata.label, "Alice");
+        assert_eq!(metadata.atom_type, "Person");
+        assert_eq!(
+            metadata.image.as_deref(),
+            Some("ipfs://QmcqqAoEQLAP84ptTY1VjL7UoXMbGQ8sjyAPHXog8Ynbrt")
+        );
+
+        let metadata = validate_json_atom(&json!({
+            "@context": {"schema": "https://schema.org/"},
+            "@graph": [{"@type": "schema:Event", "schema:name": "Denver meetup"}]
+        }))
+        .unwrap();
+        assert_eq!(metadata.label, "Denver meetup");
+        assert_eq!(metadata.atom_type, "Event");
+    }
+
+    #[test]
+    fn test_validate_empty_optional_fields() {
+        let metadata = validate_json_atom(&json!({
+            "@context": "https://schema.org",
+            "@type": "Event",
+            "name": "Denver meetup",
+            "url": "",
+            "image": ""
+        }))
+        .unwrap();
+        assert_eq!(metadata.image, None);
+
+        let errors = validate_json_atom(&json!({
+            "@context": "https://schema.org",
+            "@type": "Event",
+            "name": ""
+        }))
+        .unwrap_err()
+        .errors;
+        assert_eq!(
+            errors,
+            vec![ValidationError::new("name", "must not be empty")]
+        );
+    }
+
+    #[test]
+    fn test_validate_nested_fields() {
+        let metadata = validate_json_atom(&json!({
+            "@context": "https://schema.org",
+            "@type": "Event",
+            "name": "Denver meetup",
+            "location": {"@type": "Place", "name": "Denver"}
+        }))
+        .unwrap();
+        assert_eq!(metadata.atom_type, "Event");
+
+        let errors = validate_json_atom(&json!({
+            "@context": "https://schema.org",
+            "@type": "Event",
+            "name": "Denver meetup",
+            "location": {"@type": "Place"},
+            "description": {"text": "A meetup"}
+        }))
+        .unwrap_err()
+        .errors;
+        let fields = errors.iter().map(|e| e.field.as_str()).collect::<Vec<_>>();
+        assert_eq!(fields, vec!["description", "location"]);
+    }
+
+    #[test]
+    fn test_validate_place_coordinates() {
+        let metadata = validate_json_atom(&json!({
+            "@context": "https://schema.org",
+            "@type": "Place",
+            "name": "Eiffel Tower",
+            "latitude": 48.8584,
+            "longitude": 2.2945
+        }))
+        .unwrap();
+        assert_eq!(metadata.atom_type, "Place");
+        assert_eq!(metadata.emoji, "📍");
+
+        let errors = validate_json_atom(&json!({
+            "@context": "https://schema.org",
+            "@type": "Place",
+            "name": "Eiffel Tower",
+            "latitude": "48.8584"
+        }))
+        .unwrap_err()
+        .errors;
+        assert_eq!(
+            errors,
+            vec![ValidationError::new("latitude", "must be a number")]
+        );
+    }
+
+    #[test]
+    fn test_validate_size_limit() {
+        let errors = validate_json_atom(&json!({"data": "a".repeat(MAX_JSON_SIZE)}))
+            .unwrap_err()
+            .errors;
+        assert_eq!(errors[0].field, "$");
+    }
+}
@@ -0,0 +1,134 @@
+use crate::{error::ApiError, state::AppState};
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+use axum_macros::debug_handler;
+use log::info;
+use models::{
+    api_key::{ApiKey, ApiKeyScope},
+    traits::SimpleCrud,
+};
+use serde::{Deserialize, Serialize};
+use shared_utils::auth::generate_api_key;
+use utoipa::ToSchema;
+
+/// The default number of requests per minute allowed for a key
+const DEFAULT_KEY_RATE_LIMIT_PER_MINUTE: i32 = 60;
+
+/// A request to issue a new API key
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct IssueApiKeyRequest {
+    /// A name to recognize the key, e.g. the client using it
+    pub name: String,
+    /// The scopes granted to the key
+    pub scopes: Vec<ApiKeyScope>,
+    /// Requests allowed per minute, defaults to 60
+    pub rate_limit_per_minute: Option<i32>,
+}
+
+/// A newly issued API key
+#[derive(Debug, Serialize, ToSchema)]
+pub struct IssuedApiKey {
+    /// The key to send as `Authorization: Bearer <key>`. It is only shown
+    /// once and can't be recovered.
+    pub key: String,
+    pub api_key: ApiKey,
+}
+
+/// List the API keys and their usage
+#[utoipa::path(
+    get,
+    path = "/admin/api_keys",
+    responses(
+        (status = 200, description = "The API keys", body = Vec<ApiKey>),
+        (status = 401, description = "Missing or invalid API key", body = String),
+        (status = 403, description = "The API key is missing the admin scope", body = String),
+        (status = 429, description = "Rate limit exceeded", body = String),
+        (status = 500, description = "Internal server error", body = String)
+    ),
+    security(("api_key" = [])),
+    tag = "admin"
+)]
+#[debug_handler]
+pub async fn list_api_keys(State(state): State<AppState>) -> Result<Json<Vec<ApiKey>>, ApiError> {
+    let api_keys = ApiKey::find_all(&state.pg_pool, &state.api_key_schema).await?;
+
+    Ok(Json(api_keys))
+}
+
+/// Issue a new API key
+#[utoipa::path(
+    post,
+    path = "/admin/api_keys",
+    request_body = IssueApiKeyRequest,
+    responses(
+        (status = 200, description = "The issued key", body = IssuedApiKey),
+        (status = 400, description = "Invalid input", body = String),
+        (status = 401, description = "Missing or invalid API key", body = String),
+        (status = 403, description = "The API key is missing the admin scope", body = String),
+        (status = 429, description = "Rate limit exceeded", body = String),
+        (status = 500, description = "Internal server error", body = String)
+    ),
+    security(("api_key" = [])),
+    tag = "admin"
+)]
+#[debug_handler]
+pub async fn issue_api_key(
+    State(state): State<AppState>,
+    Json(request): Json<IssueApiKeyRequest>,
+) -> Result<Json<IssuedApiKey>, ApiError> {
+    let rate_limit_per_minute = request
+        .rate_limit_per_minute
+        .unwrap_or(DEFAULT_KEY_RATE_LIMIT_PER_MINUTE);
+    if request.name.trim().is_empty() {
+        return Err(ApiError::InvalidInput("name must not be empty".into()));
+    }
+    if request.scopes.is_empty() {
+        return Err(ApiError::InvalidInput(
+            "at least one scope is required".into(),
+        ));
+    }
+    if rate_limit_per_minute <= 0 {
+        return Err(ApiError::InvalidInput(
+            "rate_limit_per_minute must be positive".into(),
+        ));
+    }
+
+    let (key, api_key) = generate_api_key(request.name, request.scopes, rate_limit_per_minute);
+    let api_key = api_key
+        .upsert(&state.pg_pool, &state.api_key_schema)
+        .await?;
+    info!("Issued API key {} ({})", api_key.id, api_key.name);
+
+    Ok(Json(IssuedApiKey { key, api_key }))
+}
+
+/// Revoke an API key. Requests using it are rejected right away.
+#[utoipa::path(
+    post,
+    path = "/admin/api_keys/{id}/revoke",
+    params(("id" = String, Path, description = "The id of the API key")),
+    responses(
+        (status = 200, description = "The revoked key", body = ApiKey),
+        (status = 401, description = "Missing or invalid API key", body = String),
+        (status = 403, description = "The API key is missing the admin scope", body = String),
+        (status = 429, description = "Rate limit exceeded", body = String),
+        (status = 404, description = "API key not found", body = String),
+        (status = 500, description = "Internal server error", body = String)
+    ),
+    security(("api_key" = [])),
+    tag = "admin"
+)]
+#[debug_handler]
+pub async fn revoke_api_key(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<Json<ApiKey>, ApiError> {
+    let api_key = ApiKey::revoke(&id, &state.pg_pool, &state.api_key_schema)
+        .await?
+        .ok_or_else(|| ApiError::NotFound(format!("API key {}", id)))?;
+    info!("Revoked API key {} ({})", api_key.id, api_key.name);
+
+    Ok(Json(api_key))
+}
@@ -1,3 +1,6 @@
+pub mod admin;
+pub mod moderation;
+pub mod serve_image;
 pub mod upload_image;
 pub mod upload_image_from_url;
 pub mod upload_json_to_ipfs;
@@ -6,23 +9,33 @@ use crate::state::Flag;
 use crate::types::{ClassificationScore, ClassificationScoreParsed, LocalClassificationScore};
 use crate::{error::ApiError, state::AppState};
 use axum::extract::multipart::Field;
-use log::info;
+use chrono::Utc;
+use log::{info, warn};
+use models::{
+    classification_threshold::ClassificationThreshold,
+    image_moderation_audit::ImageModerationAudit, pinned_json::PinnedJson, traits::SimpleCrud,
+};
 use reqwest::Client;
-use shared_utils::types::MultiPartHandlerJson;
+use shared_utils::types::{ClassificationModel, MultiPartHandlerJson};
 use shared_utils::{
     ipfs::{IPFSResolver, IpfsResponse},
     types::MultiPartHandler,
 };
 
+/// The NSFW threshold used when no threshold is configured for a model
+pub const DEFAULT_NSFW_THRESHOLD: f64 = 0.6;
+
 /// Handles the image based on the feature flags
 async fn handle_image(
     state: &AppState,
     multi_part_handler: &MultiPartHandler,
 ) -> Result<(ClassificationScoreParsed, bool), ApiError> {
     if state.flag == Flag::HfClassification {
-        handle_image_with_hugginface(state, multi_part_handler).await
+        let nsfw_threshold = nsfw_threshold(state, &state.flag.classification_model()).await?;
+        handle_image_with_hugginface(state, multi_part_handler, nsfw_threshold).await
     } else if state.flag == Flag::LocalWithClassification {
-        handle_local_with_classification(multi_part_handler).await
+        let nsfw_threshold = nsfw_threshold(state, &state.flag.classification_model()).await?;
+        handle_local_with_classification(multi_part_handler, nsfw_threshold).await
         // This is handling the case where we have the `local_with_db` feature
         // flag enabled, but no classification feature flag.
     } else {
@@ -34,6 +47,7 @@ async fn handle_image(
 #[allow(dead_code)]
 async fn handle_local_with_classification(
     multi_part_handler: &MultiPartHandler,
+    nsfw_threshold: f64,
 ) -> Result<(ClassificationScoreParsed, bool), ApiError> {
     // Classify the image
     let classify_images = local_classify_image(&Client::new(), multi_part_handler).await?;
@@ -43,7 +57,7 @@ async fn handle_local_with_classification(
     info!("Scores parsed");
     info!("Scores for image {}: {:?}", multi_part_handler.name, scores);
     // Determine the classification status
-    let status = determine_classification_status(&scores);
+    let status = determine_classification_status(&scores, nsfw_threshold);
     Ok((scores, status))
 }
 
@@ -52,6 +66,7 @@ async fn handle_local_with_classification(
 async fn handle_image_with_hugginface(
     state: &AppState,
     multi_part_handler: &MultiPartHandler,
+    nsfw_threshold: f64,
 ) -> Result<(ClassificationScoreParsed, bool), ApiError> {
     // Classify the image
     let classify_images = hf_classify_image(
@@ -67,45 +82,99 @@ async fn handle_image_with_hugginface(
     let scores = ClassificationScoreParsed::from(classify_images);
     info!("Scores for image {}: {:?}", multi_part_handler.name, scores);
     // Determine the classification status
-    let status = determine_classification_status(&scores);
+    let status = determine_classification_status(&scores, nsfw_threshold);
     Ok((scores, status))
 }
 
+/// Builds an [`IPFSResolver`] from the app state
+pub fn ipfs_resolver(state: &AppState) -> IPFSResolver {
+    IPFSResolver::builder()
+        .http_client(Client::new())
+        .gateways(state.ipfs_gateways.clone())
+        .pinning(state.pinning.clone())
+        .build()
+}
+
 /// Uploads an image to IPFS and pins it
 async fn upload_image_to_ipfs(
     state: &AppState,
     multi_part_handler: MultiPartHandler,
 ) -> Result<IpfsResponse, ApiError> {
-    IPFSResolver::builder()
-        .http_client(Client::new())
-        .ipfs_upload_url(state.ipfs_upload_url.clone())
-        .ipfs_fetch_url(state.ipfs_fetch_url.clone())
-        .pinata_jwt(state.pinata_api_jwt.clone())
-        .build()
+    ipfs_resolver(state)
         .upload_to_ipfs_and_pin(multi_part_handler)
         .await
         .map_err(|e| ApiError::ExternalService(format!("IPFS error: {}", e)))
 }
 
-/// Uploads a json to IPFS and pins it
+/// Keeps a copy of an image in the local store, if one is configured. A
+/// failure is only logged, the image can still be fetched from IPFS.
+async fn store_image(state: &AppState, key: &str, data: &[u8]) {
+    if let Some(store) = &state.image_store {
+        if let Err(e) = store.put(key, data).await {
+            warn!("Failed to store image {} locally: {}", key, e);
+        }
+    }
+}
+
+/// Uploads a json to IPFS and pins it. The CID is recorded so the pinning
+/// reconciliation job can check it stays pinned.
 async fn upload_json_to_ipfs(
     state: &AppState,
     multi_part_handler: MultiPartHandlerJson,
 ) -> Result<IpfsResponse, ApiError> {
-    IPFSResolver::builder()
-        .http_client(Client::new())
-        .ipfs_upload_url(state.ipfs_upload_url.clone())
-        .ipfs_fetch_url(state.ipfs_fetch_url.clone())
-        .pinata_jwt(state.pinata_api_jwt.clone())
-        .build()
+    let ipfs_response = ipfs_resolver(state)
         .upload_json_to_ipfs_and_pin(multi_part_handler)
         .await
-        .map_err(|e| ApiError::ExternalService(format!("IPFS error: {}", e)))
+        .map_err(|e| ApiError::ExternalService(format!("IPFS error: {}", e)))?;
+
+    PinnedJson::builder()
+        .cid(ipfs_response.hash.clone())
+        .size(ipfs_response.size.parse::<i64>().unwrap_or_default())
+        .created_at(Utc::now())
+        .build()
+        .upsert(&state.pg_pool, &state.image_api_schema)
+        .await?;
+
+    Ok(ipfs_response)
+}
+
+/// Returns the NSFW threshold configured for a classification model,
+/// falling back to [`DEFAULT_NSFW_THRESHOLD`]
+async fn nsfw_threshold(state: &AppState, model: &ClassificationModel) -> Result<f64, ApiError> {
+    Ok(ClassificationThreshold::find_by_id(
+        model.to_string(),
+        &state.pg_pool,
+        &state.image_api_schema,
+    )
+    .await?
+    .map(|threshold| threshold.nsfw_threshold)
+    .unwrap_or(DEFAULT_NSFW_THRESHOLD))
 }
 
 /// Determines the classification status based on the scores
-fn determine_classification_status(scores: &ClassificationScoreParsed) -> bool {
-    scores.nsfw <= 0.6
+fn determine_classification_status(
+    scores: &ClassificationScoreParsed,
+    nsfw_threshold: f64,
+) -> bool {
+    f64::from(scores.nsfw) <= nsfw_threshold
+}
+
+/// Returns the verdict of a reviewer if the image was manually moderated,
+/// otherwise the classification status. This way uploading an image again
+/// does not override a manual verdict.
+async fn apply_manual_verdict(
+    state: &AppState,
+    url: &str,
+    classification_status: bool,
+) -> Result<bool, ApiError> {
+    Ok(
+        match ImageModerationAudit::find_latest_by_url(url, &state.pg_pool, &state.image_api_schema)
+            .await?
+        {
+            Some(entry) if entry.is_manual_verdict() => entry.new_safe,
+            _ => classification_status,
+        },
+    )
 }
 
 /// Checks the image format and returns a [`MultiPartHandler`]
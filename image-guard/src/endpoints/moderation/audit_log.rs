@@ -0,0 +1,43 @@
+use crate::{error::ApiError, state::AppState};
+use axum::{
+    extract::{Query, State},
+    Json,
+};
+use axum_macros::debug_handler;
+use models::image_moderation_audit::ImageModerationAudit;
+use serde::Deserialize;
+use utoipa::IntoParams;
+
+/// Query parameters used to fetch the audit log of an image
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct AuditLogQuery {
+    /// The url of the cached image, e.g. `ipfs://<cid>`
+    pub url: String,
+}
+
+/// Get the moderation audit log of an image, most recent entries first
+#[utoipa::path(
+    get,
+    path = "/moderation/images/audit",
+    params(AuditLogQuery),
+    responses(
+        (status = 200, description = "Audit log of the image", body = Vec<ImageModerationAudit>),
+        (status = 401, description = "Missing or invalid API key", body = String),
+        (status = 403, description = "The API key is missing the moderate scope", body = String),
+        (status = 429, description = "Rate limit exceeded", body = String),
+        (status = 500, description = "Internal server error", body = String)
+    ),
+    security(("api_key" = [])),
+    tag = "moderation"
+)]
+#[debug_handler]
+pub async fn audit_log(
+    State(state): State<AppState>,
+    Query(query): Query<AuditLogQuery>,
+) -> Result<Json<Vec<ImageModerationAudit>>, ApiError> {
+    let entries =
+        ImageModerationAudit::find_by_url(&query.url, &state.pg_pool, &state.image_api_schema)
+            .await?;
+
+    Ok(Json(entries))
+}
@@ -0,0 +1,79 @@
+use crate::{error::ApiError, state::AppState};
+use axum::{
+    extract::{Query, State},
+    Json,
+};
+use axum_macros::debug_handler;
+use models::cached_image::CachedImage;
+use serde::Deserialize;
+use utoipa::IntoParams;
+
+/// The maximum number of images returned in a single page
+const MAX_LIMIT: i64 = 500;
+
+/// Query parameters used to list images by their nsfw score
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct ListImagesQuery {
+    /// Lower bound of the nsfw score (inclusive), defaults to 0
+    pub min_nsfw: Option<f64>,
+    /// Upper bound of the nsfw score (inclusive), defaults to 1
+    pub max_nsfw: Option<f64>,
+    /// Only return images with this verdict
+    pub safe: Option<bool>,
+    /// Number of images to return, defaults to 50
+    pub limit: Option<i64>,
+    /// Number of images to skip, defaults to 0
+    pub offset: Option<i64>,
+}
+
+/// List images whose nsfw score falls in a given band, highest scores first
+#[utoipa::path(
+    get,
+    path = "/moderation/images",
+    params(ListImagesQuery),
+    responses(
+        (status = 200, description = "Images in the requested score band", body = Vec<CachedImage>),
+        (status = 400, description = "Invalid score band or pagination", body = String),
+        (status = 401, description = "Missing or invalid API key", body = String),
+        (status = 403, description = "The API key is missing the moderate scope", body = String),
+        (status = 429, description = "Rate limit exceeded", body = String),
+        (status = 500, description = "Internal server error", body = String)
+    ),
+    security(("api_key" = [])),
+    tag = "moderation"
+)]
+#[debug_handler]
+pub async fn list_images(
+    State(state): State<AppState>,
+    Query(query): Query<ListImagesQuery>,
+) -> Result<Json<Vec<CachedImage>>, ApiError> {
+    let min_nsfw = query.min_nsfw.unwrap_or(0.0);
+    let max_nsfw = query.max_nsfw.unwrap_or(1.0);
+    let limit = query.limit.unwrap_or(50);
+    let offset = query.offset.unwrap_or(0);
+
+    if min_nsfw > max_nsfw {
+        return Err(ApiError::InvalidInput(
+            "min_nsfw must be lower than or equal to max_nsfw".into(),
+        ));
+    }
+    if !(1..=MAX_LIMIT).contains(&limit) || offset < 0 {
+        return Err(ApiError::InvalidInput(format!(
+            "limit must be between 1 and {} and offset must not be negative",
+            MAX_LIMIT
+        )));
+    }
+
+    let images = CachedImage::find_by_nsfw_score_band(
+        &state.pg_pool,
+        min_nsfw,
+        max_nsfw,
+        query.safe,
+        limit,
+        offset,
+        &state.image_api_schema,
+    )
+    .await?;
+
+    Ok(Json(images))
+}
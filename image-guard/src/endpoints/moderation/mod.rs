@@ -0,0 +1,43 @@
+pub mod audit_log;
+pub mod list_images;
+pub mod reclassify_image;
+pub mod review_image;
+pub mod thresholds;
+
+use crate::{error::ApiError, state::AppState};
+use models::{
+    cached_image::CachedImage,
+    image_moderation_audit::{ImageModerationAudit, ModerationAction},
+    traits::SimpleCrud,
+};
+
+/// Finds a cached image by its url or returns a [`ApiError::NotFound`]
+async fn find_cached_image(state: &AppState, url: &str) -> Result<CachedImage, ApiError> {
+    CachedImage::find_by_id(url.to_string(), &state.pg_pool, &state.image_api_schema)
+        .await?
+        .ok_or_else(|| ApiError::NotFound(format!("cached image {}", url)))
+}
+
+/// Stores the updated image and records the change in the audit log, in a
+/// single transaction
+async fn record_moderation_change(
+    state: &AppState,
+    previous: &CachedImage,
+    updated: &CachedImage,
+    action: ModerationAction,
+    reviewer: String,
+    reason: Option<String>,
+) -> Result<ImageModerationAudit, ApiError> {
+    Ok(ImageModerationAudit::builder()
+        .url(updated.url.clone())
+        .action(action)
+        .reviewer(reviewer)
+        .reason(reason)
+        .previous_safe(previous.safe)
+        .new_safe(updated.safe)
+        .previous_score(previous.score.clone())
+        .new_score(updated.score.clone())
+        .build()
+        .insert_with_image(updated, &state.pg_pool, &state.image_api_schema)
+        .await?)
+}
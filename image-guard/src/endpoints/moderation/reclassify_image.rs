@@ -0,0 +1,96 @@
+use crate::{
+    endpoints::{
+        handle_image, ipfs_resolver,
+        moderation::{find_cached_image, record_moderation_change},
+        validate_image_bytes,
+    },
+    error::ApiError,
+    state::AppState,
+};
+use axum::{extract::State, Json};
+use axum_macros::debug_handler;
+use log::info;
+use models::image_moderation_audit::{ImageModerationAudit, ModerationAction};
+use serde::Deserialize;
+use shared_utils::types::MultiPartHandler;
+use utoipa::ToSchema;
+
+/// A request to run the classifier again on a cached image
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct ReclassifyImageRequest {
+    /// The url of the cached image, e.g. `ipfs://<cid>`
+    pub url: String,
+    /// Who requested the reclassification
+    pub reviewer: String,
+    /// Why the image is reclassified
+    pub reason: Option<String>,
+}
+
+/// Fetch an image from IPFS and classify it again using the current
+/// threshold. This replaces any previous manual verdict.
+#[utoipa::path(
+    post,
+    path = "/moderation/images/reclassify",
+    request_body = ReclassifyImageRequest,
+    responses(
+        (status = 200, description = "Image reclassified, returns the audit log entry", body = ImageModerationAudit),
+        (status = 400, description = "Invalid input - not an IPFS image", body = String),
+        (status = 401, description = "Missing or invalid API key", body = String),
+        (status = 403, description = "The API key is missing the moderate scope", body = String),
+        (status = 429, description = "Rate limit exceeded", body = String),
+        (status = 404, description = "Image not found", body = String),
+        (status = 500, description = "Internal server error", body = String)
+    ),
+    security(("api_key" = [])),
+    tag = "moderation"
+)]
+#[debug_handler]
+pub async fn reclassify_image(
+    State(state): State<AppState>,
+    Json(request): Json<ReclassifyImageRequest>,
+) -> Result<Json<ImageModerationAudit>, ApiError> {
+    if request.reviewer.trim().is_empty() {
+        return Err(ApiError::InvalidInput("reviewer must not be empty".into()));
+    }
+
+    let previous = find_cached_image(&state, &request.url).await?;
+    let cid = previous
+        .url
+        .strip_prefix("ipfs://")
+        .ok_or_else(|| ApiError::InvalidInput(format!("{} is not an IPFS url", previous.url)))?;
+
+    let content = ipfs_resolver(&state).fetch_from_ipfs(cid).await?;
+    let data = content.data;
+    let content_type = content
+        .content_type
+        .unwrap_or_else(|| "application/octet-stream".to_string());
+    validate_image_bytes(&data)?;
+
+    let multi_part_handler = MultiPartHandler {
+        name: previous.original_url.clone(),
+        data,
+        content_type,
+    };
+    let (scores, status) = handle_image(&state, &multi_part_handler).await?;
+    info!(
+        "{} reclassified {}: {:?}",
+        request.reviewer, request.url, scores
+    );
+
+    let mut updated = previous.clone();
+    updated.score = Some(serde_json::to_value(&scores)?);
+    updated.model = Some(state.flag.classification_model().to_string());
+    updated.safe = status;
+
+    let entry = record_moderation_change(
+        &state,
+        &previous,
+        &updated,
+        ModerationAction::Reclassified,
+        request.reviewer,
+        request.reason,
+    )
+    .await?;
+
+    Ok(Json(entry))
+}
@@ -0,0 +1,75 @@
+use crate::{
+    endpoints::moderation::{find_cached_image, record_moderation_change},
+    error::ApiError,
+    state::AppState,
+};
+use axum::{extract::State, Json};
+use axum_macros::debug_handler;
+use log::info;
+use models::image_moderation_audit::{ImageModerationAudit, ModerationAction};
+use serde::Deserialize;
+use utoipa::ToSchema;
+
+/// A manual verdict on a cached image
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct ReviewImageRequest {
+    /// The url of the cached image, e.g. `ipfs://<cid>`
+    pub url: String,
+    /// Whether the image is safe
+    pub safe: bool,
+    /// Who reviewed the image
+    pub reviewer: String,
+    /// Why the verdict was given
+    pub reason: Option<String>,
+}
+
+/// Mark an image as safe or unsafe. The verdict takes precedence over the
+/// classifier, so it is kept when the image is uploaded again.
+#[utoipa::path(
+    post,
+    path = "/moderation/images/review",
+    request_body = ReviewImageRequest,
+    responses(
+        (status = 200, description = "Verdict applied, returns the audit log entry", body = ImageModerationAudit),
+        (status = 400, description = "Invalid input", body = String),
+        (status = 401, description = "Missing or invalid API key", body = String),
+        (status = 403, description = "The API key is missing the moderate scope", body = String),
+        (status = 429, description = "Rate limit exceeded", body = String),
+        (status = 404, description = "Image not found", body = String),
+        (status = 500, description = "Internal server error", body = String)
+    ),
+    security(("api_key" = [])),
+    tag = "moderation"
+)]
+#[debug_handler]
+pub async fn review_image(
+    State(state): State<AppState>,
+    Json(request): Json<ReviewImageRequest>,
+) -> Result<Json<ImageModerationAudit>, ApiError> {
+    if request.reviewer.trim().is_empty() {
+        return Err(ApiError::InvalidInput("reviewer must not be empty".into()));
+    }
+
+    let previous = find_cached_image(&state, &request.url).await?;
+    let mut updated = previous.clone();
+    updated.safe = request.safe;
+
+    let action = if request.safe {
+        ModerationAction::MarkedSafe
+    } else {
+        ModerationAction::MarkedUnsafe
+    };
+    info!("{} marked {} as {}", request.reviewer, request.url, action);
+
+    let entry = record_moderation_change(
+        &state,
+        &previous,
+        &updated,
+        action,
+        request.reviewer,
+        request.reason,
+    )
+    .await?;
+
+    Ok(Json(entry))
+}
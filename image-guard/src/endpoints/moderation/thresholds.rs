@@ -0,0 +1,94 @@
+use crate::{error::ApiError, state::AppState};
+use axum::{extract::State, Json};
+use axum_macros::debug_handler;
+use chrono::Utc;
+use log::info;
+use models::{classification_threshold::ClassificationThreshold, traits::SimpleCrud};
+use serde::Deserialize;
+use shared_utils::types::ClassificationModel;
+use utoipa::ToSchema;
+
+/// A request to change the nsfw threshold of a classification model
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct UpdateThresholdRequest {
+    /// The classification model, defaults to the model the images are
+    /// currently classified with
+    pub model: Option<String>,
+    /// Images with an nsfw score above this value are classified as unsafe
+    pub nsfw_threshold: f64,
+}
+
+/// List the nsfw thresholds of the classification models
+#[utoipa::path(
+    get,
+    path = "/moderation/thresholds",
+    responses(
+        (status = 200, description = "Configured thresholds", body = Vec<ClassificationThreshold>),
+        (status = 401, description = "Missing or invalid API key", body = String),
+        (status = 403, description = "The API key is missing the moderate scope", body = String),
+        (status = 429, description = "Rate limit exceeded", body = String),
+        (status = 500, description = "Internal server error", body = String)
+    ),
+    security(("api_key" = [])),
+    tag = "moderation"
+)]
+#[debug_handler]
+pub async fn list_thresholds(
+    State(state): State<AppState>,
+) -> Result<Json<Vec<ClassificationThreshold>>, ApiError> {
+    let thresholds =
+        ClassificationThreshold::find_all(&state.pg_pool, &state.image_api_schema).await?;
+
+    Ok(Json(thresholds))
+}
+
+/// Update the nsfw threshold of a classification model. New uploads and
+/// reclassifications use the new threshold, existing verdicts are untouched.
+#[utoipa::path(
+    post,
+    path = "/moderation/thresholds",
+    request_body = UpdateThresholdRequest,
+    responses(
+        (status = 200, description = "Threshold updated", body = ClassificationThreshold),
+        (status = 400, description = "Unknown model or threshold outside of [0, 1]", body = String),
+        (status = 401, description = "Missing or invalid API key", body = String),
+        (status = 403, description = "The API key is missing the moderate scope", body = String),
+        (status = 429, description = "Rate limit exceeded", body = String),
+        (status = 500, description = "Internal server error", body = String)
+    ),
+    security(("api_key" = [])),
+    tag = "moderation"
+)]
+#[debug_handler]
+pub async fn update_threshold(
+    State(state): State<AppState>,
+    Json(request): Json<UpdateThresholdRequest>,
+) -> Result<Json<ClassificationThreshold>, ApiError> {
+    if !(0.0..=1.0).contains(&request.nsfw_threshold) {
+        return Err(ApiError::InvalidInput(
+            "nsfw_threshold must be between 0 and 1".into(),
+        ));
+    }
+
+    let model = match request.model {
+        Some(model) => model
+            .parse::<ClassificationModel>()
+            .map_err(ApiError::InvalidInput)?,
+        None => state.flag.classification_model(),
+    }
+    .to_string();
+    info!(
+        "Setting nsfw threshold of {} to {}",
+        model, request.nsfw_threshold
+    );
+
+    let threshold = ClassificationThreshold::builder()
+        .model(model)
+        .nsfw_threshold(request.nsfw_threshold)
+        .updated_at(Utc::now())
+        .build()
+        .upsert(&state.pg_pool, &state.image_api_schema)
+        .await?;
+
+    Ok(Json(threshold))
+}
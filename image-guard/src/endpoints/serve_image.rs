@@ -0,0 +1,369 @@
+use crate::{
+    endpoints::{ipfs_resolver, store_image},
+    error::ApiError,
+    state::AppState,
+};
+use axum::{
+    body::{Body, Bytes},
+    extract::{Path, State},
+    http::{HeaderMap, HeaderValue, Response, StatusCode},
+};
+use axum_macros::debug_handler;
+use http::header::{
+    ACCEPT, CACHE_CONTROL, CONTENT_TYPE, ETAG, IF_NONE_MATCH, VARY, X_CONTENT_TYPE_OPTIONS,
+};
+use image::ImageFormat;
+use log::{info, warn};
+use models::{cached_image::CachedImage, traits::SimpleCrud};
+use std::io::Cursor;
+
+/// Served images are revalidated on every use, since a reviewer can still
+/// mark them as unsafe. The ETag makes the revalidation a 304 without a body.
+const CACHE_CONTROL_VALUE: &str = "private, no-cache";
+
+/// The formats an image can be converted to when the client doesn't accept
+/// the original format
+const VARIANT_FORMATS: [ImageFormat; 3] = [ImageFormat::WebP, ImageFormat::Png, ImageFormat::Jpeg];
+
+/// Serve an image by its IPFS hash or by the sha256 digest of its original url
+#[utoipa::path(
+    get,
+    path = "/images/{key}",
+    params(
+        ("key" = String, Path, description = "The IPFS hash of the image, or the hex encoded sha256 digest of its original url"),
+        ("Accept" = Option<String>, Header, description = "Preferred image formats, e.g. `image/webp,image/*;q=0.8`"),
+        ("If-None-Match" = Option<String>, Header, description = "ETag of a previously served image"),
+    ),
+    responses(
+        (status = 200, description = "The image", content_type = "image/*", body = Vec<u8>),
+        (status = 304, description = "The image didn't change"),
+        (status = 403, description = "The image is classified as unsafe", body = String),
+        (status = 404, description = "Image not found", body = String),
+        (status = 406, description = "The image can't be served in any accepted format", body = String),
+        (status = 500, description = "Internal server error", body = String)
+    ),
+    tag = "images"
+)]
+#[debug_handler]
+pub async fn serve_image(
+    State(state): State<AppState>,
+    Path(key): Path<String>,
+    headers: HeaderMap,
+) -> Result<Response<Body>, ApiError> {
+    let image = find_image(&state, &key).await?;
+    if !image.safe {
+        return Err(ApiError::Forbidden(format!("image {} is not safe", key)));
+    }
+    let cid = image
+        .url
+        .strip_prefix("ipfs://")
+        .ok_or_else(|| ApiError::NotFound(format!("image {}", key)))?;
+
+    // The content of a CID never changes, so a client already holding it in
+    // an acceptable format is answered before the image is loaded
+    let accept = headers.get(ACCEPT).and_then(|value| value.to_str().ok());
+    let cached_format = headers
+        .get(IF_NONE_MATCH)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| cached_format(value, cid, accept));
+    if let Some(format) = cached_format {
+        return build_response(
+            StatusCode::NOT_MODIFIED,
+            &etag(cid, format),
+            format,
+            Body::empty(),
+        );
+    }
+
+    let original = load_original(&state, cid).await?;
+    let original_format = image::guess_format(&original).map_err(|_| {
+        ApiError::ExternalService(format!("content of {} is not a supported image", cid))
+    })?;
+    let format = negotiate_format(accept, original_format)?;
+
+    let data = if format == original_format {
+        original
+    } else {
+        load_variant(&state, cid, original, original_format, format).await?
+    };
+
+    build_response(StatusCode::OK, &etag(cid, format), format, Body::from(data))
+}
+
+/// Finds the image by its IPFS hash, or by the sha256 digest of its original
+/// url when the key is a 64 characters hex string
+async fn find_image(state: &AppState, key: &str) -> Result<CachedImage, ApiError> {
+    let image = if key.len() == 64 && key.chars().all(|c| c.is_ascii_hexdigit()) {
+        CachedImage::find_by_original_url_digest(&state.pg_pool, key, &state.image_api_schema)
+            .await?
+    } else {
+        CachedImage::find_by_id(
+            format!("ipfs://{}", key),
+            &state.pg_pool,
+            &state.image_api_schema,
+        )
+        .await?
+    };
+
+    image.ok_or_else(|| ApiError::NotFound(format!("image {}", key)))
+}
+
+/// Loads the original image from the local store, falling back to IPFS. Images
+/// fetched from IPFS are added to the store.
+async fn load_original(state: &AppState, cid: &str) -> Result<Bytes, ApiError> {
+    if let Some(data) = get_from_store(state, cid).await {
+        return Ok(data);
+    }
+
+    info!("Fetching image {} from IPFS", cid);
+    let data = ipfs_resolver(state).fetch_from_ipfs(cid).await?.data;
+    store_image(state, cid, &data).await;
+
+    Ok(data)
+}
+
+/// Loads a variant of the image in the given format, converting the original
+/// if the variant is not in the local store yet
+async fn load_variant(
+    state: &AppState,
+    cid: &str,
+    original: Bytes,
+    original_format: ImageFormat,
+    format: ImageFormat,
+) -> Result<Bytes, ApiError> {
+    let key = format!("{}.{}", cid, extension(format));
+    if let Some(data) = get_from_store(state, &key).await {
+        return Ok(data);
+    }
+
+    info!("Converting image {} to {:?}", cid, format);
+    let data = tokio::task::spawn_blocking(move || convert(&original, original_format, format))
+        .await
+        .map_err(|e| ApiError::ExternalService(format!("Image conversion failed: {}", e)))??;
+    store_image(state, &key, &data).await;
+
+    Ok(data)
+}
+
+/// Converts an image from one format to another
+fn convert(data: &[u8], from: ImageFormat, to: ImageFormat) -> Result<Bytes, ApiError> {
+    let mut image = image::load_from_memory_with_format(data, from)
+        .map_err(|e| ApiError::ExternalService(format!("Failed to decode image: {}", e)))?;
+    // JPEG has no alpha channel
+    if to == ImageFormat::Jpeg {
+        image = image.to_rgb8().into();
+    }
+
+    let mut output = Cursor::new(Vec::new());
+    image
+        .write_to(&mut output, to)
+        .map_err(|e| ApiError::ExternalService(format!("Failed to encode image: {}", e)))?;
+    Ok(Bytes::from(output.into_inner()))
+}
+
+/// Reads from the local store, if configured. A failing store is only
+/// logged so the image can still be served from IPFS.
+async fn get_from_store(state: &AppState, key: &str) -> Option<Bytes> {
+    let store = state.image_store.as_ref()?;
+    store.get(key).await.unwrap_or_else(|e| {
+        warn!("Failed to read {} from the image store: {}", key, e);
+        None
+    })
+}
+
+/// Returns the extension used for the store keys and ETags of a format
+fn extension(format: ImageFormat) -> &'static str {
+    format.extensions_str().first().copied().unwrap_or("bin")
+}
+
+/// Builds the response with the caching and content headers
+fn build_response(
+    status: StatusCode,
+    etag: &str,
+    format: ImageFormat,
+    body: Body,
+) -> Result<Response<Body>, ApiError> {
+    Response::builder()
+        .status(status)
+        .header(CONTENT_TYPE, format.to_mime_type())
+        .header(ETAG, etag)
+        .header(CACHE_CONTROL, HeaderValue::from_static(CACHE_CONTROL_VALUE))
+        .header(VARY, HeaderValue::from_static("Accept"))
+        .header(X_CONTENT_TYPE_OPTIONS, HeaderValue::from_static("nosniff"))
+        .body(body)
+        .map_err(|e| ApiError::ExternalService(format!("Failed to build response: {}", e)))
+}
+
+/// Returns the ETag of an image served in a format
+fn etag(cid: &str, format: ImageFormat) -> String {
+    format!("\"{}.{}\"", cid, extension(format))
+}
+
+/// Returns the format of the first ETag of the `If-None-Match` header that
+/// was served for the CID in a format the client accepts. Weak validators are
+/// compared as strong ones since the content never changes.
+fn cached_format(if_none_match: &str, cid: &str, accept: Option<&str>) -> Option<ImageFormat> {
+    let ranges = accept.map(parse_accept).unwrap_or_default();
+    if_none_match
+        .split(',')
+        .filter_map(|tag| {
+            tag.trim()
+                .trim_start_matches("W/")
+                .strip_prefix('"')?
+                .strip_suffix('"')?
+                .strip_prefix(cid)?
+                .strip_prefix('.')
+        })
+        .filter_map(ImageFormat::from_extension)
+        .find(|format| ranges.is_empty() || quality_of(&ranges, format.to_mime_type()) > 0.0)
+}
+
+/// Picks the format to serve from the `Accept` header. The original format
+/// is preferred over a conversion when both are equally acceptable.
+fn negotiate_format(accept: Option<&str>, original: ImageFormat) -> Result<ImageFormat, ApiError> {
+    let accept = match accept.map(str::trim) {
+        Some(accept) if !accept.is_empty() => accept,
+        _ => return Ok(original),
+    };
+    let ranges = parse_accept(accept);
+
+    let mut candidates = vec![original];
+    candidates.extend(
+        VARIANT_FORMATS
+            .into_iter()
+            .filter(|format| *format != original),
+    );
+
+    let mut best: Option<(ImageFormat, f32)> = None;
+    for format in candidates {
+        let quality = quality_of(&ranges, format.to_mime_type());
+        if quality > 0.0 && best.is_none_or(|(_, best_quality)| quality > best_quality) {
+            best = Some((format, quality));
+        }
+    }
+
+    best.map(|(format, _)| format).ok_or_else(|| {
+        ApiError::NotAcceptable(format!(
+            "the image is available as {} or {}",
+            original.to_mime_type(),
+            VARIANT_FORMATS
+                .map(|format| format.to_mime_type())
+                .join(", ")
+        ))
+    })
+}
+
+/// Parses an `Accept` header into media ranges and their quality
+fn parse_accept(accept: &str) -> Vec<(String, f32)> {
+    accept
+        .split(',')
+        .filter_map(|range| {
+            let mut parts = range.split(';');
+            let media_range = parts.next()?.trim().to_ascii_lowercase();
+            if media_range.is_empty() {
+                return None;
+            }
+            let quality = parts
+                .filter_map(|param| param.trim().strip_prefix("q="))
+                .find_map(|q| q.trim().parse::<f32>().ok())
+                .unwrap_or(1.0)
+                .clamp(0.0, 1.0);
+            Some((media_range, quality))
+        })
+        .collect()
+}
+
+/// Returns the quality of a mime type, using the most specific matching range
+fn quality_of(ranges: &[(String, f32)], mime: &str) -> f32 {
+    let main_type = mime.split('/').next().unwrap_or_default();
+    let specificity = |range: &str| {
+        if range == mime {
+            Some(2)
+        } else if range.strip_suffix("/*") == Some(main_type) {
+            Some(1)
+        } else if range == "*/*" {
+            Some(0)
+        } else {
+            None
+        }
+    };
+
+    ranges
+        .iter()
+        .filter_map(|(range, quality)| specificity(range).map(|s| (s, *quality)))
+        .max_by_key(|(s, _)| *s)
+        .map(|(_, quality)| quality)
+        .unwrap_or(0.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_negotiate_format() {
+        // No preference serves the original
+        assert_eq!(
+            negotiate_format(None, ImageFormat::Gif).unwrap(),
+            ImageFormat::Gif
+        );
+        assert_eq!(
+            negotiate_format(Some("*/*"), ImageFormat::Gif).unwrap(),
+            ImageFormat::Gif
+        );
+        // The original wins ties
+        assert_eq!(
+            negotiate_format(Some("image/webp,image/png"), ImageFormat::Png).unwrap(),
+            ImageFormat::Png
+        );
+        // Converts when the original is not accepted
+        assert_eq!(
+            negotiate_format(Some("image/webp,image/*;q=0"), ImageFormat::Png).unwrap(),
+            ImageFormat::WebP
+        );
+        // Honors the quality values
+        assert_eq!(
+            negotiate_format(
+                Some("image/avif,image/jpeg;q=0.9,image/*;q=0.8"),
+                ImageFormat::Png
+            )
+            .unwrap(),
+            ImageFormat::Jpeg
+        );
+        assert!(negotiate_format(Some("text/html"), ImageFormat::Png).is_err());
+    }
+
+    #[test]
+    fn test_cached_format() {
+        let cid = "bafy";
+        assert_eq!(
+            cached_format("\"bafy.png\"", cid, None),
+            Some(ImageFormat::Png)
+        );
+        assert_eq!(
+            cached_format("\"other.png\", W/\"bafy.webp\"", cid, Some("image/webp")),
+            Some(ImageFormat::WebP)
+        );
+        // A variant the client no longer accepts is served again
+        assert_eq!(
+            cached_format("\"bafy.webp\"", cid, Some("image/png,image/*;q=0")),
+            None
+        );
+        assert_eq!(cached_format("\"bafy2.png\"", cid, None), None);
+        assert_eq!(cached_format("*", cid, None), None);
+    }
+
+    #[test]
+    fn test_build_response_revalidates() {
+        let response = build_response(
+            StatusCode::OK,
+            &etag("bafy", ImageFormat::Png),
+            ImageFormat::Png,
+            Body::empty(),
+        )
+        .unwrap();
+        // Shared caches can't keep an image a reviewer may still flag
+        assert_eq!(response.headers()[CACHE_CONTROL], "private, no-cache");
+        assert_eq!(response.headers()[ETAG], "\"bafy.png\"");
+    }
+}
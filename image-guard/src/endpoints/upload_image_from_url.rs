@@ -1,5 +1,7 @@
 use crate::{
-    endpoints::{handle_image, upload_image_to_ipfs, validate_image_bytes},
+    endpoints::{
+        apply_manual_verdict, handle_image, store_image, upload_image_to_ipfs, validate_image_bytes,
+    },
     error::ApiError,
     state::AppState,
     types::MultipartRequest,
@@ -9,10 +11,7 @@ use axum_macros::debug_handler;
 use chrono::Utc;
 use log::{debug, info};
 use models::{cached_image::CachedImage, traits::SimpleCrud};
-use shared_utils::{
-    image::Image,
-    types::{ClassificationModel, MultiPartHandler},
-};
+use shared_utils::{image::Image, types::MultiPartHandler};
 
 /// Upload and classify an image
 #[utoipa::path(
@@ -23,15 +22,19 @@ use shared_utils::{
         (status = 200, description = "Image successfully uploaded and classified", body = Vec<CachedImage>,
             example = json!({
                 "status": "Safe",
-                "score": "{\"normal\":0.82167643,\"nsfw\":0.1601617}",
+                "score": {"normal": 0.82167643, "nsfw": 0.1601617},
                 "model": "Falconsai",
                 "date_classified": "2024-03-21T12:00:00Z",
                 "url": "QmcqqAoEQLAP84ptTY1VjL7UoXMbGQ8sjyAPHXog8Ynbrt"
             })
         ),
         (status = 400, description = "Invalid input - not an image or wrong format", body = String),
+        (status = 401, description = "Missing or invalid API key", body = String),
+        (status = 403, description = "The API key is missing the upload scope", body = String),
+        (status = 429, description = "Rate limit exceeded", body = String),
         (status = 500, description = "Internal server error", body = String)
     ),
+    security(("api_key" = [])),
     tag = "images"
 )]
 #[debug_handler]
@@ -86,14 +89,20 @@ pub async fn upload_image_from_url(
             multi_part_handler.data.len()
         );
 
+        let data = multi_part_handler.data.clone();
         let ipfs_response = upload_image_to_ipfs(&state, multi_part_handler).await?;
         info!("IPFS response: {:?}", ipfs_response);
+        store_image(&state, &ipfs_response.hash, &data).await;
+
+        let url = format!("ipfs://{}", ipfs_response.hash);
+        // Keep the verdict of a reviewer if the image was already moderated
+        let status = apply_manual_verdict(&state, &url, status).await?;
 
         let image_guard = CachedImage::builder()
-            .url(format!("ipfs://{}", ipfs_response.hash))
+            .url(url)
             .original_url(&image.url)
-            .score(serde_json::to_string(&scores)?)
-            .model(ClassificationModel::FalconsaiNsfwImageDetection.to_string())
+            .score(serde_json::to_value(&scores)?)
+            .model(state.flag.classification_model().to_string())
             .safe(status)
             .created_at(Utc::now())
             .build();
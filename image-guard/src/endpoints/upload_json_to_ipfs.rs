@@ -1,12 +1,28 @@
 use crate::{
-    endpoints::upload_json_to_ipfs, error::ApiError, state::AppState, types::MultipartRequest,
+    atom_schema::{validate_json_atom, ValidationErrors},
+    endpoints::upload_json_to_ipfs,
+    error::ApiError,
+    state::AppState,
+    types::MultipartRequest,
+};
+use axum::{
+    extract::{Query, State},
+    Json,
 };
-use axum::{extract::State, Json};
 use axum_macros::debug_handler;
 use log::info;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use shared_utils::{ipfs::IpfsResponse, types::MultiPartHandlerJson};
+use shared_utils::{atom_metadata::AtomMetadata, ipfs::IpfsResponse, types::MultiPartHandlerJson};
+use utoipa::{IntoParams, ToSchema};
+
+/// Query parameters of the JSON upload
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct UploadJsonQuery {
+    /// Only validate the JSON and return the metadata the resolver would
+    /// derive, without pinning it
+    pub dry_run: Option<bool>,
+}
 
 /// The response from the IPFS gateway
 #[derive(Deserialize, Serialize, Default, Debug)]
@@ -17,6 +33,20 @@ pub struct IpfsResponseStandard {
     pub size: String,
 }
 
+/// The result of a dry run
+#[derive(Serialize, Debug, ToSchema)]
+pub struct DryRunResponse {
+    pub metadata: AtomMetadata,
+}
+
+/// The response of the JSON upload, depending on the dry run mode
+#[derive(Serialize, Debug)]
+#[serde(untagged)]
+pub enum UploadJsonResponse {
+    Uploaded(IpfsResponseStandard),
+    DryRun(DryRunResponse),
+}
+
 impl IpfsResponseStandard {
     pub fn from_ipfs_response(ipfs_response: IpfsResponse) -> Self {
         IpfsResponseStandard {
@@ -27,29 +57,44 @@ impl IpfsResponseStandard {
     }
 }
 
-/// Upload and classify an image
+/// Validate a JSON atom and upload it to IPFS. Objects with a schema.org
+/// `@context` must have a supported `@type` and its required fields.
 #[utoipa::path(
     post,
-    path = "/upload_json_to_jpfs",
+    path = "/upload_json_to_ipfs",
+    params(UploadJsonQuery),
     request_body = inline(MultipartRequest),
     responses(
-        (status = 200, description = "Json successfully uploaded to IPFS", body = String,
+        (status = 200, description = "Json successfully uploaded to IPFS, or on a dry run the metadata the resolver would derive", body = String,
             example = json!({
                 "name": "json",
                 "hash": "QmcqqAoEQLAP84ptTY1VjL7UoXMbGQ8sjyAPHXog8Ynbrt",
                 "size": "100"
             })
         ),
-        (status = 400, description = "Invalid input - not a json or wrong format", body = String),
+        (status = 400, description = "Invalid input - the JSON atom failed validation", body = ValidationErrors),
+        (status = 401, description = "Missing or invalid API key", body = String),
+        (status = 403, description = "The API key is missing the upload scope", body = String),
+        (status = 429, description = "Rate limit exceeded", body = String),
         (status = 500, description = "Internal server error", body = String)
     ),
+    security(("api_key" = [])),
     tag = "images"
 )]
 #[debug_handler]
 pub async fn upload_json_to_jpfs(
     State(state): State<AppState>,
+    Query(query): Query<UploadJsonQuery>,
     Json(json): Json<Value>,
-) -> Result<Json<IpfsResponseStandard>, ApiError> {
+) -> Result<Json<UploadJsonResponse>, ApiError> {
+    let metadata = validate_json_atom(&json).map_err(ApiError::Validation)?;
+    if query.dry_run.unwrap_or(false) {
+        info!("Dry run, JSON atom resolves to a {}", metadata.atom_type);
+        return Ok(Json(UploadJsonResponse::DryRun(DryRunResponse {
+            metadata,
+        })));
+    }
+
     info!("Uploading JSON to IPFS");
 
     // Construct the MultipartHandler
@@ -62,7 +107,7 @@ pub async fn upload_json_to_jpfs(
     let ipfs_response = upload_json_to_ipfs(&state, multi_part_handler).await?;
     info!("IPFS response: {:?}", ipfs_response);
 
-    Ok(Json(IpfsResponseStandard::from_ipfs_response(
-        ipfs_response,
+    Ok(Json(UploadJsonResponse::Uploaded(
+        IpfsResponseStandard::from_ipfs_response(ipfs_response),
     )))
 }
@@ -1,7 +1,9 @@
+use crate::atom_schema::ValidationErrors;
 use axum::{
     body::Body,
     http::{Response, StatusCode},
     response::IntoResponse,
+    Json,
 };
 use thiserror::Error;
 
@@ -23,6 +25,14 @@ pub enum ApiError {
     HFToken(String),
     #[error("Invalid input: {0}")]
     InvalidInput(String),
+    #[error("Invalid JSON atom: {} validation errors", .0.errors.len())]
+    Validation(ValidationErrors),
+    #[error("Resource not found: {0}")]
+    NotFound(String),
+    #[error("Forbidden: {0}")]
+    Forbidden(String),
+    #[error("Not acceptable: {0}")]
+    NotAcceptable(String),
     #[error(transparent)]
     Lib(#[from] shared_utils::error::LibError),
     #[error(transparent)]
@@ -39,6 +49,16 @@ pub enum ApiError {
 
 impl IntoResponse for ApiError {
     fn into_response(self) -> Response<Body> {
-        (StatusCode::INTERNAL_SERVER_ERROR, self.to_string()).into_response()
+        if let ApiError::Validation(errors) = self {
+            return (StatusCode::BAD_REQUEST, Json(errors)).into_response();
+        }
+        let status = match self {
+            ApiError::InvalidInput(_) => StatusCode::BAD_REQUEST,
+            ApiError::NotFound(_) => StatusCode::NOT_FOUND,
+            ApiError::Forbidden(_) => StatusCode::FORBIDDEN,
+            ApiError::NotAcceptable(_) => StatusCode::NOT_ACCEPTABLE,
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        (status, self.to_string()).into_response()
     }
 }
@@ -2,10 +2,13 @@ use app::App;
 use error::ApiError;
 
 mod app;
+mod atom_schema;
 mod endpoints;
 mod error;
 mod openapi;
+mod reconcile;
 mod state;
+mod store;
 mod types;
 
 #[tokio::main]
@@ -1,10 +1,27 @@
 use crate::{
-    endpoints,
+    atom_schema::{ValidationError, ValidationErrors},
+    endpoints::{
+        self,
+        admin::api_keys::{IssueApiKeyRequest, IssuedApiKey},
+        moderation::{
+            reclassify_image::ReclassifyImageRequest, review_image::ReviewImageRequest,
+            thresholds::UpdateThresholdRequest,
+        },
+        upload_json_to_ipfs::DryRunResponse,
+    },
     types::{ClassificationScoreParsed, LocalClassificationScore},
 };
-use models::cached_image::CachedImage;
-use shared_utils::{image::Image, types::ClassificationModel};
-use utoipa::OpenApi;
+use models::{
+    api_key::{ApiKey, ApiKeyScope},
+    cached_image::CachedImage,
+    classification_threshold::ClassificationThreshold,
+    image_moderation_audit::{ImageModerationAudit, ModerationAction},
+};
+use shared_utils::{atom_metadata::AtomMetadata, image::Image, types::ClassificationModel};
+use utoipa::{
+    openapi::security::{HttpAuthScheme, HttpBuilder, SecurityScheme},
+    Modify, OpenApi,
+};
 
 #[derive(OpenApi)]
 #[openapi(
@@ -12,6 +29,16 @@ use utoipa::OpenApi;
         endpoints::upload_image::upload_image,
         endpoints::upload_image_from_url::upload_image_from_url,
         endpoints::upload_json_to_ipfs::upload_json_to_jpfs,
+        endpoints::serve_image::serve_image,
+        endpoints::moderation::list_images::list_images,
+        endpoints::moderation::review_image::review_image,
+        endpoints::moderation::reclassify_image::reclassify_image,
+        endpoints::moderation::audit_log::audit_log,
+        endpoints::moderation::thresholds::list_thresholds,
+        endpoints::moderation::thresholds::update_threshold,
+        endpoints::admin::api_keys::list_api_keys,
+        endpoints::admin::api_keys::issue_api_key,
+        endpoints::admin::api_keys::revoke_api_key,
     ),
     components(
         schemas(
@@ -20,10 +47,41 @@ use utoipa::OpenApi;
             ClassificationModel,
             ClassificationScoreParsed,
             LocalClassificationScore,
+            ClassificationThreshold,
+            ImageModerationAudit,
+            ModerationAction,
+            ReviewImageRequest,
+            ReclassifyImageRequest,
+            UpdateThresholdRequest,
+            ApiKey,
+            ApiKeyScope,
+            IssueApiKeyRequest,
+            IssuedApiKey,
+            ValidationError,
+            ValidationErrors,
+            AtomMetadata,
+            DryRunResponse,
         )
     ),
+    modifiers(&ApiKeySecurity),
     tags(
-        (name = "images", description = "Image upload and classification endpoints")
+        (name = "images", description = "Image upload, classification and serving endpoints"),
+        (name = "moderation", description = "Image moderation endpoints, require an API key with the moderate scope"),
+        (name = "admin", description = "API key management endpoints, require an API key with the admin scope")
     )
 )]
 pub struct ApiDoc;
+
+/// Registers the API key sent as a bearer token
+struct ApiKeySecurity;
+
+impl Modify for ApiKeySecurity {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        if let Some(components) = openapi.components.as_mut() {
+            components.add_security_scheme(
+                "api_key",
+                SecurityScheme::Http(HttpBuilder::new().scheme(HttpAuthScheme::Bearer).build()),
+            )
+        }
+    }
+}
@@ -0,0 +1,135 @@
+use crate::{endpoints::ipfs_resolver, error::ApiError, state::AppState};
+use axum::body::Bytes;
+use log::{info, warn};
+use models::{cached_image::CachedImage, pinned_json::PinnedJson};
+use std::time::Duration;
+
+/// The number of rows read from the database at once
+const PAGE_SIZE: i64 = 500;
+
+/// The outcome of a reconciliation run
+#[derive(Debug, Default)]
+struct ReconcileReport {
+    checked: usize,
+    repinned: usize,
+    failed: usize,
+}
+
+/// Spawns the pinning reconciliation job. Every `interval`, it checks that
+/// every uploaded image and JSON document is still pinned on each configured
+/// backend, and pins it again where it's missing.
+pub fn spawn_pin_reconciliation(state: AppState, interval: Duration) {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            ticker.tick().await;
+            match reconcile_pins(&state).await {
+                Ok(report) => info!(
+                    "Pin reconciliation checked {} files, {} pinned again, {} failures",
+                    report.checked, report.repinned, report.failed
+                ),
+                Err(e) => warn!("Pin reconciliation failed: {}", e),
+            }
+        }
+    });
+}
+
+/// Walks the cached images and the pinned JSON documents page by page and
+/// reconciles their pins
+async fn reconcile_pins(state: &AppState) -> Result<ReconcileReport, ApiError> {
+    let mut report = ReconcileReport::default();
+
+    let mut after = String::new();
+    loop {
+        let images =
+            CachedImage::find_page(&state.pg_pool, &after, PAGE_SIZE, &state.image_api_schema)
+                .await?;
+        let Some(last) = images.last() else {
+            break;
+        };
+        after = last.url.clone();
+        for image in &images {
+            // Only images uploaded to IPFS are pinned
+            if let Some(cid) = image.url.strip_prefix("ipfs://") {
+                reconcile_cid(state, cid, &image.original_url, &mut report).await;
+            }
+        }
+    }
+
+    let mut after = String::new();
+    loop {
+        let documents =
+            PinnedJson::find_page(&state.pg_pool, &after, PAGE_SIZE, &state.image_api_schema)
+                .await?;
+        let Some(last) = documents.last() else {
+            break;
+        };
+        after = last.cid.clone();
+        for document in &documents {
+            reconcile_cid(state, &document.cid, "json", &mut report).await;
+        }
+    }
+
+    Ok(report)
+}
+
+/// Pins a CID again on every backend that lost it. The content is only
+/// loaded if a backend needs it.
+async fn reconcile_cid(state: &AppState, cid: &str, name: &str, report: &mut ReconcileReport) {
+    report.checked += 1;
+    let mut content: Option<Bytes> = None;
+
+    for backend in state.pinning.backends() {
+        match state.pinning.is_pinned_on(backend.as_ref(), cid).await {
+            Ok(true) => continue,
+            Ok(false) => warn!(
+                "{} is not pinned on {}, pinning it again",
+                cid,
+                backend.name()
+            ),
+            Err(e) => {
+                warn!(
+                    "Failed to check the pin of {} on {}: {}",
+                    cid,
+                    backend.name(),
+                    e
+                );
+                report.failed += 1;
+                continue;
+            }
+        }
+
+        let data = match &content {
+            Some(data) => data.clone(),
+            None => match load_content(state, cid).await {
+                Ok(data) => content.insert(data).clone(),
+                Err(e) => {
+                    warn!("Failed to load {} to pin it again: {}", cid, e);
+                    report.failed += 1;
+                    return;
+                }
+            },
+        };
+        match state
+            .pinning
+            .pin_on(backend.as_ref(), cid, name, &data)
+            .await
+        {
+            Ok(()) => report.repinned += 1,
+            Err(e) => {
+                warn!("Failed to pin {} again on {}: {}", cid, backend.name(), e);
+                report.failed += 1;
+            }
+        }
+    }
+}
+
+/// Loads the content of a CID from the local image store, or from IPFS
+async fn load_content(state: &AppState, cid: &str) -> Result<Bytes, ApiError> {
+    if let Some(store) = &state.image_store {
+        if let Some(data) = store.get(cid).await? {
+            return Ok(data);
+        }
+    }
+    Ok(ipfs_resolver(state).fetch_from_ipfs(cid).await?.data)
+}
@@ -1,7 +1,17 @@
-use crate::types::Env;
-use shared_utils::postgres::connect_to_db;
+use crate::{error::ApiError, store::ImageStore, types::Env};
+use reqwest::Client;
+use shared_utils::{
+    auth::{parse_trusted_proxies, ApiKeyAuth},
+    ipfs_gateway::GatewayPool,
+    pinning::PinningPool,
+    postgres::connect_to_db,
+    types::ClassificationModel,
+};
 use sqlx::{Pool, Postgres};
 
+/// The default number of requests per minute allowed from a single IP
+const DEFAULT_IP_RATE_LIMIT_PER_MINUTE: u32 = 120;
+
 #[derive(Clone, PartialEq)]
 pub enum Flag {
     LocalWithClassification,
@@ -19,29 +29,61 @@ impl Flag {
             Flag::HfClassification
         }
     }
+
+    /// Returns the model the images are classified with, so each model keeps
+    /// its own nsfw threshold
+    pub fn classification_model(&self) -> ClassificationModel {
+        match self {
+            Flag::LocalWithClassification => ClassificationModel::SafeContentAi,
+            Flag::LocalWithDbOnly | Flag::HfClassification => {
+                ClassificationModel::FalconsaiNsfwImageDetection
+            }
+        }
+    }
 }
 
 #[derive(Clone)]
 pub struct AppState {
     pub pg_pool: Pool<Postgres>,
     pub image_api_schema: String,
-    pub pinata_api_jwt: String,
-    pub ipfs_upload_url: String,
-    pub ipfs_fetch_url: String,
+    pub api_key_schema: String,
+    pub ipfs_gateways: GatewayPool,
+    pub pinning: PinningPool,
     pub hf_token: Option<String>,
+    pub auth: ApiKeyAuth,
+    pub image_store: Option<ImageStore>,
     pub flag: Flag,
 }
 
 impl AppState {
-    pub async fn new(env: &Env) -> Self {
-        Self {
-            pg_pool: connect_to_db(&env.indexer_database_url).await.unwrap(),
+    pub async fn new(env: &Env) -> Result<Self, ApiError> {
+        let pg_pool = connect_to_db(&env.indexer_database_url).await?;
+        Ok(Self {
+            auth: ApiKeyAuth::new(
+                pg_pool.clone(),
+                env.api_key_schema.clone(),
+                env.admin_api_key.clone(),
+                env.ip_rate_limit_per_minute
+                    .unwrap_or(DEFAULT_IP_RATE_LIMIT_PER_MINUTE),
+                parse_trusted_proxies(env.trusted_proxies.as_deref())?,
+            ),
+            pg_pool,
             image_api_schema: env.image_api_schema.clone(),
-            pinata_api_jwt: env.pinata_api_jwt.clone(),
-            ipfs_fetch_url: env.ipfs_gateway_url.clone(),
-            ipfs_upload_url: env.ipfs_upload_url.clone(),
+            api_key_schema: env.api_key_schema.clone(),
+            ipfs_gateways: GatewayPool::from_config(
+                env.ipfs_gateways.as_deref(),
+                &env.ipfs_gateway_url,
+                env.ipfs_gateway_race.unwrap_or(false),
+            )?,
+            pinning: PinningPool::from_config(
+                env.pinning_backends.as_deref(),
+                env.ipfs_upload_url.as_deref(),
+                env.pinata_api_jwt.as_deref(),
+                &Client::new(),
+            )?,
             hf_token: env.hf_token.clone(),
+            image_store: env.image_store_dir.as_ref().map(ImageStore::new),
             flag: Flag::enabled(env),
-        }
+        })
     }
 }
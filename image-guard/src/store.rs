@@ -0,0 +1,54 @@
+use crate::error::ApiError;
+use axum::body::Bytes;
+use std::{io::ErrorKind, path::PathBuf};
+use tokio::fs;
+
+/// A content-addressed image store on the local filesystem. Originals are
+/// stored under their IPFS CID and variants under `<cid>.<extension>`, so a
+/// key always maps to the same content.
+#[derive(Clone, Debug)]
+pub struct ImageStore {
+    root: PathBuf,
+}
+
+impl ImageStore {
+    /// Creates a store rooted at the given directory
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    /// Returns the path of a key. Keys are CIDs with an optional extension,
+    /// anything else is rejected so a key can't escape the store directory.
+    fn path(&self, key: &str) -> Result<PathBuf, ApiError> {
+        let is_valid = !key.is_empty()
+            && !key.starts_with('.')
+            && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '.');
+        if !is_valid {
+            return Err(ApiError::InvalidInput(format!(
+                "Invalid store key: {}",
+                key
+            )));
+        }
+        Ok(self.root.join(key))
+    }
+
+    /// Returns the content stored under `key`, if any
+    pub async fn get(&self, key: &str) -> Result<Option<Bytes>, ApiError> {
+        match fs::read(self.path(key)?).await {
+            Ok(data) => Ok(Some(Bytes::from(data))),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(ApiError::from(e)),
+        }
+    }
+
+    /// Stores `data` under `key`. The content is written to a temporary file
+    /// first, so readers never see a partially written image.
+    pub async fn put(&self, key: &str, data: &[u8]) -> Result<(), ApiError> {
+        let path = self.path(key)?;
+        fs::create_dir_all(&self.root).await?;
+        let tmp = self.root.join(format!(".{}.tmp", key));
+        fs::write(&tmp, data).await?;
+        fs::rename(&tmp, &path).await?;
+        Ok(())
+    }
+}
@@ -3,16 +3,25 @@ use utoipa::ToSchema;
 
 #[derive(Deserialize)]
 pub struct Env {
+    pub admin_api_key: Option<String>,
+    pub api_key_schema: String,
     pub classification_api_port: u16,
     pub indexer_database_url: String,
     pub flag_hf_classification: Option<bool>,
     pub hf_token: Option<String>,
     pub ipfs_gateway_url: String,
-    pub ipfs_upload_url: String,
+    pub ipfs_gateways: Option<String>,
+    pub ipfs_gateway_race: Option<bool>,
+    pub ipfs_upload_url: Option<String>,
     pub flag_local_with_classification: Option<bool>,
     pub flag_local_with_db_only: Option<bool>,
-    pub pinata_api_jwt: String,
+    pub ip_rate_limit_per_minute: Option<u32>,
+    pub pinata_api_jwt: Option<String>,
+    pub pinning_backends: Option<String>,
+    pub pin_reconcile_interval_secs: Option<u64>,
+    pub trusted_proxies: Option<String>,
     pub image_api_schema: String,
+    pub image_store_dir: Option<String>,
 }
 
 /// A multipart request with an image
@@ -0,0 +1,7 @@
+DROP TRIGGER IF EXISTS image_moderation_audit_append_only ON cached_images.image_moderation_audit;
+DROP FUNCTION IF EXISTS cached_images.image_moderation_audit_append_only();
+DROP INDEX IF EXISTS cached_images.idx_image_moderation_audit_url;
+DROP TABLE IF EXISTS cached_images.image_moderation_audit;
+DROP TYPE IF EXISTS cached_images.moderation_action;
+DROP TABLE IF EXISTS cached_images.classification_threshold;
+DROP INDEX IF EXISTS cached_images.idx_cached_image_nsfw_score;
@@ -0,0 +1,47 @@
+-- Scores used to be stored as a JSON encoded string, normalize them into
+-- objects so we can filter by score band
+UPDATE cached_images.cached_image
+SET score = (score #>> '{}')::jsonb
+WHERE jsonb_typeof(score) = 'string';
+
+CREATE INDEX idx_cached_image_nsfw_score ON cached_images.cached_image(((score->>'nsfw')::DOUBLE PRECISION));
+
+-- NSFW threshold per classification model. Images with an nsfw score above
+-- the threshold are classified as unsafe
+CREATE TABLE cached_images.classification_threshold (
+  model TEXT PRIMARY KEY NOT NULL,
+  nsfw_threshold DOUBLE PRECISION NOT NULL CHECK (nsfw_threshold >= 0 AND nsfw_threshold <= 1),
+  updated_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+INSERT INTO cached_images.classification_threshold (model, nsfw_threshold)
+VALUES ('Falconsai/nsfw_image_detection', 0.6);
+
+CREATE TYPE cached_images.moderation_action AS ENUM ('MarkedSafe', 'MarkedUnsafe', 'Reclassified');
+
+-- Every moderation change is recorded here, rows are never updated or deleted
+CREATE TABLE cached_images.image_moderation_audit (
+  id BIGSERIAL PRIMARY KEY NOT NULL,
+  url TEXT NOT NULL REFERENCES cached_images.cached_image(url),
+  action cached_images.moderation_action NOT NULL,
+  reviewer TEXT NOT NULL,
+  reason TEXT,
+  previous_safe BOOLEAN NOT NULL,
+  new_safe BOOLEAN NOT NULL,
+  previous_score JSONB,
+  new_score JSONB,
+  created_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE INDEX idx_image_moderation_audit_url ON cached_images.image_moderation_audit(url);
+
+CREATE OR REPLACE FUNCTION cached_images.image_moderation_audit_append_only()
+RETURNS TRIGGER AS $$
+BEGIN
+  RAISE EXCEPTION 'image_moderation_audit is append-only';
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER image_moderation_audit_append_only
+BEFORE UPDATE OR DELETE ON cached_images.image_moderation_audit
+FOR EACH ROW EXECUTE FUNCTION cached_images.image_moderation_audit_append_only();
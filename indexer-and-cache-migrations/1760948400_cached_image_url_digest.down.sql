@@ -0,0 +1,2 @@
+DROP INDEX IF EXISTS cached_images.idx_cached_image_original_url_digest;
+DROP FUNCTION IF EXISTS cached_images.url_digest(TEXT);
@@ -0,0 +1,13 @@
+-- Images can be served by the sha256 digest of their original url, so the
+-- digest is exposed as an immutable function and indexed.
+CREATE OR REPLACE FUNCTION cached_images.url_digest(url TEXT)
+RETURNS TEXT
+LANGUAGE SQL
+IMMUTABLE
+PARALLEL SAFE
+AS $$
+    SELECT encode(sha256(convert_to(url, 'UTF8')), 'hex')
+$$;
+
+CREATE INDEX IF NOT EXISTS idx_cached_image_original_url_digest
+    ON cached_images.cached_image (cached_images.url_digest(original_url));
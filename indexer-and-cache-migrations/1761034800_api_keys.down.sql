@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS api_keys.api_key;
+DROP SCHEMA IF EXISTS api_keys;
@@ -0,0 +1,18 @@
+CREATE SCHEMA IF NOT EXISTS api_keys;
+
+-- API keys used by image-guard and consumer-api. Keys look like
+-- `ik_<id>_<secret>`, only the sha256 digest of the secret is stored.
+CREATE TABLE api_keys.api_key (
+  id TEXT PRIMARY KEY NOT NULL,
+  name TEXT NOT NULL,
+  key_hash TEXT NOT NULL,
+  scopes TEXT[] NOT NULL CHECK (
+    cardinality(scopes) > 0 AND scopes <@ ARRAY['upload', 'moderate', 'admin']::TEXT[]
+  ),
+  rate_limit_per_minute INTEGER NOT NULL CHECK (rate_limit_per_minute > 0),
+  request_count BIGINT NOT NULL DEFAULT 0,
+  rate_limited_count BIGINT NOT NULL DEFAULT 0,
+  last_used_at TIMESTAMPTZ,
+  created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+  revoked_at TIMESTAMPTZ
+);
@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS ipfs_cache.content;
+DROP SCHEMA IF EXISTS ipfs_cache;
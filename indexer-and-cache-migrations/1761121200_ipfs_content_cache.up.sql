@@ -0,0 +1,16 @@
+CREATE SCHEMA IF NOT EXISTS ipfs_cache;
+
+-- Content fetched from IPFS, keyed by the CID (optionally followed by a
+-- path). CIDs are immutable so entries are never revalidated, they are only
+-- evicted, least recently accessed first. Large blobs are stored in a local
+-- directory, in which case `data` is NULL.
+CREATE TABLE ipfs_cache.content (
+  cid TEXT PRIMARY KEY NOT NULL,
+  content_type TEXT,
+  size BIGINT NOT NULL CHECK (size >= 0),
+  data BYTEA,
+  fetched_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+  last_accessed_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE INDEX content_last_accessed_at_idx ON ipfs_cache.content (last_accessed_at);
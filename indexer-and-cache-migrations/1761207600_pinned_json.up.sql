@@ -0,0 +1,8 @@
+-- JSON documents uploaded through image-guard, so the pinning
+-- reconciliation job can check they are still pinned on every backend, like
+-- the images in `cached_image`
+CREATE TABLE cached_images.pinned_json (
+  cid TEXT PRIMARY KEY NOT NULL,
+  size BIGINT NOT NULL,
+  created_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
@@ -111,13 +111,22 @@ export async function pinJson(json: any) {
   if (!apiEndpoint) {
     throw new Error('API_ENDPOINT is not set')
   }
+  // The upload endpoint requires an image-guard API key with the `upload` scope
+  const apiKey = process.env.IMAGE_GUARD_API_KEY
+  if (!apiKey) {
+    throw new Error('IMAGE_GUARD_API_KEY is not set')
+  }
   const response = await fetch(apiEndpoint, {
     method: 'POST',
     body: JSON.stringify(json),
     headers: {
       'Content-Type': 'application/json',
+      Authorization: `Bearer ${apiKey}`,
     },
   })
+  if (!response.ok) {
+    throw new Error(`Failed to pin JSON: ${response.status} ${await response.text()}`)
+  }
   const data = await response.json()
   return `ipfs://${data.Hash}`
 }
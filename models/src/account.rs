@@ -17,6 +17,11 @@ pub struct Account {
     pub label: String,
     pub image: Option<String>,
     pub account_type: AccountType,
+    /// The text records of the ENS name or Basename of the account
+    pub description: Option<String>,
+    pub url: Option<String>,
+    pub twitter: Option<String>,
+    pub github: Option<String>,
 }
 
 /// This is the `AccountType` enum that represents the type of an account.
@@ -38,19 +43,27 @@ impl SimpleCrud<String> for Account {
     async fn upsert(&self, pool: &PgPool, schema: &str) -> Result<Self, ModelError> {
         let query = format!(
             r#"
-            INSERT INTO {}.account (id, atom_id, label, image, type)
-            VALUES ($1, $2, $3, $4, $5::text::{}.account_type)
+            INSERT INTO {}.account (id, atom_id, label, image, type, description, url, twitter, github)
+            VALUES ($1, $2, $3, $4, $5::text::{}.account_type, $6, $7, $8, $9)
             ON CONFLICT (id) DO UPDATE SET
                 atom_id = EXCLUDED.atom_id,
                 label = EXCLUDED.label,
                 image = EXCLUDED.image,
-                type = EXCLUDED.type
+                type = EXCLUDED.type,
+                description = EXCLUDED.description,
+                url = EXCLUDED.url,
+                twitter = EXCLUDED.twitter,
+                github = EXCLUDED.github
             RETURNING 
                 id, 
                 atom_id, 
                 label, 
                 image, 
-                type as account_type
+                type as account_type,
+                description,
+                url,
+                twitter,
+                github
             "#,
             schema, schema
         );
@@ -61,6 +74,10 @@ impl SimpleCrud<String> for Account {
             .bind(&self.label)
             .bind(&self.image)
             .bind(self.account_type.to_string())
+            .bind(&self.description)
+            .bind(&self.url)
+            .bind(&self.twitter)
+            .bind(&self.github)
             .fetch_one(pool)
             .await
             .map_err(|e| ModelError::InsertError(e.to_string()))
@@ -79,7 +96,11 @@ impl SimpleCrud<String> for Account {
                 atom_id, 
                 label, 
                 image, 
-                type as account_type
+                type as account_type,
+                description,
+                url,
+                twitter,
+                github
             FROM {}.account
             WHERE id = $1
             "#,
@@ -93,3 +114,73 @@ impl SimpleCrud<String> for Account {
             .map_err(|e| ModelError::QueryError(e.to_string()))
     }
 }
+
+impl Account {
+    /// Records that the profile of an account was resolved
+    pub async fn mark_profile_resolved(
+        id: &str,
+        pool: &PgPool,
+        schema: &str,
+    ) -> Result<(), ModelError> {
+        let query = format!(
+            r#"UPDATE {}.account SET profile_resolved_at = now() WHERE id = $1"#,
+            schema
+        );
+
+        sqlx::query(&query)
+            .bind(id.to_lowercase())
+            .execute(pool)
+            .await
+            .map_err(ModelError::from)
+            .map(|_| ())
+    }
+
+    /// Claims up to `limit` accounts whose profile was resolved more than
+    /// `ttl_secs` seconds ago, or never, oldest first. Claimed accounts are
+    /// not claimed again before `ttl_secs`, even if their profile isn't
+    /// resolved in the meantime. Protocol vaults and the zero address have
+    /// no profile, so they are skipped.
+    pub async fn claim_stale_profiles(
+        ttl_secs: i64,
+        limit: i64,
+        pool: &PgPool,
+        schema: &str,
+    ) -> Result<Vec<Self>, ModelError> {
+        let query = format!(
+            r#"
+            UPDATE {schema}.account
+            SET profile_refresh_enqueued_at = now()
+            WHERE id IN (
+                SELECT id
+                FROM {schema}.account
+                WHERE type <> 'ProtocolVault'
+                  AND id <> '0x0000000000000000000000000000000000000000'
+                  AND (profile_resolved_at IS NULL
+                       OR profile_resolved_at < now() - $1 * INTERVAL '1 second')
+                  AND (profile_refresh_enqueued_at IS NULL
+                       OR profile_refresh_enqueued_at < now() - $1 * INTERVAL '1 second')
+                ORDER BY profile_resolved_at NULLS FIRST, id
+                LIMIT $2
+                FOR UPDATE SKIP LOCKED
+            )
+            RETURNING
+                id,
+                atom_id,
+                label,
+                image,
+                type as account_type,
+                description,
+                url,
+                twitter,
+                github
+            "#,
+        );
+
+        sqlx::query_as::<_, Account>(&query)
+            .bind(ttl_secs)
+            .bind(limit)
+            .fetch_all(pool)
+            .await
+            .map_err(|e| ModelError::QueryError(e.to_string()))
+    }
+}
@@ -0,0 +1,69 @@
+use crate::error::ModelError;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+
+/// This struct represents a change of a field of the profile of an account,
+/// e.g. a new ENS name or avatar. The table is append-only, so entries are
+/// inserted and never updated.
+#[derive(sqlx::FromRow, Debug, PartialEq, Clone, Builder, Serialize, Deserialize)]
+#[builder(fields(Default, Option=!))]
+#[sqlx(type_name = "account_profile_change")]
+pub struct AccountProfileChange {
+    #[builder(Default)]
+    pub id: i64,
+    pub account_id: String,
+    /// The changed field of the account, e.g. `label` or `image`
+    pub field: String,
+    pub previous_value: Option<String>,
+    pub value: Option<String>,
+    #[builder(Default)]
+    pub changed_at: DateTime<Utc>,
+}
+
+impl AccountProfileChange {
+    /// Inserts a new entry into the history. The `id` and `changed_at` are
+    /// assigned by the database.
+    pub async fn insert(&self, pool: &PgPool, schema: &str) -> Result<Self, ModelError> {
+        let query = format!(
+            r#"
+            INSERT INTO {}.account_profile_change (account_id, field, previous_value, value)
+            VALUES ($1, $2, $3, $4)
+            RETURNING id, account_id, field, previous_value, value, changed_at
+            "#,
+            schema
+        );
+
+        sqlx::query_as::<_, AccountProfileChange>(&query)
+            .bind(self.account_id.to_lowercase())
+            .bind(self.field.clone())
+            .bind(self.previous_value.clone())
+            .bind(self.value.clone())
+            .fetch_one(pool)
+            .await
+            .map_err(|e| ModelError::InsertError(e.to_string()))
+    }
+
+    /// Returns the profile history of an account, most recent entries first.
+    pub async fn find_by_account_id(
+        account_id: &str,
+        pool: &PgPool,
+        schema: &str,
+    ) -> Result<Vec<Self>, ModelError> {
+        let query = format!(
+            r#"
+            SELECT id, account_id, field, previous_value, value, changed_at
+            FROM {}.account_profile_change
+            WHERE account_id = $1
+            ORDER BY id DESC
+            "#,
+            schema
+        );
+
+        sqlx::query_as::<_, AccountProfileChange>(&query)
+            .bind(account_id.to_lowercase())
+            .fetch_all(pool)
+            .await
+            .map_err(|e| ModelError::QueryError(e.to_string()))
+    }
+}
@@ -0,0 +1,206 @@
+use crate::{
+    error::ModelError,
+    traits::{Model, SimpleCrud},
+};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{
+    postgres::{PgHasArrayType, PgTypeInfo},
+    PgPool,
+};
+use strum_macros::{Display, EnumString};
+use utoipa::ToSchema;
+
+/// The scopes an API key can be granted. `Admin` implies every other scope.
+#[derive(
+    sqlx::Type,
+    Clone,
+    Copy,
+    Debug,
+    Display,
+    EnumString,
+    PartialEq,
+    Eq,
+    Hash,
+    Serialize,
+    Deserialize,
+    ToSchema,
+)]
+#[sqlx(type_name = "text", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase")]
+pub enum ApiKeyScope {
+    Upload,
+    Moderate,
+    Admin,
+}
+
+impl ApiKeyScope {
+    /// Returns true if the scopes grant this scope, or the admin scope.
+    pub fn is_granted_by(self, scopes: &[ApiKeyScope]) -> bool {
+        scopes.contains(&self) || scopes.contains(&ApiKeyScope::Admin)
+    }
+}
+
+/// Scopes are stored as a `TEXT[]`
+impl PgHasArrayType for ApiKeyScope {
+    fn array_type_info() -> PgTypeInfo {
+        <String as PgHasArrayType>::array_type_info()
+    }
+}
+
+/// This struct represents an API key. Only the digest of the secret part of
+/// the key is stored, the key itself is shown once when it is issued.
+#[derive(sqlx::FromRow, Debug, PartialEq, Clone, Builder, Serialize, Deserialize, ToSchema)]
+#[builder(fields(Default, Option=!))]
+#[sqlx(type_name = "api_key")]
+pub struct ApiKey {
+    pub id: String,
+    pub name: String,
+    #[serde(skip_serializing)]
+    pub key_hash: String,
+    pub scopes: Vec<ApiKeyScope>,
+    pub rate_limit_per_minute: i32,
+    #[builder(Default)]
+    pub request_count: i64,
+    #[builder(Default)]
+    pub rate_limited_count: i64,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub revoked_at: Option<DateTime<Utc>>,
+}
+
+impl Model for ApiKey {}
+
+#[async_trait]
+impl SimpleCrud<String> for ApiKey {
+    /// Upserts an API key. Usage counters are only updated by
+    /// [`ApiKey::record_usage`].
+    async fn upsert(&self, pool: &PgPool, schema: &str) -> Result<Self, ModelError> {
+        let query = format!(
+            r#"
+            INSERT INTO {}.api_key
+                (id, name, key_hash, scopes, rate_limit_per_minute, created_at, revoked_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            ON CONFLICT (id) DO UPDATE SET
+                name = EXCLUDED.name,
+                key_hash = EXCLUDED.key_hash,
+                scopes = EXCLUDED.scopes,
+                rate_limit_per_minute = EXCLUDED.rate_limit_per_minute,
+                revoked_at = EXCLUDED.revoked_at
+            RETURNING id, name, key_hash, scopes, rate_limit_per_minute, request_count,
+                      rate_limited_count, last_used_at, created_at, revoked_at
+            "#,
+            schema,
+        );
+
+        sqlx::query_as::<_, ApiKey>(&query)
+            .bind(self.id.clone())
+            .bind(self.name.clone())
+            .bind(self.key_hash.clone())
+            .bind(self.scopes.clone())
+            .bind(self.rate_limit_per_minute)
+            .bind(self.created_at)
+            .bind(self.revoked_at)
+            .fetch_one(pool)
+            .await
+            .map_err(|e| ModelError::InsertError(e.to_string()))
+    }
+
+    /// Finds an API key by its id.
+    async fn find_by_id(
+        id: String,
+        pool: &PgPool,
+        schema: &str,
+    ) -> Result<Option<Self>, ModelError> {
+        let query = format!(
+            r#"
+            SELECT id, name, key_hash, scopes, rate_limit_per_minute, request_count,
+                   rate_limited_count, last_used_at, created_at, revoked_at
+            FROM {}.api_key
+            WHERE id = $1
+            "#,
+            schema
+        );
+
+        sqlx::query_as::<_, ApiKey>(&query)
+            .bind(id)
+            .fetch_optional(pool)
+            .await
+            .map_err(|e| ModelError::QueryError(e.to_string()))
+    }
+}
+
+impl ApiKey {
+    /// Returns true if the key is revoked.
+    pub fn is_revoked(&self) -> bool {
+        self.revoked_at.is_some()
+    }
+
+    /// Returns all the API keys, most recent first.
+    pub async fn find_all(pool: &PgPool, schema: &str) -> Result<Vec<Self>, ModelError> {
+        let query = format!(
+            r#"
+            SELECT id, name, key_hash, scopes, rate_limit_per_minute, request_count,
+                   rate_limited_count, last_used_at, created_at, revoked_at
+            FROM {}.api_key
+            ORDER BY created_at DESC
+            "#,
+            schema
+        );
+
+        sqlx::query_as::<_, ApiKey>(&query)
+            .fetch_all(pool)
+            .await
+            .map_err(|e| ModelError::QueryError(e.to_string()))
+    }
+
+    /// Revokes an API key. Revoking a key twice keeps the first revocation
+    /// date. Returns `None` if the key does not exist.
+    pub async fn revoke(id: &str, pool: &PgPool, schema: &str) -> Result<Option<Self>, ModelError> {
+        let query = format!(
+            r#"
+            UPDATE {}.api_key
+            SET revoked_at = COALESCE(revoked_at, now())
+            WHERE id = $1
+            RETURNING id, name, key_hash, scopes, rate_limit_per_minute, request_count,
+                      rate_limited_count, last_used_at, created_at, revoked_at
+            "#,
+            schema
+        );
+
+        sqlx::query_as::<_, ApiKey>(&query)
+            .bind(id)
+            .fetch_optional(pool)
+            .await
+            .map_err(|e| ModelError::UpdateError(e.to_string()))
+    }
+
+    /// Increments the usage counters of an API key.
+    pub async fn record_usage(
+        id: &str,
+        rate_limited: bool,
+        pool: &PgPool,
+        schema: &str,
+    ) -> Result<(), ModelError> {
+        let query = format!(
+            r#"
+            UPDATE {}.api_key
+            SET request_count = request_count + 1,
+                rate_limited_count = rate_limited_count + CASE WHEN $2 THEN 1 ELSE 0 END,
+                last_used_at = now()
+            WHERE id = $1
+            "#,
+            schema
+        );
+
+        sqlx::query(&query)
+            .bind(id)
+            .bind(rate_limited)
+            .execute(pool)
+            .await
+            .map(|_| ())
+            .map_err(|e| ModelError::UpdateError(e.to_string()))
+    }
+}
@@ -45,6 +45,11 @@ pub enum AtomType {
     ByteObject,
     Book,
     Caip10,
+    Caip19,
+    Caip2,
+    CreativeWork,
+    Did,
+    Event,
     FollowAction,
     JsonObject,
     Keywords,
@@ -53,10 +58,15 @@ pub enum AtomType {
     OrganizationPredicate,
     Person,
     PersonPredicate,
+    Place,
+    Product,
+    SoftwareApplication,
     TextObject,
     Thing,
     ThingPredicate,
     Unknown,
+    Url,
+    WebSite,
 }
 
 /// This is a trait that all models must implement.
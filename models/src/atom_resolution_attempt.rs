@@ -0,0 +1,271 @@
+use crate::{
+    atom::{AtomResolvingStatus, AtomType},
+    error::ModelError,
+    traits::{Model, SimpleCrud},
+    types::U256Wrapper,
+};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use strum_macros::{Display, EnumString};
+use utoipa::ToSchema;
+
+/// The category of the error that made the resolution of an atom fail
+#[derive(
+    sqlx::Type,
+    Clone,
+    Copy,
+    Debug,
+    Display,
+    EnumString,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+    ToSchema,
+)]
+#[sqlx(type_name = "resolution_error_category")]
+pub enum ResolutionErrorCategory {
+    /// An IPFS gateway or a web server didn't answer in time
+    GatewayTimeout,
+    /// The document couldn't be fetched, e.g. a connection or server error
+    NetworkError,
+    /// The document was refused, e.g. too large or at a forbidden address
+    InvalidContent,
+    /// The document was fetched but couldn't be parsed
+    ParseError,
+    /// The atom data was parsed but isn't of a supported type
+    UnsupportedType,
+    /// Anything else
+    Other,
+}
+
+impl ResolutionErrorCategory {
+    /// Returns true if resolving the atom again might succeed. Parse errors
+    /// and unsupported types fail the same way every time.
+    pub fn is_retryable(&self) -> bool {
+        matches!(
+            self,
+            ResolutionErrorCategory::GatewayTimeout
+                | ResolutionErrorCategory::NetworkError
+                | ResolutionErrorCategory::Other
+        )
+    }
+}
+
+/// This struct records the failed resolutions of an atom, and when the
+/// resolution should be retried
+#[derive(Debug, sqlx::FromRow, Builder, Clone)]
+pub struct AtomResolutionAttempt {
+    pub atom_id: U256Wrapper,
+    /// The number of failed resolutions
+    pub attempts: i32,
+    pub last_error_category: ResolutionErrorCategory,
+    pub last_error: String,
+    pub last_attempt_at: DateTime<Utc>,
+    /// When the atom should be resolved again, `None` once the error is not
+    /// retryable, the attempts are exhausted or the retry was enqueued
+    #[builder(Option=!)]
+    pub next_retry_at: Option<DateTime<Utc>>,
+}
+
+/// The filters used to select atoms to resolve again. Every filter is
+/// optional, and the ones that are set must all match.
+#[derive(Debug, Default, Clone)]
+pub struct AtomResolutionFilter {
+    pub resolving_status: Option<AtomResolvingStatus>,
+    pub atom_type: Option<AtomType>,
+    pub error_category: Option<ResolutionErrorCategory>,
+    pub from_block: Option<i64>,
+    pub to_block: Option<i64>,
+}
+
+/// This trait implements the Model trait for the AtomResolutionAttempt struct.
+impl Model for AtomResolutionAttempt {}
+
+#[async_trait]
+impl SimpleCrud<U256Wrapper> for AtomResolutionAttempt {
+    /// This method upserts the resolution attempts of an atom into the database.
+    async fn upsert(&self, pool: &PgPool, schema: &str) -> Result<Self, ModelError> {
+        let query = format!(
+            r#"
+            INSERT INTO {}.atom_resolution_attempt (atom_id, attempts, last_error_category, last_error, last_attempt_at, next_retry_at)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            ON CONFLICT (atom_id) DO UPDATE SET
+                attempts = EXCLUDED.attempts,
+                last_error_category = EXCLUDED.last_error_category,
+                last_error = EXCLUDED.last_error,
+                last_attempt_at = EXCLUDED.last_attempt_at,
+                next_retry_at = EXCLUDED.next_retry_at
+            RETURNING
+                atom_id,
+                attempts,
+                last_error_category,
+                last_error,
+                last_attempt_at,
+                next_retry_at
+            "#,
+            schema,
+        );
+
+        sqlx::query_as::<_, AtomResolutionAttempt>(&query)
+            .bind(self.atom_id.to_big_decimal()?)
+            .bind(self.attempts)
+            .bind(self.last_error_category)
+            .bind(self.last_error.clone())
+            .bind(self.last_attempt_at)
+            .bind(self.next_retry_at)
+            .fetch_one(pool)
+            .await
+            .map_err(|e| ModelError::InsertError(e.to_string()))
+    }
+
+    /// This method finds the resolution attempts of an atom by its ID in the database.
+    async fn find_by_id(
+        id: U256Wrapper,
+        pool: &PgPool,
+        schema: &str,
+    ) -> Result<Option<Self>, ModelError> {
+        let query = format!(
+            r#"
+            SELECT atom_id,
+                   attempts,
+                   last_error_category,
+                   last_error,
+                   last_attempt_at,
+                   next_retry_at
+            FROM {}.atom_resolution_attempt
+            WHERE atom_id = $1
+            "#,
+            schema
+        );
+
+        sqlx::query_as::<_, AtomResolutionAttempt>(&query)
+            .bind(id.to_big_decimal()?)
+            .fetch_optional(pool)
+            .await
+            .map_err(|e| ModelError::QueryError(e.to_string()))
+    }
+}
+
+impl AtomResolutionAttempt {
+    /// Claims up to `limit` failed atoms whose retry is due, oldest first.
+    /// Their retry is postponed by `lease` so they are only claimed once,
+    /// even by concurrent schedulers, and claimed again if they are not
+    /// marked as enqueued in time.
+    pub async fn claim_due(
+        limit: i64,
+        lease: chrono::Duration,
+        pool: &PgPool,
+        schema: &str,
+    ) -> Result<Vec<Self>, ModelError> {
+        let query = format!(
+            r#"
+            UPDATE {schema}.atom_resolution_attempt
+            SET next_retry_at = now() + $2 * INTERVAL '1 second'
+            WHERE atom_id IN (
+                SELECT r.atom_id
+                FROM {schema}.atom_resolution_attempt r
+                JOIN {schema}.atom a ON a.id = r.atom_id
+                WHERE a.resolving_status = 'Failed'
+                  AND r.next_retry_at <= now()
+                ORDER BY r.next_retry_at
+                LIMIT $1
+                FOR UPDATE OF r SKIP LOCKED
+            )
+            RETURNING
+                atom_id,
+                attempts,
+                last_error_category,
+                last_error,
+                last_attempt_at,
+                next_retry_at
+            "#,
+        );
+
+        sqlx::query_as::<_, AtomResolutionAttempt>(&query)
+            .bind(limit)
+            .bind(lease.num_seconds())
+            .fetch_all(pool)
+            .await
+            .map_err(|e| ModelError::UpdateError(e.to_string()))
+    }
+
+    /// Clears the retry of a claimed atom once it was enqueued. The retry is
+    /// kept if it changed since the claim, e.g. the atom already failed
+    /// again and a new one was scheduled.
+    pub async fn mark_enqueued(&self, pool: &PgPool, schema: &str) -> Result<(), ModelError> {
+        let query = format!(
+            r#"
+            UPDATE {}.atom_resolution_attempt
+            SET next_retry_at = NULL
+            WHERE atom_id = $1 AND next_retry_at = $2
+            "#,
+            schema
+        );
+
+        sqlx::query(&query)
+            .bind(self.atom_id.to_big_decimal()?)
+            .bind(self.next_retry_at)
+            .execute(pool)
+            .await
+            .map(|_| ())
+            .map_err(|e| ModelError::UpdateError(e.to_string()))
+    }
+
+    /// Deletes the resolution attempts of an atom, once it was resolved
+    pub async fn delete(
+        atom_id: &U256Wrapper,
+        pool: &PgPool,
+        schema: &str,
+    ) -> Result<(), ModelError> {
+        let query = format!(
+            "DELETE FROM {}.atom_resolution_attempt WHERE atom_id = $1",
+            schema
+        );
+
+        sqlx::query(&query)
+            .bind(atom_id.to_big_decimal()?)
+            .execute(pool)
+            .await
+            .map(|_| ())
+            .map_err(|e| ModelError::DeleteError(e.to_string()))
+    }
+
+    /// Finds up to `limit` atoms matching a filter, by block number. Atoms
+    /// that never failed only match filters without an error category.
+    pub async fn find_atom_ids(
+        filter: &AtomResolutionFilter,
+        limit: i64,
+        pool: &PgPool,
+        schema: &str,
+    ) -> Result<Vec<U256Wrapper>, ModelError> {
+        let query = format!(
+            r#"
+            SELECT a.id
+            FROM {schema}.atom a
+            LEFT JOIN {schema}.atom_resolution_attempt r ON r.atom_id = a.id
+            WHERE ($1 IS NULL OR a.resolving_status = $1)
+              AND ($2 IS NULL OR a.type = $2)
+              AND ($3 IS NULL OR r.last_error_category = $3)
+              AND ($4::BIGINT IS NULL OR a.block_number >= $4)
+              AND ($5::BIGINT IS NULL OR a.block_number <= $5)
+            ORDER BY a.block_number, a.id
+            LIMIT $6
+            "#,
+        );
+
+        sqlx::query_as::<_, (U256Wrapper,)>(&query)
+            .bind(filter.resolving_status.clone())
+            .bind(filter.atom_type.clone())
+            .bind(filter.error_category)
+            .bind(filter.from_block)
+            .bind(filter.to_block)
+            .bind(limit)
+            .fetch_all(pool)
+            .await
+            .map(|rows| rows.into_iter().map(|(atom_id,)| atom_id).collect())
+            .map_err(|e| ModelError::QueryError(e.to_string()))
+    }
+}
@@ -18,6 +18,17 @@ pub struct AtomValue {
     pub json_object_id: Option<U256Wrapper>,
     pub text_object_id: Option<U256Wrapper>,
     pub byte_object_id: Option<U256Wrapper>,
+    pub creative_work_id: Option<U256Wrapper>,
+    pub place_id: Option<U256Wrapper>,
+    pub product_id: Option<U256Wrapper>,
+    pub schema_event_id: Option<U256Wrapper>,
+    pub software_application_id: Option<U256Wrapper>,
+    pub web_site_id: Option<U256Wrapper>,
+    pub caip10_id: Option<U256Wrapper>,
+    pub caip2_id: Option<U256Wrapper>,
+    pub caip19_id: Option<U256Wrapper>,
+    pub did_id: Option<U256Wrapper>,
+    pub web_page_id: Option<U256Wrapper>,
 }
 
 /// This is the implementation of the `Model` trait for the `AtomValue` struct.
@@ -29,8 +40,8 @@ impl SimpleCrud<U256Wrapper> for AtomValue {
     async fn upsert(&self, pool: &PgPool, schema: &str) -> Result<Self, ModelError> {
         let query = format!(
             r#"
-            INSERT INTO {}.atom_value (id, account_id, thing_id, person_id, organization_id, book_id, json_object_id, text_object_id, byte_object_id)
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+            INSERT INTO {}.atom_value (id, account_id, thing_id, person_id, organization_id, book_id, json_object_id, text_object_id, byte_object_id, creative_work_id, place_id, product_id, schema_event_id, software_application_id, web_site_id, caip10_id, caip2_id, caip19_id, did_id, web_page_id)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19, $20)
             ON CONFLICT (id) DO UPDATE SET
                 account_id = EXCLUDED.account_id,
                 thing_id = EXCLUDED.thing_id,
@@ -39,7 +50,18 @@ impl SimpleCrud<U256Wrapper> for AtomValue {
                 book_id = EXCLUDED.book_id,
                 json_object_id = EXCLUDED.json_object_id,
                 text_object_id = EXCLUDED.text_object_id,
-                byte_object_id = EXCLUDED.byte_object_id
+                byte_object_id = EXCLUDED.byte_object_id,
+                creative_work_id = EXCLUDED.creative_work_id,
+                place_id = EXCLUDED.place_id,
+                product_id = EXCLUDED.product_id,
+                schema_event_id = EXCLUDED.schema_event_id,
+                software_application_id = EXCLUDED.software_application_id,
+                web_site_id = EXCLUDED.web_site_id,
+                caip10_id = EXCLUDED.caip10_id,
+                caip2_id = EXCLUDED.caip2_id,
+                caip19_id = EXCLUDED.caip19_id,
+                did_id = EXCLUDED.did_id,
+                web_page_id = EXCLUDED.web_page_id
             RETURNING 
                 id, 
                 account_id,
@@ -49,7 +71,18 @@ impl SimpleCrud<U256Wrapper> for AtomValue {
                 book_id,
                 json_object_id,
                 text_object_id,
-                byte_object_id
+                byte_object_id,
+                creative_work_id,
+                place_id,
+                product_id,
+                schema_event_id,
+                software_application_id,
+                web_site_id,
+                caip10_id,
+                caip2_id,
+                caip19_id,
+                did_id,
+                web_page_id
             "#,
             schema
         );
@@ -84,6 +117,49 @@ impl SimpleCrud<U256Wrapper> for AtomValue {
                     .as_ref()
                     .and_then(|w| w.to_big_decimal().ok()),
             )
+            .bind(
+                self.creative_work_id
+                    .as_ref()
+                    .and_then(|w| w.to_big_decimal().ok()),
+            )
+            .bind(self.place_id.as_ref().and_then(|w| w.to_big_decimal().ok()))
+            .bind(
+                self.product_id
+                    .as_ref()
+                    .and_then(|w| w.to_big_decimal().ok()),
+            )
+            .bind(
+                self.schema_event_id
+                    .as_ref()
+                    .and_then(|w| w.to_big_decimal().ok()),
+            )
+            .bind(
+                self.software_application_id
+                    .as_ref()
+                    .and_then(|w| w.to_big_decimal().ok()),
+            )
+            .bind(
+                self.web_site_id
+                    .as_ref()
+                    .and_then(|w| w.to_big_decimal().ok()),
+            )
+            .bind(
+                self.caip10_id
+                    .as_ref()
+                    .and_then(|w| w.to_big_decimal().ok()),
+            )
+            .bind(self.caip2_id.as_ref().and_then(|w| w.to_big_decimal().ok()))
+            .bind(
+                self.caip19_id
+                    .as_ref()
+                    .and_then(|w| w.to_big_decimal().ok()),
+            )
+            .bind(self.did_id.as_ref().and_then(|w| w.to_big_decimal().ok()))
+            .bind(
+                self.web_page_id
+                    .as_ref()
+                    .and_then(|w| w.to_big_decimal().ok()),
+            )
             .fetch_one(pool)
             .await
             .map_err(|e| ModelError::InsertError(e.to_string()))
@@ -107,7 +183,17 @@ impl SimpleCrud<U256Wrapper> for AtomValue {
                 caip10_id,
                 json_object_id,
                 text_object_id,
-                byte_object_id
+                byte_object_id,
+                creative_work_id,
+                place_id,
+                product_id,
+                schema_event_id,
+                software_application_id,
+                web_site_id,
+                caip2_id,
+                caip19_id,
+                did_id,
+                web_page_id
             FROM {}.atom_value
             WHERE id = $1
             "#,
@@ -0,0 +1,89 @@
+use crate::{error::ModelError, types::U256Wrapper};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use strum_macros::{Display, EnumString};
+
+/// The indexed entity an audit finding is about
+#[derive(
+    sqlx::Type, Clone, Copy, Debug, Display, EnumString, PartialEq, Eq, Serialize, Deserialize,
+)]
+#[sqlx(type_name = "audit_entity")]
+pub enum AuditEntity {
+    Position,
+    Vault,
+    Stats,
+}
+
+/// This struct represents a value of the indexer that doesn't match the
+/// contract at the audited block, e.g. the shares of a position. The table is
+/// append-only, and a field of an entity is only recorded once per block.
+#[derive(sqlx::FromRow, Debug, PartialEq, Clone, Builder, Serialize, Deserialize)]
+#[builder(fields(Default, Option=!))]
+#[sqlx(type_name = "audit_finding")]
+pub struct AuditFinding {
+    #[builder(Default)]
+    pub id: i64,
+    pub block_number: i64,
+    pub entity_type: AuditEntity,
+    /// The id of the position, the vault or the stats row
+    pub entity_id: String,
+    /// The mismatched field, e.g. `shares` or `current_share_price`
+    pub field: String,
+    pub indexed_value: U256Wrapper,
+    pub onchain_value: U256Wrapper,
+    #[builder(Default)]
+    pub found_at: DateTime<Utc>,
+}
+
+impl AuditFinding {
+    /// Inserts a new finding, or returns `None` if the field of the entity
+    /// was already found at this block. The `id` and `found_at` are assigned
+    /// by the database.
+    pub async fn insert(&self, pool: &PgPool, schema: &str) -> Result<Option<Self>, ModelError> {
+        let query = format!(
+            r#"
+            INSERT INTO {}.audit_finding (block_number, entity_type, entity_id, field, indexed_value, onchain_value)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            ON CONFLICT (block_number, entity_type, entity_id, field) DO NOTHING
+            RETURNING id, block_number, entity_type, entity_id, field, indexed_value, onchain_value, found_at
+            "#,
+            schema
+        );
+
+        sqlx::query_as::<_, AuditFinding>(&query)
+            .bind(self.block_number)
+            .bind(self.entity_type)
+            .bind(self.entity_id.clone())
+            .bind(self.field.clone())
+            .bind(self.indexed_value.to_big_decimal()?)
+            .bind(self.onchain_value.to_big_decimal()?)
+            .fetch_optional(pool)
+            .await
+            .map_err(|e| ModelError::InsertError(e.to_string()))
+    }
+
+    /// Returns the findings of the audits of a block, in the order they were
+    /// found
+    pub async fn find_by_block_number(
+        block_number: i64,
+        pool: &PgPool,
+        schema: &str,
+    ) -> Result<Vec<Self>, ModelError> {
+        let query = format!(
+            r#"
+            SELECT id, block_number, entity_type, entity_id, field, indexed_value, onchain_value, found_at
+            FROM {}.audit_finding
+            WHERE block_number = $1
+            ORDER BY id
+            "#,
+            schema
+        );
+
+        sqlx::query_as::<_, AuditFinding>(&query)
+            .bind(block_number)
+            .fetch_all(pool)
+            .await
+            .map_err(|e| ModelError::QueryError(e.to_string()))
+    }
+}
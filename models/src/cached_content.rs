@@ -0,0 +1,166 @@
+use crate::{
+    error::ModelError,
+    traits::{Model, SimpleCrud},
+};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+
+/// This struct represents content fetched from IPFS, keyed by its CID. The
+/// `data` is `None` when the content is stored in a local directory instead.
+#[derive(sqlx::FromRow, Debug, PartialEq, Clone, Builder, Serialize, Deserialize)]
+#[builder(fields(Default, Option=!))]
+#[sqlx(type_name = "content")]
+pub struct CachedContent {
+    pub cid: String,
+    pub content_type: Option<String>,
+    pub size: i64,
+    #[serde(skip)]
+    pub data: Option<Vec<u8>>,
+    pub fetched_at: DateTime<Utc>,
+    pub last_accessed_at: DateTime<Utc>,
+}
+
+/// An entry evicted from the cache
+#[derive(sqlx::FromRow, Debug, PartialEq, Clone)]
+pub struct EvictedContent {
+    pub cid: String,
+    /// True if the content was stored in the local directory, which then
+    /// has to be removed
+    pub stored_on_disk: bool,
+}
+
+impl Model for CachedContent {}
+
+#[async_trait]
+impl SimpleCrud<String> for CachedContent {
+    async fn upsert(&self, pool: &PgPool, schema: &str) -> Result<Self, ModelError> {
+        let query = format!(
+            r#"
+            INSERT INTO {}.content (cid, content_type, size, data, fetched_at, last_accessed_at)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            ON CONFLICT (cid) DO UPDATE SET
+                content_type = EXCLUDED.content_type,
+                size = EXCLUDED.size,
+                data = EXCLUDED.data,
+                fetched_at = EXCLUDED.fetched_at,
+                last_accessed_at = EXCLUDED.last_accessed_at
+            RETURNING cid, content_type, size, data, fetched_at, last_accessed_at
+            "#,
+            schema,
+        );
+
+        sqlx::query_as::<_, CachedContent>(&query)
+            .bind(self.cid.clone())
+            .bind(self.content_type.clone())
+            .bind(self.size)
+            .bind(self.data.clone())
+            .bind(self.fetched_at)
+            .bind(self.last_accessed_at)
+            .fetch_one(pool)
+            .await
+            .map_err(|e| ModelError::InsertError(e.to_string()))
+    }
+
+    async fn find_by_id(
+        id: String,
+        pool: &PgPool,
+        schema: &str,
+    ) -> Result<Option<Self>, ModelError> {
+        let query = format!(
+            r#"
+            SELECT cid, content_type, size, data, fetched_at, last_accessed_at
+            FROM {}.content
+            WHERE cid = $1
+            "#,
+            schema,
+        );
+
+        sqlx::query_as::<_, CachedContent>(&query)
+            .bind(id)
+            .fetch_optional(pool)
+            .await
+            .map_err(|e| ModelError::QueryError(e.to_string()))
+    }
+}
+
+impl CachedContent {
+    /// Finds cached content by its CID and marks it as accessed, so it's
+    /// evicted last.
+    pub async fn find_and_touch(
+        cid: &str,
+        pool: &PgPool,
+        schema: &str,
+    ) -> Result<Option<Self>, ModelError> {
+        let query = format!(
+            r#"
+            UPDATE {}.content
+            SET last_accessed_at = now()
+            WHERE cid = $1
+            RETURNING cid, content_type, size, data, fetched_at, last_accessed_at
+            "#,
+            schema,
+        );
+
+        sqlx::query_as::<_, CachedContent>(&query)
+            .bind(cid)
+            .fetch_optional(pool)
+            .await
+            .map_err(|e| ModelError::QueryError(e.to_string()))
+    }
+
+    /// Deletes cached content by its CID.
+    pub async fn delete(cid: &str, pool: &PgPool, schema: &str) -> Result<(), ModelError> {
+        let query = format!("DELETE FROM {}.content WHERE cid = $1", schema);
+
+        sqlx::query(&query)
+            .bind(cid)
+            .execute(pool)
+            .await
+            .map(|_| ())
+            .map_err(|e| ModelError::DeleteError(e.to_string()))
+    }
+
+    /// Returns the total size of the cached content, in bytes
+    pub async fn total_size(pool: &PgPool, schema: &str) -> Result<i64, ModelError> {
+        let query = format!(
+            "SELECT COALESCE(SUM(size), 0)::BIGINT FROM {}.content",
+            schema
+        );
+
+        sqlx::query_scalar::<_, i64>(&query)
+            .fetch_one(pool)
+            .await
+            .map_err(|e| ModelError::QueryError(e.to_string()))
+    }
+
+    /// Evicts the least recently accessed entries until the total size of
+    /// the cache is at most `max_size` bytes. Returns the evicted entries.
+    pub async fn evict_least_recently_used(
+        max_size: i64,
+        pool: &PgPool,
+        schema: &str,
+    ) -> Result<Vec<EvictedContent>, ModelError> {
+        let query = format!(
+            r#"
+            WITH ranked AS (
+                SELECT cid,
+                       SUM(size) OVER (ORDER BY last_accessed_at DESC, cid) AS cumulative_size
+                FROM {}.content
+            )
+            DELETE FROM {}.content content
+            USING ranked
+            WHERE content.cid = ranked.cid AND ranked.cumulative_size > $1
+            RETURNING content.cid, content.data IS NULL AS stored_on_disk
+            "#,
+            schema, schema,
+        );
+
+        sqlx::query_as::<_, EvictedContent>(&query)
+            .bind(max_size)
+            .fetch_all(pool)
+            .await
+            .map_err(|e| ModelError::DeleteError(e.to_string()))
+    }
+}
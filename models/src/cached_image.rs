@@ -90,4 +90,91 @@ impl CachedImage {
             .await
             .map_err(|e| ModelError::QueryError(e.to_string()))
     }
+
+    /// Finds the most recent image whose original url has the given sha256
+    /// digest (hex encoded).
+    pub async fn find_by_original_url_digest(
+        pool: &PgPool,
+        digest: &str,
+        schema: &str,
+    ) -> Result<Option<Self>, ModelError> {
+        let query = format!(
+            r#"
+            SELECT url, original_url, score, model, safe, created_at
+            FROM {}.cached_image
+            WHERE {}.url_digest(original_url) = $1
+            ORDER BY created_at DESC
+            LIMIT 1
+            "#,
+            schema, schema
+        );
+
+        sqlx::query_as::<_, CachedImage>(&query)
+            .bind(digest.to_lowercase())
+            .fetch_optional(pool)
+            .await
+            .map_err(|e| ModelError::QueryError(e.to_string()))
+    }
+
+    /// Returns the images whose nsfw score is within `[min_nsfw, max_nsfw]`,
+    /// optionally filtered by their current verdict. Results are ordered by
+    /// nsfw score, highest first.
+    pub async fn find_by_nsfw_score_band(
+        pool: &PgPool,
+        min_nsfw: f64,
+        max_nsfw: f64,
+        safe: Option<bool>,
+        limit: i64,
+        offset: i64,
+        schema: &str,
+    ) -> Result<Vec<Self>, ModelError> {
+        let query = format!(
+            r#"
+            SELECT url, original_url, score, model, safe, created_at
+            FROM {}.cached_image
+            WHERE (score->>'nsfw')::DOUBLE PRECISION BETWEEN $1 AND $2
+              AND ($3::BOOLEAN IS NULL OR safe = $3)
+            ORDER BY (score->>'nsfw')::DOUBLE PRECISION DESC, url
+            LIMIT $4 OFFSET $5
+            "#,
+            schema
+        );
+
+        sqlx::query_as::<_, CachedImage>(&query)
+            .bind(min_nsfw)
+            .bind(max_nsfw)
+            .bind(safe)
+            .bind(limit)
+            .bind(offset)
+            .fetch_all(pool)
+            .await
+            .map_err(|e| ModelError::QueryError(e.to_string()))
+    }
+
+    /// Returns up to `limit` images with a url greater than `after`, ordered
+    /// by url, to walk the table page by page.
+    pub async fn find_page(
+        pool: &PgPool,
+        after: &str,
+        limit: i64,
+        schema: &str,
+    ) -> Result<Vec<Self>, ModelError> {
+        let query = format!(
+            r#"
+            SELECT url, original_url, score, model, safe, created_at
+            FROM {}.cached_image
+            WHERE url > $1
+            ORDER BY url
+            LIMIT $2
+            "#,
+            schema
+        );
+
+        sqlx::query_as::<_, CachedImage>(&query)
+            .bind(after)
+            .bind(limit)
+            .fetch_all(pool)
+            .await
+            .map_err(|e| ModelError::QueryError(e.to_string()))
+    }
 }
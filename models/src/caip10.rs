@@ -12,7 +12,11 @@ use sqlx::PgPool;
 pub struct Caip10 {
     pub id: U256Wrapper,
     pub namespace: String,
-    pub chain_id: i32,
+    /// The chain id of `eip155` accounts, if it fits
+    #[builder(Option=!)]
+    pub chain_id: Option<i32>,
+    /// The CAIP-2 reference of the chain, e.g. `1` or `cosmoshub-4`
+    pub chain_reference: String,
     pub account_address: String,
 }
 
@@ -25,15 +29,17 @@ impl SimpleCrud<U256Wrapper> for Caip10 {
     async fn upsert(&self, pool: &PgPool, schema: &str) -> Result<Self, ModelError> {
         let query = format!(
             r#"
-            INSERT INTO {}.caip10 (id, namespace, chain_id, account_address) 
-            VALUES ($1, $2, $3, $4) 
+            INSERT INTO {}.caip10 (id, namespace, chain_id, chain_reference, account_address) 
+            VALUES ($1, $2, $3, $4, $5) 
             ON CONFLICT (id) DO UPDATE SET 
                 namespace = EXCLUDED.namespace, 
                 chain_id = EXCLUDED.chain_id, 
+                chain_reference = EXCLUDED.chain_reference, 
                 account_address = EXCLUDED.account_address
             RETURNING id, 
                       namespace, 
                       chain_id, 
+                      chain_reference, 
                       account_address
             "#,
             schema,
@@ -43,6 +49,7 @@ impl SimpleCrud<U256Wrapper> for Caip10 {
             .bind(self.id.to_big_decimal()?)
             .bind(self.namespace.clone())
             .bind(self.chain_id)
+            .bind(self.chain_reference.clone())
             .bind(self.account_address.clone())
             .fetch_one(pool)
             .await
@@ -60,6 +67,7 @@ impl SimpleCrud<U256Wrapper> for Caip10 {
             SELECT id, 
                    namespace, 
                    chain_id, 
+                   chain_reference, 
                    account_address 
             FROM {}.caip10 
             WHERE id = $1
@@ -0,0 +1,88 @@
+use crate::{
+    error::ModelError,
+    traits::{Model, SimpleCrud},
+    types::U256Wrapper,
+};
+use async_trait::async_trait;
+use sqlx::PgPool;
+
+/// Caip19 is a struct that represents a CAIP-19 asset id atom in the database.
+#[derive(Debug, sqlx::FromRow, Builder)]
+#[sqlx(type_name = "caip19")]
+pub struct Caip19 {
+    pub id: U256Wrapper,
+    pub namespace: String,
+    pub chain_reference: String,
+    pub asset_namespace: String,
+    pub asset_reference: String,
+    /// The token of ERC-721 and ERC-1155 assets
+    #[builder(Option=!)]
+    pub token_id: Option<String>,
+}
+
+/// This is a trait that all models must implement.
+impl Model for Caip19 {}
+
+#[async_trait]
+impl SimpleCrud<U256Wrapper> for Caip19 {
+    /// Upserts a CAIP-19 asset id into the database.
+    async fn upsert(&self, pool: &PgPool, schema: &str) -> Result<Self, ModelError> {
+        let query = format!(
+            r#"
+            INSERT INTO {}.caip19 (id, namespace, chain_reference, asset_namespace, asset_reference, token_id)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            ON CONFLICT (id) DO UPDATE SET
+                namespace = EXCLUDED.namespace,
+                chain_reference = EXCLUDED.chain_reference,
+                asset_namespace = EXCLUDED.asset_namespace,
+                asset_reference = EXCLUDED.asset_reference,
+                token_id = EXCLUDED.token_id
+            RETURNING id,
+                      namespace,
+                      chain_reference,
+                      asset_namespace,
+                      asset_reference,
+                      token_id
+            "#,
+            schema,
+        );
+
+        sqlx::query_as::<_, Caip19>(&query)
+            .bind(self.id.to_big_decimal()?)
+            .bind(self.namespace.clone())
+            .bind(self.chain_reference.clone())
+            .bind(self.asset_namespace.clone())
+            .bind(self.asset_reference.clone())
+            .bind(self.token_id.clone())
+            .fetch_one(pool)
+            .await
+            .map_err(|e| ModelError::InsertError(e.to_string()))
+    }
+
+    /// Finds a CAIP-19 asset id by its id.
+    async fn find_by_id(
+        id: U256Wrapper,
+        pool: &PgPool,
+        schema: &str,
+    ) -> Result<Option<Self>, ModelError> {
+        let query = format!(
+            r#"
+            SELECT id,
+                   namespace,
+                   chain_reference,
+                   asset_namespace,
+                   asset_reference,
+                   token_id
+            FROM {}.caip19
+            WHERE id = $1
+            "#,
+            schema
+        );
+
+        sqlx::query_as::<_, Caip19>(&query)
+            .bind(id.to_big_decimal()?)
+            .fetch_optional(pool)
+            .await
+            .map_err(|e| ModelError::QueryError(e.to_string()))
+    }
+}
@@ -0,0 +1,71 @@
+use crate::{
+    error::ModelError,
+    traits::{Model, SimpleCrud},
+    types::U256Wrapper,
+};
+use async_trait::async_trait;
+use sqlx::PgPool;
+
+/// Caip2 is a struct that represents a CAIP-2 chain id atom in the database.
+#[derive(Debug, sqlx::FromRow, Builder)]
+#[sqlx(type_name = "caip2")]
+pub struct Caip2 {
+    pub id: U256Wrapper,
+    pub namespace: String,
+    pub reference: String,
+}
+
+/// This is a trait that all models must implement.
+impl Model for Caip2 {}
+
+#[async_trait]
+impl SimpleCrud<U256Wrapper> for Caip2 {
+    /// Upserts a CAIP-2 chain id into the database.
+    async fn upsert(&self, pool: &PgPool, schema: &str) -> Result<Self, ModelError> {
+        let query = format!(
+            r#"
+            INSERT INTO {}.caip2 (id, namespace, reference)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (id) DO UPDATE SET
+                namespace = EXCLUDED.namespace,
+                reference = EXCLUDED.reference
+            RETURNING id,
+                      namespace,
+                      reference
+            "#,
+            schema,
+        );
+
+        sqlx::query_as::<_, Caip2>(&query)
+            .bind(self.id.to_big_decimal()?)
+            .bind(self.namespace.clone())
+            .bind(self.reference.clone())
+            .fetch_one(pool)
+            .await
+            .map_err(|e| ModelError::InsertError(e.to_string()))
+    }
+
+    /// Finds a CAIP-2 chain id by its id.
+    async fn find_by_id(
+        id: U256Wrapper,
+        pool: &PgPool,
+        schema: &str,
+    ) -> Result<Option<Self>, ModelError> {
+        let query = format!(
+            r#"
+            SELECT id,
+                   namespace,
+                   reference
+            FROM {}.caip2
+            WHERE id = $1
+            "#,
+            schema
+        );
+
+        sqlx::query_as::<_, Caip2>(&query)
+            .bind(id.to_big_decimal()?)
+            .fetch_optional(pool)
+            .await
+            .map_err(|e| ModelError::QueryError(e.to_string()))
+    }
+}
@@ -0,0 +1,139 @@
+use crate::{
+    error::ModelError,
+    traits::{Model, SimpleCrud},
+    types::U256Wrapper,
+};
+use async_trait::async_trait;
+use sqlx::PgPool;
+
+/// The number of wei in an ether
+const WEI_PER_ETHER: f64 = 1e18;
+
+/// This struct represents a round of the Chainlink ETH/USD price feed. The
+/// `id` is the round id of the feed proxy, `block_number` is the block the
+/// answer was observed at and `updated_at` the timestamp of the answer.
+#[derive(sqlx::FromRow, Debug, PartialEq, Clone, Builder)]
+#[sqlx(type_name = "chainlink_price")]
+pub struct ChainlinkPrice {
+    pub id: U256Wrapper,
+    pub usd: Option<f64>,
+    pub block_number: Option<i64>,
+    pub updated_at: Option<i64>,
+}
+
+/// This is a trait that all models must implement.
+impl Model for ChainlinkPrice {}
+
+/// This trait works as a contract for all models that need to be upserted into the database.
+#[async_trait]
+impl SimpleCrud<U256Wrapper> for ChainlinkPrice {
+    /// Upserts a round of the price feed
+    async fn upsert(&self, pool: &PgPool, schema: &str) -> Result<Self, ModelError> {
+        let query = format!(
+            r#"
+            INSERT INTO {}.chainlink_price (id, usd, block_number, updated_at)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (id) DO UPDATE SET
+                usd = EXCLUDED.usd,
+                block_number = EXCLUDED.block_number,
+                updated_at = EXCLUDED.updated_at
+            RETURNING id, usd, block_number, updated_at
+            "#,
+            schema,
+        );
+
+        sqlx::query_as::<_, ChainlinkPrice>(&query)
+            .bind(self.id.to_big_decimal()?)
+            .bind(self.usd)
+            .bind(self.block_number)
+            .bind(self.updated_at)
+            .fetch_one(pool)
+            .await
+            .map_err(|e| ModelError::InsertError(e.to_string()))
+    }
+
+    /// Finds a round of the price feed by its id
+    async fn find_by_id(
+        id: U256Wrapper,
+        pool: &PgPool,
+        schema: &str,
+    ) -> Result<Option<Self>, ModelError> {
+        let query = format!(
+            r#"
+            SELECT id, usd, block_number, updated_at
+            FROM {}.chainlink_price
+            WHERE id = $1
+            "#,
+            schema,
+        );
+
+        sqlx::query_as::<_, ChainlinkPrice>(&query)
+            .bind(id.to_big_decimal()?)
+            .fetch_optional(pool)
+            .await
+            .map_err(|e| ModelError::QueryError(e.to_string()))
+    }
+}
+
+impl ChainlinkPrice {
+    /// Returns the round observed at the highest block, if any
+    pub async fn find_last_observed(
+        pool: &PgPool,
+        schema: &str,
+    ) -> Result<Option<Self>, ModelError> {
+        let query = format!(
+            r#"
+            SELECT id, usd, block_number, updated_at
+            FROM {}.chainlink_price
+            WHERE block_number IS NOT NULL
+            ORDER BY block_number DESC
+            LIMIT 1
+            "#,
+            schema,
+        );
+
+        sqlx::query_as::<_, ChainlinkPrice>(&query)
+            .fetch_optional(pool)
+            .await
+            .map_err(|e| ModelError::QueryError(e.to_string()))
+    }
+
+    /// Returns the price at a timestamp, that is the last answer given at or
+    /// before it
+    pub async fn find_at_timestamp(
+        timestamp: i64,
+        pool: &PgPool,
+        schema: &str,
+    ) -> Result<Option<Self>, ModelError> {
+        let query = format!(
+            r#"
+            SELECT id, usd, block_number, updated_at
+            FROM {}.chainlink_price
+            WHERE updated_at <= $1 AND usd IS NOT NULL
+            ORDER BY updated_at DESC
+            LIMIT 1
+            "#,
+            schema,
+        );
+
+        sqlx::query_as::<_, ChainlinkPrice>(&query)
+            .bind(timestamp)
+            .fetch_optional(pool)
+            .await
+            .map_err(|e| ModelError::QueryError(e.to_string()))
+    }
+
+    /// Converts an answer of the feed with `decimals` decimals to a price
+    pub fn usd_from_answer(answer: &str, decimals: u8) -> Result<f64, ModelError> {
+        let answer = answer
+            .parse::<f64>()
+            .map_err(|e| ModelError::ConversionError(e.to_string()))?;
+        Ok(answer / 10f64.powi(decimals.into()))
+    }
+
+    /// Returns the value in dollars of an amount of wei at this price
+    pub fn wei_to_usd(&self, wei: &U256Wrapper) -> Option<f64> {
+        let ether = wei.0.to_string().parse::<f64>().ok()? / WEI_PER_ETHER;
+        self.usd.map(|usd| ether * usd)
+    }
+}
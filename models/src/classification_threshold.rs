@@ -0,0 +1,80 @@
+use crate::{
+    error::ModelError,
+    traits::{Model, SimpleCrud},
+};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use utoipa::ToSchema;
+
+/// This struct represents the NSFW threshold of a classification model.
+/// Images with an nsfw score above `nsfw_threshold` are classified as unsafe.
+#[derive(sqlx::FromRow, Debug, PartialEq, Clone, Builder, Serialize, Deserialize, ToSchema)]
+#[sqlx(type_name = "classification_threshold")]
+pub struct ClassificationThreshold {
+    pub model: String,
+    pub nsfw_threshold: f64,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl Model for ClassificationThreshold {}
+
+#[async_trait]
+impl SimpleCrud<String> for ClassificationThreshold {
+    /// Upserts the threshold of a model into the database.
+    async fn upsert(&self, pool: &PgPool, schema: &str) -> Result<Self, ModelError> {
+        let query = format!(
+            r#"
+            INSERT INTO {}.classification_threshold (model, nsfw_threshold, updated_at)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (model) DO UPDATE SET
+                nsfw_threshold = EXCLUDED.nsfw_threshold,
+                updated_at = EXCLUDED.updated_at
+            RETURNING model, nsfw_threshold, updated_at
+            "#,
+            schema,
+        );
+
+        sqlx::query_as::<_, ClassificationThreshold>(&query)
+            .bind(self.model.clone())
+            .bind(self.nsfw_threshold)
+            .bind(self.updated_at)
+            .fetch_one(pool)
+            .await
+            .map_err(|e| ModelError::InsertError(e.to_string()))
+    }
+
+    /// Finds the threshold of a model.
+    async fn find_by_id(
+        id: String,
+        pool: &PgPool,
+        schema: &str,
+    ) -> Result<Option<Self>, ModelError> {
+        let query = format!(
+            r#"SELECT model, nsfw_threshold, updated_at FROM {}.classification_threshold WHERE model = $1"#,
+            schema
+        );
+
+        sqlx::query_as::<_, ClassificationThreshold>(&query)
+            .bind(id)
+            .fetch_optional(pool)
+            .await
+            .map_err(|e| ModelError::QueryError(e.to_string()))
+    }
+}
+
+impl ClassificationThreshold {
+    /// Returns the thresholds of all the models.
+    pub async fn find_all(pool: &PgPool, schema: &str) -> Result<Vec<Self>, ModelError> {
+        let query = format!(
+            r#"SELECT model, nsfw_threshold, updated_at FROM {}.classification_threshold ORDER BY model"#,
+            schema
+        );
+
+        sqlx::query_as::<_, ClassificationThreshold>(&query)
+            .fetch_all(pool)
+            .await
+            .map_err(|e| ModelError::QueryError(e.to_string()))
+    }
+}
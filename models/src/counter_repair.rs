@@ -0,0 +1,188 @@
+use crate::error::ModelError;
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+
+/// The base tables the derived counters are computed from. They are locked
+/// against writes while the counters are repaired, so the counters match them
+/// when the repair commits.
+const BASE_TABLES: [&str; 7] = [
+    "account",
+    "atom",
+    "triple",
+    "claim",
+    "position",
+    "signal",
+    "fee_transfer",
+];
+
+/// The number of rows of a derived counter that differed from the value
+/// recomputed from the base tables
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct CounterDrift {
+    pub table: String,
+    pub column: String,
+    pub drifted_rows: u64,
+}
+
+/// A derived counter, with the subquery `c` recomputing its `value` from the
+/// base tables for the rows of `relation` it `matches`
+struct Counter {
+    table: &'static str,
+    column: &'static str,
+    relation: String,
+    recomputed: String,
+    matches: String,
+}
+
+impl Counter {
+    /// Returns the query counting the rows where the counter drifted
+    fn count_query(&self) -> String {
+        format!(
+            "SELECT COUNT(*) FROM {} JOIN ({}) c ON {} WHERE {}.{} IS DISTINCT FROM c.value",
+            self.relation, self.recomputed, self.matches, self.table, self.column,
+        )
+    }
+
+    /// Returns the query setting the counter to its recomputed value where it
+    /// drifted
+    fn repair_query(&self) -> String {
+        format!(
+            "UPDATE {} SET {} = c.value FROM ({}) c WHERE {} AND {}.{} IS DISTINCT FROM c.value",
+            self.relation, self.column, self.recomputed, self.matches, self.table, self.column,
+        )
+    }
+}
+
+/// Returns the counters maintained by increments in the consumer handlers and
+/// the SQL triggers
+fn counters(schema: &str) -> Vec<Counter> {
+    let mut counters = vec![
+        Counter {
+            table: "predicate_object",
+            column: "triple_count",
+            relation: format!("{schema}.predicate_object"),
+            recomputed: format!(
+                r#"
+                SELECT po.id, COUNT(t.id)::int AS value
+                FROM {schema}.predicate_object po
+                LEFT JOIN {schema}.triple t
+                    ON t.predicate_id = po.predicate_id AND t.object_id = po.object_id
+                GROUP BY po.id
+                "#,
+            ),
+            matches: "predicate_object.id = c.id".to_string(),
+        },
+        Counter {
+            table: "predicate_object",
+            column: "claim_count",
+            relation: format!("{schema}.predicate_object"),
+            recomputed: format!(
+                r#"
+                SELECT po.id, COUNT(cl.id)::int AS value
+                FROM {schema}.predicate_object po
+                LEFT JOIN {schema}.claim cl
+                    ON cl.predicate_id = po.predicate_id AND cl.object_id = po.object_id
+                GROUP BY po.id
+                "#,
+            ),
+            matches: "predicate_object.id = c.id".to_string(),
+        },
+        Counter {
+            table: "vault",
+            column: "position_count",
+            relation: format!("{schema}.vault"),
+            recomputed: format!(
+                r#"
+                SELECT v.id, COUNT(p.id)::int AS value
+                FROM {schema}.vault v
+                LEFT JOIN {schema}.position p ON p.vault_id = v.id
+                GROUP BY v.id
+                "#,
+            ),
+            matches: "vault.id = c.id".to_string(),
+        },
+    ];
+
+    // The totals of the single stats row, and the aggregate of their table
+    let stats = [
+        ("total_accounts", "account", "COUNT(*)::int"),
+        ("total_atoms", "atom", "COUNT(*)::int"),
+        ("total_triples", "triple", "COUNT(*)::int"),
+        ("total_positions", "position", "COUNT(*)::int"),
+        ("total_signals", "signal", "COUNT(*)::int"),
+        ("total_fees", "fee_transfer", "COALESCE(SUM(amount), 0)"),
+    ];
+    counters.extend(
+        stats
+            .into_iter()
+            .map(|(column, source, aggregate)| Counter {
+                table: "stats",
+                column,
+                relation: format!("{schema}.stats"),
+                recomputed: format!("SELECT {aggregate} AS value FROM {schema}.{source}"),
+                matches: "stats.id = 0".to_string(),
+            }),
+    );
+    counters
+}
+
+/// Recomputes every derived counter from the base tables, and returns the
+/// drift of each counter. The repair runs in a transaction locking the base
+/// tables against writes. With `dry_run`, the drift is only counted, from a
+/// read-only snapshot that takes no locks.
+pub async fn repair_counters(
+    dry_run: bool,
+    pool: &PgPool,
+    schema: &str,
+) -> Result<Vec<CounterDrift>, ModelError> {
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|e| ModelError::QueryError(e.to_string()))?;
+
+    if dry_run {
+        // Every count reads the same snapshot
+        sqlx::query("SET TRANSACTION ISOLATION LEVEL REPEATABLE READ READ ONLY")
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| ModelError::QueryError(e.to_string()))?;
+    } else {
+        // Reads go on, but the consumers wait for the repair to insert events
+        let tables = BASE_TABLES
+            .iter()
+            .map(|table| format!("{schema}.{table}"))
+            .collect::<Vec<_>>()
+            .join(", ");
+        sqlx::query(&format!("LOCK TABLE {tables} IN SHARE MODE"))
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| ModelError::QueryError(e.to_string()))?;
+    }
+
+    let mut drifts = Vec::new();
+    for counter in counters(schema) {
+        let drifted_rows = if dry_run {
+            sqlx::query_scalar::<_, i64>(&counter.count_query())
+                .fetch_one(&mut *tx)
+                .await
+                .map_err(|e| ModelError::QueryError(e.to_string()))? as u64
+        } else {
+            sqlx::query(&counter.repair_query())
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| ModelError::UpdateError(e.to_string()))?
+                .rows_affected()
+        };
+        drifts.push(CounterDrift {
+            table: counter.table.to_string(),
+            column: counter.column.to_string(),
+            drifted_rows,
+        });
+    }
+
+    tx.commit()
+        .await
+        .map_err(|e| ModelError::UpdateError(e.to_string()))?;
+    Ok(drifts)
+}
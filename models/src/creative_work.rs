@@ -0,0 +1,91 @@
+use crate::{
+    error::ModelError,
+    traits::{Model, SimpleCrud},
+    types::U256Wrapper,
+};
+use async_trait::async_trait;
+use sqlx::PgPool;
+
+/// This struct represents a creative work in the database.
+#[derive(Debug, sqlx::FromRow, Builder)]
+pub struct CreativeWork {
+    pub id: U256Wrapper,
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub image: Option<String>,
+    pub url: Option<String>,
+    pub author: Option<String>,
+    pub date_published: Option<String>,
+}
+
+/// This trait implements the Model trait for the CreativeWork struct.
+impl Model for CreativeWork {}
+
+#[async_trait]
+impl SimpleCrud<U256Wrapper> for CreativeWork {
+    /// This method upserts a creative work into the database.
+    async fn upsert(&self, pool: &PgPool, schema: &str) -> Result<Self, ModelError> {
+        let query = format!(
+            r#"
+            INSERT INTO {}.creative_work (id, name, description, image, url, author, date_published)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            ON CONFLICT (id) DO UPDATE SET
+                name = EXCLUDED.name,
+                description = EXCLUDED.description,
+                image = EXCLUDED.image,
+                url = EXCLUDED.url,
+                author = EXCLUDED.author,
+                date_published = EXCLUDED.date_published
+            RETURNING
+                id,
+                name,
+                description,
+                image,
+                url,
+                author,
+                date_published
+            "#,
+            schema,
+        );
+
+        sqlx::query_as::<_, CreativeWork>(&query)
+            .bind(self.id.to_big_decimal()?)
+            .bind(self.name.clone())
+            .bind(self.description.clone())
+            .bind(self.image.clone())
+            .bind(self.url.clone())
+            .bind(self.author.clone())
+            .bind(self.date_published.clone())
+            .fetch_one(pool)
+            .await
+            .map_err(|e| ModelError::InsertError(e.to_string()))
+    }
+
+    /// This method finds a creative work by its ID in the database.
+    async fn find_by_id(
+        id: U256Wrapper,
+        pool: &PgPool,
+        schema: &str,
+    ) -> Result<Option<Self>, ModelError> {
+        let query = format!(
+            r#"
+            SELECT id,
+                   name,
+                   description,
+                   image,
+                   url,
+                   author,
+                   date_published
+            FROM {}.creative_work
+            WHERE id = $1
+            "#,
+            schema
+        );
+
+        sqlx::query_as::<_, CreativeWork>(&query)
+            .bind(id.to_big_decimal()?)
+            .fetch_optional(pool)
+            .await
+            .map_err(|e| ModelError::QueryError(e.to_string()))
+    }
+}
@@ -0,0 +1,76 @@
+use crate::{
+    error::ModelError,
+    traits::{Model, SimpleCrud},
+    types::U256Wrapper,
+};
+use async_trait::async_trait;
+use sqlx::PgPool;
+
+/// Did is a struct that represents a DID atom in the database.
+#[derive(Debug, sqlx::FromRow, Builder)]
+#[sqlx(type_name = "did")]
+pub struct Did {
+    pub id: U256Wrapper,
+    pub did: String,
+    pub method: String,
+    pub identifier: String,
+}
+
+/// This is a trait that all models must implement.
+impl Model for Did {}
+
+#[async_trait]
+impl SimpleCrud<U256Wrapper> for Did {
+    /// Upserts a DID into the database.
+    async fn upsert(&self, pool: &PgPool, schema: &str) -> Result<Self, ModelError> {
+        let query = format!(
+            r#"
+            INSERT INTO {}.did (id, did, method, identifier)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (id) DO UPDATE SET
+                did = EXCLUDED.did,
+                method = EXCLUDED.method,
+                identifier = EXCLUDED.identifier
+            RETURNING id,
+                      did,
+                      method,
+                      identifier
+            "#,
+            schema,
+        );
+
+        sqlx::query_as::<_, Did>(&query)
+            .bind(self.id.to_big_decimal()?)
+            .bind(self.did.clone())
+            .bind(self.method.clone())
+            .bind(self.identifier.clone())
+            .fetch_one(pool)
+            .await
+            .map_err(|e| ModelError::InsertError(e.to_string()))
+    }
+
+    /// Finds a DID by its id.
+    async fn find_by_id(
+        id: U256Wrapper,
+        pool: &PgPool,
+        schema: &str,
+    ) -> Result<Option<Self>, ModelError> {
+        let query = format!(
+            r#"
+            SELECT id,
+                   did,
+                   method,
+                   identifier
+            FROM {}.did
+            WHERE id = $1
+            "#,
+            schema
+        );
+
+        sqlx::query_as::<_, Did>(&query)
+            .bind(id.to_big_decimal()?)
+            .fetch_optional(pool)
+            .await
+            .map_err(|e| ModelError::QueryError(e.to_string()))
+    }
+}
@@ -0,0 +1,198 @@
+use crate::{
+    error::ModelError,
+    traits::{Model, SimpleCrud},
+    types::U256Wrapper,
+};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use strum_macros::{Display, EnumString};
+use utoipa::ToSchema;
+
+/// The kind of a fee paid to the protocol or to the vaults
+#[derive(
+    sqlx::Type,
+    Clone,
+    Copy,
+    Debug,
+    Display,
+    EnumString,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+    ToSchema,
+)]
+#[sqlx(type_name = "fee_type")]
+pub enum FeeType {
+    /// Transferred to the protocol vault, it isn't tied to a vault
+    Protocol,
+    /// Kept by the vault deposited into
+    Entry,
+    /// Kept by the vault redeemed from
+    Exit,
+    /// The share of a triple deposit deposited into one of its atom vaults
+    AtomDepositFraction,
+}
+
+/// This struct classifies a fee paid in an event. An event can pay several
+/// fees, e.g. a triple deposit pays an entry fee and an atom deposit fraction
+/// to each of its atoms, so the `id` is the event id followed by the fee type
+/// and the vault. `vault_id` is the vault receiving the fee, and is `None` for
+/// protocol fees.
+#[derive(sqlx::FromRow, Debug, PartialEq, Clone, Builder)]
+#[sqlx(type_name = "fee_breakdown")]
+pub struct FeeBreakdown {
+    pub id: String,
+    pub fee_type: FeeType,
+    pub vault_id: Option<U256Wrapper>,
+    pub payer_id: String,
+    pub amount: U256Wrapper,
+    pub block_number: U256Wrapper,
+    pub block_timestamp: i64,
+    pub transaction_hash: String,
+}
+
+/// The fees an atom earned from the deposits into the triples it is part of,
+/// read from the `atom_fee_earnings` view
+#[derive(sqlx::FromRow, Debug, PartialEq, Clone)]
+pub struct AtomFeeEarnings {
+    pub atom_id: U256Wrapper,
+    pub triple_deposit_fees: U256Wrapper,
+    pub fee_count: i64,
+}
+
+/// The fees an account paid, by fee type, read from the `account_fees_paid`
+/// view
+#[derive(sqlx::FromRow, Debug, PartialEq, Clone)]
+pub struct AccountFeesPaid {
+    pub account_id: String,
+    pub protocol_fees: U256Wrapper,
+    pub entry_fees: U256Wrapper,
+    pub exit_fees: U256Wrapper,
+    pub atom_deposit_fractions: U256Wrapper,
+    pub total_fees: U256Wrapper,
+}
+
+impl FeeBreakdown {
+    /// Returns the id of a fee paid in an event. The vault tells apart the
+    /// atom deposit fractions of a triple deposit.
+    pub fn format_id(event_id: &str, fee_type: FeeType, vault_id: Option<&U256Wrapper>) -> String {
+        match vault_id {
+            Some(vault_id) => format!("{event_id}-{fee_type}-{vault_id}"),
+            None => format!("{event_id}-{fee_type}"),
+        }
+    }
+
+    /// Returns the fees earned by an atom from triple deposits, if any
+    pub async fn find_atom_fee_earnings(
+        atom_id: &U256Wrapper,
+        pool: &PgPool,
+        schema: &str,
+    ) -> Result<Option<AtomFeeEarnings>, ModelError> {
+        let query = format!(
+            r#"
+            SELECT atom_id, triple_deposit_fees, fee_count
+            FROM {schema}.atom_fee_earnings
+            WHERE atom_id = $1
+            "#,
+        );
+
+        sqlx::query_as::<_, AtomFeeEarnings>(&query)
+            .bind(atom_id.to_big_decimal()?)
+            .fetch_optional(pool)
+            .await
+            .map_err(|e| ModelError::QueryError(e.to_string()))
+    }
+
+    /// Returns the fees paid by an account, if any
+    pub async fn find_account_fees_paid(
+        account_id: &str,
+        pool: &PgPool,
+        schema: &str,
+    ) -> Result<Option<AccountFeesPaid>, ModelError> {
+        let query = format!(
+            r#"
+            SELECT account_id, protocol_fees, entry_fees, exit_fees, atom_deposit_fractions,
+                total_fees
+            FROM {schema}.account_fees_paid
+            WHERE account_id = $1
+            "#,
+        );
+
+        sqlx::query_as::<_, AccountFeesPaid>(&query)
+            .bind(account_id.to_lowercase())
+            .fetch_optional(pool)
+            .await
+            .map_err(|e| ModelError::QueryError(e.to_string()))
+    }
+}
+
+/// This is a trait that all models must implement.
+impl Model for FeeBreakdown {}
+
+/// This trait works as a contract for all models that need to be upserted into the database.
+#[async_trait]
+impl SimpleCrud<String> for FeeBreakdown {
+    /// Upserts a fee, so replaying an event doesn't count its fees twice
+    async fn upsert(&self, pool: &PgPool, schema: &str) -> Result<Self, ModelError> {
+        let query = format!(
+            r#"
+            INSERT INTO {schema}.fee_breakdown (
+                id, fee_type, vault_id, payer_id, amount, block_number, block_timestamp,
+                transaction_hash
+            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            ON CONFLICT (id) DO UPDATE SET
+                fee_type = EXCLUDED.fee_type,
+                vault_id = EXCLUDED.vault_id,
+                payer_id = EXCLUDED.payer_id,
+                amount = EXCLUDED.amount,
+                block_number = EXCLUDED.block_number,
+                block_timestamp = EXCLUDED.block_timestamp,
+                transaction_hash = EXCLUDED.transaction_hash
+            RETURNING id, fee_type, vault_id, payer_id, amount, block_number, block_timestamp,
+                transaction_hash
+            "#,
+        );
+
+        sqlx::query_as::<_, FeeBreakdown>(&query)
+            .bind(self.id.clone())
+            .bind(self.fee_type)
+            .bind(
+                self.vault_id
+                    .as_ref()
+                    .map(|id| id.to_big_decimal())
+                    .transpose()?,
+            )
+            .bind(self.payer_id.to_lowercase())
+            .bind(self.amount.to_big_decimal()?)
+            .bind(self.block_number.to_big_decimal()?)
+            .bind(self.block_timestamp)
+            .bind(self.transaction_hash.clone())
+            .fetch_one(pool)
+            .await
+            .map_err(|e| ModelError::InsertError(e.to_string()))
+    }
+
+    /// Finds a fee by its id
+    async fn find_by_id(
+        id: String,
+        pool: &PgPool,
+        schema: &str,
+    ) -> Result<Option<Self>, ModelError> {
+        let query = format!(
+            r#"
+            SELECT id, fee_type, vault_id, payer_id, amount, block_number, block_timestamp,
+                transaction_hash
+            FROM {schema}.fee_breakdown
+            WHERE id = $1
+            "#,
+        );
+
+        sqlx::query_as::<_, FeeBreakdown>(&query)
+            .bind(id)
+            .fetch_optional(pool)
+            .await
+            .map_err(|e| ModelError::QueryError(e.to_string()))
+    }
+}
@@ -0,0 +1,181 @@
+use crate::{cached_image::CachedImage, error::ModelError};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::PgPool;
+use strum_macros::{Display, EnumString};
+use utoipa::ToSchema;
+
+/// The moderation actions that can be applied to a cached image.
+#[derive(
+    sqlx::Type, Clone, Debug, Display, EnumString, PartialEq, Serialize, Deserialize, ToSchema,
+)]
+#[sqlx(type_name = "moderation_action")]
+pub enum ModerationAction {
+    MarkedSafe,
+    MarkedUnsafe,
+    Reclassified,
+}
+
+/// This struct represents an entry of the image moderation audit log.
+/// The table is append-only, so entries are inserted and never updated.
+#[derive(sqlx::FromRow, Debug, PartialEq, Clone, Builder, Serialize, Deserialize, ToSchema)]
+#[builder(fields(Default, Option=!))]
+#[sqlx(type_name = "image_moderation_audit")]
+pub struct ImageModerationAudit {
+    #[builder(Default)]
+    pub id: i64,
+    pub url: String,
+    pub action: ModerationAction,
+    pub reviewer: String,
+    pub reason: Option<String>,
+    pub previous_safe: bool,
+    pub new_safe: bool,
+    pub previous_score: Option<Value>,
+    pub new_score: Option<Value>,
+    #[builder(Default)]
+    pub created_at: DateTime<Utc>,
+}
+
+impl ImageModerationAudit {
+    /// Returns true if the entry is a manual verdict, i.e. the image was
+    /// marked safe or unsafe by a reviewer.
+    pub fn is_manual_verdict(&self) -> bool {
+        matches!(
+            self.action,
+            ModerationAction::MarkedSafe | ModerationAction::MarkedUnsafe
+        )
+    }
+
+    /// Returns the query inserting an entry into the audit log
+    fn insert_query(schema: &str) -> String {
+        format!(
+            r#"
+            INSERT INTO {}.image_moderation_audit
+                (url, action, reviewer, reason, previous_safe, new_safe, previous_score, new_score)
+            VALUES ($1, $2::text::{}.moderation_action, $3, $4, $5, $6, $7, $8)
+            RETURNING id, url, action, reviewer, reason, previous_safe, new_safe,
+                      previous_score, new_score, created_at
+            "#,
+            schema, schema
+        )
+    }
+
+    /// Inserts a new entry into the audit log. The `id` and `created_at`
+    /// are assigned by the database.
+    pub async fn insert(&self, pool: &PgPool, schema: &str) -> Result<Self, ModelError> {
+        sqlx::query_as::<_, ImageModerationAudit>(&Self::insert_query(schema))
+            .bind(self.url.clone())
+            .bind(self.action.to_string())
+            .bind(self.reviewer.clone())
+            .bind(self.reason.clone())
+            .bind(self.previous_safe)
+            .bind(self.new_safe)
+            .bind(self.previous_score.clone())
+            .bind(self.new_score.clone())
+            .fetch_one(pool)
+            .await
+            .map_err(|e| ModelError::InsertError(e.to_string()))
+    }
+
+    /// Stores the verdict of a moderated image and inserts the entry in a
+    /// single transaction, so an image is never changed without its entry.
+    pub async fn insert_with_image(
+        &self,
+        image: &CachedImage,
+        pool: &PgPool,
+        schema: &str,
+    ) -> Result<Self, ModelError> {
+        let mut tx = pool
+            .begin()
+            .await
+            .map_err(|e| ModelError::UpdateError(e.to_string()))?;
+
+        let query = format!(
+            r#"UPDATE {}.cached_image SET score = $2, model = $3, safe = $4 WHERE url = $1"#,
+            schema
+        );
+        let updated = sqlx::query(&query)
+            .bind(image.url.clone())
+            .bind(image.score.clone())
+            .bind(image.model.clone())
+            .bind(image.safe)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| ModelError::UpdateError(e.to_string()))?
+            .rows_affected();
+        if updated == 0 {
+            return Err(ModelError::UpdateError(format!(
+                "cached image {} not found",
+                image.url
+            )));
+        }
+
+        let entry = sqlx::query_as::<_, ImageModerationAudit>(&Self::insert_query(schema))
+            .bind(self.url.clone())
+            .bind(self.action.to_string())
+            .bind(self.reviewer.clone())
+            .bind(self.reason.clone())
+            .bind(self.previous_safe)
+            .bind(self.new_safe)
+            .bind(self.previous_score.clone())
+            .bind(self.new_score.clone())
+            .fetch_one(&mut *tx)
+            .await
+            .map_err(|e| ModelError::InsertError(e.to_string()))?;
+
+        tx.commit()
+            .await
+            .map_err(|e| ModelError::UpdateError(e.to_string()))?;
+        Ok(entry)
+    }
+
+    /// Returns the audit log of an image, most recent entries first.
+    pub async fn find_by_url(
+        url: &str,
+        pool: &PgPool,
+        schema: &str,
+    ) -> Result<Vec<Self>, ModelError> {
+        let query = format!(
+            r#"
+            SELECT id, url, action, reviewer, reason, previous_safe, new_safe,
+                   previous_score, new_score, created_at
+            FROM {}.image_moderation_audit
+            WHERE url = $1
+            ORDER BY id DESC
+            "#,
+            schema
+        );
+
+        sqlx::query_as::<_, ImageModerationAudit>(&query)
+            .bind(url)
+            .fetch_all(pool)
+            .await
+            .map_err(|e| ModelError::QueryError(e.to_string()))
+    }
+
+    /// Returns the most recent entry of the audit log of an image, if any.
+    pub async fn find_latest_by_url(
+        url: &str,
+        pool: &PgPool,
+        schema: &str,
+    ) -> Result<Option<Self>, ModelError> {
+        let query = format!(
+            r#"
+            SELECT id, url, action, reviewer, reason, previous_safe, new_safe,
+                   previous_score, new_score, created_at
+            FROM {}.image_moderation_audit
+            WHERE url = $1
+            ORDER BY id DESC
+            LIMIT 1
+            "#,
+            schema
+        );
+
+        sqlx::query_as::<_, ImageModerationAudit>(&query)
+            .bind(url)
+            .fetch_optional(pool)
+            .await
+            .map_err(|e| ModelError::QueryError(e.to_string()))
+    }
+}
@@ -1,23 +1,46 @@
 pub mod account;
+pub mod account_profile_change;
+pub mod api_key;
 pub mod atom;
+pub mod atom_resolution_attempt;
 pub mod atom_value;
+pub mod audit_finding;
 pub mod book;
 pub mod byte_object;
+pub mod cached_content;
 pub mod cached_image;
 pub mod caip10;
+pub mod caip19;
+pub mod caip2;
+pub mod chainlink_price;
 pub mod claim;
+pub mod classification_threshold;
+pub mod counter_repair;
+pub mod creative_work;
 pub mod deposit;
+pub mod did;
 pub mod error;
 pub mod event;
+pub mod fee_breakdown;
 pub mod fee_transfer;
+pub mod image_moderation_audit;
 pub mod json_object;
 pub mod organization;
 pub mod person;
+pub mod pinned_json;
+pub mod place;
 pub mod position;
+pub mod position_cost_basis;
 pub mod predicate_object;
+pub mod product;
 pub mod raw_logs;
 pub mod redemption;
+pub mod relevance_score;
+pub mod rollup;
+pub mod schema_event;
+pub mod share_price_history;
 pub mod signal;
+pub mod software_application;
 pub mod stats;
 pub mod stats_hour;
 pub mod substreams_cursor;
@@ -26,7 +49,10 @@ pub mod text_object;
 pub mod thing;
 pub mod traits;
 pub mod triple;
+pub mod triple_consensus;
 pub mod types;
 pub mod vault;
+pub mod web_page;
+pub mod web_site;
 #[macro_use]
 extern crate macon;
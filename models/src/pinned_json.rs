@@ -0,0 +1,90 @@
+use crate::{
+    error::ModelError,
+    traits::{Model, SimpleCrud},
+};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+
+/// This struct represents a JSON document pinned to IPFS through
+/// image-guard, keyed by its CID.
+#[derive(sqlx::FromRow, Debug, PartialEq, Clone, Builder, Serialize, Deserialize)]
+#[sqlx(type_name = "pinned_json")]
+pub struct PinnedJson {
+    pub cid: String,
+    pub size: i64,
+    pub created_at: DateTime<Utc>,
+}
+
+impl Model for PinnedJson {}
+
+#[async_trait]
+impl SimpleCrud<String> for PinnedJson {
+    async fn upsert(&self, pool: &PgPool, schema: &str) -> Result<Self, ModelError> {
+        let query = format!(
+            r#"
+            INSERT INTO {}.pinned_json (cid, size, created_at)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (cid) DO UPDATE SET
+                size = EXCLUDED.size
+            RETURNING cid, size, created_at
+            "#,
+            schema,
+        );
+
+        sqlx::query_as::<_, PinnedJson>(&query)
+            .bind(self.cid.clone())
+            .bind(self.size)
+            .bind(self.created_at)
+            .fetch_one(pool)
+            .await
+            .map_err(|e| ModelError::InsertError(e.to_string()))
+    }
+
+    async fn find_by_id(
+        id: String,
+        pool: &PgPool,
+        schema: &str,
+    ) -> Result<Option<Self>, ModelError> {
+        let query = format!(
+            r#"SELECT cid, size, created_at FROM {}.pinned_json WHERE cid = $1"#,
+            schema
+        );
+
+        sqlx::query_as::<_, PinnedJson>(&query)
+            .bind(id)
+            .fetch_optional(pool)
+            .await
+            .map_err(|e| ModelError::QueryError(e.to_string()))
+    }
+}
+
+impl PinnedJson {
+    /// Returns up to `limit` documents with a CID greater than `after`,
+    /// ordered by CID, to walk the table page by page.
+    pub async fn find_page(
+        pool: &PgPool,
+        after: &str,
+        limit: i64,
+        schema: &str,
+    ) -> Result<Vec<Self>, ModelError> {
+        let query = format!(
+            r#"
+            SELECT cid, size, created_at
+            FROM {}.pinned_json
+            WHERE cid > $1
+            ORDER BY cid
+            LIMIT $2
+            "#,
+            schema
+        );
+
+        sqlx::query_as::<_, PinnedJson>(&query)
+            .bind(after)
+            .bind(limit)
+            .fetch_all(pool)
+            .await
+            .map_err(|e| ModelError::QueryError(e.to_string()))
+    }
+}
@@ -0,0 +1,99 @@
+use crate::{
+    error::ModelError,
+    traits::{Model, SimpleCrud},
+    types::U256Wrapper,
+};
+use async_trait::async_trait;
+use sqlx::PgPool;
+
+/// This struct represents a place in the database.
+#[derive(Debug, sqlx::FromRow, Builder)]
+pub struct Place {
+    pub id: U256Wrapper,
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub image: Option<String>,
+    pub url: Option<String>,
+    pub address: Option<String>,
+    /// Coordinates are set as a whole, since a missing one isn't zero
+    #[builder(Option=!)]
+    pub latitude: Option<f64>,
+    #[builder(Option=!)]
+    pub longitude: Option<f64>,
+}
+
+/// This trait implements the Model trait for the Place struct.
+impl Model for Place {}
+
+#[async_trait]
+impl SimpleCrud<U256Wrapper> for Place {
+    /// This method upserts a place into the database.
+    async fn upsert(&self, pool: &PgPool, schema: &str) -> Result<Self, ModelError> {
+        let query = format!(
+            r#"
+            INSERT INTO {}.place (id, name, description, image, url, address, latitude, longitude)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            ON CONFLICT (id) DO UPDATE SET
+                name = EXCLUDED.name,
+                description = EXCLUDED.description,
+                image = EXCLUDED.image,
+                url = EXCLUDED.url,
+                address = EXCLUDED.address,
+                latitude = EXCLUDED.latitude,
+                longitude = EXCLUDED.longitude
+            RETURNING
+                id,
+                name,
+                description,
+                image,
+                url,
+                address,
+                latitude,
+                longitude
+            "#,
+            schema,
+        );
+
+        sqlx::query_as::<_, Place>(&query)
+            .bind(self.id.to_big_decimal()?)
+            .bind(self.name.clone())
+            .bind(self.description.clone())
+            .bind(self.image.clone())
+            .bind(self.url.clone())
+            .bind(self.address.clone())
+            .bind(self.latitude)
+            .bind(self.longitude)
+            .fetch_one(pool)
+            .await
+            .map_err(|e| ModelError::InsertError(e.to_string()))
+    }
+
+    /// This method finds a place by its ID in the database.
+    async fn find_by_id(
+        id: U256Wrapper,
+        pool: &PgPool,
+        schema: &str,
+    ) -> Result<Option<Self>, ModelError> {
+        let query = format!(
+            r#"
+            SELECT id,
+                   name,
+                   description,
+                   image,
+                   url,
+                   address,
+                   latitude,
+                   longitude
+            FROM {}.place
+            WHERE id = $1
+            "#,
+            schema
+        );
+
+        sqlx::query_as::<_, Place>(&query)
+            .bind(id.to_big_decimal()?)
+            .fetch_optional(pool)
+            .await
+            .map_err(|e| ModelError::QueryError(e.to_string()))
+    }
+}
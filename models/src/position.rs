@@ -141,4 +141,54 @@ impl Position {
             .await
             .map_err(|e| ModelError::QueryError(e.to_string()))
     }
+
+    /// Returns up to `limit` positions whose id comes after `after_id`, by id,
+    /// to scan every position page by page
+    pub async fn find_page(
+        after_id: &str,
+        limit: i64,
+        pool: &PgPool,
+        schema: &str,
+    ) -> Result<Vec<Self>, ModelError> {
+        let query = format!(
+            r#"
+            SELECT id, account_id, vault_id, shares
+            FROM {}.position
+            WHERE id > $1
+            ORDER BY id
+            LIMIT $2
+            "#,
+            schema,
+        );
+
+        sqlx::query_as::<_, Position>(&query)
+            .bind(after_id)
+            .bind(limit)
+            .fetch_all(pool)
+            .await
+            .map_err(|e| ModelError::QueryError(e.to_string()))
+    }
+
+    /// Returns up to `limit` random positions
+    pub async fn find_sample(
+        limit: i64,
+        pool: &PgPool,
+        schema: &str,
+    ) -> Result<Vec<Self>, ModelError> {
+        let query = format!(
+            r#"
+            SELECT id, account_id, vault_id, shares
+            FROM {}.position
+            ORDER BY random()
+            LIMIT $1
+            "#,
+            schema,
+        );
+
+        sqlx::query_as::<_, Position>(&query)
+            .bind(limit)
+            .fetch_all(pool)
+            .await
+            .map_err(|e| ModelError::QueryError(e.to_string()))
+    }
 }
@@ -0,0 +1,229 @@
+use crate::{
+    error::ModelError,
+    traits::{Model, SimpleCrud},
+    types::U256Wrapper,
+};
+use alloy::primitives::U256;
+use async_trait::async_trait;
+use sqlx::{types::BigDecimal, PgPool};
+
+/// Share prices are the assets of 10^18 shares
+pub(crate) const SHARE_PRICE_SCALE: u64 = 1_000_000_000_000_000_000;
+
+/// This struct is the cost-basis ledger of a position, built from the
+/// `Deposited` and `Redeemed` events of its account in its vault. It has the
+/// same id as the position. The cost basis of the remaining shares is their
+/// average cost: a redemption removes the cost of the redeemed shares at the
+/// average entry price, and realizes the difference with the assets received.
+/// `realized_pnl` is signed, so it's a `BigDecimal`.
+#[derive(Debug, Clone, PartialEq, sqlx::FromRow, Builder)]
+#[sqlx(type_name = "position_cost_basis")]
+pub struct PositionCostBasis {
+    pub id: String,
+    pub account_id: String,
+    pub vault_id: U256Wrapper,
+    /// The shares of the account in the vault after the last event
+    #[builder(Default)]
+    pub shares: U256Wrapper,
+    /// The assets deposited, after fees
+    #[builder(Default)]
+    pub total_assets_in: U256Wrapper,
+    /// The assets received from redemptions
+    #[builder(Default)]
+    pub total_assets_out: U256Wrapper,
+    /// The cost of the remaining shares
+    #[builder(Default)]
+    pub cost_basis: U256Wrapper,
+    /// The cost of 10^18 remaining shares, in the scale of the share price
+    #[builder(Default)]
+    pub average_entry_price: U256Wrapper,
+    #[builder(Default)]
+    pub realized_pnl: BigDecimal,
+    /// The block and log index of the last event applied to the ledger
+    #[builder(Default)]
+    pub last_block_number: i64,
+    #[builder(Default)]
+    pub last_log_index: i64,
+}
+
+/// This struct is a position ledger with the profit and loss of its remaining
+/// shares at the current share price of the vault.
+#[derive(Debug, Clone, PartialEq, sqlx::FromRow)]
+pub struct PositionPnl {
+    #[sqlx(flatten)]
+    pub cost_basis: PositionCostBasis,
+    pub current_share_price: U256Wrapper,
+    pub unrealized_pnl: BigDecimal,
+}
+
+/// This is a trait that all models must implement.
+impl Model for PositionCostBasis {}
+
+/// This trait works as a contract for all models that need to be upserted into the database.
+#[async_trait]
+impl SimpleCrud<String> for PositionCostBasis {
+    /// Creates a new ledger or updates an existing one in the database
+    async fn upsert(&self, pool: &PgPool, schema: &str) -> Result<Self, ModelError> {
+        let query = format!(
+            r#"
+            INSERT INTO {}.position_cost_basis (
+                id, account_id, vault_id, shares, total_assets_in, total_assets_out, cost_basis,
+                average_entry_price, realized_pnl, last_block_number, last_log_index
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+            ON CONFLICT (id) DO UPDATE SET
+                account_id = EXCLUDED.account_id,
+                vault_id = EXCLUDED.vault_id,
+                shares = EXCLUDED.shares,
+                total_assets_in = EXCLUDED.total_assets_in,
+                total_assets_out = EXCLUDED.total_assets_out,
+                cost_basis = EXCLUDED.cost_basis,
+                average_entry_price = EXCLUDED.average_entry_price,
+                realized_pnl = EXCLUDED.realized_pnl,
+                last_block_number = EXCLUDED.last_block_number,
+                last_log_index = EXCLUDED.last_log_index
+            RETURNING id, account_id, vault_id, shares, total_assets_in, total_assets_out, cost_basis,
+                average_entry_price, realized_pnl, last_block_number, last_log_index
+            "#,
+            schema,
+        );
+
+        sqlx::query_as::<_, PositionCostBasis>(&query)
+            .bind(self.id.to_lowercase())
+            .bind(self.account_id.to_lowercase())
+            .bind(self.vault_id.to_big_decimal()?)
+            .bind(self.shares.to_big_decimal()?)
+            .bind(self.total_assets_in.to_big_decimal()?)
+            .bind(self.total_assets_out.to_big_decimal()?)
+            .bind(self.cost_basis.to_big_decimal()?)
+            .bind(self.average_entry_price.to_big_decimal()?)
+            .bind(self.realized_pnl.clone())
+            .bind(self.last_block_number)
+            .bind(self.last_log_index)
+            .fetch_one(pool)
+            .await
+            .map_err(|e| ModelError::InsertError(e.to_string()))
+    }
+
+    /// Finds the ledger of a position by its id
+    async fn find_by_id(
+        id: String,
+        pool: &PgPool,
+        schema: &str,
+    ) -> Result<Option<Self>, ModelError> {
+        let query = format!(
+            r#"
+            SELECT id, account_id, vault_id, shares, total_assets_in, total_assets_out, cost_basis,
+                average_entry_price, realized_pnl, last_block_number, last_log_index
+            FROM {}.position_cost_basis
+            WHERE id = $1
+            "#,
+            schema,
+        );
+
+        sqlx::query_as::<_, PositionCostBasis>(&query)
+            .bind(id.to_lowercase())
+            .fetch_optional(pool)
+            .await
+            .map_err(|e| ModelError::QueryError(e.to_string()))
+    }
+}
+
+impl PositionCostBasis {
+    /// Returns true if an event at this block and log index was already
+    /// applied to the ledger, so replayed events are not counted twice.
+    pub fn is_applied(&self, block_number: i64, log_index: i64) -> bool {
+        (block_number, log_index) <= (self.last_block_number, self.last_log_index)
+    }
+
+    /// Applies a deposit of `assets` that brought the shares of the account
+    /// to `shares_after`. Returns false if the event was already applied.
+    pub fn apply_deposit(
+        &mut self,
+        assets: U256,
+        shares_after: U256,
+        block_number: i64,
+        log_index: i64,
+    ) -> bool {
+        if self.is_applied(block_number, log_index) {
+            return false;
+        }
+        self.total_assets_in.0 += assets;
+        self.cost_basis.0 += assets;
+        self.shares = U256Wrapper(shares_after);
+        self.update_average_entry_price();
+        self.last_block_number = block_number;
+        self.last_log_index = log_index;
+        true
+    }
+
+    /// Applies a redemption of `shares_redeemed` for `assets`, which left the
+    /// account with `shares_after`. Returns false if the event was already
+    /// applied.
+    pub fn apply_redemption(
+        &mut self,
+        assets: U256,
+        shares_redeemed: U256,
+        shares_after: U256,
+        block_number: i64,
+        log_index: i64,
+    ) -> Result<bool, ModelError> {
+        if self.is_applied(block_number, log_index) {
+            return Ok(false);
+        }
+        let shares_before = shares_after + shares_redeemed;
+        let cost_removed = if shares_after.is_zero() {
+            self.cost_basis.0
+        } else {
+            self.cost_basis.0 * shares_redeemed / shares_before
+        };
+
+        self.total_assets_out.0 += assets;
+        self.realized_pnl +=
+            U256Wrapper(assets).to_big_decimal()? - U256Wrapper(cost_removed).to_big_decimal()?;
+        self.cost_basis.0 -= cost_removed;
+        self.shares = U256Wrapper(shares_after);
+        self.update_average_entry_price();
+        self.last_block_number = block_number;
+        self.last_log_index = log_index;
+        Ok(true)
+    }
+
+    /// Recomputes the average entry price from the cost basis and the shares
+    fn update_average_entry_price(&mut self) {
+        self.average_entry_price = if self.shares.0.is_zero() {
+            U256Wrapper::default()
+        } else {
+            U256Wrapper(self.cost_basis.0 * U256::from(SHARE_PRICE_SCALE) / self.shares.0)
+        };
+    }
+
+    /// Returns the ledgers of the positions of an account with their
+    /// unrealized profit and loss at the current share price of their vault,
+    /// the largest positions by cost basis first.
+    pub async fn find_pnl_by_account_id(
+        account_id: &str,
+        pool: &PgPool,
+        schema: &str,
+    ) -> Result<Vec<PositionPnl>, ModelError> {
+        let query = format!(
+            r#"
+            SELECT pcb.id, pcb.account_id, pcb.vault_id, pcb.shares, pcb.total_assets_in,
+                pcb.total_assets_out, pcb.cost_basis, pcb.average_entry_price, pcb.realized_pnl,
+                pcb.last_block_number, pcb.last_log_index, v.current_share_price,
+                TRUNC(pcb.shares * v.current_share_price / $2) - pcb.cost_basis AS unrealized_pnl
+            FROM {schema}.position_cost_basis pcb
+            JOIN {schema}.vault v ON v.id = pcb.vault_id
+            WHERE pcb.account_id = $1
+            ORDER BY pcb.cost_basis DESC, pcb.id
+            "#,
+        );
+
+        sqlx::query_as::<_, PositionPnl>(&query)
+            .bind(account_id.to_lowercase())
+            .bind(U256Wrapper(U256::from(SHARE_PRICE_SCALE)).to_big_decimal()?)
+            .fetch_all(pool)
+            .await
+            .map_err(|e| ModelError::QueryError(e.to_string()))
+    }
+}
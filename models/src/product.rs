@@ -0,0 +1,91 @@
+use crate::{
+    error::ModelError,
+    traits::{Model, SimpleCrud},
+    types::U256Wrapper,
+};
+use async_trait::async_trait;
+use sqlx::PgPool;
+
+/// This struct represents a product in the database.
+#[derive(Debug, sqlx::FromRow, Builder)]
+pub struct Product {
+    pub id: U256Wrapper,
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub image: Option<String>,
+    pub url: Option<String>,
+    pub brand: Option<String>,
+    pub sku: Option<String>,
+}
+
+/// This trait implements the Model trait for the Product struct.
+impl Model for Product {}
+
+#[async_trait]
+impl SimpleCrud<U256Wrapper> for Product {
+    /// This method upserts a product into the database.
+    async fn upsert(&self, pool: &PgPool, schema: &str) -> Result<Self, ModelError> {
+        let query = format!(
+            r#"
+            INSERT INTO {}.product (id, name, description, image, url, brand, sku)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            ON CONFLICT (id) DO UPDATE SET
+                name = EXCLUDED.name,
+                description = EXCLUDED.description,
+                image = EXCLUDED.image,
+                url = EXCLUDED.url,
+                brand = EXCLUDED.brand,
+                sku = EXCLUDED.sku
+            RETURNING
+                id,
+                name,
+                description,
+                image,
+                url,
+                brand,
+                sku
+            "#,
+            schema,
+        );
+
+        sqlx::query_as::<_, Product>(&query)
+            .bind(self.id.to_big_decimal()?)
+            .bind(self.name.clone())
+            .bind(self.description.clone())
+            .bind(self.image.clone())
+            .bind(self.url.clone())
+            .bind(self.brand.clone())
+            .bind(self.sku.clone())
+            .fetch_one(pool)
+            .await
+            .map_err(|e| ModelError::InsertError(e.to_string()))
+    }
+
+    /// This method finds a product by its ID in the database.
+    async fn find_by_id(
+        id: U256Wrapper,
+        pool: &PgPool,
+        schema: &str,
+    ) -> Result<Option<Self>, ModelError> {
+        let query = format!(
+            r#"
+            SELECT id,
+                   name,
+                   description,
+                   image,
+                   url,
+                   brand,
+                   sku
+            FROM {}.product
+            WHERE id = $1
+            "#,
+            schema
+        );
+
+        sqlx::query_as::<_, Product>(&query)
+            .bind(id.to_big_decimal()?)
+            .fetch_optional(pool)
+            .await
+            .map_err(|e| ModelError::QueryError(e.to_string()))
+    }
+}
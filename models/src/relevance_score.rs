@@ -0,0 +1,278 @@
+use crate::{error::ModelError, types::U256Wrapper};
+use serde::{Deserialize, Serialize};
+use sqlx::{types::BigDecimal, PgPool};
+use strum_macros::{Display, EnumString};
+use utoipa::ToSchema;
+
+/// The default half-life of the signal scores, a week
+pub const DEFAULT_HALF_LIFE_SECS: i64 = 7 * 24 * 60 * 60;
+/// Past this number of half-lives a signal no longer counts, which also keeps
+/// the decayed scores away from the float underflow
+const MAX_HALF_LIVES: f64 = 64.0;
+
+/// The entity a relevance score is kept for
+#[derive(
+    sqlx::Type,
+    Clone,
+    Copy,
+    Debug,
+    Display,
+    EnumString,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+    ToSchema,
+)]
+#[sqlx(type_name = "relevance_entity")]
+pub enum RelevanceEntity {
+    Atom,
+    Triple,
+}
+
+/// This struct holds the time-decayed signal of an atom or a triple. Every
+/// signal delta loses half of its weight every `half_life_secs`, and
+/// `signal_score` is the sum of the decayed deltas at `last_signal_timestamp`,
+/// so it must be decayed again to the time it is read at. Redemptions count as
+/// negative deltas. `unique_accounts` is the number of accounts that ever
+/// signaled the entity. The block and log index of the last signal applied
+/// keep a replayed signal from being counted twice.
+#[derive(Debug, Clone, PartialEq, sqlx::FromRow)]
+#[sqlx(type_name = "relevance_score")]
+pub struct RelevanceScore {
+    pub entity_type: RelevanceEntity,
+    pub entity_id: U256Wrapper,
+    pub signal_score: f64,
+    pub unique_accounts: i64,
+    pub half_life_secs: i64,
+    pub last_signal_timestamp: i64,
+    pub last_block_number: i64,
+    pub last_log_index: i64,
+}
+
+/// Returns a score decayed over `elapsed_secs`. It mirrors the
+/// `relevance_decay` SQL function.
+pub fn decay(score: f64, elapsed_secs: i64, half_life_secs: i64) -> f64 {
+    let half_lives = elapsed_secs.max(0) as f64 / half_life_secs as f64;
+    if half_lives > MAX_HALF_LIVES {
+        0.0
+    } else {
+        score * 0.5_f64.powf(half_lives)
+    }
+}
+
+impl RelevanceScore {
+    /// Returns the signal score decayed to a timestamp
+    pub fn signal_score_at(&self, timestamp: i64) -> f64 {
+        decay(
+            self.signal_score,
+            timestamp - self.last_signal_timestamp,
+            self.half_life_secs,
+        )
+    }
+
+    /// Returns the relevance at a timestamp: the decayed signal score weighted
+    /// by the logarithm of the number of accounts, so an entity signaled by
+    /// many accounts ranks above one signaled by a single large account
+    pub fn relevance_at(&self, timestamp: i64) -> f64 {
+        self.signal_score_at(timestamp) * (self.unique_accounts as f64).ln_1p()
+    }
+
+    /// Adds a signal delta to the score of an entity, decaying the score to
+    /// the signal's timestamp. The signal must already be stored, since the
+    /// unique accounts are counted from the signals. Returns `None` if a
+    /// signal at this block and log index, or a later one, was already
+    /// applied, so replayed signals are not counted twice.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn apply_signal(
+        entity_type: RelevanceEntity,
+        entity_id: &U256Wrapper,
+        delta: &BigDecimal,
+        block_timestamp: i64,
+        block_number: i64,
+        log_index: i64,
+        half_life_secs: i64,
+        pool: &PgPool,
+        schema: &str,
+    ) -> Result<Option<Self>, ModelError> {
+        let column = match entity_type {
+            RelevanceEntity::Atom => "atom_id",
+            RelevanceEntity::Triple => "triple_id",
+        };
+        let query = format!(
+            r#"
+            INSERT INTO {schema}.relevance_score AS r (
+                entity_type, entity_id, signal_score, unique_accounts, half_life_secs,
+                last_signal_timestamp, last_block_number, last_log_index
+            ) VALUES (
+                $1, $2, $3::float8,
+                (SELECT COUNT(DISTINCT account_id) FROM {schema}.signal WHERE {column} = $2),
+                $4, $5, $6, $7
+            )
+            ON CONFLICT (entity_type, entity_id) DO UPDATE SET
+                signal_score = {schema}.relevance_decay(
+                    r.signal_score,
+                    EXCLUDED.last_signal_timestamp - r.last_signal_timestamp,
+                    EXCLUDED.half_life_secs
+                ) + EXCLUDED.signal_score,
+                unique_accounts = EXCLUDED.unique_accounts,
+                half_life_secs = EXCLUDED.half_life_secs,
+                last_signal_timestamp =
+                    GREATEST(r.last_signal_timestamp, EXCLUDED.last_signal_timestamp),
+                last_block_number = EXCLUDED.last_block_number,
+                last_log_index = EXCLUDED.last_log_index
+            WHERE (r.last_block_number, r.last_log_index)
+                < (EXCLUDED.last_block_number, EXCLUDED.last_log_index)
+            RETURNING entity_type, entity_id, signal_score, unique_accounts, half_life_secs,
+                last_signal_timestamp, last_block_number, last_log_index
+            "#,
+        );
+
+        sqlx::query_as::<_, RelevanceScore>(&query)
+            .bind(entity_type)
+            .bind(entity_id.to_big_decimal()?)
+            .bind(delta)
+            .bind(half_life_secs)
+            .bind(block_timestamp)
+            .bind(block_number)
+            .bind(log_index)
+            .fetch_optional(pool)
+            .await
+            .map_err(|e| ModelError::InsertError(e.to_string()))
+    }
+
+    /// Recomputes every score from the signals with a half-life, and returns
+    /// the number of scores written. The half-life must be the one the
+    /// decoded consumer applies signals with, or the scores mix decay rates. The redemption signals are counted with
+    /// the assets of their redemption. The signals and the scores are locked
+    /// against writes meanwhile, so the consumers wait for the rebuild.
+    pub async fn rebuild(
+        half_life_secs: i64,
+        pool: &PgPool,
+        schema: &str,
+    ) -> Result<u64, ModelError> {
+        let insert = format!(
+            r#"
+            WITH signals AS (
+                SELECT CASE WHEN s.atom_id IS NOT NULL THEN 'Atom' ELSE 'Triple' END
+                        AS entity_type,
+                    COALESCE(s.atom_id, s.triple_id) AS entity_id,
+                    s.account_id,
+                    s.block_timestamp,
+                    s.block_number::BIGINT AS block_number,
+                    -- Signal ids are `<transaction hash>-<log index>`
+                    COALESCE(substring(s.id FROM '-([0-9]+)$')::BIGINT, 0) AS log_index,
+                    CASE WHEN s.redemption_id IS NOT NULL THEN -r.assets_for_receiver
+                        ELSE s.delta END::float8 AS delta
+                FROM {schema}.signal s
+                LEFT JOIN {schema}.redemption r ON r.id = s.redemption_id
+                WHERE s.atom_id IS NOT NULL OR s.triple_id IS NOT NULL
+            ),
+            entities AS (
+                SELECT entity_type, entity_id,
+                    COUNT(DISTINCT account_id) AS unique_accounts,
+                    MAX(block_timestamp) AS last_signal_timestamp,
+                    MAX(block_number) AS last_block_number,
+                    (ARRAY_AGG(log_index ORDER BY block_number DESC, log_index DESC))[1]
+                        AS last_log_index
+                FROM signals
+                GROUP BY 1, 2
+            )
+            INSERT INTO {schema}.relevance_score (
+                entity_type, entity_id, signal_score, unique_accounts, half_life_secs,
+                last_signal_timestamp, last_block_number, last_log_index
+            )
+            SELECT e.entity_type::{schema}.relevance_entity, e.entity_id,
+                SUM({schema}.relevance_decay(
+                    s.delta, e.last_signal_timestamp - s.block_timestamp, $1
+                )),
+                e.unique_accounts, $1, e.last_signal_timestamp, e.last_block_number,
+                e.last_log_index
+            FROM entities e
+            JOIN signals s ON s.entity_type = e.entity_type AND s.entity_id = e.entity_id
+            GROUP BY e.entity_type, e.entity_id, e.unique_accounts, e.last_signal_timestamp,
+                e.last_block_number, e.last_log_index
+            "#,
+        );
+
+        let mut tx = pool
+            .begin()
+            .await
+            .map_err(|e| ModelError::QueryError(e.to_string()))?;
+        // A second rebuild waits for this one, instead of deadlocking on the delete
+        for lock in [
+            format!("LOCK TABLE {schema}.signal IN SHARE MODE"),
+            format!("LOCK TABLE {schema}.relevance_score IN SHARE ROW EXCLUSIVE MODE"),
+        ] {
+            sqlx::query(&lock)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| ModelError::QueryError(e.to_string()))?;
+        }
+        sqlx::query(&format!("DELETE FROM {schema}.relevance_score"))
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| ModelError::DeleteError(e.to_string()))?;
+        let inserted = sqlx::query(&insert)
+            .bind(half_life_secs)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| ModelError::InsertError(e.to_string()))?
+            .rows_affected();
+        tx.commit()
+            .await
+            .map_err(|e| ModelError::InsertError(e.to_string()))?;
+        Ok(inserted)
+    }
+
+    /// Finds the score of an entity
+    pub async fn find_by_entity(
+        entity_type: RelevanceEntity,
+        entity_id: &U256Wrapper,
+        pool: &PgPool,
+        schema: &str,
+    ) -> Result<Option<Self>, ModelError> {
+        let query = format!(
+            r#"
+            SELECT entity_type, entity_id, signal_score, unique_accounts, half_life_secs,
+                last_signal_timestamp, last_block_number, last_log_index
+            FROM {schema}.relevance_score
+            WHERE entity_type = $1 AND entity_id = $2
+            "#,
+        );
+
+        sqlx::query_as::<_, RelevanceScore>(&query)
+            .bind(entity_type)
+            .bind(entity_id.to_big_decimal()?)
+            .fetch_optional(pool)
+            .await
+            .map_err(|e| ModelError::QueryError(e.to_string()))
+    }
+
+    /// Returns the most relevant entities of a type right now, most relevant
+    /// first
+    pub async fn find_trending(
+        entity_type: RelevanceEntity,
+        limit: i64,
+        pool: &PgPool,
+        schema: &str,
+    ) -> Result<Vec<Self>, ModelError> {
+        let query = format!(
+            r#"
+            SELECT entity_type, entity_id, signal_score, unique_accounts, half_life_secs,
+                last_signal_timestamp, last_block_number, last_log_index
+            FROM {schema}.current_relevance_score
+            WHERE entity_type = $1
+            ORDER BY relevance DESC
+            LIMIT $2
+            "#,
+        );
+
+        sqlx::query_as::<_, RelevanceScore>(&query)
+            .bind(entity_type)
+            .bind(limit)
+            .fetch_all(pool)
+            .await
+            .map_err(|e| ModelError::QueryError(e.to_string()))
+    }
+}
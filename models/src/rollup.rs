@@ -0,0 +1,338 @@
+use crate::{
+    error::ModelError,
+    traits::{Model, SimpleCrud},
+    types::U256Wrapper,
+};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use sqlx::{types::BigDecimal, PgPool};
+use strum_macros::{Display, EnumString};
+use utoipa::ToSchema;
+
+/// The number of seconds in a day
+const DAY_SECS: i64 = 24 * 60 * 60;
+/// Weeks start on Monday, and the first Monday after the epoch is 1970-01-05
+const WEEK_OFFSET_SECS: i64 = 4 * DAY_SECS;
+
+/// The length of the buckets of a rollup
+#[derive(
+    sqlx::Type,
+    Clone,
+    Copy,
+    Debug,
+    Display,
+    EnumString,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+    ToSchema,
+)]
+#[sqlx(type_name = "rollup_granularity")]
+pub enum RollupGranularity {
+    /// UTC days
+    Day,
+    /// UTC weeks, starting on Monday
+    Week,
+}
+
+impl RollupGranularity {
+    /// Every granularity, in the order they are rolled up
+    pub const ALL: [RollupGranularity; 2] = [RollupGranularity::Day, RollupGranularity::Week];
+
+    /// The length of a bucket in seconds
+    pub fn length(&self) -> i64 {
+        match self {
+            RollupGranularity::Day => DAY_SECS,
+            RollupGranularity::Week => 7 * DAY_SECS,
+        }
+    }
+
+    /// The timestamp a bucket starts at, modulo its length
+    fn offset(&self) -> i64 {
+        match self {
+            RollupGranularity::Day => 0,
+            RollupGranularity::Week => WEEK_OFFSET_SECS,
+        }
+    }
+
+    /// Returns the start of the bucket containing a timestamp
+    pub fn bucket_start(&self, timestamp: i64) -> i64 {
+        (timestamp - self.offset()).div_euclid(self.length()) * self.length() + self.offset()
+    }
+}
+
+/// The entity a rollup is computed for. Global rollups cover every vault and
+/// have an `entity_id` of 0, the triple rollups cover both vaults of a triple.
+#[derive(
+    sqlx::Type,
+    Clone,
+    Copy,
+    Debug,
+    Display,
+    EnumString,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+    ToSchema,
+)]
+#[sqlx(type_name = "rollup_entity")]
+pub enum RollupEntity {
+    Global,
+    Atom,
+    Triple,
+}
+
+/// This struct holds the activity of an entity over a bucket, aggregated from
+/// the `deposit` and `redemption` tables by the rollup worker of the
+/// consumer-api. `net_signal` is the deposited assets minus the redeemed
+/// assets, so it's a signed `BigDecimal`. `fees` are the entry and exit fees,
+/// and `position_count` the number of open positions at the end of the bucket.
+#[derive(Debug, Clone, PartialEq, sqlx::FromRow)]
+#[sqlx(type_name = "rollup")]
+pub struct Rollup {
+    pub granularity: RollupGranularity,
+    pub entity_type: RollupEntity,
+    pub entity_id: U256Wrapper,
+    pub bucket_start: i64,
+    pub deposit_count: i64,
+    pub deposit_assets: U256Wrapper,
+    pub redemption_count: i64,
+    pub redemption_assets: U256Wrapper,
+    pub unique_depositors: i64,
+    pub net_signal: BigDecimal,
+    pub fees: U256Wrapper,
+    pub position_count: i64,
+}
+
+/// This struct records the last block timestamp rolled up for a granularity,
+/// so the worker only recomputes the buckets touched since its last run.
+#[derive(Debug, Clone, PartialEq, sqlx::FromRow, Builder)]
+#[sqlx(type_name = "rollup_cursor")]
+pub struct RollupCursor {
+    pub granularity: RollupGranularity,
+    pub last_block_timestamp: i64,
+}
+
+/// This is a trait that all models must implement.
+impl Model for RollupCursor {}
+
+/// This trait works as a contract for all models that need to be upserted into the database.
+#[async_trait]
+impl SimpleCrud<RollupGranularity> for RollupCursor {
+    /// Creates or moves the cursor of a granularity
+    async fn upsert(&self, pool: &PgPool, schema: &str) -> Result<Self, ModelError> {
+        let query = format!(
+            r#"
+            INSERT INTO {}.rollup_cursor (granularity, last_block_timestamp)
+            VALUES ($1, $2)
+            ON CONFLICT (granularity) DO UPDATE SET
+                last_block_timestamp = EXCLUDED.last_block_timestamp
+            RETURNING granularity, last_block_timestamp
+            "#,
+            schema,
+        );
+
+        sqlx::query_as::<_, RollupCursor>(&query)
+            .bind(self.granularity)
+            .bind(self.last_block_timestamp)
+            .fetch_one(pool)
+            .await
+            .map_err(|e| ModelError::InsertError(e.to_string()))
+    }
+
+    /// Finds the cursor of a granularity
+    async fn find_by_id(
+        granularity: RollupGranularity,
+        pool: &PgPool,
+        schema: &str,
+    ) -> Result<Option<Self>, ModelError> {
+        let query = format!(
+            r#"
+            SELECT granularity, last_block_timestamp
+            FROM {}.rollup_cursor
+            WHERE granularity = $1
+            "#,
+            schema,
+        );
+
+        sqlx::query_as::<_, RollupCursor>(&query)
+            .bind(granularity)
+            .fetch_optional(pool)
+            .await
+            .map_err(|e| ModelError::QueryError(e.to_string()))
+    }
+}
+
+impl Rollup {
+    /// Returns the block timestamp of the last deposit or redemption, if any
+    pub async fn find_last_event_timestamp(
+        pool: &PgPool,
+        schema: &str,
+    ) -> Result<Option<i64>, ModelError> {
+        let query = format!(
+            r#"
+            SELECT GREATEST(
+                (SELECT MAX(block_timestamp) FROM {schema}.deposit),
+                (SELECT MAX(block_timestamp) FROM {schema}.redemption)
+            )
+            "#,
+        );
+
+        sqlx::query_scalar::<_, Option<i64>>(&query)
+            .fetch_one(pool)
+            .await
+            .map_err(|e| ModelError::QueryError(e.to_string()))
+    }
+
+    /// Rebuilds the rollups of every entity over the buckets overlapping the
+    /// block timestamps from `from_timestamp` to `to_timestamp` (excluded),
+    /// and returns the number of rollups written. The buckets are deleted and
+    /// computed again from the events, so rebuilding is idempotent. The
+    /// position counts carry on from the last bucket before the range.
+    pub async fn rebuild(
+        granularity: RollupGranularity,
+        from_timestamp: i64,
+        to_timestamp: i64,
+        pool: &PgPool,
+        schema: &str,
+    ) -> Result<u64, ModelError> {
+        if to_timestamp <= from_timestamp {
+            return Ok(0);
+        }
+        let from_bucket = granularity.bucket_start(from_timestamp);
+        let to_bucket = granularity.bucket_start(to_timestamp - 1) + granularity.length();
+
+        let delete = format!(
+            r#"
+            DELETE FROM {schema}.rollup
+            WHERE granularity = $1 AND bucket_start >= $2 AND bucket_start < $3
+            "#,
+        );
+        let insert = format!(
+            r#"
+            WITH events AS (
+                SELECT d.block_timestamp, d.vault_id, d.sender_id AS depositor,
+                    1 AS deposits, d.sender_assets_after_total_fees AS deposit_assets,
+                    0 AS redemptions, 0 AS redemption_assets, d.entry_fee AS fees,
+                    CASE WHEN d.receiver_total_shares_in_vault = d.shares_for_receiver
+                        THEN 1 ELSE 0 END AS position_delta
+                FROM {schema}.deposit d
+                WHERE d.block_timestamp >= $3 AND d.block_timestamp < $4
+                UNION ALL
+                SELECT r.block_timestamp, r.vault_id, NULL,
+                    0, 0,
+                    1, r.assets_for_receiver, r.exit_fee,
+                    CASE WHEN r.sender_total_shares_in_vault = 0 THEN -1 ELSE 0 END
+                FROM {schema}.redemption r
+                WHERE r.block_timestamp >= $3 AND r.block_timestamp < $4
+            ),
+            entity_events AS (
+                SELECT 'Global' AS entity_type, 0::numeric AS entity_id, e.*
+                FROM events e
+                UNION ALL
+                SELECT CASE WHEN v.atom_id IS NOT NULL THEN 'Atom' ELSE 'Triple' END,
+                    COALESCE(v.atom_id, v.triple_id), e.*
+                FROM events e
+                JOIN {schema}.vault v ON v.id = e.vault_id
+                WHERE v.atom_id IS NOT NULL OR v.triple_id IS NOT NULL
+            ),
+            buckets AS (
+                SELECT entity_type, entity_id,
+                    ((block_timestamp - $5) / $2) * $2 + $5 AS bucket_start,
+                    SUM(deposits) AS deposit_count,
+                    SUM(deposit_assets) AS deposit_assets,
+                    SUM(redemptions) AS redemption_count,
+                    SUM(redemption_assets) AS redemption_assets,
+                    COUNT(DISTINCT depositor) AS unique_depositors,
+                    SUM(fees) AS fees,
+                    SUM(position_delta) AS position_delta
+                FROM entity_events
+                GROUP BY 1, 2, 3
+            )
+            INSERT INTO {schema}.rollup (
+                granularity, entity_type, entity_id, bucket_start, deposit_count, deposit_assets,
+                redemption_count, redemption_assets, unique_depositors, net_signal, fees,
+                position_count
+            )
+            SELECT $1, b.entity_type::{schema}.rollup_entity, b.entity_id, b.bucket_start,
+                b.deposit_count, b.deposit_assets, b.redemption_count, b.redemption_assets,
+                b.unique_depositors, b.deposit_assets - b.redemption_assets, b.fees,
+                COALESCE((
+                    SELECT p.position_count
+                    FROM {schema}.rollup p
+                    WHERE p.granularity = $1
+                      AND p.entity_type = b.entity_type::{schema}.rollup_entity
+                      AND p.entity_id = b.entity_id
+                      AND p.bucket_start < $3
+                    ORDER BY p.bucket_start DESC
+                    LIMIT 1
+                ), 0) + SUM(b.position_delta) OVER (
+                    PARTITION BY b.entity_type, b.entity_id ORDER BY b.bucket_start
+                )
+            FROM buckets b
+            "#,
+        );
+
+        let mut tx = pool
+            .begin()
+            .await
+            .map_err(|e| ModelError::QueryError(e.to_string()))?;
+        sqlx::query(&delete)
+            .bind(granularity)
+            .bind(from_bucket)
+            .bind(to_bucket)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| ModelError::DeleteError(e.to_string()))?;
+        let inserted = sqlx::query(&insert)
+            .bind(granularity)
+            .bind(granularity.length())
+            .bind(from_bucket)
+            .bind(to_bucket)
+            .bind(granularity.offset())
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| ModelError::InsertError(e.to_string()))?
+            .rows_affected();
+        tx.commit()
+            .await
+            .map_err(|e| ModelError::InsertError(e.to_string()))?;
+        Ok(inserted)
+    }
+
+    /// Returns the rollups of an entity whose bucket starts between two
+    /// timestamps (the end is excluded), oldest first
+    pub async fn find_by_entity(
+        granularity: RollupGranularity,
+        entity_type: RollupEntity,
+        entity_id: &U256Wrapper,
+        from_timestamp: i64,
+        to_timestamp: i64,
+        pool: &PgPool,
+        schema: &str,
+    ) -> Result<Vec<Self>, ModelError> {
+        let query = format!(
+            r#"
+            SELECT granularity, entity_type, entity_id, bucket_start, deposit_count,
+                deposit_assets, redemption_count, redemption_assets, unique_depositors,
+                net_signal, fees, position_count
+            FROM {schema}.rollup
+            WHERE granularity = $1 AND entity_type = $2 AND entity_id = $3
+              AND bucket_start >= $4 AND bucket_start < $5
+            ORDER BY bucket_start
+            "#,
+        );
+
+        sqlx::query_as::<_, Rollup>(&query)
+            .bind(granularity)
+            .bind(entity_type)
+            .bind(entity_id.to_big_decimal()?)
+            .bind(from_timestamp)
+            .bind(to_timestamp)
+            .fetch_all(pool)
+            .await
+            .map_err(|e| ModelError::QueryError(e.to_string()))
+    }
+}
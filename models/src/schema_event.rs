@@ -0,0 +1,97 @@
+use crate::{
+    error::ModelError,
+    traits::{Model, SimpleCrud},
+    types::U256Wrapper,
+};
+use async_trait::async_trait;
+use sqlx::PgPool;
+
+/// This struct represents a schema.org event in the database.
+/// It's named after schema.org to avoid a clash with the chain [`crate::event::Event`].
+#[derive(Debug, sqlx::FromRow, Builder)]
+pub struct SchemaEvent {
+    pub id: U256Wrapper,
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub image: Option<String>,
+    pub url: Option<String>,
+    pub start_date: Option<String>,
+    pub end_date: Option<String>,
+    pub location: Option<String>,
+}
+
+/// This trait implements the Model trait for the SchemaEvent struct.
+impl Model for SchemaEvent {}
+
+#[async_trait]
+impl SimpleCrud<U256Wrapper> for SchemaEvent {
+    /// This method upserts a schema.org event into the database.
+    async fn upsert(&self, pool: &PgPool, schema: &str) -> Result<Self, ModelError> {
+        let query = format!(
+            r#"
+            INSERT INTO {}.schema_event (id, name, description, image, url, start_date, end_date, location)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            ON CONFLICT (id) DO UPDATE SET
+                name = EXCLUDED.name,
+                description = EXCLUDED.description,
+                image = EXCLUDED.image,
+                url = EXCLUDED.url,
+                start_date = EXCLUDED.start_date,
+                end_date = EXCLUDED.end_date,
+                location = EXCLUDED.location
+            RETURNING
+                id,
+                name,
+                description,
+                image,
+                url,
+                start_date,
+                end_date,
+                location
+            "#,
+            schema,
+        );
+
+        sqlx::query_as::<_, SchemaEvent>(&query)
+            .bind(self.id.to_big_decimal()?)
+            .bind(self.name.clone())
+            .bind(self.description.clone())
+            .bind(self.image.clone())
+            .bind(self.url.clone())
+            .bind(self.start_date.clone())
+            .bind(self.end_date.clone())
+            .bind(self.location.clone())
+            .fetch_one(pool)
+            .await
+            .map_err(|e| ModelError::InsertError(e.to_string()))
+    }
+
+    /// This method finds a schema.org event by its ID in the database.
+    async fn find_by_id(
+        id: U256Wrapper,
+        pool: &PgPool,
+        schema: &str,
+    ) -> Result<Option<Self>, ModelError> {
+        let query = format!(
+            r#"
+            SELECT id,
+                   name,
+                   description,
+                   image,
+                   url,
+                   start_date,
+                   end_date,
+                   location
+            FROM {}.schema_event
+            WHERE id = $1
+            "#,
+            schema
+        );
+
+        sqlx::query_as::<_, SchemaEvent>(&query)
+            .bind(id.to_big_decimal()?)
+            .fetch_optional(pool)
+            .await
+            .map_err(|e| ModelError::QueryError(e.to_string()))
+    }
+}
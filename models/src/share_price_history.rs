@@ -0,0 +1,71 @@
+use crate::{error::ModelError, types::U256Wrapper};
+use sqlx::PgPool;
+
+/// This struct represents a share price of a vault observed by an event. The
+/// history is keyed by the vault, the block and the log index of the event, so
+/// replaying an event records the same entry again.
+#[derive(sqlx::FromRow, Debug, PartialEq, Clone, Builder)]
+#[sqlx(type_name = "share_price_history")]
+pub struct SharePriceHistory {
+    pub vault_id: U256Wrapper,
+    pub block_number: i64,
+    pub log_index: i64,
+    pub block_timestamp: i64,
+    pub share_price: U256Wrapper,
+}
+
+impl SharePriceHistory {
+    /// Records the share price of a vault at an event. If the event was
+    /// already recorded, its share price is updated.
+    pub async fn upsert(&self, pool: &PgPool, schema: &str) -> Result<Self, ModelError> {
+        let query = format!(
+            r#"
+            INSERT INTO {}.share_price_history (vault_id, block_number, log_index, block_timestamp, share_price)
+            VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT (vault_id, block_number, log_index) DO UPDATE SET
+                block_timestamp = EXCLUDED.block_timestamp,
+                share_price = EXCLUDED.share_price
+            RETURNING vault_id, block_number, log_index, block_timestamp, share_price
+            "#,
+            schema,
+        );
+
+        sqlx::query_as::<_, SharePriceHistory>(&query)
+            .bind(self.vault_id.to_big_decimal()?)
+            .bind(self.block_number)
+            .bind(self.log_index)
+            .bind(self.block_timestamp)
+            .bind(self.share_price.to_big_decimal()?)
+            .fetch_one(pool)
+            .await
+            .map_err(|e| ModelError::InsertError(e.to_string()))
+    }
+
+    /// Returns the share prices of a vault observed between two blocks, both
+    /// included, in the order of the events.
+    pub async fn find_by_vault_id(
+        vault_id: &U256Wrapper,
+        from_block: i64,
+        to_block: i64,
+        pool: &PgPool,
+        schema: &str,
+    ) -> Result<Vec<Self>, ModelError> {
+        let query = format!(
+            r#"
+            SELECT vault_id, block_number, log_index, block_timestamp, share_price
+            FROM {}.share_price_history
+            WHERE vault_id = $1 AND block_number BETWEEN $2 AND $3
+            ORDER BY block_number, log_index
+            "#,
+            schema,
+        );
+
+        sqlx::query_as::<_, SharePriceHistory>(&query)
+            .bind(vault_id.to_big_decimal()?)
+            .bind(from_block)
+            .bind(to_block)
+            .fetch_all(pool)
+            .await
+            .map_err(|e| ModelError::QueryError(e.to_string()))
+    }
+}
@@ -0,0 +1,91 @@
+use crate::{
+    error::ModelError,
+    traits::{Model, SimpleCrud},
+    types::U256Wrapper,
+};
+use async_trait::async_trait;
+use sqlx::PgPool;
+
+/// This struct represents a software application in the database.
+#[derive(Debug, sqlx::FromRow, Builder)]
+pub struct SoftwareApplication {
+    pub id: U256Wrapper,
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub image: Option<String>,
+    pub url: Option<String>,
+    pub application_category: Option<String>,
+    pub operating_system: Option<String>,
+}
+
+/// This trait implements the Model trait for the SoftwareApplication struct.
+impl Model for SoftwareApplication {}
+
+#[async_trait]
+impl SimpleCrud<U256Wrapper> for SoftwareApplication {
+    /// This method upserts a software application into the database.
+    async fn upsert(&self, pool: &PgPool, schema: &str) -> Result<Self, ModelError> {
+        let query = format!(
+            r#"
+            INSERT INTO {}.software_application (id, name, description, image, url, application_category, operating_system)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            ON CONFLICT (id) DO UPDATE SET
+                name = EXCLUDED.name,
+                description = EXCLUDED.description,
+                image = EXCLUDED.image,
+                url = EXCLUDED.url,
+                application_category = EXCLUDED.application_category,
+                operating_system = EXCLUDED.operating_system
+            RETURNING
+                id,
+                name,
+                description,
+                image,
+                url,
+                application_category,
+                operating_system
+            "#,
+            schema,
+        );
+
+        sqlx::query_as::<_, SoftwareApplication>(&query)
+            .bind(self.id.to_big_decimal()?)
+            .bind(self.name.clone())
+            .bind(self.description.clone())
+            .bind(self.image.clone())
+            .bind(self.url.clone())
+            .bind(self.application_category.clone())
+            .bind(self.operating_system.clone())
+            .fetch_one(pool)
+            .await
+            .map_err(|e| ModelError::InsertError(e.to_string()))
+    }
+
+    /// This method finds a software application by its ID in the database.
+    async fn find_by_id(
+        id: U256Wrapper,
+        pool: &PgPool,
+        schema: &str,
+    ) -> Result<Option<Self>, ModelError> {
+        let query = format!(
+            r#"
+            SELECT id,
+                   name,
+                   description,
+                   image,
+                   url,
+                   application_category,
+                   operating_system
+            FROM {}.software_application
+            WHERE id = $1
+            "#,
+            schema
+        );
+
+        sqlx::query_as::<_, SoftwareApplication>(&query)
+            .bind(id.to_big_decimal()?)
+            .fetch_optional(pool)
+            .await
+            .map_err(|e| ModelError::QueryError(e.to_string()))
+    }
+}
@@ -29,6 +29,9 @@ use sqlx::{postgres::PgPoolOptions, PgPool};
 pub const TEST_SCHEMA: &str = "public";
 pub const TEST_PROXY_SCHEMA: &str = "base_proxy";
 pub const TEST_INDEXER_SCHEMA: &str = "base_indexer";
+pub const TEST_IMAGE_SCHEMA: &str = "cached_images";
+pub const TEST_API_KEY_SCHEMA: &str = "api_keys";
+pub const TEST_IPFS_CACHE_SCHEMA: &str = "ipfs_cache";
 
 /// This function sets up a test database connection pool.
 pub async fn setup_test_db() -> PgPool {
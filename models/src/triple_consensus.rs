@@ -0,0 +1,101 @@
+use crate::{error::ModelError, position_cost_basis::SHARE_PRICE_SCALE, types::U256Wrapper};
+use alloy::primitives::U256;
+use sqlx::PgPool;
+
+/// This struct holds how much the network agrees with a triple. The figures
+/// "for" come from the vault of the triple and the ones "against" from its
+/// counter vault: the assets are the shares at the current share price, and
+/// the supporters and opponents are the accounts with shares in each vault.
+/// `agreement_score` is `(assets_for - assets_against) / (assets_for +
+/// assets_against)`, from -1 (everyone disagrees) to 1, and 0 without assets.
+#[derive(Debug, Clone, PartialEq, sqlx::FromRow)]
+#[sqlx(type_name = "triple_consensus")]
+pub struct TripleConsensus {
+    pub id: U256Wrapper,
+    pub assets_for: U256Wrapper,
+    pub assets_against: U256Wrapper,
+    pub shares_for: U256Wrapper,
+    pub shares_against: U256Wrapper,
+    pub supporters: i64,
+    pub opponents: i64,
+    pub agreement_score: f64,
+}
+
+impl TripleConsensus {
+    /// Computes the consensus of a triple from its vaults and the positions
+    /// in them, and stores it. Returns `None` if the triple doesn't exist.
+    pub async fn refresh(
+        triple_id: &U256Wrapper,
+        pool: &PgPool,
+        schema: &str,
+    ) -> Result<Option<Self>, ModelError> {
+        let query = format!(
+            r#"
+            WITH figures AS (
+                SELECT t.id,
+                    TRUNC(v.total_shares * v.current_share_price / $2) AS assets_for,
+                    TRUNC(cv.total_shares * cv.current_share_price / $2) AS assets_against,
+                    v.total_shares AS shares_for,
+                    cv.total_shares AS shares_against,
+                    (SELECT COUNT(*) FROM {schema}.position p
+                        WHERE p.vault_id = t.vault_id AND p.shares > 0) AS supporters,
+                    (SELECT COUNT(*) FROM {schema}.position p
+                        WHERE p.vault_id = t.counter_vault_id AND p.shares > 0) AS opponents
+                FROM {schema}.triple t
+                JOIN {schema}.vault v ON v.id = t.vault_id
+                JOIN {schema}.vault cv ON cv.id = t.counter_vault_id
+                WHERE t.id = $1
+            )
+            INSERT INTO {schema}.triple_consensus (
+                id, assets_for, assets_against, shares_for, shares_against, supporters,
+                opponents, agreement_score
+            )
+            SELECT id, assets_for, assets_against, shares_for, shares_against, supporters,
+                opponents,
+                CASE WHEN assets_for + assets_against = 0 THEN 0
+                    ELSE ((assets_for - assets_against) / (assets_for + assets_against))::float8
+                END
+            FROM figures
+            ON CONFLICT (id) DO UPDATE SET
+                assets_for = EXCLUDED.assets_for,
+                assets_against = EXCLUDED.assets_against,
+                shares_for = EXCLUDED.shares_for,
+                shares_against = EXCLUDED.shares_against,
+                supporters = EXCLUDED.supporters,
+                opponents = EXCLUDED.opponents,
+                agreement_score = EXCLUDED.agreement_score
+            RETURNING id, assets_for, assets_against, shares_for, shares_against, supporters,
+                opponents, agreement_score
+            "#,
+        );
+
+        sqlx::query_as::<_, TripleConsensus>(&query)
+            .bind(triple_id.to_big_decimal()?)
+            .bind(U256Wrapper(U256::from(SHARE_PRICE_SCALE)).to_big_decimal()?)
+            .fetch_optional(pool)
+            .await
+            .map_err(|e| ModelError::InsertError(e.to_string()))
+    }
+
+    /// Finds the consensus of a triple
+    pub async fn find_by_id(
+        triple_id: &U256Wrapper,
+        pool: &PgPool,
+        schema: &str,
+    ) -> Result<Option<Self>, ModelError> {
+        let query = format!(
+            r#"
+            SELECT id, assets_for, assets_against, shares_for, shares_against, supporters,
+                opponents, agreement_score
+            FROM {schema}.triple_consensus
+            WHERE id = $1
+            "#,
+        );
+
+        sqlx::query_as::<_, TripleConsensus>(&query)
+            .bind(triple_id.to_big_decimal()?)
+            .fetch_optional(pool)
+            .await
+            .map_err(|e| ModelError::QueryError(e.to_string()))
+    }
+}
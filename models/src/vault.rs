@@ -23,6 +23,20 @@ pub struct Vault {
 /// This is a trait that all models must implement.
 impl Model for Vault {}
 
+/// This struct represents the open, high, low and close share prices of a
+/// vault over an interval, from the share price history. `bucket_start` is
+/// the block timestamp the interval starts at.
+#[derive(Debug, PartialEq, Clone, sqlx::FromRow)]
+pub struct SharePriceCandle {
+    pub bucket_start: i64,
+    pub open: U256Wrapper,
+    pub high: U256Wrapper,
+    pub low: U256Wrapper,
+    pub close: U256Wrapper,
+    /// The number of share prices observed during the interval
+    pub sample_count: i64,
+}
+
 /// This trait works as a contract for all models that need to be upserted into the database.
 #[async_trait]
 impl SimpleCrud<U256Wrapper> for Vault {
@@ -112,4 +126,99 @@ impl Vault {
             .await
             .map_err(|e| ModelError::UpdateError(e.to_string()))
     }
+
+    /// Returns the share price candles of a vault over intervals of
+    /// `interval_secs`, between two block timestamps. The start is included
+    /// and the end is not, and intervals without share prices are skipped.
+    pub async fn share_price_candles(
+        id: &U256Wrapper,
+        interval_secs: i64,
+        from_timestamp: i64,
+        to_timestamp: i64,
+        pool: &PgPool,
+        schema: &str,
+    ) -> Result<Vec<SharePriceCandle>, ModelError> {
+        if interval_secs <= 0 {
+            return Err(ModelError::QueryError(format!(
+                "Invalid candle interval: {}",
+                interval_secs
+            )));
+        }
+
+        let query = format!(
+            r#"
+            SELECT
+                (block_timestamp / $2) * $2 AS bucket_start,
+                (array_agg(share_price ORDER BY block_number, log_index))[1] AS open,
+                MAX(share_price) AS high,
+                MIN(share_price) AS low,
+                (array_agg(share_price ORDER BY block_number DESC, log_index DESC))[1] AS close,
+                COUNT(*) AS sample_count
+            FROM {}.share_price_history
+            WHERE vault_id = $1 AND block_timestamp >= $3 AND block_timestamp < $4
+            GROUP BY bucket_start
+            ORDER BY bucket_start
+            "#,
+            schema,
+        );
+
+        sqlx::query_as::<_, SharePriceCandle>(&query)
+            .bind(id.to_big_decimal()?)
+            .bind(interval_secs)
+            .bind(from_timestamp)
+            .bind(to_timestamp)
+            .fetch_all(pool)
+            .await
+            .map_err(|e| ModelError::QueryError(e.to_string()))
+    }
+
+    /// Returns up to `limit` vaults whose id is above `after_id`, by id, to
+    /// scan every vault page by page
+    pub async fn find_page(
+        after_id: &U256Wrapper,
+        limit: i64,
+        pool: &PgPool,
+        schema: &str,
+    ) -> Result<Vec<Self>, ModelError> {
+        let query = format!(
+            r#"
+            SELECT id, atom_id, triple_id, total_shares, current_share_price, position_count
+            FROM {}.vault
+            WHERE id > $1
+            ORDER BY id
+            LIMIT $2
+            "#,
+            schema,
+        );
+
+        sqlx::query_as::<_, Vault>(&query)
+            .bind(after_id.to_big_decimal()?)
+            .bind(limit)
+            .fetch_all(pool)
+            .await
+            .map_err(|e| ModelError::QueryError(e.to_string()))
+    }
+
+    /// Returns up to `limit` random vaults
+    pub async fn find_sample(
+        limit: i64,
+        pool: &PgPool,
+        schema: &str,
+    ) -> Result<Vec<Self>, ModelError> {
+        let query = format!(
+            r#"
+            SELECT id, atom_id, triple_id, total_shares, current_share_price, position_count
+            FROM {}.vault
+            ORDER BY random()
+            LIMIT $1
+            "#,
+            schema,
+        );
+
+        sqlx::query_as::<_, Vault>(&query)
+            .bind(limit)
+            .fetch_all(pool)
+            .await
+            .map_err(|e| ModelError::QueryError(e.to_string()))
+    }
 }
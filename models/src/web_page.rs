@@ -0,0 +1,103 @@
+use crate::{
+    error::ModelError,
+    traits::{Model, SimpleCrud},
+    types::U256Wrapper,
+};
+use async_trait::async_trait;
+use sqlx::PgPool;
+
+/// This struct represents the metadata of a web page atom in the database.
+#[derive(Debug, sqlx::FromRow, Builder)]
+pub struct WebPage {
+    pub id: U256Wrapper,
+    /// The URL stored in the atom
+    pub url: String,
+    /// The canonical URL declared by the page, or the atom URL
+    pub canonical_url: String,
+    #[builder(Option=!)]
+    pub title: Option<String>,
+    #[builder(Option=!)]
+    pub description: Option<String>,
+    #[builder(Option=!)]
+    pub image: Option<String>,
+    #[builder(Option=!)]
+    pub site_name: Option<String>,
+    #[builder(Option=!)]
+    pub favicon: Option<String>,
+}
+
+/// This trait implements the Model trait for the WebPage struct.
+impl Model for WebPage {}
+
+#[async_trait]
+impl SimpleCrud<U256Wrapper> for WebPage {
+    /// This method upserts a web page into the database.
+    async fn upsert(&self, pool: &PgPool, schema: &str) -> Result<Self, ModelError> {
+        let query = format!(
+            r#"
+            INSERT INTO {}.web_page (id, url, canonical_url, title, description, image, site_name, favicon)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            ON CONFLICT (id) DO UPDATE SET
+                url = EXCLUDED.url,
+                canonical_url = EXCLUDED.canonical_url,
+                title = EXCLUDED.title,
+                description = EXCLUDED.description,
+                image = EXCLUDED.image,
+                site_name = EXCLUDED.site_name,
+                favicon = EXCLUDED.favicon
+            RETURNING
+                id,
+                url,
+                canonical_url,
+                title,
+                description,
+                image,
+                site_name,
+                favicon
+            "#,
+            schema,
+        );
+
+        sqlx::query_as::<_, WebPage>(&query)
+            .bind(self.id.to_big_decimal()?)
+            .bind(self.url.clone())
+            .bind(self.canonical_url.clone())
+            .bind(self.title.clone())
+            .bind(self.description.clone())
+            .bind(self.image.clone())
+            .bind(self.site_name.clone())
+            .bind(self.favicon.clone())
+            .fetch_one(pool)
+            .await
+            .map_err(|e| ModelError::InsertError(e.to_string()))
+    }
+
+    /// This method finds a web page by its ID in the database.
+    async fn find_by_id(
+        id: U256Wrapper,
+        pool: &PgPool,
+        schema: &str,
+    ) -> Result<Option<Self>, ModelError> {
+        let query = format!(
+            r#"
+            SELECT id,
+                   url,
+                   canonical_url,
+                   title,
+                   description,
+                   image,
+                   site_name,
+                   favicon
+            FROM {}.web_page
+            WHERE id = $1
+            "#,
+            schema
+        );
+
+        sqlx::query_as::<_, WebPage>(&query)
+            .bind(id.to_big_decimal()?)
+            .fetch_optional(pool)
+            .await
+            .map_err(|e| ModelError::QueryError(e.to_string()))
+    }
+}
@@ -36,6 +36,10 @@ mod tests {
             label: "Updated Test Account".to_string(),
             image: None,
             account_type: AccountType::AtomWallet,
+            description: Some("Builder".to_string()),
+            url: Some("https://example.com".to_string()),
+            twitter: Some("example".to_string()),
+            github: None,
         }
         .upsert(&pool, TEST_SCHEMA)
         .await
@@ -59,6 +63,10 @@ mod tests {
         assert_eq!(found_updated.id, updated_account.id);
         assert_eq!(found_updated.label, updated_account.label);
         assert_eq!(found_updated.image, updated_account.image);
+        assert_eq!(found_updated.description, Some("Builder".to_string()));
+        assert_eq!(found_updated.url, Some("https://example.com".to_string()));
+        assert_eq!(found_updated.twitter, Some("example".to_string()));
+        assert_eq!(found_updated.github, None);
         assert!(matches!(
             found_updated.account_type,
             AccountType::AtomWallet
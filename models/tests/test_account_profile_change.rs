@@ -0,0 +1,62 @@
+use models::{
+    account::Account,
+    account_profile_change::AccountProfileChange,
+    error::ModelError,
+    test_helpers::{create_test_account_db, setup_test_db, TEST_SCHEMA},
+};
+
+#[tokio::test]
+async fn test_account_profile_change() -> Result<(), ModelError> {
+    let pool = setup_test_db().await;
+    let account = create_test_account_db(&pool).await;
+
+    let change = AccountProfileChange::builder()
+        .account_id(account.id.clone())
+        .field("label")
+        .previous_value(Some(account.label.clone()))
+        .value(Some("vitalik.eth".to_string()))
+        .build()
+        .insert(&pool, TEST_SCHEMA)
+        .await?;
+    assert_eq!(change.field, "label");
+    assert_eq!(change.value, Some("vitalik.eth".to_string()));
+
+    AccountProfileChange::builder()
+        .account_id(account.id.clone())
+        .field("image")
+        .previous_value(account.image.clone())
+        .value(None)
+        .build()
+        .insert(&pool, TEST_SCHEMA)
+        .await?;
+
+    // The most recent change comes first
+    let history = AccountProfileChange::find_by_account_id(&account.id, &pool, TEST_SCHEMA).await?;
+    assert_eq!(history.len(), 2);
+    assert_eq!(history[0].field, "image");
+    assert_eq!(history[0].value, None);
+    assert_eq!(history[1].id, change.id);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_claim_stale_profiles() -> Result<(), ModelError> {
+    let pool = setup_test_db().await;
+    let account = create_test_account_db(&pool).await;
+
+    // The profile of a new account was never resolved
+    let claimed = Account::claim_stale_profiles(3600, i64::MAX, &pool, TEST_SCHEMA).await?;
+    assert!(claimed.iter().any(|a| a.id == account.id));
+
+    // It's not claimed again while its refresh is enqueued
+    let claimed = Account::claim_stale_profiles(3600, i64::MAX, &pool, TEST_SCHEMA).await?;
+    assert!(!claimed.iter().any(|a| a.id == account.id));
+
+    // Nor once its profile is resolved
+    Account::mark_profile_resolved(&account.id, &pool, TEST_SCHEMA).await?;
+    let claimed = Account::claim_stale_profiles(3600, i64::MAX, &pool, TEST_SCHEMA).await?;
+    assert!(!claimed.iter().any(|a| a.id == account.id));
+
+    Ok(())
+}
@@ -0,0 +1,62 @@
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+    use models::{
+        api_key::{ApiKey, ApiKeyScope},
+        error::ModelError,
+        test_helpers::{create_random_string, setup_test_db, TEST_API_KEY_SCHEMA},
+        traits::SimpleCrud,
+    };
+
+    #[tokio::test]
+    async fn test_api_key_crud() -> Result<(), ModelError> {
+        let pool = setup_test_db().await;
+        let id = create_random_string();
+
+        let api_key = ApiKey::builder()
+            .id(id.clone())
+            .name("uploader")
+            .key_hash("digest")
+            .scopes(vec![ApiKeyScope::Upload])
+            .rate_limit_per_minute(60)
+            .created_at(Utc::now())
+            .build()
+            .upsert(&pool, TEST_API_KEY_SCHEMA)
+            .await?;
+        assert!(ApiKeyScope::Upload.is_granted_by(&api_key.scopes));
+        assert!(!ApiKeyScope::Moderate.is_granted_by(&api_key.scopes));
+        assert!(!api_key.is_revoked());
+
+        ApiKey::record_usage(&id, false, &pool, TEST_API_KEY_SCHEMA).await?;
+        ApiKey::record_usage(&id, true, &pool, TEST_API_KEY_SCHEMA).await?;
+
+        let found = ApiKey::find_by_id(id.clone(), &pool, TEST_API_KEY_SCHEMA)
+            .await?
+            .unwrap();
+        assert_eq!(found.scopes, vec![ApiKeyScope::Upload]);
+        assert_eq!(found.request_count, 2);
+        assert_eq!(found.rate_limited_count, 1);
+        assert!(found.last_used_at.is_some());
+
+        let all = ApiKey::find_all(&pool, TEST_API_KEY_SCHEMA).await?;
+        assert!(all.iter().any(|k| k.id == id));
+
+        let revoked = ApiKey::revoke(&id, &pool, TEST_API_KEY_SCHEMA)
+            .await?
+            .unwrap();
+        assert!(revoked.is_revoked());
+        assert!(ApiKey::revoke("missing", &pool, TEST_API_KEY_SCHEMA)
+            .await?
+            .is_none());
+
+        // The admin scope implies every other scope
+        let mut admin = revoked;
+        admin.scopes = vec![ApiKeyScope::Admin];
+        let admin = admin.upsert(&pool, TEST_API_KEY_SCHEMA).await?;
+        assert!(ApiKeyScope::Moderate.is_granted_by(&admin.scopes));
+        // Usage counters are not reset by an upsert
+        assert_eq!(admin.request_count, 2);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,102 @@
+use chrono::{Duration, Utc};
+use models::{
+    atom::{AtomResolvingStatus, AtomType},
+    atom_resolution_attempt::{
+        AtomResolutionAttempt, AtomResolutionFilter, ResolutionErrorCategory,
+    },
+    error::ModelError,
+    test_helpers::{create_test_atom_db, setup_test_db, TEST_SCHEMA},
+    traits::SimpleCrud,
+};
+
+#[tokio::test]
+async fn test_atom_resolution_attempt_crud() -> Result<(), ModelError> {
+    let pool = setup_test_db().await;
+    let atom = create_test_atom_db(&pool).await;
+
+    // Record a first failure
+    let attempt = AtomResolutionAttempt::builder()
+        .atom_id(atom.id.clone())
+        .attempts(1)
+        .last_error_category(ResolutionErrorCategory::GatewayTimeout)
+        .last_error("IPFS request timed out")
+        .last_attempt_at(Utc::now())
+        .next_retry_at(Some(Utc::now() - Duration::seconds(1)))
+        .build()
+        .upsert(&pool, TEST_SCHEMA)
+        .await?;
+    assert_eq!(attempt.attempts, 1);
+    assert_eq!(
+        attempt.last_error_category,
+        ResolutionErrorCategory::GatewayTimeout
+    );
+
+    // Only failed atoms are claimed
+    let lease = Duration::minutes(10);
+    let claimed = AtomResolutionAttempt::claim_due(1000, lease, &pool, TEST_SCHEMA).await?;
+    assert!(!claimed.iter().any(|claim| claim.atom_id == atom.id));
+
+    atom.mark_as_failed(&pool, TEST_SCHEMA).await?;
+    let claimed = AtomResolutionAttempt::claim_due(1000, lease, &pool, TEST_SCHEMA).await?;
+    let claim = claimed
+        .into_iter()
+        .find(|claim| claim.atom_id == atom.id)
+        .expect("Should claim the atom");
+    assert!(claim.next_retry_at > Some(Utc::now()));
+
+    // A claimed retry is not claimed again until its lease expires
+    let claimed = AtomResolutionAttempt::claim_due(1000, lease, &pool, TEST_SCHEMA).await?;
+    assert!(!claimed.iter().any(|claim| claim.atom_id == atom.id));
+    let found = AtomResolutionAttempt::find_by_id(atom.id.clone(), &pool, TEST_SCHEMA)
+        .await?
+        .expect("Should find the resolution attempts");
+    assert_eq!(found.next_retry_at, claim.next_retry_at);
+
+    // An expired lease is claimed again
+    let expired = AtomResolutionAttempt {
+        next_retry_at: Some(Utc::now() - Duration::seconds(1)),
+        ..found
+    }
+    .upsert(&pool, TEST_SCHEMA)
+    .await?;
+    let claimed =
+        AtomResolutionAttempt::claim_due(1000, Duration::zero(), &pool, TEST_SCHEMA).await?;
+    let claim = claimed
+        .into_iter()
+        .find(|claim| claim.atom_id == atom.id)
+        .expect("Should claim the atom again");
+    assert_eq!(claim.attempts, expired.attempts);
+
+    // The retry is cleared once the atom is enqueued
+    claim.mark_enqueued(&pool, TEST_SCHEMA).await?;
+    let found = AtomResolutionAttempt::find_by_id(atom.id.clone(), &pool, TEST_SCHEMA)
+        .await?
+        .expect("Should find the resolution attempts");
+    assert_eq!(found.next_retry_at, None);
+
+    // Atoms are found by status, type and error category
+    let filter = AtomResolutionFilter {
+        resolving_status: Some(AtomResolvingStatus::Failed),
+        atom_type: Some(AtomType::Thing),
+        error_category: Some(ResolutionErrorCategory::GatewayTimeout),
+        ..Default::default()
+    };
+    let atom_ids =
+        AtomResolutionAttempt::find_atom_ids(&filter, 100_000, &pool, TEST_SCHEMA).await?;
+    assert!(atom_ids.contains(&atom.id));
+
+    let filter = AtomResolutionFilter {
+        error_category: Some(ResolutionErrorCategory::ParseError),
+        ..filter
+    };
+    let atom_ids =
+        AtomResolutionAttempt::find_atom_ids(&filter, 100_000, &pool, TEST_SCHEMA).await?;
+    assert!(!atom_ids.contains(&atom.id));
+
+    // The attempts are reset once the atom is resolved
+    AtomResolutionAttempt::delete(&atom.id, &pool, TEST_SCHEMA).await?;
+    let found = AtomResolutionAttempt::find_by_id(atom.id.clone(), &pool, TEST_SCHEMA).await?;
+    assert!(found.is_none());
+
+    Ok(())
+}
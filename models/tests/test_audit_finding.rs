@@ -0,0 +1,36 @@
+use models::{
+    audit_finding::{AuditEntity, AuditFinding},
+    error::ModelError,
+    test_helpers::{create_random_number, setup_test_db, TEST_SCHEMA},
+    types::U256Wrapper,
+};
+use std::str::FromStr;
+
+#[tokio::test]
+async fn test_audit_finding_insert_and_find() -> Result<(), ModelError> {
+    let pool = setup_test_db().await;
+    let block_number = create_random_number() as i64;
+
+    let finding = AuditFinding::builder()
+        .block_number(block_number)
+        .entity_type(AuditEntity::Vault)
+        .entity_id("1".to_string())
+        .field("total_shares".to_string())
+        .indexed_value(U256Wrapper::from_str("100")?)
+        .onchain_value(U256Wrapper::from_str("120")?)
+        .build();
+    let inserted = finding
+        .insert(&pool, TEST_SCHEMA)
+        .await?
+        .expect("The finding is new");
+    assert_eq!(inserted.entity_type, AuditEntity::Vault);
+    assert_eq!(inserted.onchain_value, U256Wrapper::from_str("120")?);
+
+    // The same field of the same entity is only recorded once per block
+    assert!(finding.insert(&pool, TEST_SCHEMA).await?.is_none());
+
+    let findings = AuditFinding::find_by_block_number(block_number, &pool, TEST_SCHEMA).await?;
+    assert_eq!(findings, vec![inserted]);
+
+    Ok(())
+}
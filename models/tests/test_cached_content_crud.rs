@@ -0,0 +1,75 @@
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, Utc};
+    use models::{
+        cached_content::CachedContent,
+        error::ModelError,
+        test_helpers::{create_random_string, setup_test_db, TEST_IPFS_CACHE_SCHEMA},
+        traits::SimpleCrud,
+    };
+
+    #[tokio::test]
+    async fn test_cached_content_crud() -> Result<(), ModelError> {
+        let pool = setup_test_db().await;
+        let cid = create_random_string();
+
+        let content = CachedContent::builder()
+            .cid(cid.clone())
+            .content_type(Some("application/json".to_string()))
+            .size(2)
+            .data(Some(b"{}".to_vec()))
+            .fetched_at(Utc::now())
+            .last_accessed_at(Utc::now() - Duration::hours(1))
+            .build()
+            .upsert(&pool, TEST_IPFS_CACHE_SCHEMA)
+            .await?;
+
+        let found = CachedContent::find_by_id(cid.clone(), &pool, TEST_IPFS_CACHE_SCHEMA)
+            .await?
+            .unwrap();
+        assert_eq!(found, content);
+
+        let touched = CachedContent::find_and_touch(&cid, &pool, TEST_IPFS_CACHE_SCHEMA)
+            .await?
+            .unwrap();
+        assert!(touched.last_accessed_at > content.last_accessed_at);
+        assert_eq!(touched.data, Some(b"{}".to_vec()));
+
+        CachedContent::delete(&cid, &pool, TEST_IPFS_CACHE_SCHEMA).await?;
+        assert!(
+            CachedContent::find_by_id(cid, &pool, TEST_IPFS_CACHE_SCHEMA)
+                .await?
+                .is_none()
+        );
+
+        // Eviction is checked in the same test, as it can evict the entries
+        // of any other test using the schema
+        let (stale, fresh) = (create_random_string(), create_random_string());
+
+        for (cid, accessed_hours_ago, data) in [(&stale, 48, None), (&fresh, 0, Some(vec![0; 10]))]
+        {
+            CachedContent::builder()
+                .cid(cid.clone())
+                .size(10)
+                .data(data)
+                .fetched_at(Utc::now())
+                .last_accessed_at(Utc::now() - Duration::hours(accessed_hours_ago))
+                .build()
+                .upsert(&pool, TEST_IPFS_CACHE_SCHEMA)
+                .await?;
+        }
+
+        let evicted =
+            CachedContent::evict_least_recently_used(0, &pool, TEST_IPFS_CACHE_SCHEMA).await?;
+        let stale_entry = evicted.iter().find(|e| e.cid == stale).unwrap();
+        assert!(stale_entry.stored_on_disk);
+        let fresh_entry = evicted.iter().find(|e| e.cid == fresh).unwrap();
+        assert!(!fresh_entry.stored_on_disk);
+        assert_eq!(
+            CachedContent::total_size(&pool, TEST_IPFS_CACHE_SCHEMA).await?,
+            0
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,48 @@
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+    use models::{
+        cached_image::CachedImage,
+        error::ModelError,
+        test_helpers::{create_random_string, setup_test_db, TEST_IMAGE_SCHEMA},
+        traits::SimpleCrud,
+    };
+
+    #[tokio::test]
+    async fn test_find_by_original_url_digest() -> Result<(), ModelError> {
+        let pool = setup_test_db().await;
+        let original_url = format!("https://example.com/{}.png", create_random_string());
+
+        let image = CachedImage::builder()
+            .url(format!("ipfs://{}", create_random_string()))
+            .original_url(original_url.clone())
+            .safe(true)
+            .created_at(Utc::now())
+            .build()
+            .upsert(&pool, TEST_IMAGE_SCHEMA)
+            .await?;
+
+        let digest: String =
+            sqlx::query_scalar("SELECT encode(sha256(convert_to($1, 'UTF8')), 'hex')")
+                .bind(&original_url)
+                .fetch_one(&pool)
+                .await
+                .map_err(|e| ModelError::QueryError(e.to_string()))?;
+
+        let found = CachedImage::find_by_original_url_digest(
+            &pool,
+            &digest.to_uppercase(),
+            TEST_IMAGE_SCHEMA,
+        )
+        .await?
+        .unwrap();
+        assert_eq!(found.url, image.url);
+
+        let missing =
+            CachedImage::find_by_original_url_digest(&pool, &"0".repeat(64), TEST_IMAGE_SCHEMA)
+                .await?;
+        assert!(missing.is_none());
+
+        Ok(())
+    }
+}
@@ -1,7 +1,7 @@
 use models::{
     caip10::Caip10,
     error::ModelError,
-    test_helpers::{setup_test_db, TEST_SCHEMA},
+    test_helpers::{create_random_u256wrapper, setup_test_db, TEST_SCHEMA},
     traits::SimpleCrud,
     types::U256Wrapper,
 };
@@ -15,7 +15,8 @@ async fn test_caip10_crud() -> Result<(), ModelError> {
     let caip10 = Caip10::builder()
         .id(id.clone())
         .namespace("eip155")
-        .chain_id(8453)
+        .chain_id(Some(8453))
+        .chain_reference("8453")
         .account_address("0x71041dddad3595F9CEd3DcCFBe3D1F4b0a16Bb70")
         .build()
         .upsert(&pool, TEST_SCHEMA)
@@ -24,7 +25,8 @@ async fn test_caip10_crud() -> Result<(), ModelError> {
     // Insert and verify
     let inserted = caip10.upsert(&pool, TEST_SCHEMA).await?;
     assert_eq!(inserted.namespace, "eip155");
-    assert_eq!(inserted.chain_id, 8453);
+    assert_eq!(inserted.chain_id, Some(8453));
+    assert_eq!(inserted.chain_reference, "8453");
     assert_eq!(
         inserted.account_address,
         "0x71041dddad3595F9CEd3DcCFBe3D1F4b0a16Bb70"
@@ -34,7 +36,8 @@ async fn test_caip10_crud() -> Result<(), ModelError> {
     let updated_caip10 = Caip10::builder()
         .id(id.clone())
         .namespace("eip155")
-        .chain_id(1)
+        .chain_id(Some(1))
+        .chain_reference("1")
         .account_address("0x71041dddad3595F9CEd3DcCFBe3D1F4b0a16Bb71")
         .build()
         .upsert(&pool, TEST_SCHEMA)
@@ -42,7 +45,7 @@ async fn test_caip10_crud() -> Result<(), ModelError> {
 
     // Upsert and verify updates
     let updated = updated_caip10.upsert(&pool, TEST_SCHEMA).await?;
-    assert_eq!(updated.chain_id, 1);
+    assert_eq!(updated.chain_id, Some(1));
     assert_eq!(
         updated.account_address,
         "0x71041dddad3595F9CEd3DcCFBe3D1F4b0a16Bb71"
@@ -52,11 +55,25 @@ async fn test_caip10_crud() -> Result<(), ModelError> {
     let found = Caip10::find_by_id(id, &pool, TEST_SCHEMA)
         .await?
         .expect("Should find CAIP10");
-    assert_eq!(found.chain_id, 1);
+    assert_eq!(found.chain_id, Some(1));
+    assert_eq!(found.chain_reference, "1");
     assert_eq!(
         found.account_address,
         "0x71041dddad3595F9CEd3DcCFBe3D1F4b0a16Bb71"
     );
 
+    // Accounts of other namespaces have no numeric chain id
+    let cosmos = Caip10::builder()
+        .id(create_random_u256wrapper())
+        .namespace("cosmos")
+        .chain_id(None)
+        .chain_reference("cosmoshub-4")
+        .account_address("cosmos1t2uflqwqe0fsj0shcfkrvpukewcw40yjj6hdc0")
+        .build()
+        .upsert(&pool, TEST_SCHEMA)
+        .await?;
+    assert_eq!(cosmos.chain_id, None);
+    assert_eq!(cosmos.chain_reference, "cosmoshub-4");
+
     Ok(())
 }
@@ -0,0 +1,52 @@
+use models::{
+    caip19::Caip19,
+    error::ModelError,
+    test_helpers::{create_random_u256wrapper, setup_test_db, TEST_SCHEMA},
+    traits::SimpleCrud,
+};
+
+#[tokio::test]
+async fn test_caip19_crud() -> Result<(), ModelError> {
+    let pool = setup_test_db().await;
+    let id = create_random_u256wrapper();
+
+    // Insert and verify
+    let inserted = Caip19::builder()
+        .id(id.clone())
+        .namespace("eip155")
+        .chain_reference("1")
+        .asset_namespace("erc20")
+        .asset_reference("0x6B175474E89094C44Da98b954EedeAC495271d0F")
+        .token_id(None)
+        .build()
+        .upsert(&pool, TEST_SCHEMA)
+        .await?;
+    assert_eq!(inserted.asset_namespace, "erc20");
+    assert_eq!(inserted.token_id, None);
+
+    // Upsert and verify updates
+    let updated = Caip19::builder()
+        .id(id.clone())
+        .namespace("eip155")
+        .chain_reference("1")
+        .asset_namespace("erc721")
+        .asset_reference("0x06012c8cf97BEaD5deAe237070F9587f8E7A266d")
+        .token_id(Some("771769".to_string()))
+        .build()
+        .upsert(&pool, TEST_SCHEMA)
+        .await?;
+    assert_eq!(updated.token_id.as_deref(), Some("771769"));
+
+    // Find by id and verify
+    let found = Caip19::find_by_id(id, &pool, TEST_SCHEMA)
+        .await?
+        .expect("Should find CAIP19");
+    assert_eq!(found.asset_namespace, "erc721");
+    assert_eq!(
+        found.asset_reference,
+        "0x06012c8cf97BEaD5deAe237070F9587f8E7A266d"
+    );
+    assert_eq!(found.token_id.as_deref(), Some("771769"));
+
+    Ok(())
+}
@@ -0,0 +1,42 @@
+use models::{
+    caip2::Caip2,
+    error::ModelError,
+    test_helpers::{create_random_u256wrapper, setup_test_db, TEST_SCHEMA},
+    traits::SimpleCrud,
+};
+
+#[tokio::test]
+async fn test_caip2_crud() -> Result<(), ModelError> {
+    let pool = setup_test_db().await;
+    let id = create_random_u256wrapper();
+
+    // Insert and verify
+    let inserted = Caip2::builder()
+        .id(id.clone())
+        .namespace("eip155")
+        .reference("8453")
+        .build()
+        .upsert(&pool, TEST_SCHEMA)
+        .await?;
+    assert_eq!(inserted.namespace, "eip155");
+    assert_eq!(inserted.reference, "8453");
+
+    // Upsert and verify updates
+    let updated = Caip2::builder()
+        .id(id.clone())
+        .namespace("solana")
+        .reference("5eykt4UsFv8P8NJdTREpY1vzqKqZKvdp")
+        .build()
+        .upsert(&pool, TEST_SCHEMA)
+        .await?;
+    assert_eq!(updated.namespace, "solana");
+
+    // Find by id and verify
+    let found = Caip2::find_by_id(id, &pool, TEST_SCHEMA)
+        .await?
+        .expect("Should find CAIP2");
+    assert_eq!(found.namespace, "solana");
+    assert_eq!(found.reference, "5eykt4UsFv8P8NJdTREpY1vzqKqZKvdp");
+
+    Ok(())
+}
@@ -0,0 +1,53 @@
+use models::{
+    chainlink_price::ChainlinkPrice,
+    error::ModelError,
+    test_helpers::{create_random_u256wrapper, setup_test_db, TEST_SCHEMA},
+    traits::SimpleCrud,
+    types::U256Wrapper,
+};
+use std::str::FromStr;
+
+#[tokio::test]
+async fn test_chainlink_price_upsert_and_find() -> Result<(), ModelError> {
+    let pool = setup_test_db().await;
+    let price = ChainlinkPrice::builder()
+        .id(create_random_u256wrapper())
+        .usd(2500.0)
+        .block_number(20_000_000)
+        .updated_at(1_700_000_000)
+        .build()
+        .upsert(&pool, TEST_SCHEMA)
+        .await?;
+
+    let found = ChainlinkPrice::find_by_id(price.id.clone(), &pool, TEST_SCHEMA).await?;
+    assert_eq!(found, Some(price.clone()));
+
+    // The price at a timestamp is the last answer given at or before it
+    let at = ChainlinkPrice::find_at_timestamp(1_700_000_000, &pool, TEST_SCHEMA).await?;
+    assert!(at.is_some_and(|at| at.updated_at <= Some(1_700_000_000)));
+    assert!(ChainlinkPrice::find_at_timestamp(0, &pool, TEST_SCHEMA)
+        .await?
+        .is_none());
+
+    Ok(())
+}
+
+#[test]
+fn test_usd_conversions() -> Result<(), ModelError> {
+    // ETH/USD answers have 8 decimals
+    assert_eq!(
+        ChainlinkPrice::usd_from_answer("250012345678", 8)?,
+        2500.12345678
+    );
+    assert!(ChainlinkPrice::usd_from_answer("not a number", 8).is_err());
+
+    let price = ChainlinkPrice::builder()
+        .id(U256Wrapper::from_str("1")?)
+        .usd(2000.0)
+        .build();
+    // 1.5 ether
+    let wei = U256Wrapper::from_str("1500000000000000000")?;
+    assert_eq!(price.wei_to_usd(&wei), Some(3000.0));
+
+    Ok(())
+}
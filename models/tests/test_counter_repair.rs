@@ -0,0 +1,51 @@
+use models::{
+    counter_repair::repair_counters,
+    error::ModelError,
+    predicate_object::PredicateObject,
+    test_helpers::{
+        create_test_atom_db, create_test_predicate_object, create_test_predicate_object_db,
+        setup_test_db, TEST_SCHEMA,
+    },
+    traits::SimpleCrud,
+};
+
+#[tokio::test]
+async fn test_repair_counters() -> Result<(), ModelError> {
+    let pool = setup_test_db().await;
+    // Start from repaired counters, so only the drift below is reported
+    repair_counters(false, &pool, TEST_SCHEMA).await?;
+    let predicate = create_test_atom_db(&pool).await;
+    let object = create_test_atom_db(&pool).await;
+
+    // A predicate object counting triples and claims that don't exist
+    let mut predicate_object = create_test_predicate_object(predicate.id, object.id);
+    predicate_object.triple_count = 5;
+    predicate_object.claim_count = 3;
+    let predicate_object = create_test_predicate_object_db(&pool, predicate_object).await;
+
+    // A dry run reports the drift and leaves the counters as they are
+    let drifts = repair_counters(true, &pool, TEST_SCHEMA).await?;
+    let triple_count = drifts
+        .iter()
+        .find(|drift| drift.table == "predicate_object" && drift.column == "triple_count")
+        .expect("The triple count is repaired");
+    assert_eq!(triple_count.drifted_rows, 1);
+    let found = PredicateObject::find_by_id(predicate_object.id.clone(), &pool, TEST_SCHEMA)
+        .await?
+        .expect("The predicate object exists");
+    assert_eq!(found.triple_count, 5);
+
+    let drifts = repair_counters(false, &pool, TEST_SCHEMA).await?;
+    let claim_count = drifts
+        .iter()
+        .find(|drift| drift.table == "predicate_object" && drift.column == "claim_count")
+        .expect("The claim count is repaired");
+    assert_eq!(claim_count.drifted_rows, 1);
+    let found = PredicateObject::find_by_id(predicate_object.id.clone(), &pool, TEST_SCHEMA)
+        .await?
+        .expect("The predicate object exists");
+    assert_eq!(found.triple_count, 0);
+    assert_eq!(found.claim_count, 0);
+
+    Ok(())
+}
@@ -0,0 +1,64 @@
+#[cfg(test)]
+mod tests {
+    use models::{
+        creative_work::CreativeWork,
+        error::ModelError,
+        test_helpers::{create_random_u256wrapper, setup_test_db, TEST_SCHEMA},
+        traits::SimpleCrud,
+    };
+
+    #[tokio::test]
+    async fn test_creative_work_crud() -> Result<(), ModelError> {
+        // Set up test database
+        let pool = setup_test_db().await;
+
+        let test_creative_work = CreativeWork::builder()
+            .id(create_random_u256wrapper())
+            .name("The Test Article".to_string())
+            .description("An article for testing".to_string())
+            .author("Test Author".to_string())
+            .date_published("2024-01-01".to_string())
+            .url("https://test.article".to_string())
+            .build();
+
+        // Test inserting
+        let inserted_creative_work = test_creative_work.upsert(&pool, TEST_SCHEMA).await?;
+        assert_eq!(inserted_creative_work.name, test_creative_work.name);
+        assert_eq!(
+            inserted_creative_work.description,
+            test_creative_work.description
+        );
+
+        // Test updating
+        let mut updated_creative_work = inserted_creative_work;
+        updated_creative_work.name = Some("Updated Test Article".to_string());
+        updated_creative_work.description = Some("An updated article for testing".to_string());
+
+        let updated_result = updated_creative_work.upsert(&pool, TEST_SCHEMA).await?;
+        assert_eq!(
+            updated_result.name,
+            Some("Updated Test Article".to_string())
+        );
+        assert_eq!(
+            updated_result.description,
+            Some("An updated article for testing".to_string())
+        );
+
+        // Test finding by id
+        let found_creative_work =
+            CreativeWork::find_by_id(test_creative_work.id.clone(), &pool, TEST_SCHEMA)
+                .await?
+                .unwrap();
+        assert_eq!(found_creative_work.id, test_creative_work.id);
+        assert_eq!(
+            found_creative_work.name,
+            Some("Updated Test Article".to_string())
+        );
+        assert_eq!(
+            found_creative_work.description,
+            Some("An updated article for testing".to_string())
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,44 @@
+use models::{
+    did::Did,
+    error::ModelError,
+    test_helpers::{create_random_u256wrapper, setup_test_db, TEST_SCHEMA},
+    traits::SimpleCrud,
+};
+
+#[tokio::test]
+async fn test_did_crud() -> Result<(), ModelError> {
+    let pool = setup_test_db().await;
+    let id = create_random_u256wrapper();
+
+    // Insert and verify
+    let inserted = Did::builder()
+        .id(id.clone())
+        .did("did:web:example.com")
+        .method("web")
+        .identifier("example.com")
+        .build()
+        .upsert(&pool, TEST_SCHEMA)
+        .await?;
+    assert_eq!(inserted.method, "web");
+    assert_eq!(inserted.identifier, "example.com");
+
+    // Upsert and verify updates
+    let updated = Did::builder()
+        .id(id.clone())
+        .did("did:web:example.com:users:alice")
+        .method("web")
+        .identifier("example.com:users:alice")
+        .build()
+        .upsert(&pool, TEST_SCHEMA)
+        .await?;
+    assert_eq!(updated.did, "did:web:example.com:users:alice");
+
+    // Find by id and verify
+    let found = Did::find_by_id(id, &pool, TEST_SCHEMA)
+        .await?
+        .expect("Should find DID");
+    assert_eq!(found.did, "did:web:example.com:users:alice");
+    assert_eq!(found.identifier, "example.com:users:alice");
+
+    Ok(())
+}
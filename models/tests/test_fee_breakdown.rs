@@ -0,0 +1,135 @@
+use models::{
+    error::ModelError,
+    fee_breakdown::{FeeBreakdown, FeeType},
+    test_helpers::{
+        create_test_account_db, create_test_atom_db, create_test_vault_db,
+        create_test_vault_with_atom, setup_test_db, TEST_SCHEMA,
+    },
+    traits::SimpleCrud,
+    types::U256Wrapper,
+};
+use std::str::FromStr;
+
+/// Builds a fee paid in a test event
+fn create_test_fee(
+    event_id: &str,
+    fee_type: FeeType,
+    vault_id: Option<U256Wrapper>,
+    payer_id: &str,
+    amount: &str,
+) -> FeeBreakdown {
+    let fee = FeeBreakdown::builder()
+        .id(FeeBreakdown::format_id(
+            event_id,
+            fee_type,
+            vault_id.as_ref(),
+        ))
+        .fee_type(fee_type)
+        .payer_id(payer_id.to_string())
+        .amount(U256Wrapper::from_str(amount).unwrap())
+        .block_number(U256Wrapper::from_str("1").unwrap())
+        .block_timestamp(1_700_000_000)
+        .transaction_hash(event_id.to_string());
+    match vault_id {
+        Some(vault_id) => fee.vault_id(vault_id).build(),
+        None => fee.build(),
+    }
+}
+
+#[test]
+fn test_format_id() {
+    let vault_id = U256Wrapper::from_str("7").unwrap();
+    assert_eq!(
+        FeeBreakdown::format_id("0xabc-1", FeeType::AtomDepositFraction, Some(&vault_id)),
+        "0xabc-1-AtomDepositFraction-7"
+    );
+    assert_eq!(
+        FeeBreakdown::format_id("0xabc-1", FeeType::Protocol, None),
+        "0xabc-1-Protocol"
+    );
+}
+
+#[tokio::test]
+async fn test_fee_breakdown_aggregates() -> Result<(), ModelError> {
+    let pool = setup_test_db().await;
+    let payer = create_test_account_db(&pool).await;
+    let atom = create_test_atom_db(&pool).await;
+    let atom_vault =
+        create_test_vault_db(&pool, create_test_vault_with_atom(atom.id.clone())).await;
+    let event_id = format!("{}-0", payer.id);
+
+    let fees = [
+        create_test_fee(&event_id, FeeType::Protocol, None, &payer.id, "10"),
+        create_test_fee(
+            &event_id,
+            FeeType::Entry,
+            Some(atom_vault.id.clone()),
+            &payer.id,
+            "20",
+        ),
+        create_test_fee(
+            &event_id,
+            FeeType::AtomDepositFraction,
+            Some(atom_vault.id.clone()),
+            &payer.id,
+            "30",
+        ),
+    ];
+    for fee in &fees {
+        let stored = fee.upsert(&pool, TEST_SCHEMA).await?;
+        assert_eq!(&stored, fee);
+    }
+    // Replaying the events doesn't count the fees twice
+    fees[2].upsert(&pool, TEST_SCHEMA).await?;
+
+    let found = FeeBreakdown::find_by_id(fees[0].id.clone(), &pool, TEST_SCHEMA)
+        .await?
+        .expect("the protocol fee should exist");
+    assert_eq!(found.vault_id, None);
+
+    let earnings = FeeBreakdown::find_atom_fee_earnings(&atom.id, &pool, TEST_SCHEMA)
+        .await?
+        .expect("the atom should have earned fees");
+    assert_eq!(earnings.triple_deposit_fees, U256Wrapper::from_str("30")?);
+    assert_eq!(earnings.fee_count, 1);
+
+    let paid = FeeBreakdown::find_account_fees_paid(&payer.id, &pool, TEST_SCHEMA)
+        .await?
+        .expect("the account should have paid fees");
+    assert_eq!(paid.protocol_fees, U256Wrapper::from_str("10")?);
+    assert_eq!(paid.entry_fees, U256Wrapper::from_str("20")?);
+    assert_eq!(paid.exit_fees, U256Wrapper::from_str("0")?);
+    assert_eq!(paid.atom_deposit_fractions, U256Wrapper::from_str("30")?);
+    assert_eq!(paid.total_fees, U256Wrapper::from_str("60")?);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_fee_breakdown_checksummed_payer() -> Result<(), ModelError> {
+    let pool = setup_test_db().await;
+    let payer = create_test_account_db(&pool).await;
+    let atom = create_test_atom_db(&pool).await;
+    let atom_vault =
+        create_test_vault_db(&pool, create_test_vault_with_atom(atom.id.clone())).await;
+    // Addresses are formatted in mixed case by their EIP-55 checksum, while
+    // account ids are lowercase
+    let checksummed_id = payer.id.to_uppercase();
+
+    let fee = create_test_fee(
+        &format!("{}-0", payer.id),
+        FeeType::Exit,
+        Some(atom_vault.id.clone()),
+        &checksummed_id,
+        "5",
+    );
+    let stored = fee.upsert(&pool, TEST_SCHEMA).await?;
+    assert_eq!(stored.payer_id, payer.id);
+
+    let paid = FeeBreakdown::find_account_fees_paid(&checksummed_id, &pool, TEST_SCHEMA)
+        .await?
+        .expect("the account should have paid fees");
+    assert_eq!(paid.exit_fees, U256Wrapper::from_str("5")?);
+
+    Ok(())
+}
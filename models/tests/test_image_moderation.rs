@@ -0,0 +1,168 @@
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+    use models::{
+        cached_image::CachedImage,
+        classification_threshold::ClassificationThreshold,
+        error::ModelError,
+        image_moderation_audit::{ImageModerationAudit, ModerationAction},
+        test_helpers::{create_random_string, setup_test_db, TEST_IMAGE_SCHEMA},
+        traits::SimpleCrud,
+    };
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn test_classification_threshold_crud() -> Result<(), ModelError> {
+        let pool = setup_test_db().await;
+        let model = create_random_string();
+
+        let threshold = ClassificationThreshold::builder()
+            .model(model.clone())
+            .nsfw_threshold(0.6)
+            .updated_at(Utc::now())
+            .build()
+            .upsert(&pool, TEST_IMAGE_SCHEMA)
+            .await?;
+        assert_eq!(threshold.nsfw_threshold, 0.6);
+
+        let mut updated = threshold;
+        updated.nsfw_threshold = 0.8;
+        updated.upsert(&pool, TEST_IMAGE_SCHEMA).await?;
+
+        let found = ClassificationThreshold::find_by_id(model.clone(), &pool, TEST_IMAGE_SCHEMA)
+            .await?
+            .unwrap();
+        assert_eq!(found.nsfw_threshold, 0.8);
+
+        let all = ClassificationThreshold::find_all(&pool, TEST_IMAGE_SCHEMA).await?;
+        assert!(all.iter().any(|t| t.model == model));
+
+        // Thresholds outside of [0, 1] are rejected
+        updated.nsfw_threshold = 1.5;
+        assert!(updated.upsert(&pool, TEST_IMAGE_SCHEMA).await.is_err());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_image_moderation_audit() -> Result<(), ModelError> {
+        let pool = setup_test_db().await;
+        let url = format!("ipfs://{}", create_random_string());
+
+        let image = CachedImage::builder()
+            .url(url.clone())
+            .original_url("test.png")
+            .score(json!({"normal": 0.3, "nsfw": 0.7}))
+            .model("Falconsai/nsfw_image_detection")
+            .safe(false)
+            .created_at(Utc::now())
+            .build()
+            .upsert(&pool, TEST_IMAGE_SCHEMA)
+            .await?;
+
+        // The image shows up in its score band
+        let band = CachedImage::find_by_nsfw_score_band(
+            &pool,
+            0.69,
+            0.71,
+            Some(false),
+            1000,
+            0,
+            TEST_IMAGE_SCHEMA,
+        )
+        .await?;
+        assert!(band.iter().any(|i| i.url == url));
+        let band = CachedImage::find_by_nsfw_score_band(
+            &pool,
+            0.69,
+            0.71,
+            Some(true),
+            1000,
+            0,
+            TEST_IMAGE_SCHEMA,
+        )
+        .await?;
+        assert!(!band.iter().any(|i| i.url == url));
+
+        let entry = ImageModerationAudit::builder()
+            .url(url.clone())
+            .action(ModerationAction::MarkedSafe)
+            .reviewer("reviewer")
+            .reason("false positive".to_string())
+            .previous_safe(image.safe)
+            .new_safe(true)
+            .previous_score(image.score.clone())
+            .new_score(image.score.clone())
+            .build()
+            .insert(&pool, TEST_IMAGE_SCHEMA)
+            .await?;
+        assert!(entry.id > 0);
+        assert!(entry.is_manual_verdict());
+
+        ImageModerationAudit::builder()
+            .url(url.clone())
+            .action(ModerationAction::Reclassified)
+            .reviewer("reviewer")
+            .previous_safe(true)
+            .new_safe(false)
+            .build()
+            .insert(&pool, TEST_IMAGE_SCHEMA)
+            .await?;
+
+        let log = ImageModerationAudit::find_by_url(&url, &pool, TEST_IMAGE_SCHEMA).await?;
+        assert_eq!(log.len(), 2);
+        assert_eq!(log[0].action, ModerationAction::Reclassified);
+
+        let latest = ImageModerationAudit::find_latest_by_url(&url, &pool, TEST_IMAGE_SCHEMA)
+            .await?
+            .unwrap();
+        assert!(!latest.is_manual_verdict());
+
+        // The verdict and its entry are stored together
+        let mut moderated = image.clone();
+        moderated.safe = true;
+        ImageModerationAudit::builder()
+            .url(url.clone())
+            .action(ModerationAction::MarkedSafe)
+            .reviewer("reviewer")
+            .previous_safe(image.safe)
+            .new_safe(moderated.safe)
+            .build()
+            .insert_with_image(&moderated, &pool, TEST_IMAGE_SCHEMA)
+            .await?;
+        let stored = CachedImage::find_by_id(url.clone(), &pool, TEST_IMAGE_SCHEMA)
+            .await?
+            .unwrap();
+        assert!(stored.safe);
+        let log = ImageModerationAudit::find_by_url(&url, &pool, TEST_IMAGE_SCHEMA).await?;
+        assert_eq!(log.len(), 3);
+
+        // Without the image nothing is recorded
+        let mut missing = moderated.clone();
+        missing.url = format!("ipfs://{}", create_random_string());
+        assert!(ImageModerationAudit::builder()
+            .url(missing.url.clone())
+            .action(ModerationAction::MarkedSafe)
+            .reviewer("reviewer")
+            .previous_safe(false)
+            .new_safe(true)
+            .build()
+            .insert_with_image(&missing, &pool, TEST_IMAGE_SCHEMA)
+            .await
+            .is_err());
+        assert!(
+            ImageModerationAudit::find_by_url(&missing.url, &pool, TEST_IMAGE_SCHEMA)
+                .await?
+                .is_empty()
+        );
+
+        // The audit log is append-only
+        let query = format!(
+            "DELETE FROM {}.image_moderation_audit WHERE url = $1",
+            TEST_IMAGE_SCHEMA
+        );
+        assert!(sqlx::query(&query).bind(&url).execute(&pool).await.is_err());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,38 @@
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+    use models::{
+        error::ModelError,
+        pinned_json::PinnedJson,
+        test_helpers::{create_random_string, setup_test_db, TEST_IMAGE_SCHEMA},
+        traits::SimpleCrud,
+    };
+
+    #[tokio::test]
+    async fn test_pinned_json_crud() -> Result<(), ModelError> {
+        let pool = setup_test_db().await;
+        let cid = format!("Qm{}", create_random_string());
+
+        let pinned = PinnedJson::builder()
+            .cid(cid.clone())
+            .size(42)
+            .created_at(Utc::now())
+            .build()
+            .upsert(&pool, TEST_IMAGE_SCHEMA)
+            .await?;
+
+        let found = PinnedJson::find_by_id(cid.clone(), &pool, TEST_IMAGE_SCHEMA)
+            .await?
+            .unwrap();
+        assert_eq!(found, pinned);
+
+        // Pages start after the given CID
+        let page = PinnedJson::find_page(&pool, "", 1000, TEST_IMAGE_SCHEMA).await?;
+        assert!(page.iter().any(|json| json.cid == cid));
+        assert!(page.windows(2).all(|pair| pair[0].cid < pair[1].cid));
+        let page = PinnedJson::find_page(&pool, &cid, 1000, TEST_IMAGE_SCHEMA).await?;
+        assert!(page.iter().all(|json| json.cid > cid));
+
+        Ok(())
+    }
+}
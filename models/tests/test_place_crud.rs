@@ -0,0 +1,56 @@
+#[cfg(test)]
+mod tests {
+    use models::{
+        error::ModelError,
+        place::Place,
+        test_helpers::{create_random_u256wrapper, setup_test_db, TEST_SCHEMA},
+        traits::SimpleCrud,
+    };
+
+    #[tokio::test]
+    async fn test_place_crud() -> Result<(), ModelError> {
+        // Set up test database
+        let pool = setup_test_db().await;
+
+        let test_place = Place::builder()
+            .id(create_random_u256wrapper())
+            .name("The Test Place".to_string())
+            .description("A place for testing".to_string())
+            .address("1 Test Street".to_string())
+            .latitude(Some(48.8584))
+            .longitude(Some(2.2945))
+            .build();
+
+        // Test inserting
+        let inserted_place = test_place.upsert(&pool, TEST_SCHEMA).await?;
+        assert_eq!(inserted_place.name, test_place.name);
+        assert_eq!(inserted_place.description, test_place.description);
+
+        // Test updating
+        let mut updated_place = inserted_place;
+        updated_place.name = Some("Updated Test Place".to_string());
+        updated_place.description = Some("An updated place for testing".to_string());
+
+        let updated_result = updated_place.upsert(&pool, TEST_SCHEMA).await?;
+        assert_eq!(updated_result.name, Some("Updated Test Place".to_string()));
+        assert_eq!(
+            updated_result.description,
+            Some("An updated place for testing".to_string())
+        );
+
+        // Test finding by id
+        let found_place = Place::find_by_id(test_place.id.clone(), &pool, TEST_SCHEMA)
+            .await?
+            .unwrap();
+        assert_eq!(found_place.id, test_place.id);
+        assert_eq!(found_place.name, Some("Updated Test Place".to_string()));
+        assert_eq!(
+            found_place.description,
+            Some("An updated place for testing".to_string())
+        );
+        assert_eq!(found_place.latitude, test_place.latitude);
+        assert_eq!(found_place.longitude, test_place.longitude);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,93 @@
+use alloy::primitives::U256;
+use models::{
+    error::ModelError,
+    position_cost_basis::PositionCostBasis,
+    test_helpers::{
+        create_test_account_db, create_test_atom_db, create_test_vault_with_atom, setup_test_db,
+        TEST_SCHEMA,
+    },
+    traits::SimpleCrud,
+    types::U256Wrapper,
+};
+use sqlx::types::BigDecimal;
+use std::str::FromStr;
+
+/// 1 ether, the scale of the share price
+const ETHER: u64 = 1_000_000_000_000_000_000;
+
+fn ether(amount: u64) -> U256 {
+    U256::from(amount) * U256::from(ETHER)
+}
+
+#[test]
+fn test_cost_basis_ledger() -> Result<(), ModelError> {
+    let mut ledger = PositionCostBasis::builder()
+        .id("1-0xabc")
+        .account_id("0xabc")
+        .vault_id(U256::from(1))
+        .build();
+
+    // 10 shares for 10 ether, then 10 shares for 30 ether
+    assert!(ledger.apply_deposit(ether(10), ether(10), 100, 0));
+    assert!(ledger.apply_deposit(ether(30), ether(20), 101, 3));
+    // A replayed event is ignored
+    assert!(!ledger.apply_deposit(ether(30), ether(20), 101, 3));
+    assert_eq!(ledger.total_assets_in, U256Wrapper(ether(40)));
+    assert_eq!(ledger.average_entry_price, U256Wrapper(ether(2)));
+
+    // 5 shares cost 10 ether and are redeemed for 15
+    assert!(ledger.apply_redemption(ether(15), ether(5), ether(15), 102, 0)?);
+    assert_eq!(ledger.cost_basis, U256Wrapper(ether(30)));
+    assert_eq!(ledger.average_entry_price, U256Wrapper(ether(2)));
+    assert_eq!(
+        ledger.realized_pnl,
+        BigDecimal::from_str("5000000000000000000").unwrap()
+    );
+
+    // The remaining 15 shares cost 30 ether and are redeemed for 20
+    assert!(ledger.apply_redemption(ether(20), ether(15), U256::ZERO, 103, 0)?);
+    assert_eq!(ledger.cost_basis, U256Wrapper::default());
+    assert_eq!(ledger.average_entry_price, U256Wrapper::default());
+    assert_eq!(ledger.total_assets_out, U256Wrapper(ether(35)));
+    assert_eq!(
+        ledger.realized_pnl,
+        BigDecimal::from_str("-5000000000000000000").unwrap()
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_position_pnl() -> Result<(), ModelError> {
+    let pool = setup_test_db().await;
+    let account = create_test_account_db(&pool).await;
+    let atom = create_test_atom_db(&pool).await;
+    let mut vault = create_test_vault_with_atom(atom.id);
+    // 3 ether per share
+    vault.current_share_price = U256Wrapper(ether(3));
+    let vault = vault.upsert(&pool, TEST_SCHEMA).await?;
+
+    let mut ledger = PositionCostBasis::builder()
+        .id(format!("{}-{}", vault.id, account.id))
+        .account_id(account.id.clone())
+        .vault_id(vault.id.clone())
+        .build();
+    ledger.apply_deposit(ether(20), ether(10), 100, 0);
+    let stored = ledger.upsert(&pool, TEST_SCHEMA).await?;
+    assert_eq!(
+        PositionCostBasis::find_by_id(stored.id.clone(), &pool, TEST_SCHEMA).await?,
+        Some(stored.clone())
+    );
+
+    let pnl = PositionCostBasis::find_pnl_by_account_id(&account.id, &pool, TEST_SCHEMA).await?;
+    assert_eq!(pnl.len(), 1);
+    assert_eq!(pnl[0].cost_basis, stored);
+    assert_eq!(pnl[0].current_share_price, vault.current_share_price);
+    // 10 shares worth 30 ether cost 20
+    assert_eq!(
+        pnl[0].unrealized_pnl,
+        BigDecimal::from_str("10000000000000000000").unwrap()
+    );
+
+    Ok(())
+}
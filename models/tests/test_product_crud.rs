@@ -0,0 +1,57 @@
+#[cfg(test)]
+mod tests {
+    use models::{
+        error::ModelError,
+        product::Product,
+        test_helpers::{create_random_u256wrapper, setup_test_db, TEST_SCHEMA},
+        traits::SimpleCrud,
+    };
+
+    #[tokio::test]
+    async fn test_product_crud() -> Result<(), ModelError> {
+        // Set up test database
+        let pool = setup_test_db().await;
+
+        let test_product = Product::builder()
+            .id(create_random_u256wrapper())
+            .name("The Test Product".to_string())
+            .description("A product for testing".to_string())
+            .brand("Test Brand".to_string())
+            .sku("TEST-001".to_string())
+            .url("https://test.product".to_string())
+            .build();
+
+        // Test inserting
+        let inserted_product = test_product.upsert(&pool, TEST_SCHEMA).await?;
+        assert_eq!(inserted_product.name, test_product.name);
+        assert_eq!(inserted_product.description, test_product.description);
+
+        // Test updating
+        let mut updated_product = inserted_product;
+        updated_product.name = Some("Updated Test Product".to_string());
+        updated_product.description = Some("An updated product for testing".to_string());
+
+        let updated_result = updated_product.upsert(&pool, TEST_SCHEMA).await?;
+        assert_eq!(
+            updated_result.name,
+            Some("Updated Test Product".to_string())
+        );
+        assert_eq!(
+            updated_result.description,
+            Some("An updated product for testing".to_string())
+        );
+
+        // Test finding by id
+        let found_product = Product::find_by_id(test_product.id.clone(), &pool, TEST_SCHEMA)
+            .await?
+            .unwrap();
+        assert_eq!(found_product.id, test_product.id);
+        assert_eq!(found_product.name, Some("Updated Test Product".to_string()));
+        assert_eq!(
+            found_product.description,
+            Some("An updated product for testing".to_string())
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,122 @@
+use models::{
+    error::ModelError,
+    relevance_score::{decay, RelevanceEntity, RelevanceScore},
+    test_helpers::{
+        create_test_account_db, create_test_atom_db, create_test_deposit,
+        create_test_signal_with_atom_and_deposit, create_test_vault_with_atom, setup_test_db,
+        TEST_SCHEMA,
+    },
+    traits::SimpleCrud,
+    types::U256Wrapper,
+};
+use sqlx::types::BigDecimal;
+use std::str::FromStr;
+
+/// A day, the half-life used by the tests
+const HALF_LIFE_SECS: i64 = 24 * 60 * 60;
+
+#[test]
+fn test_decay() {
+    assert_eq!(decay(100.0, 0, HALF_LIFE_SECS), 100.0);
+    assert_eq!(decay(100.0, HALF_LIFE_SECS, HALF_LIFE_SECS), 50.0);
+    assert_eq!(decay(100.0, 2 * HALF_LIFE_SECS, HALF_LIFE_SECS), 25.0);
+    // A score isn't decayed backwards in time
+    assert_eq!(decay(100.0, -HALF_LIFE_SECS, HALF_LIFE_SECS), 100.0);
+    // Past 64 half-lives a score no longer counts
+    assert_eq!(decay(100.0, 65 * HALF_LIFE_SECS, HALF_LIFE_SECS), 0.0);
+}
+
+#[test]
+fn test_relevance_at() {
+    let score = RelevanceScore {
+        entity_type: RelevanceEntity::Atom,
+        entity_id: U256Wrapper::from_str("1").unwrap(),
+        signal_score: 100.0,
+        unique_accounts: 1,
+        half_life_secs: HALF_LIFE_SECS,
+        last_signal_timestamp: 0,
+        last_block_number: 0,
+        last_log_index: 0,
+    };
+    assert_eq!(score.signal_score_at(HALF_LIFE_SECS), 50.0);
+    assert_eq!(score.relevance_at(HALF_LIFE_SECS), 50.0 * 2f64.ln());
+
+    // More accounts rank higher for the same signal
+    let popular = RelevanceScore {
+        unique_accounts: 10,
+        ..score.clone()
+    };
+    assert!(popular.relevance_at(HALF_LIFE_SECS) > score.relevance_at(HALF_LIFE_SECS));
+}
+
+#[tokio::test]
+async fn test_apply_signal_and_rebuild() -> Result<(), ModelError> {
+    let pool = setup_test_db().await;
+    let atom = create_test_atom_db(&pool).await;
+    let vault = create_test_vault_with_atom(atom.id.clone())
+        .upsert(&pool, TEST_SCHEMA)
+        .await?;
+
+    // Two accounts signal 100 wei, a half-life apart
+    let start = 1_700_000_000;
+    for (block_number, timestamp) in [(1, start), (2, start + HALF_LIFE_SECS)] {
+        let account = create_test_account_db(&pool).await;
+        let deposit = create_test_deposit(account.id.clone(), account.id.clone(), vault.id.clone())
+            .upsert(&pool, TEST_SCHEMA)
+            .await?;
+        let mut signal =
+            create_test_signal_with_atom_and_deposit(account.id, atom.id.clone(), deposit.id);
+        signal.id = format!("0x{}-3", signal.id);
+        signal.delta = U256Wrapper::from_str("100")?;
+        signal.block_number = U256Wrapper::from_str(&block_number.to_string())?;
+        signal.block_timestamp = timestamp;
+        signal.upsert(&pool, TEST_SCHEMA).await?;
+
+        let applied = RelevanceScore::apply_signal(
+            RelevanceEntity::Atom,
+            &atom.id,
+            &signal.delta.to_big_decimal()?,
+            timestamp,
+            block_number,
+            3,
+            HALF_LIFE_SECS,
+            &pool,
+            TEST_SCHEMA,
+        )
+        .await?;
+        assert!(applied.is_some());
+    }
+
+    // Replaying the last signal doesn't count it twice
+    let replayed = RelevanceScore::apply_signal(
+        RelevanceEntity::Atom,
+        &atom.id,
+        &BigDecimal::from(100),
+        start + HALF_LIFE_SECS,
+        2,
+        3,
+        HALF_LIFE_SECS,
+        &pool,
+        TEST_SCHEMA,
+    )
+    .await?;
+    assert!(replayed.is_none());
+
+    let score = RelevanceScore::find_by_entity(RelevanceEntity::Atom, &atom.id, &pool, TEST_SCHEMA)
+        .await?
+        .expect("the atom should have a score");
+    assert_eq!(score.signal_score, 150.0);
+    assert_eq!(score.unique_accounts, 2);
+    assert_eq!(score.last_signal_timestamp, start + HALF_LIFE_SECS);
+    assert_eq!((score.last_block_number, score.last_log_index), (2, 3));
+
+    // Rebuilding from the signals gives the same score
+    RelevanceScore::rebuild(HALF_LIFE_SECS, &pool, TEST_SCHEMA).await?;
+    let rebuilt =
+        RelevanceScore::find_by_entity(RelevanceEntity::Atom, &atom.id, &pool, TEST_SCHEMA)
+            .await?
+            .expect("the atom should have a score");
+    assert_eq!(rebuilt, score);
+
+    Ok(())
+}
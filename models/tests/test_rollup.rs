@@ -0,0 +1,92 @@
+use models::{
+    error::ModelError,
+    rollup::{Rollup, RollupEntity, RollupGranularity},
+    test_helpers::{
+        create_test_account_db, create_test_atom_db, create_test_deposit, create_test_deposit_db,
+        create_test_redemption, create_test_redemption_db, create_test_vault_with_atom,
+        setup_test_db, TEST_SCHEMA,
+    },
+    traits::SimpleCrud,
+    types::U256Wrapper,
+};
+use sqlx::types::BigDecimal;
+use std::str::FromStr;
+
+/// 2025-01-01T00:00:00Z, a Wednesday
+const DAY: i64 = 1735689600;
+
+#[test]
+fn test_bucket_start() {
+    assert_eq!(RollupGranularity::Day.bucket_start(DAY), DAY);
+    assert_eq!(RollupGranularity::Day.bucket_start(DAY + 86399), DAY);
+    // The week starts on Monday 2024-12-30
+    assert_eq!(RollupGranularity::Week.bucket_start(DAY), DAY - 2 * 86400);
+    assert_eq!(
+        RollupGranularity::Week.bucket_start(DAY + 5 * 86400),
+        DAY + 5 * 86400
+    );
+}
+
+#[tokio::test]
+async fn test_rollup_rebuild() -> Result<(), ModelError> {
+    let pool = setup_test_db().await;
+    let account = create_test_account_db(&pool).await;
+    let atom = create_test_atom_db(&pool).await;
+    let vault = create_test_vault_with_atom(atom.id.clone())
+        .upsert(&pool, TEST_SCHEMA)
+        .await?;
+
+    // A deposit opening a position, a second deposit, then a redemption of
+    // every share the next day
+    let mut deposit = create_test_deposit(account.id.clone(), account.id.clone(), vault.id.clone());
+    deposit.block_timestamp = DAY + 60;
+    deposit.sender_assets_after_total_fees = U256Wrapper::from_str("100")?;
+    deposit.entry_fee = U256Wrapper::from_str("1")?;
+    deposit.shares_for_receiver = U256Wrapper::from_str("100")?;
+    deposit.receiver_total_shares_in_vault = U256Wrapper::from_str("100")?;
+    create_test_deposit_db(&pool, deposit).await;
+    let mut deposit = create_test_deposit(account.id.clone(), account.id.clone(), vault.id.clone());
+    deposit.block_timestamp = DAY + 120;
+    deposit.sender_assets_after_total_fees = U256Wrapper::from_str("50")?;
+    deposit.entry_fee = U256Wrapper::from_str("1")?;
+    deposit.shares_for_receiver = U256Wrapper::from_str("50")?;
+    deposit.receiver_total_shares_in_vault = U256Wrapper::from_str("150")?;
+    create_test_deposit_db(&pool, deposit).await;
+    let mut redemption =
+        create_test_redemption(account.id.clone(), account.id.clone(), vault.id.clone());
+    redemption.block_timestamp = DAY + 86400 + 60;
+    redemption.assets_for_receiver = U256Wrapper::from_str("160")?;
+    redemption.exit_fee = U256Wrapper::from_str("2")?;
+    redemption.sender_total_shares_in_vault = U256Wrapper::from_str("0")?;
+    create_test_redemption_db(&pool, redemption).await;
+
+    let to = DAY + 2 * 86400;
+    Rollup::rebuild(RollupGranularity::Day, DAY, to, &pool, TEST_SCHEMA).await?;
+    // Rebuilding again gives the same rollups
+    Rollup::rebuild(RollupGranularity::Day, DAY, to, &pool, TEST_SCHEMA).await?;
+
+    let rollups = Rollup::find_by_entity(
+        RollupGranularity::Day,
+        RollupEntity::Atom,
+        &atom.id,
+        DAY,
+        to,
+        &pool,
+        TEST_SCHEMA,
+    )
+    .await?;
+    assert_eq!(rollups.len(), 2);
+    assert_eq!(rollups[0].bucket_start, DAY);
+    assert_eq!(rollups[0].deposit_count, 2);
+    assert_eq!(rollups[0].deposit_assets, U256Wrapper::from_str("150")?);
+    assert_eq!(rollups[0].unique_depositors, 1);
+    assert_eq!(rollups[0].fees, U256Wrapper::from_str("2")?);
+    assert_eq!(rollups[0].net_signal, BigDecimal::from(150));
+    assert_eq!(rollups[0].position_count, 1);
+    assert_eq!(rollups[1].redemption_count, 1);
+    assert_eq!(rollups[1].redemption_assets, U256Wrapper::from_str("160")?);
+    assert_eq!(rollups[1].net_signal, BigDecimal::from(-160));
+    assert_eq!(rollups[1].position_count, 0);
+
+    Ok(())
+}
@@ -0,0 +1,54 @@
+#[cfg(test)]
+mod tests {
+    use models::{
+        error::ModelError,
+        schema_event::SchemaEvent,
+        test_helpers::{create_random_u256wrapper, setup_test_db, TEST_SCHEMA},
+        traits::SimpleCrud,
+    };
+
+    #[tokio::test]
+    async fn test_schema_event_crud() -> Result<(), ModelError> {
+        // Set up test database
+        let pool = setup_test_db().await;
+
+        let test_event = SchemaEvent::builder()
+            .id(create_random_u256wrapper())
+            .name("The Test Event".to_string())
+            .description("An event for testing".to_string())
+            .start_date("2024-01-01T10:00:00Z".to_string())
+            .end_date("2024-01-01T18:00:00Z".to_string())
+            .location("Test Hall".to_string())
+            .build();
+
+        // Test inserting
+        let inserted_event = test_event.upsert(&pool, TEST_SCHEMA).await?;
+        assert_eq!(inserted_event.name, test_event.name);
+        assert_eq!(inserted_event.description, test_event.description);
+
+        // Test updating
+        let mut updated_event = inserted_event;
+        updated_event.name = Some("Updated Test Event".to_string());
+        updated_event.description = Some("An updated event for testing".to_string());
+
+        let updated_result = updated_event.upsert(&pool, TEST_SCHEMA).await?;
+        assert_eq!(updated_result.name, Some("Updated Test Event".to_string()));
+        assert_eq!(
+            updated_result.description,
+            Some("An updated event for testing".to_string())
+        );
+
+        // Test finding by id
+        let found_event = SchemaEvent::find_by_id(test_event.id.clone(), &pool, TEST_SCHEMA)
+            .await?
+            .unwrap();
+        assert_eq!(found_event.id, test_event.id);
+        assert_eq!(found_event.name, Some("Updated Test Event".to_string()));
+        assert_eq!(
+            found_event.description,
+            Some("An updated event for testing".to_string())
+        );
+
+        Ok(())
+    }
+}
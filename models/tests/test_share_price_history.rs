@@ -0,0 +1,75 @@
+use models::{
+    error::ModelError,
+    share_price_history::SharePriceHistory,
+    test_helpers::{create_test_atom_db, create_test_vault_with_atom, setup_test_db, TEST_SCHEMA},
+    traits::SimpleCrud,
+    types::U256Wrapper,
+    vault::Vault,
+};
+use std::str::FromStr;
+
+/// Records a share price of a vault at the given block, log index and timestamp
+async fn record(
+    vault_id: &U256Wrapper,
+    block_number: i64,
+    log_index: i64,
+    block_timestamp: i64,
+    share_price: &str,
+    pool: &sqlx::PgPool,
+) -> Result<SharePriceHistory, ModelError> {
+    SharePriceHistory::builder()
+        .vault_id(vault_id.clone())
+        .block_number(block_number)
+        .log_index(log_index)
+        .block_timestamp(block_timestamp)
+        .share_price(U256Wrapper::from_str(share_price)?)
+        .build()
+        .upsert(pool, TEST_SCHEMA)
+        .await
+}
+
+#[tokio::test]
+async fn test_share_price_history() -> Result<(), ModelError> {
+    let pool = setup_test_db().await;
+    let atom = create_test_atom_db(&pool).await;
+    let vault = create_test_vault_with_atom(atom.id)
+        .upsert(&pool, TEST_SCHEMA)
+        .await?;
+
+    record(&vault.id, 10, 1, 1200, "100", &pool).await?;
+    record(&vault.id, 10, 2, 1200, "120", &pool).await?;
+    record(&vault.id, 11, 0, 1230, "90", &pool).await?;
+    record(&vault.id, 12, 0, 1260, "110", &pool).await?;
+    // Replaying an event doesn't record it twice
+    record(&vault.id, 12, 0, 1260, "110", &pool).await?;
+
+    let history =
+        SharePriceHistory::find_by_vault_id(&vault.id, 0, 100, &pool, TEST_SCHEMA).await?;
+    assert_eq!(history.len(), 4);
+    assert_eq!(history[1].share_price, U256Wrapper::from_str("120")?);
+
+    // Candles of a minute: [1200, 1260) and [1260, 1320)
+    let candles = Vault::share_price_candles(&vault.id, 60, 0, 2000, &pool, TEST_SCHEMA).await?;
+    assert_eq!(candles.len(), 2);
+    assert_eq!(candles[0].bucket_start, 1200);
+    assert_eq!(candles[0].open, U256Wrapper::from_str("100")?);
+    assert_eq!(candles[0].high, U256Wrapper::from_str("120")?);
+    assert_eq!(candles[0].low, U256Wrapper::from_str("90")?);
+    assert_eq!(candles[0].close, U256Wrapper::from_str("90")?);
+    assert_eq!(candles[0].sample_count, 3);
+    assert_eq!(candles[1].bucket_start, 1260);
+    assert_eq!(candles[1].open, U256Wrapper::from_str("110")?);
+    assert_eq!(candles[1].close, U256Wrapper::from_str("110")?);
+
+    // The end of the range is excluded
+    let candles = Vault::share_price_candles(&vault.id, 60, 0, 1260, &pool, TEST_SCHEMA).await?;
+    assert_eq!(candles.len(), 1);
+
+    assert!(
+        Vault::share_price_candles(&vault.id, 0, 0, 2000, &pool, TEST_SCHEMA)
+            .await
+            .is_err()
+    );
+
+    Ok(())
+}
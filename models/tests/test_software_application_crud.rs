@@ -0,0 +1,54 @@
+#[cfg(test)]
+mod tests {
+    use models::{
+        error::ModelError,
+        software_application::SoftwareApplication,
+        test_helpers::{create_random_u256wrapper, setup_test_db, TEST_SCHEMA},
+        traits::SimpleCrud,
+    };
+
+    #[tokio::test]
+    async fn test_software_application_crud() -> Result<(), ModelError> {
+        // Set up test database
+        let pool = setup_test_db().await;
+
+        let test_app = SoftwareApplication::builder()
+            .id(create_random_u256wrapper())
+            .name("The Test App".to_string())
+            .description("An app for testing".to_string())
+            .application_category("DeveloperApplication".to_string())
+            .operating_system("Linux".to_string())
+            .url("https://test.app".to_string())
+            .build();
+
+        // Test inserting
+        let inserted_app = test_app.upsert(&pool, TEST_SCHEMA).await?;
+        assert_eq!(inserted_app.name, test_app.name);
+        assert_eq!(inserted_app.description, test_app.description);
+
+        // Test updating
+        let mut updated_app = inserted_app;
+        updated_app.name = Some("Updated Test App".to_string());
+        updated_app.description = Some("An updated app for testing".to_string());
+
+        let updated_result = updated_app.upsert(&pool, TEST_SCHEMA).await?;
+        assert_eq!(updated_result.name, Some("Updated Test App".to_string()));
+        assert_eq!(
+            updated_result.description,
+            Some("An updated app for testing".to_string())
+        );
+
+        // Test finding by id
+        let found_app = SoftwareApplication::find_by_id(test_app.id.clone(), &pool, TEST_SCHEMA)
+            .await?
+            .unwrap();
+        assert_eq!(found_app.id, test_app.id);
+        assert_eq!(found_app.name, Some("Updated Test App".to_string()));
+        assert_eq!(
+            found_app.description,
+            Some("An updated app for testing".to_string())
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,62 @@
+use models::{
+    error::ModelError,
+    test_helpers::{
+        create_test_account_db, create_test_atom_db, create_test_position, create_test_triple,
+        create_test_triple_db, create_test_vault_with_triple, setup_test_db, TEST_SCHEMA,
+    },
+    traits::SimpleCrud,
+    triple_consensus::TripleConsensus,
+    types::U256Wrapper,
+};
+use std::str::FromStr;
+
+/// One ether, also the share price of a vault whose shares are worth one asset
+const ONE: &str = "1000000000000000000";
+
+#[tokio::test]
+async fn test_triple_consensus_refresh() -> Result<(), ModelError> {
+    let pool = setup_test_db().await;
+    let creator = create_test_account_db(&pool).await;
+    let supporter = create_test_account_db(&pool).await;
+    let opponent = create_test_account_db(&pool).await;
+    let subject = create_test_atom_db(&pool).await;
+    let predicate = create_test_atom_db(&pool).await;
+    let object = create_test_atom_db(&pool).await;
+    let triple = create_test_triple_db(
+        &pool,
+        create_test_triple(creator.id, subject.id, predicate.id, object.id),
+    )
+    .await;
+
+    // 300 shares for and 100 against, both at a share price of one
+    let mut vault = create_test_vault_with_triple(triple.id.clone());
+    vault.id = triple.vault_id.clone();
+    vault.total_shares = U256Wrapper::from_str("300")?;
+    vault.current_share_price = U256Wrapper::from_str(ONE)?;
+    vault.upsert(&pool, TEST_SCHEMA).await?;
+    let mut counter_vault = create_test_vault_with_triple(triple.id.clone());
+    counter_vault.id = triple.counter_vault_id.clone();
+    counter_vault.total_shares = U256Wrapper::from_str("100")?;
+    counter_vault.current_share_price = U256Wrapper::from_str(ONE)?;
+    counter_vault.upsert(&pool, TEST_SCHEMA).await?;
+    create_test_position(supporter.id, triple.vault_id.clone())
+        .upsert(&pool, TEST_SCHEMA)
+        .await?;
+    create_test_position(opponent.id, triple.counter_vault_id.clone())
+        .upsert(&pool, TEST_SCHEMA)
+        .await?;
+
+    let consensus = TripleConsensus::refresh(&triple.id, &pool, TEST_SCHEMA)
+        .await?
+        .expect("The triple exists");
+    assert_eq!(consensus.assets_for, U256Wrapper::from_str("300")?);
+    assert_eq!(consensus.assets_against, U256Wrapper::from_str("100")?);
+    assert_eq!(consensus.supporters, 1);
+    assert_eq!(consensus.opponents, 1);
+    assert_eq!(consensus.agreement_score, 0.5);
+
+    let found = TripleConsensus::find_by_id(&triple.id, &pool, TEST_SCHEMA).await?;
+    assert_eq!(found, Some(consensus));
+
+    Ok(())
+}
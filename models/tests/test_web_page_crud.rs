@@ -0,0 +1,55 @@
+#[cfg(test)]
+mod tests {
+    use models::{
+        error::ModelError,
+        test_helpers::{create_random_u256wrapper, setup_test_db, TEST_SCHEMA},
+        traits::SimpleCrud,
+        web_page::WebPage,
+    };
+
+    #[tokio::test]
+    async fn test_web_page_crud() -> Result<(), ModelError> {
+        // Set up test database
+        let pool = setup_test_db().await;
+
+        let test_web_page = WebPage::builder()
+            .id(create_random_u256wrapper())
+            .url("https://test.website/post?utm_source=feed")
+            .canonical_url("https://test.website/post")
+            .title(Some("A Test Post".to_string()))
+            .description(None)
+            .image(Some("https://test.website/preview.png".to_string()))
+            .site_name(Some("The Test Website".to_string()))
+            .favicon(None)
+            .build();
+
+        // Test inserting
+        let inserted_web_page = test_web_page.upsert(&pool, TEST_SCHEMA).await?;
+        assert_eq!(inserted_web_page.canonical_url, test_web_page.canonical_url);
+        assert_eq!(inserted_web_page.title, test_web_page.title);
+        assert_eq!(inserted_web_page.description, None);
+
+        // Test updating
+        let mut updated_web_page = inserted_web_page;
+        updated_web_page.description = Some("A post for testing".to_string());
+        updated_web_page.favicon = Some("https://test.website/favicon.ico".to_string());
+
+        let updated_result = updated_web_page.upsert(&pool, TEST_SCHEMA).await?;
+        assert_eq!(
+            updated_result.description,
+            Some("A post for testing".to_string())
+        );
+
+        // Test finding by id
+        let found_web_page = WebPage::find_by_id(test_web_page.id.clone(), &pool, TEST_SCHEMA)
+            .await?
+            .unwrap();
+        assert_eq!(found_web_page.url, test_web_page.url);
+        assert_eq!(
+            found_web_page.favicon,
+            Some("https://test.website/favicon.ico".to_string())
+        );
+
+        Ok(())
+    }
+}
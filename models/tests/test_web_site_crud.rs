@@ -0,0 +1,56 @@
+#[cfg(test)]
+mod tests {
+    use models::{
+        error::ModelError,
+        test_helpers::{create_random_u256wrapper, setup_test_db, TEST_SCHEMA},
+        traits::SimpleCrud,
+        web_site::WebSite,
+    };
+
+    #[tokio::test]
+    async fn test_web_site_crud() -> Result<(), ModelError> {
+        // Set up test database
+        let pool = setup_test_db().await;
+
+        let test_website = WebSite::builder()
+            .id(create_random_u256wrapper())
+            .name("The Test Website".to_string())
+            .description("A website for testing".to_string())
+            .image("https://test.website/logo.png".to_string())
+            .url("https://test.website".to_string())
+            .build();
+
+        // Test inserting
+        let inserted_website = test_website.upsert(&pool, TEST_SCHEMA).await?;
+        assert_eq!(inserted_website.name, test_website.name);
+        assert_eq!(inserted_website.description, test_website.description);
+
+        // Test updating
+        let mut updated_website = inserted_website;
+        updated_website.name = Some("Updated Test Website".to_string());
+        updated_website.description = Some("An updated website for testing".to_string());
+
+        let updated_result = updated_website.upsert(&pool, TEST_SCHEMA).await?;
+        assert_eq!(
+            updated_result.name,
+            Some("Updated Test Website".to_string())
+        );
+        assert_eq!(
+            updated_result.description,
+            Some("An updated website for testing".to_string())
+        );
+
+        // Test finding by id
+        let found_website = WebSite::find_by_id(test_website.id.clone(), &pool, TEST_SCHEMA)
+            .await?
+            .unwrap();
+        assert_eq!(found_website.id, test_website.id);
+        assert_eq!(found_website.name, Some("Updated Test Website".to_string()));
+        assert_eq!(
+            found_website.description,
+            Some("An updated website for testing".to_string())
+        );
+
+        Ok(())
+    }
+}
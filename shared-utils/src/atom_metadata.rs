@@ -0,0 +1,301 @@
+use models::atom::AtomType;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// Shortens an address string by taking first 6 and last 4 chars
+pub fn short_id(address: &str) -> String {
+    format!("{}...{}", &address[..6], &address[address.len() - 4..])
+}
+
+/// Represents the metadata for an atom
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct AtomMetadata {
+    pub label: String,
+    pub emoji: String,
+    pub atom_type: String,
+    pub image: Option<String>,
+}
+
+impl AtomMetadata {
+    /// Creates a new atom metadata for an address
+    pub fn address(address: &str, image: Option<String>) -> Self {
+        Self {
+            label: short_id(address),
+            emoji: "⛓️".to_string(),
+            atom_type: "Account".to_string(),
+            image,
+        }
+    }
+
+    /// Creates a new atom metadata for a book
+    pub fn book(name: String) -> Self {
+        Self {
+            label: name,
+            emoji: "📚".to_string(),
+            atom_type: "Book".to_string(),
+            image: None,
+        }
+    }
+
+    /// Creates a new atom metadata for a byte object
+    pub fn byte_object(image: Option<String>) -> Self {
+        Self {
+            label: "byte object".to_string(),
+            emoji: "🔢".to_string(),
+            atom_type: "ByteObject".to_string(),
+            image,
+        }
+    }
+
+    /// Creates a new atom metadata for a caip10
+    pub fn caip10(caip10: String) -> Self {
+        Self {
+            label: caip10,
+            emoji: "🔗".to_string(),
+            atom_type: "Caip10".to_string(),
+            image: None,
+        }
+    }
+
+    /// Creates a new atom metadata for a caip19
+    pub fn caip19(caip19: String) -> Self {
+        Self {
+            label: caip19,
+            emoji: "🪙".to_string(),
+            atom_type: "Caip19".to_string(),
+            image: None,
+        }
+    }
+
+    /// Creates a new atom metadata for a caip2
+    pub fn caip2(caip2: String) -> Self {
+        Self {
+            label: caip2,
+            emoji: "🧱".to_string(),
+            atom_type: "Caip2".to_string(),
+            image: None,
+        }
+    }
+
+    /// Creates a new atom metadata for a creative work
+    pub fn creative_work(name: String, image: Option<String>) -> Self {
+        Self {
+            label: name,
+            emoji: "🎨".to_string(),
+            atom_type: "CreativeWork".to_string(),
+            image,
+        }
+    }
+
+    /// Creates a new atom metadata for a did
+    pub fn did(did: String) -> Self {
+        Self {
+            label: did,
+            emoji: "🆔".to_string(),
+            atom_type: "Did".to_string(),
+            image: None,
+        }
+    }
+
+    /// Creates a new atom metadata for an event
+    pub fn event(name: String, image: Option<String>) -> Self {
+        Self {
+            label: name,
+            emoji: "📅".to_string(),
+            atom_type: "Event".to_string(),
+            image,
+        }
+    }
+
+    /// Creates a new atom metadata for a follow action
+    pub fn follow_action(image: Option<String>) -> Self {
+        Self {
+            label: "follow".to_string(),
+            emoji: "🔔".to_string(),
+            atom_type: "FollowAction".to_string(),
+            image,
+        }
+    }
+
+    /// Creates a new atom metadata for a json object
+    pub fn json_object(image: Option<String>) -> Self {
+        Self {
+            label: "json object".to_string(),
+            emoji: "📦".to_string(),
+            atom_type: "JsonObject".to_string(),
+            image,
+        }
+    }
+
+    /// Creates a new atom metadata for a keywords predicate
+    pub fn keywords_predicate(image: Option<String>) -> Self {
+        Self {
+            label: "has tag".to_string(),
+            emoji: "🏷️".to_string(),
+            atom_type: "Keywords".to_string(),
+            image,
+        }
+    }
+
+    /// Creates a new atom metadata for a like action
+    pub fn like_action(image: Option<String>) -> Self {
+        Self {
+            label: "like".to_string(),
+            emoji: "👍".to_string(),
+            atom_type: "LikeAction".to_string(),
+            image,
+        }
+    }
+
+    /// Creates a new atom metadata for an organization
+    pub fn organization(name: String, image: Option<String>) -> Self {
+        Self {
+            label: name,
+            emoji: "🏢".to_string(),
+            atom_type: "Organization".to_string(),
+            image,
+        }
+    }
+
+    /// Creates a new atom metadata for an organization predicate
+    pub fn organization_predicate(image: Option<String>) -> Self {
+        Self {
+            label: "is organization".to_string(),
+            emoji: "🏢".to_string(),
+            atom_type: "OrganizationPredicate".to_string(),
+            image,
+        }
+    }
+
+    /// Creates a new atom metadata for a person
+    pub fn person(name: String, image: Option<String>) -> Self {
+        Self {
+            label: name,
+            emoji: "👤".to_string(),
+            atom_type: "Person".to_string(),
+            image,
+        }
+    }
+
+    /// Creates a new atom metadata for a person predicate
+    pub fn person_predicate(image: Option<String>) -> Self {
+        Self {
+            label: "is person".to_string(),
+            emoji: "👤".to_string(),
+            atom_type: "PersonPredicate".to_string(),
+            image,
+        }
+    }
+
+    /// Creates a new atom metadata for a place
+    pub fn place(name: String, image: Option<String>) -> Self {
+        Self {
+            label: name,
+            emoji: "📍".to_string(),
+            atom_type: "Place".to_string(),
+            image,
+        }
+    }
+
+    /// Creates a new atom metadata for a product
+    pub fn product(name: String, image: Option<String>) -> Self {
+        Self {
+            label: name,
+            emoji: "🛍️".to_string(),
+            atom_type: "Product".to_string(),
+            image,
+        }
+    }
+
+    /// Creates a new atom metadata for a schema.org object of a supported
+    /// type, the way the resolver does once the object is stored
+    pub fn schema_org(atom_type: &AtomType, name: String, image: Option<String>) -> Self {
+        match atom_type {
+            AtomType::Thing => Self::thing(name, image),
+            AtomType::Person => Self::person(name, image),
+            AtomType::Organization => Self::organization(name, image),
+            AtomType::Book => Self::book(name),
+            AtomType::CreativeWork => Self::creative_work(name, image),
+            AtomType::Event => Self::event(name, image),
+            AtomType::Place => Self::place(name, image),
+            AtomType::Product => Self::product(name, image),
+            AtomType::SoftwareApplication => Self::software_application(name, image),
+            AtomType::WebSite => Self::web_site(name, image),
+            _ => Self::unknown(),
+        }
+    }
+
+    /// Creates a new atom metadata for a software application
+    pub fn software_application(name: String, image: Option<String>) -> Self {
+        Self {
+            label: name,
+            emoji: "💻".to_string(),
+            atom_type: "SoftwareApplication".to_string(),
+            image,
+        }
+    }
+
+    /// Creates a new atom metadata for a text object
+    pub fn text_object(name: Option<String>) -> Self {
+        Self {
+            label: name
+                .unwrap_or("text object".to_string())
+                .chars()
+                .take(256)
+                .collect(),
+            emoji: "📝".to_string(),
+            atom_type: "TextObject".to_string(),
+            image: None,
+        }
+    }
+
+    /// Creates a new atom metadata for a thing
+    pub fn thing(name: String, image: Option<String>) -> Self {
+        Self {
+            label: name,
+            emoji: "🧩".to_string(),
+            atom_type: "Thing".to_string(),
+            image,
+        }
+    }
+
+    /// Creates a new atom metadata for a thing predicate
+    pub fn thing_predicate(image: Option<String>) -> Self {
+        Self {
+            label: "is thing".to_string(),
+            emoji: "🧩".to_string(),
+            atom_type: "ThingPredicate".to_string(),
+            image,
+        }
+    }
+
+    /// Returns an unknown atom metadata
+    pub fn unknown() -> Self {
+        Self {
+            label: "Unknown".to_string(),
+            emoji: "❓".to_string(),
+            atom_type: "Unknown".to_string(),
+            image: None,
+        }
+    }
+
+    /// Creates a new atom metadata for an url
+    pub fn url(label: String, image: Option<String>) -> Self {
+        Self {
+            label,
+            emoji: "📄".to_string(),
+            atom_type: "Url".to_string(),
+            image,
+        }
+    }
+
+    /// Creates a new atom metadata for a website
+    pub fn web_site(name: String, image: Option<String>) -> Self {
+        Self {
+            label: name,
+            emoji: "🌐".to_string(),
+            atom_type: "WebSite".to_string(),
+            image,
+        }
+    }
+}
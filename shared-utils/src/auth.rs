@@ -0,0 +1,379 @@
+use crate::{error::LibError, rate_limit::RateLimiter};
+use axum::{
+    extract::{ConnectInfo, Request, State},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use chrono::Utc;
+use http::{
+    header::{AUTHORIZATION, RETRY_AFTER},
+    HeaderValue, StatusCode,
+};
+use ipnet::IpNet;
+use log::warn;
+use models::{
+    api_key::{ApiKey, ApiKeyScope},
+    error::ModelError,
+    traits::SimpleCrud,
+};
+use rand::distributions::{Alphanumeric, DistString};
+use sha2::{Digest, Sha256};
+use sqlx::PgPool;
+use std::{
+    net::{IpAddr, SocketAddr},
+    sync::Arc,
+    time::Duration,
+};
+use thiserror::Error;
+
+/// The prefix of every API key, keys look like `ik_<id>_<secret>`
+const KEY_PREFIX: &str = "ik";
+/// The id used for the bootstrap admin key configured in the environment
+const BOOTSTRAP_KEY_ID: &str = "bootstrap";
+
+/// The errors returned by the API key middleware
+#[derive(Error, Debug)]
+pub enum AuthError {
+    #[error("Missing API key")]
+    MissingKey,
+    #[error("Invalid API key")]
+    InvalidKey,
+    #[error("The API key is missing the `{0}` scope")]
+    MissingScope(ApiKeyScope),
+    #[error("Rate limit exceeded, retry in {} seconds", .0.as_secs().max(1))]
+    RateLimited(Duration),
+    #[error(transparent)]
+    Model(#[from] ModelError),
+}
+
+impl IntoResponse for AuthError {
+    fn into_response(self) -> Response {
+        let status = match self {
+            AuthError::MissingKey | AuthError::InvalidKey => StatusCode::UNAUTHORIZED,
+            AuthError::MissingScope(_) => StatusCode::FORBIDDEN,
+            AuthError::RateLimited(_) => StatusCode::TOO_MANY_REQUESTS,
+            AuthError::Model(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        let mut response = (status, self.to_string()).into_response();
+        if let AuthError::RateLimited(retry_after) = self {
+            response
+                .headers_mut()
+                .insert(RETRY_AFTER, HeaderValue::from(retry_after.as_secs().max(1)));
+        }
+        response
+    }
+}
+
+/// The API key that authenticated a request. It is added to the request
+/// extensions so handlers can tell who made the request.
+#[derive(Clone, Debug)]
+pub struct AuthenticatedKey {
+    pub id: String,
+    pub name: String,
+    pub scopes: Vec<ApiKeyScope>,
+}
+
+impl AuthenticatedKey {
+    /// Returns true if the key was granted the scope, or the admin scope
+    pub fn has_scope(&self, scope: ApiKeyScope) -> bool {
+        scope.is_granted_by(&self.scopes)
+    }
+}
+
+/// Generates a new API key. Returns the key, which has to be handed to the
+/// client as it can't be recovered, and the [`ApiKey`] to store.
+pub fn generate_api_key(
+    name: String,
+    scopes: Vec<ApiKeyScope>,
+    rate_limit_per_minute: i32,
+) -> (String, ApiKey) {
+    let mut rng = rand::thread_rng();
+    let id = Alphanumeric.sample_string(&mut rng, 12).to_lowercase();
+    let secret = Alphanumeric.sample_string(&mut rng, 40);
+
+    let api_key = ApiKey::builder()
+        .id(id.clone())
+        .name(name)
+        .key_hash(hash_secret(&secret))
+        .scopes(scopes)
+        .rate_limit_per_minute(rate_limit_per_minute)
+        .created_at(Utc::now())
+        .build();
+
+    (format!("{}_{}_{}", KEY_PREFIX, id, secret), api_key)
+}
+
+/// Returns the hex encoded sha256 digest of the secret part of a key
+pub fn hash_secret(secret: &str) -> String {
+    hex::encode(Sha256::digest(secret.as_bytes()))
+}
+
+/// Splits a key into its id and secret
+fn parse_key(key: &str) -> Option<(&str, &str)> {
+    key.strip_prefix(KEY_PREFIX)?
+        .strip_prefix('_')?
+        .split_once('_')
+}
+
+/// Parses the comma separated IPs or CIDR ranges of the proxies trusted to
+/// set the `X-Forwarded-For` header
+pub fn parse_trusted_proxies(value: Option<&str>) -> Result<Vec<IpNet>, LibError> {
+    value
+        .unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|proxy| !proxy.is_empty())
+        .map(|proxy| {
+            proxy
+                .parse::<IpNet>()
+                .or_else(|_| proxy.parse::<IpAddr>().map(IpNet::from))
+                .map_err(|_| LibError::InvalidTrustedProxy(proxy.to_string()))
+        })
+        .collect()
+}
+
+/// Compares two byte slices without short-circuiting on the first mismatch
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+/// Authenticates requests with API keys stored in Postgres and rate limits
+/// them per key and per client IP.
+#[derive(Clone)]
+pub struct ApiKeyAuth {
+    pg_pool: PgPool,
+    schema: String,
+    bootstrap_admin_key: Option<String>,
+    ip_rate_limit_per_minute: u32,
+    trusted_proxies: Vec<IpNet>,
+    key_limiter: Arc<RateLimiter>,
+    ip_limiter: Arc<RateLimiter>,
+}
+
+impl ApiKeyAuth {
+    /// Creates the authenticator. The `bootstrap_admin_key` is accepted as a
+    /// key with the admin scope, so the first keys can be issued. The client
+    /// IP is only read from `X-Forwarded-For` on requests coming from one of
+    /// the `trusted_proxies`.
+    pub fn new(
+        pg_pool: PgPool,
+        schema: String,
+        bootstrap_admin_key: Option<String>,
+        ip_rate_limit_per_minute: u32,
+        trusted_proxies: Vec<IpNet>,
+    ) -> Self {
+        Self {
+            pg_pool,
+            schema,
+            bootstrap_admin_key: bootstrap_admin_key.filter(|key| !key.is_empty()),
+            ip_rate_limit_per_minute,
+            trusted_proxies,
+            key_limiter: Arc::new(RateLimiter::new()),
+            ip_limiter: Arc::new(RateLimiter::new()),
+        }
+    }
+
+    /// Returns the state of the [`require_scope`] middleware for a scope
+    pub fn require(&self, scope: ApiKeyScope) -> RequiredScope {
+        RequiredScope {
+            auth: self.clone(),
+            scope,
+        }
+    }
+
+    /// Looks up a key and checks its secret. Returns the key and its rate
+    /// limit, the bootstrap key has none.
+    async fn authenticate(&self, key: &str) -> Result<(AuthenticatedKey, Option<u32>), AuthError> {
+        let is_bootstrap_key =
+            self.bootstrap_admin_key
+                .as_ref()
+                .is_some_and(|bootstrap_admin_key| {
+                    constant_time_eq(key.as_bytes(), bootstrap_admin_key.as_bytes())
+                });
+        if is_bootstrap_key {
+            let authenticated = AuthenticatedKey {
+                id: BOOTSTRAP_KEY_ID.into(),
+                name: BOOTSTRAP_KEY_ID.into(),
+                scopes: vec![ApiKeyScope::Admin],
+            };
+            return Ok((authenticated, None));
+        }
+
+        let (id, secret) = parse_key(key).ok_or(AuthError::InvalidKey)?;
+        let api_key = ApiKey::find_by_id(id.to_string(), &self.pg_pool, &self.schema)
+            .await?
+            .filter(|api_key| !api_key.is_revoked())
+            .ok_or(AuthError::InvalidKey)?;
+        if !constant_time_eq(hash_secret(secret).as_bytes(), api_key.key_hash.as_bytes()) {
+            return Err(AuthError::InvalidKey);
+        }
+
+        let rate_limit = u32::try_from(api_key.rate_limit_per_minute).unwrap_or(1);
+        let authenticated = AuthenticatedKey {
+            id: api_key.id,
+            name: api_key.name,
+            scopes: api_key.scopes,
+        };
+        Ok((authenticated, Some(rate_limit)))
+    }
+
+    /// Increments the usage counters of a key in the background
+    fn record_usage(&self, id: String, rate_limited: bool) {
+        let pg_pool = self.pg_pool.clone();
+        let schema = self.schema.clone();
+        tokio::spawn(async move {
+            if let Err(e) = ApiKey::record_usage(&id, rate_limited, &pg_pool, &schema).await {
+                warn!("Failed to record the usage of API key {}: {}", id, e);
+            }
+        });
+    }
+}
+
+/// The state of the [`require_scope`] middleware
+#[derive(Clone)]
+pub struct RequiredScope {
+    auth: ApiKeyAuth,
+    scope: ApiKeyScope,
+}
+
+/// Returns the IP of the client. `X-Forwarded-For` can be set by anyone, so
+/// it's only read when the peer is a trusted proxy. The client is then the
+/// last entry that wasn't added by a trusted proxy.
+fn client_ip(request: &Request, trusted_proxies: &[IpNet]) -> Option<IpAddr> {
+    let is_trusted = |ip: &IpAddr| trusted_proxies.iter().any(|proxy| proxy.contains(ip));
+    let ConnectInfo(peer) = request.extensions().get::<ConnectInfo<SocketAddr>>()?;
+    let peer = peer.ip();
+    if !is_trusted(&peer) {
+        return Some(peer);
+    }
+
+    let forwarded = request
+        .headers()
+        .get("x-forwarded-for")
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or_default();
+    Some(
+        forwarded
+            .rsplit(',')
+            .map_while(|ip| ip.trim().parse::<IpAddr>().ok())
+            .find(|ip| !is_trusted(ip))
+            .unwrap_or(peer),
+    )
+}
+
+/// Middleware that only lets requests through if they carry an API key with
+/// the required scope, as `Authorization: Bearer <key>`, and are within the
+/// rate limits of the key and of the client IP.
+pub async fn require_scope(
+    State(required): State<RequiredScope>,
+    mut request: Request,
+    next: Next,
+) -> Result<Response, AuthError> {
+    let auth = &required.auth;
+
+    if let Some(ip) = client_ip(&request, &auth.trusted_proxies) {
+        auth.ip_limiter
+            .check(&ip.to_string(), auth.ip_rate_limit_per_minute)
+            .map_err(AuthError::RateLimited)?;
+    }
+
+    let key = request
+        .headers()
+        .get(AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .ok_or(AuthError::MissingKey)?;
+    let (authenticated, rate_limit) = auth.authenticate(key.trim()).await?;
+
+    if !authenticated.has_scope(required.scope) {
+        return Err(AuthError::MissingScope(required.scope));
+    }
+
+    if let Some(rate_limit) = rate_limit {
+        let limited = auth.key_limiter.check(&authenticated.id, rate_limit);
+        auth.record_usage(authenticated.id.clone(), limited.is_err());
+        limited.map_err(AuthError::RateLimited)?;
+    }
+
+    request.extensions_mut().insert(authenticated);
+    Ok(next.run(request).await)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generate_api_key() {
+        let (key, api_key) = generate_api_key("test".into(), vec![ApiKeyScope::Upload], 60);
+
+        let (id, secret) = parse_key(&key).unwrap();
+        assert_eq!(id, api_key.id);
+        assert_eq!(hash_secret(secret), api_key.key_hash);
+        assert!(!api_key.key_hash.contains(secret));
+    }
+
+    #[test]
+    fn test_authenticated_key_has_scope() {
+        let key = |scopes| AuthenticatedKey {
+            id: "id".into(),
+            name: "name".into(),
+            scopes,
+        };
+        assert!(key(vec![ApiKeyScope::Upload]).has_scope(ApiKeyScope::Upload));
+        assert!(!key(vec![ApiKeyScope::Upload]).has_scope(ApiKeyScope::Moderate));
+        // The admin scope implies every other scope
+        assert!(key(vec![ApiKeyScope::Admin]).has_scope(ApiKeyScope::Moderate));
+    }
+
+    #[test]
+    fn test_parse_key() {
+        assert_eq!(parse_key("ik_abc_secret"), Some(("abc", "secret")));
+        assert_eq!(parse_key("ik_abc"), None);
+        assert_eq!(parse_key("xx_abc_secret"), None);
+    }
+
+    /// Builds a request from a peer with an `X-Forwarded-For` header
+    fn forwarded_request(peer: &str, forwarded_for: &str) -> Request {
+        let mut request = Request::builder()
+            .header("x-forwarded-for", forwarded_for)
+            .body(axum::body::Body::empty())
+            .unwrap();
+        request
+            .extensions_mut()
+            .insert(ConnectInfo(SocketAddr::new(peer.parse().unwrap(), 443)));
+        request
+    }
+
+    #[test]
+    fn test_client_ip() {
+        let proxies = parse_trusted_proxies(Some("10.0.0.1, 10.1.0.0/16")).unwrap();
+        let ip = |ip: &str| Some(ip.parse::<IpAddr>().unwrap());
+
+        // The header is ignored when the peer isn't a trusted proxy
+        let request = forwarded_request("203.0.113.7", "198.51.100.1");
+        assert_eq!(client_ip(&request, &proxies), ip("203.0.113.7"));
+        assert_eq!(client_ip(&request, &[]), ip("203.0.113.7"));
+
+        // Behind the proxies, the entries added by a client are skipped
+        let request = forwarded_request("10.0.0.1", "192.0.2.9, 198.51.100.1, 10.1.2.3");
+        assert_eq!(client_ip(&request, &proxies), ip("198.51.100.1"));
+
+        // A proxy that didn't forward anything is the client
+        let request = forwarded_request("10.0.0.1", "");
+        assert_eq!(client_ip(&request, &proxies), ip("10.0.0.1"));
+    }
+
+    #[test]
+    fn test_parse_trusted_proxies() {
+        assert!(parse_trusted_proxies(None).unwrap().is_empty());
+        assert_eq!(
+            parse_trusted_proxies(Some("10.0.0.1,10.0.0.0/8,::1"))
+                .unwrap()
+                .len(),
+            3
+        );
+        assert!(parse_trusted_proxies(Some("10.0.0.0/33")).is_err());
+        assert!(parse_trusted_proxies(Some("proxy")).is_err());
+    }
+}
@@ -0,0 +1,175 @@
+use crate::{error::LibError, types::FetchedContent};
+use bytes::Bytes;
+use chrono::Utc;
+use macon::Builder;
+use models::{cached_content::CachedContent, traits::SimpleCrud};
+use sha2::{Digest, Sha256};
+use sqlx::PgPool;
+use std::{
+    path::PathBuf,
+    sync::{Arc, Mutex},
+};
+use tracing::warn;
+
+/// The default size cap of the cache, 1GiB
+pub const DEFAULT_MAX_SIZE_BYTES: u64 = 1 << 30;
+/// The default size above which content is stored in the local directory
+/// instead of Postgres, 1MiB
+pub const DEFAULT_INLINE_LIMIT_BYTES: usize = 1 << 20;
+/// An eviction frees the cache down to this percentage of its size cap, so
+/// that a full cache isn't evicted on every put
+const EVICTION_TARGET_PERCENT: u64 = 90;
+
+/// A persistent cache of the content fetched from IPFS, keyed by CID. Since
+/// CIDs are immutable, entries are never revalidated. Once the cache exceeds
+/// its size cap the least recently accessed entries are evicted. The size
+/// is tracked approximately in memory, and only measured in Postgres when
+/// evicting, so other writers to the cache are noticed at the next eviction.
+///
+/// Content is stored in Postgres, unless a `directory` is configured, in
+/// which case content larger than `inline_limit_bytes` is stored there.
+/// Without a directory, large content is not cached.
+#[derive(Clone, Builder)]
+pub struct ContentCache {
+    #[builder(Option=!)]
+    pub directory: Option<PathBuf>,
+    pub inline_limit_bytes: Option<usize>,
+    #[builder(Option=!)]
+    pub max_size_bytes: Option<u64>,
+    pub pg_pool: PgPool,
+    pub schema: String,
+    /// The approximate size of the cache in bytes, unknown until the first
+    /// eviction measures it
+    #[builder(Default)]
+    size_estimate: Arc<Mutex<Option<u64>>>,
+}
+
+impl ContentCache {
+    /// Returns the cached content of a CID, if any
+    pub async fn get(&self, cid: &str) -> Result<Option<FetchedContent>, LibError> {
+        let Some(entry) = CachedContent::find_and_touch(cid, &self.pg_pool, &self.schema).await?
+        else {
+            return Ok(None);
+        };
+
+        let data = match entry.data {
+            Some(data) => Bytes::from(data),
+            None => match self.read_blob(cid, entry.size).await {
+                Some(data) => data,
+                None => {
+                    // The file is gone or truncated, drop the entry so the
+                    // content is fetched again
+                    warn!("Cached content of {} is missing from the directory", cid);
+                    CachedContent::delete(cid, &self.pg_pool, &self.schema).await?;
+                    return Ok(None);
+                }
+            },
+        };
+
+        Ok(Some(FetchedContent {
+            data,
+            content_type: entry.content_type,
+        }))
+    }
+
+    /// Caches the content of a CID, then evicts the least recently accessed
+    /// entries if the cache may be over its size cap
+    pub async fn put(&self, cid: &str, content: &FetchedContent) -> Result<(), LibError> {
+        let size = content.data.len();
+        let max_size = self.max_size_bytes.unwrap_or(DEFAULT_MAX_SIZE_BYTES);
+        if size as u64 > max_size {
+            return Ok(());
+        }
+
+        let inline_limit = self
+            .inline_limit_bytes
+            .unwrap_or(DEFAULT_INLINE_LIMIT_BYTES);
+        let data = if size <= inline_limit {
+            Some(content.data.to_vec())
+        } else if let Some(path) = self.blob_path(cid) {
+            if let Some(parent) = path.parent() {
+                tokio::fs::create_dir_all(parent).await?;
+            }
+            // Write to a temporary file first, so a crash never leaves a
+            // truncated file behind
+            let tmp_path = path.with_extension("tmp");
+            tokio::fs::write(&tmp_path, &content.data).await?;
+            tokio::fs::rename(&tmp_path, &path).await?;
+            None
+        } else {
+            return Ok(());
+        };
+
+        let now = Utc::now();
+        CachedContent::builder()
+            .cid(cid.to_string())
+            .content_type(content.content_type.clone())
+            .size(size as i64)
+            .data(data)
+            .fetched_at(now)
+            .last_accessed_at(now)
+            .build()
+            .upsert(&self.pg_pool, &self.schema)
+            .await?;
+
+        // Until the size of the cache is known, the first put evicts to
+        // measure it
+        let estimate = self.add_to_size_estimate(size as u64);
+        if estimate.is_none_or(|total| total > max_size) {
+            self.evict(max_size).await?;
+        }
+        Ok(())
+    }
+
+    /// Adds the size of new content to the size estimate and returns it, or
+    /// `None` if the size of the cache is still unknown. Replacing content
+    /// counts its size twice, which at worst evicts a bit early.
+    fn add_to_size_estimate(&self, size: u64) -> Option<u64> {
+        let mut estimate = self.size_estimate.lock().unwrap_or_else(|e| e.into_inner());
+        *estimate = estimate.map(|total| total.saturating_add(size));
+        *estimate
+    }
+
+    /// Evicts the least recently accessed entries until the cache fits in
+    /// `EVICTION_TARGET_PERCENT` of `max_size` bytes, removing their files
+    /// from the directory, then measures the size of the cache
+    async fn evict(&self, max_size: u64) -> Result<(), LibError> {
+        let target_size = max_size / 100 * EVICTION_TARGET_PERCENT;
+        let evicted = CachedContent::evict_least_recently_used(
+            i64::try_from(target_size).unwrap_or(i64::MAX),
+            &self.pg_pool,
+            &self.schema,
+        )
+        .await?;
+
+        for entry in evicted.iter().filter(|entry| entry.stored_on_disk) {
+            if let Some(path) = self.blob_path(&entry.cid) {
+                if let Err(e) = tokio::fs::remove_file(&path).await {
+                    warn!("Failed to remove evicted content {:?}: {}", path, e);
+                }
+            }
+        }
+
+        let total_size = CachedContent::total_size(&self.pg_pool, &self.schema).await?;
+        *self.size_estimate.lock().unwrap_or_else(|e| e.into_inner()) =
+            Some(u64::try_from(total_size).unwrap_or_default());
+        Ok(())
+    }
+
+    /// Returns the path of the file storing the content of a CID. Files are
+    /// named after the sha256 digest of the CID, so CIDs with a path map to
+    /// a single file, and spread over 256 subdirectories.
+    fn blob_path(&self, cid: &str) -> Option<PathBuf> {
+        let digest = hex::encode(Sha256::digest(cid.as_bytes()));
+        self.directory
+            .as_ref()
+            .map(|directory| directory.join(&digest[..2]).join(digest))
+    }
+
+    /// Reads the content of a CID from the directory, if it has the size
+    /// recorded in the cache
+    async fn read_blob(&self, cid: &str, size: i64) -> Option<Bytes> {
+        let data = tokio::fs::read(self.blob_path(cid)?).await.ok()?;
+        (data.len() as i64 == size).then(|| Bytes::from(data))
+    }
+}
@@ -6,17 +6,35 @@ use thiserror::Error;
 /// libraries
 #[derive(Error, Debug)]
 pub enum LibError {
+    #[error("Content is larger than {0} bytes")]
+    ContentTooLarge(usize),
     #[error("Extract name and extension error")]
     ExtractNameAndExtension,
+    #[error("Content failed integrity verification: {0}")]
+    IntegrityError(String),
+    #[error("Invalid CID: {0}")]
+    InvalidCid(String),
+    #[error("Invalid trusted proxy: {0}")]
+    InvalidTrustedProxy(String),
+    #[error("URL is not allowed: {0}")]
+    ForbiddenUrl(String),
+    #[error("Invalid IPFS gateway configuration: {0}")]
+    GatewayConfigError(String),
     #[error("Network error: {0}")]
     NetworkError(String),
-    #[error("Pinata error: {0}")]
-    PinataError(String),
+    #[error("Invalid pinning configuration: {0}")]
+    PinningConfigError(String),
+    #[error("Pinning error: {0}")]
+    PinningError(String),
     #[error("Postgres connection error: {0}")]
     PostgresConnectError(String),
     #[error("Resource does not exist")]
     ResourceNotFoundError(String),
     #[error(transparent)]
+    IO(#[from] std::io::Error),
+    #[error(transparent)]
+    Model(#[from] models::error::ModelError),
+    #[error(transparent)]
     Reqwest(#[from] reqwest::Error),
     #[error(transparent)]
     SerdeJson(#[from] serde_json::Error),
@@ -36,22 +36,26 @@ impl Image {
         }
         Ok(Some(response.bytes().await?.to_vec()))
     }
-    /// This function downloads an avatar, classifies it and stores it in the database
+    /// This function downloads an avatar, classifies it and stores it in the database.
+    /// The `api_key` needs the upload scope.
     pub async fn download_image_classify_and_store(
         url: String,
         reqwest_client: reqwest::Client,
         image_guard_url: String,
+        api_key: Option<String>,
     ) -> Result<(), LibError> {
         // Send request with multipart form
         let endpoint = format!("{}/upload_image_from_url", image_guard_url);
         info!("Uploading image to image guard: {}", endpoint);
 
-        let response = reqwest_client
+        let mut request = reqwest_client
             .post(endpoint)
             .timeout(std::time::Duration::from_secs(120))
-            .json(&Self::new(url.clone()))
-            .send()
-            .await?;
+            .json(&Self::new(url.clone()));
+        if let Some(api_key) = api_key {
+            request = request.bearer_auth(api_key);
+        }
+        let response = request.send().await?;
 
         let status = response.status();
         if !status.is_success() {
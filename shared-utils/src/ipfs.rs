@@ -1,25 +1,57 @@
 use crate::{
     error::LibError,
-    types::{MultiPartHandler, MultiPartHandlerJson},
+    ipfs_gateway::{Gateway, GatewayPool},
+    pinning::PinningPool,
+    types::{FetchedContent, MultiPartHandler, MultiPartHandlerJson},
+    unixfs::{verify_file, CarBlocks},
 };
+use bytes::Bytes;
+use cid::Cid;
 use macon::Builder;
-use reqwest::{
-    multipart::{Form, Part},
-    Client, Response, StatusCode,
-};
+use reqwest::{header::CONTENT_TYPE, Client, Response, StatusCode};
 use serde::Deserialize;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use tokio::time::sleep;
 use tracing::warn;
 
 /// The base delays for the retry mechanism and timeouts
 pub const BASE_DELAY: Duration = Duration::from_secs(1);
 pub const FETCH_TIMEOUT: Duration = Duration::from_secs(5);
-pub const PIN_TIMEOUT: Duration = Duration::from_secs(10);
-pub const PINATA_API_URL: &str = "https://api.pinata.cloud";
 pub const RETRY_ATTEMPTS: i32 = 3;
 
-/// The response from the IPFS gateway
+/// What to fetch from the gateways
+#[derive(Clone, Copy, Debug)]
+enum GatewayPath<'a> {
+    /// A CID, optionally followed by a path
+    Ipfs(&'a str),
+    /// An IPNS name, optionally followed by a path
+    Ipns(&'a str),
+}
+
+impl GatewayPath<'_> {
+    fn url(&self, gateway: &Gateway) -> String {
+        match self {
+            GatewayPath::Ipfs(cid) => gateway.config.fetch_url(cid),
+            GatewayPath::Ipns(name) => gateway.config.ipns_url(name),
+        }
+    }
+}
+
+/// Splits `<cid>/<path>` into the CID and the path
+fn split_ipfs_path(cid: &str) -> (&str, &str) {
+    cid.split_once('/').unwrap_or((cid, ""))
+}
+
+/// Reads the body of a gateway response, a failure to read it counts as a
+/// gateway failure
+async fn read_body(resp: Response, gateway: &Gateway) -> Result<Bytes, LibError> {
+    resp.bytes().await.map_err(|e| {
+        gateway.record_failure();
+        e.into()
+    })
+}
+
+/// The name, CID and size of an uploaded file, as returned by `ipfs add`
 #[derive(Deserialize, Default, Debug)]
 #[serde(rename_all = "PascalCase")]
 pub struct IpfsResponse {
@@ -30,43 +62,51 @@ pub struct IpfsResponse {
 
 /// This represents the current IPFS resolver implementation.
 /// It's responsible for fetching IPFS data from the configured
-/// IPFS gateway. Currently we are using [`reqwest`] as HTTP client
+/// pool of IPFS gateways. Currently we are using [`reqwest`] as HTTP client
 /// and we are implementing a simple exponential backoff retry mechanism
 /// to fetch the data from IPFS. You can configure the number of attempts
 /// by changing the `IPFS_RETRY_ATTEMPTS` constant.
+/// Fetched content is verified against the requested CID, set
+/// `verify_content` to `false` to trust the gateways instead.
+/// Uploads are pinned on the `pinning` backends, a resolver that only
+/// fetches doesn't need any.
 #[derive(Clone, Builder)]
 pub struct IPFSResolver {
     pub base_delay: Option<Duration>,
     pub fetch_timeout: Option<Duration>,
+    pub gateways: GatewayPool,
     pub http_client: Client,
-    pub ipfs_fetch_url: String,
-    pub ipfs_upload_url: String,
-    pub pin_timeout: Option<Duration>,
-    pub pinata_jwt: String,
-    pub pinata_gateway_token: Option<String>,
+    pub pinning: Option<PinningPool>,
     pub retry_attempts: Option<i32>,
+    pub verify_content: Option<bool>,
 }
 
 impl IPFSResolver {
-    /// Adds a remote pin to Pinata
-    async fn add_remote_pin_to_pinata(
-        &self,
-        cid: &str,
-        name: &str,
-    ) -> Result<Response, reqwest::Error> {
-        self.http_client
-            .post(self.format_add_remote_pin_to_pinata(cid, name))
-            .send()
-            .await
+    /// Fetches a file from IPFS using the configured gateways and returns
+    /// its content. `cid` can be followed by a path, e.g. `<cid>/image.png`.
+    pub async fn fetch_from_ipfs(&self, cid: &str) -> Result<FetchedContent, LibError> {
+        // Don't retry requests for content that can't be verified
+        if self.verify_content.unwrap_or(true) {
+            Cid::try_from(split_ipfs_path(cid).0)
+                .map_err(|_| LibError::InvalidCid(cid.to_string()))?;
+        }
+
+        self.fetch_with_retries(GatewayPath::Ipfs(cid)).await
+    }
+
+    /// Fetches the content an IPNS name points to. IPNS records are mutable
+    /// and resolved by the gateways, so the content can't be verified.
+    pub async fn fetch_from_ipns(&self, name: &str) -> Result<FetchedContent, LibError> {
+        self.fetch_with_retries(GatewayPath::Ipns(name)).await
     }
-    /// Fetches a file and returns its content as a string from IPFS
-    /// using the configured gateway.
-    pub async fn fetch_from_ipfs(&self, cid: &str) -> Result<Response, LibError> {
+
+    /// Fetches a path from the gateways, retrying with exponential backoff
+    async fn fetch_with_retries(&self, path: GatewayPath<'_>) -> Result<FetchedContent, LibError> {
         let mut attempts = 0;
 
         let response = loop {
             attempts += 1;
-            match self.fetch_from_ipfs_request(cid).await {
+            match self.fetch_from_ipfs_request(path).await {
                 Ok(body) => {
                     break Ok(body);
                 }
@@ -80,31 +120,31 @@ impl IPFSResolver {
         Ok(response)
     }
 
-    /// Returns the list of IPFS nodes to fetch from in a form of a tuple.
-    /// The first element is the node URL, the second element is a boolean
-    /// indicating whether the node requires a pinata gateway token.
-    /// The first node is the main node, the second node is the aquamarine node.
-    /// The aquamarine node is used as a failover node. This node requires a
-    /// pinata gateway token.
-    fn get_ipfs_nodes(&self) -> Vec<(&str, Option<String>)> {
-        vec![
-            (&self.ipfs_fetch_url, None),
-            (
-                "https://aquamarine-tragic-mockingbird-747.mypinata.cloud",
-                self.pinata_gateway_token.clone(),
-            ),
-        ]
-    }
+    /// Fetches a file from the gateways of the pool, best first. When racing
+    /// is enabled the two best gateways are queried at once.
+    async fn fetch_from_ipfs_request(
+        &self,
+        path: GatewayPath<'_>,
+    ) -> Result<FetchedContent, LibError> {
+        let ranked = self.gateways.ranked();
+        let mut remaining = &ranked[..];
+
+        if self.gateways.race {
+            if let [first, second, rest @ ..] = remaining {
+                match self.race_gateways(path, first, second).await {
+                    Ok(resp) => return Ok(resp),
+                    Err(e) => warn!("IPFS fetch raced on the two best gateways failed: {}", e),
+                }
+                remaining = rest;
+            }
+        }
 
-    /// Fetches a file from IPFS using the configured gateway.
-    async fn fetch_from_ipfs_request(&self, cid: &str) -> Result<Response, LibError> {
-        let nodes = self.get_ipfs_nodes();
-        for (node, pinata_token) in nodes {
-            match self.try_fetch_from_node(cid, node, &pinata_token).await {
+        for gateway in remaining {
+            match self.try_fetch_from_gateway(path, gateway).await {
                 Ok(resp) => {
                     return Ok(resp);
                 }
-                Err(e) => warn!("IPFS fetch from {} failed: {}", node, e),
+                Err(e) => warn!("IPFS fetch from {} failed: {}", gateway.config.url, e),
             }
         }
 
@@ -113,23 +153,150 @@ impl IPFSResolver {
         ))
     }
 
-    async fn try_fetch_from_node(
+    /// Fetches a file from two gateways at once and returns the first
+    /// successful response. The slower request is cancelled.
+    async fn race_gateways(
+        &self,
+        path: GatewayPath<'_>,
+        first: &Gateway,
+        second: &Gateway,
+    ) -> Result<FetchedContent, LibError> {
+        let first_fetch = self.try_fetch_from_gateway(path, first);
+        let second_fetch = self.try_fetch_from_gateway(path, second);
+        tokio::pin!(first_fetch, second_fetch);
+
+        tokio::select! {
+            result = &mut first_fetch => match result {
+                Ok(resp) => Ok(resp),
+                Err(e) => {
+                    warn!("IPFS fetch from {} failed: {}", first.config.url, e);
+                    second_fetch.await
+                }
+            },
+            result = &mut second_fetch => match result {
+                Ok(resp) => Ok(resp),
+                Err(e) => {
+                    warn!("IPFS fetch from {} failed: {}", second.config.url, e);
+                    first_fetch.await
+                }
+            },
+        }
+    }
+
+    /// Fetches a file from a gateway and records the outcome in its health.
+    /// Server errors and rate limiting count as gateway failures, other
+    /// unsuccessful statuses are returned as errors without penalizing the
+    /// gateway. Content that doesn't match its CID counts as a failure too.
+    async fn try_fetch_from_gateway(
+        &self,
+        path: GatewayPath<'_>,
+        gateway: &Gateway,
+    ) -> Result<FetchedContent, LibError> {
+        let _permit = gateway.acquire().await;
+        let started_at = Instant::now();
+
+        let resp = self.send_to_gateway(&path.url(gateway), gateway).await?;
+        let content_type = resp
+            .headers()
+            .get(CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_string);
+        let data = read_body(resp, gateway).await?;
+
+        let data = match path {
+            GatewayPath::Ipfs(cid) if self.verify_content.unwrap_or(true) => {
+                match self.verify_from_gateway(cid, &data, gateway).await? {
+                    // The gateway served other content than the CID's, so
+                    // only the verified CAR is used and it isn't rewarded
+                    Some(verified) => {
+                        return Ok(FetchedContent {
+                            data: verified,
+                            content_type,
+                        });
+                    }
+                    None => data,
+                }
+            }
+            _ => data,
+        };
+
+        gateway.record_success(started_at.elapsed());
+        Ok(FetchedContent { data, content_type })
+    }
+
+    /// Checks that the content served by a gateway matches its CID. Content
+    /// behind a path, or added with other settings than the UnixFS defaults,
+    /// can't be verified by hashing it again. In that case the blocks are
+    /// fetched from the same gateway as a CAR file and verified one by one.
+    /// Returns the content read from the CAR if it differs from the content
+    /// served, which counts as a failure of the gateway.
+    async fn verify_from_gateway(
         &self,
         cid: &str,
-        node: &str,
-        token: &Option<String>,
-    ) -> Result<Response, LibError> {
-        let url = self.format_ipfs_fetch_url(cid, node, token);
+        data: &Bytes,
+        gateway: &Gateway,
+    ) -> Result<Option<Bytes>, LibError> {
+        let (root, path) = split_ipfs_path(cid);
+        let root = Cid::try_from(root).map_err(|_| LibError::InvalidCid(root.to_string()))?;
+        if path.is_empty() && verify_file(&root, data).unwrap_or(false) {
+            return Ok(None);
+        }
 
+        let resp = self
+            .send_to_gateway(&gateway.config.car_url(cid), gateway)
+            .await?;
+        let car = read_body(resp, gateway).await?;
+        let segments = path
+            .split('/')
+            .filter(|segment| !segment.is_empty())
+            .collect::<Vec<_>>();
+        match CarBlocks::read(&car).and_then(|blocks| blocks.read_file(&root, &segments)) {
+            Ok(content) if content == data.as_ref() => Ok(None),
+            Ok(content) => {
+                warn!(
+                    "{} served content for {} that doesn't match its CID, using the verified CAR",
+                    gateway.config.url, cid
+                );
+                gateway.record_failure();
+                Ok(Some(content.into()))
+            }
+            Err(e) => {
+                warn!(
+                    "Content of {} served by {} failed verification: {}",
+                    cid, gateway.config.url, e
+                );
+                gateway.record_failure();
+                Err(e)
+            }
+        }
+    }
+
+    /// Sends a GET request to a gateway, with its auth and timeout
+    async fn send_to_gateway(&self, url: &str, gateway: &Gateway) -> Result<Response, LibError> {
         // Debug log the URL being called
         tracing::debug!("Attempting to fetch from URL: {}", url);
 
-        self.http_client
-            .get(&url)
-            .timeout(self.fetch_timeout.unwrap_or(FETCH_TIMEOUT))
-            .send()
-            .await
-            .map_err(|e| {
+        let mut request = self.http_client.get(url).timeout(
+            gateway
+                .config
+                .timeout()
+                .or(self.fetch_timeout)
+                .unwrap_or(FETCH_TIMEOUT),
+        );
+        if let Some(token) = gateway.config.bearer_token() {
+            request = request.bearer_auth(token);
+        }
+
+        match request.send().await {
+            Ok(resp) if resp.status().is_success() => Ok(resp),
+            Ok(resp) => {
+                let status = resp.status();
+                if status.is_server_error() || status == StatusCode::TOO_MANY_REQUESTS {
+                    gateway.record_failure();
+                }
+                Err(status.into())
+            }
+            Err(e) => {
                 // Log detailed error info
                 tracing::error!("Request failed: {:#?}", e);
                 if e.is_timeout() {
@@ -141,63 +308,9 @@ impl IPFSResolver {
                 if e.is_request() {
                     tracing::error!("Invalid request");
                 }
-                e.into()
-            })
-    }
-
-    /// Formats the URL to add a remote pin to Pinata
-    fn format_add_remote_pin_to_pinata(&self, cid: &str, name: &str) -> String {
-        format!(
-            "{}/api/v0/pin/remote/add?arg={}&service=Pinata&name={}",
-            self.ipfs_upload_url, cid, name
-        )
-    }
-
-    /// Formats the URL to fetch IPFS data
-    fn format_ipfs_fetch_url(
-        &self,
-        cid: &str,
-        ipfs_node: &str,
-        pinata_gateway_token: &Option<String>,
-    ) -> String {
-        if let Some(token) = pinata_gateway_token {
-            format!("{}/ipfs/{}?pinataGatewayToken={}", ipfs_node, cid, token)
-        } else {
-            format!("{}/ipfs/{}", ipfs_node, cid)
-        }
-    }
-
-    /// Formats the URL to pin a hash to IPFS
-    fn format_ipfs_pin_url(&self) -> String {
-        format!("{}/pinning/pinByHash", PINATA_API_URL)
-    }
-
-    /// Formats the URL to upload a file to IPFS
-    fn format_ipfs_upload_url(&self) -> String {
-        format!("{}/api/v0/add", self.ipfs_upload_url)
-    }
-
-    /// Formats the URL to pin a CID to local IPFS
-    fn format_pin_with_cid(&self, cid: &str) -> String {
-        format!("{}/api/v0/pin/add?arg={}", self.ipfs_upload_url, cid)
-    }
-
-    /// Handles retry logic for pin operations
-    async fn handle_existing_file_retry_logic(
-        &self,
-        e: reqwest::Error,
-        attempts: i32,
-    ) -> Result<(), LibError> {
-        if attempts < self.retry_attempts.unwrap_or(RETRY_ATTEMPTS) {
-            warn!("Pin error: {}, retrying... (attempt {})", e, attempts);
-            let backoff = self
-                .base_delay
-                .unwrap_or(BASE_DELAY)
-                .mul_f64(2_f64.powi(attempts - 1));
-            sleep(backoff).await;
-            Ok(())
-        } else {
-            Err(LibError::NetworkError(e.to_string()))
+                gateway.record_failure();
+                Err(e.into())
+            }
         }
     }
 
@@ -223,251 +336,35 @@ impl IPFSResolver {
         }
     }
 
-    /// Handles the error response for IPFS uploads
-    async fn handle_upload_error_response(
-        &self,
-        status: StatusCode,
-        attempts: i32,
-    ) -> Result<bool, LibError> {
-        if !status.is_success() {
-            warn!("IPFS upload failed with status {}", status);
-
-            if attempts < self.retry_attempts.unwrap_or(RETRY_ATTEMPTS) {
-                let backoff = self
-                    .base_delay
-                    .unwrap_or(BASE_DELAY)
-                    .mul_f64(2_f64.powi(attempts - 1));
-                sleep(backoff).await;
-                return Ok(true); // true means "should continue"
-            }
-            return Err(LibError::NetworkError(format!(
-                "Upload failed with status {}",
-                status
-            )));
-        }
-        Ok(false) // false means "don't continue"
-    }
-
-    /// Handles the retry error for IPFS uploads
-    async fn handle_upload_retry_error(
-        &self,
-        e: reqwest::Error,
-        attempts: i32,
-    ) -> Result<(), LibError> {
-        if attempts < self.retry_attempts.unwrap_or(RETRY_ATTEMPTS) {
-            warn!("Upload error: {}, retrying... (attempt {})", e, attempts);
-            let backoff = self
-                .base_delay
-                .unwrap_or(BASE_DELAY)
-                .mul_f64(2_f64.powi(attempts - 1));
-            sleep(backoff).await;
-            Ok(())
-        } else {
-            Err(match e.is_timeout() {
-                true => LibError::TimeoutError("IPFS upload timed out".into()),
-                false => LibError::NetworkError(e.to_string()),
-            })
-        }
-    }
-
-    /// Formats the multipart form to upload a file to IPFS
-    fn multipart_form(&self, multi_part_handler: MultiPartHandler) -> Form {
-        Form::new().part(
-            multi_part_handler.name.clone(),
-            Part::bytes(multi_part_handler.data.clone().to_vec())
-                .file_name(multi_part_handler.name.clone()),
-        )
-    }
-
-    /// Formats the multipart form to upload a json to IPFS
-    fn multipart_form_json(&self, multi_part_handler: MultiPartHandlerJson) -> Form {
-        Form::new().part(
-            multi_part_handler.name.clone(),
-            Part::bytes(serde_json::to_vec(&multi_part_handler.data).unwrap())
-                .file_name(multi_part_handler.name.clone())
-                .mime_str("application/json")
-                .unwrap(),
-        )
-    }
-
-    /// Pins an already uploaded file hash to Pinata. Keep in mind that
-    /// for this function to work, the file must have been uploaded to IPFS.
-    #[allow(dead_code)]
-    async fn pin_existing_file_hash(&self, hash: &str) -> Result<(), LibError> {
-        let mut attempts = 0;
-        loop {
-            attempts += 1;
-            match self.pin_to_ipfs_request(hash).await {
-                Ok(response) => {
-                    // Check if the response is successful
-                    if !response.status().is_success() {
-                        let status = response.status();
-                        let error_text = response.text().await.unwrap_or_default();
-                        warn!("Pinata pin failed: Status {}, Body: {}", status, error_text);
-
-                        if attempts < self.retry_attempts.unwrap_or(RETRY_ATTEMPTS) {
-                            let backoff = self
-                                .base_delay
-                                .unwrap_or(BASE_DELAY)
-                                .mul_f64(2_f64.powi(attempts - 1));
-                            sleep(backoff).await;
-                            continue;
-                        }
-
-                        return Err(LibError::PinataError(format!(
-                            "Failed to pin: Status {}, Body: {}",
-                            status, error_text
-                        )));
-                    }
-                    break Ok(());
-                }
-                Err(e) => match self.handle_existing_file_retry_logic(e, attempts).await {
-                    Ok(()) => continue,
-                    Err(e) => break Err(e),
-                },
-            }
-        }
-    }
-
-    /// Pins a hash to keep it persistent in IPFS
-    async fn pin_to_ipfs_request(&self, hash: &str) -> Result<Response, reqwest::Error> {
-        let json_body = serde_json::json!({
-            "hashToPin": hash,
-            "pinataMetadata": {
-                "name": format!("Pin request for {}", hash)
-            }
-        });
-
-        self.http_client
-            .post(self.format_ipfs_pin_url())
-            .header("Authorization", format!("Bearer {}", self.pinata_jwt))
-            .header("Content-Type", "application/json")
-            .json(&json_body)
-            .timeout(self.pin_timeout.unwrap_or(PIN_TIMEOUT))
-            .send()
-            .await
+    /// Returns the pinning backends, or an error if none are configured
+    fn pinning(&self) -> Result<&PinningPool, LibError> {
+        self.pinning
+            .as_ref()
+            .ok_or_else(|| LibError::PinningConfigError("no pinning backend configured".into()))
     }
 
-    /// Uploads and pins a file to IPFS using the configured gateway
+    /// Uploads and pins a file on the configured pinning backends.
     /// Returns an [`IpfsResponse`] with the `name`, `hash` and `size` of
     /// the uploaded file.
     pub async fn upload_to_ipfs_and_pin(
         &self,
         multi_part_handler: MultiPartHandler,
     ) -> Result<IpfsResponse, LibError> {
-        let mut attempts = 0;
-
-        loop {
-            attempts += 1;
-
-            match self
-                .upload_to_ipfs_request(multi_part_handler.clone())
-                .await
-            {
-                Ok(resp) => {
-                    let status = resp.status();
-                    let body = resp.text().await.unwrap_or_default();
-
-                    if self.handle_upload_error_response(status, attempts).await? {
-                        continue;
-                    }
-
-                    // Attempt to parse JSON from the body
-                    let result: IpfsResponse = serde_json::from_str(&body).map_err(|e| {
-                        warn!("Failed to parse JSON response: {}", e);
-                        LibError::NetworkError(format!("Invalid JSON: {}", body))
-                    })?;
-
-                    // Pin the CID to local IPFS
-                    self.pin_with_cid(&result.hash).await?;
-                    // Add a remote pin to Pinata
-                    self.add_remote_pin_to_pinata(&result.hash, &multi_part_handler.name)
-                        .await?;
-
-                    return Ok(result);
-                }
-                Err(e) => match self.handle_upload_retry_error(e, attempts).await {
-                    Ok(()) => continue,
-                    Err(e) => break Err(e),
-                },
-            }
-        }?
+        self.pinning()?
+            .pin(&multi_part_handler.name, multi_part_handler.data)
+            .await
     }
 
-    /// Uploads and pins a file to IPFS using the configured gateway
+    /// Uploads and pins a json on the configured pinning backends.
     /// Returns an [`IpfsResponse`] with the `name`, `hash` and `size` of
     /// the uploaded file.
     pub async fn upload_json_to_ipfs_and_pin(
         &self,
         multi_part_handler: MultiPartHandlerJson,
     ) -> Result<IpfsResponse, LibError> {
-        let mut attempts = 0;
-
-        loop {
-            attempts += 1;
-
-            match self
-                .upload_json_to_ipfs_request(multi_part_handler.clone())
-                .await
-            {
-                Ok(resp) => {
-                    let status = resp.status();
-                    let body = resp.text().await.unwrap_or_default();
-
-                    if self.handle_upload_error_response(status, attempts).await? {
-                        continue;
-                    }
-
-                    // Attempt to parse JSON from the body
-                    let result: IpfsResponse = serde_json::from_str(&body).map_err(|e| {
-                        warn!("Failed to parse JSON response: {}", e);
-                        LibError::NetworkError(format!("Invalid JSON: {}", body))
-                    })?;
-
-                    // Pin the CID to local IPFS
-                    self.pin_with_cid(&result.hash).await?;
-                    // Add a remote pin to Pinata
-                    self.add_remote_pin_to_pinata(&result.hash, &multi_part_handler.name)
-                        .await?;
-
-                    return Ok(result);
-                }
-                Err(e) => match self.handle_upload_retry_error(e, attempts).await {
-                    Ok(()) => continue,
-                    Err(e) => break Err(e),
-                },
-            }
-        }?
-    }
-    /// Pins a CID to local IPFS
-    async fn pin_with_cid(&self, cid: &str) -> Result<Response, reqwest::Error> {
-        self.http_client
-            .post(self.format_pin_with_cid(cid))
-            .send()
-            .await
-    }
-
-    /// Sends a request to upload a file to IPFS
-    async fn upload_to_ipfs_request(
-        &self,
-        multi_part_handler: MultiPartHandler,
-    ) -> Result<Response, reqwest::Error> {
-        self.http_client
-            .post(self.format_ipfs_upload_url())
-            .multipart(self.multipart_form(multi_part_handler.clone()))
-            .send()
-            .await
-    }
-
-    /// Sends a request to upload a file to IPFS
-    async fn upload_json_to_ipfs_request(
-        &self,
-        multi_part_handler: MultiPartHandlerJson,
-    ) -> Result<Response, reqwest::Error> {
-        self.http_client
-            .post(self.format_ipfs_upload_url())
-            .multipart(self.multipart_form_json(multi_part_handler.clone()))
-            .send()
+        let data = serde_json::to_vec(&multi_part_handler.data)?;
+        self.pinning()?
+            .pin(&multi_part_handler.name, data.into())
             .await
     }
 }
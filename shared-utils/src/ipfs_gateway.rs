@@ -0,0 +1,345 @@
+use crate::error::LibError;
+use serde::Deserialize;
+use std::{
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+/// The weight of the latest request in the rolling success rate and latency
+const HEALTH_SMOOTHING: f64 = 0.2;
+/// The number of consecutive failures after which a gateway is skipped
+const CIRCUIT_BREAKER_THRESHOLD: u32 = 3;
+/// How long a gateway is skipped once its circuit is open. It doubles every
+/// time the gateway fails again, up to [`MAX_CIRCUIT_OPEN_DURATION`].
+const CIRCUIT_OPEN_DURATION: Duration = Duration::from_secs(30);
+const MAX_CIRCUIT_OPEN_DURATION: Duration = Duration::from_secs(600);
+/// The latency a gateway starts with, before it served any request
+const INITIAL_LATENCY_MS: f64 = 500.0;
+
+/// How to authenticate against a gateway
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum GatewayAuth {
+    /// A Pinata dedicated gateway token, sent as the `pinataGatewayToken`
+    /// query parameter
+    PinataGatewayToken { token: String },
+    /// A token sent as `Authorization: Bearer <token>`
+    Bearer { token: String },
+}
+
+/// The configuration of an IPFS gateway. The pool is configured with a JSON
+/// array of gateways, e.g.
+/// `[{"url": "http://ipfs:8080", "timeout_ms": 5000, "max_concurrency": 32}]`
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct GatewayConfig {
+    pub url: String,
+    pub auth: Option<GatewayAuth>,
+    /// The request timeout, defaults to the fetch timeout of the resolver
+    pub timeout_ms: Option<u64>,
+    /// The maximum number of concurrent requests, unlimited by default
+    pub max_concurrency: Option<usize>,
+}
+
+impl GatewayConfig {
+    /// Creates the configuration of a gateway without auth nor limits
+    pub fn new(url: impl Into<String>) -> Self {
+        Self {
+            url: url.into(),
+            auth: None,
+            timeout_ms: None,
+            max_concurrency: None,
+        }
+    }
+
+    /// Formats the URL to fetch a CID from the gateway
+    pub fn fetch_url(&self, cid: &str) -> String {
+        self.namespace_url("ipfs", cid)
+    }
+
+    /// Formats the URL to fetch the content of an IPNS name from the gateway
+    pub fn ipns_url(&self, name: &str) -> String {
+        self.namespace_url("ipns", name)
+    }
+
+    fn namespace_url(&self, namespace: &str, path: &str) -> String {
+        let url = self.url.trim_end_matches('/');
+        match &self.auth {
+            Some(GatewayAuth::PinataGatewayToken { token }) => {
+                format!(
+                    "{}/{}/{}?pinataGatewayToken={}",
+                    url, namespace, path, token
+                )
+            }
+            _ => format!("{}/{}/{}", url, namespace, path),
+        }
+    }
+
+    /// Formats the URL to fetch a CID and its blocks as a CAR file, used to
+    /// verify the content block by block
+    pub fn car_url(&self, cid: &str) -> String {
+        let url = self.fetch_url(cid);
+        let separator = if url.contains('?') { '&' } else { '?' };
+        format!("{}{}format=car", url, separator)
+    }
+
+    /// Returns the bearer token of the gateway, if any
+    pub fn bearer_token(&self) -> Option<&str> {
+        match &self.auth {
+            Some(GatewayAuth::Bearer { token }) => Some(token),
+            _ => None,
+        }
+    }
+
+    /// Returns the request timeout of the gateway, if configured
+    pub fn timeout(&self) -> Option<Duration> {
+        self.timeout_ms.map(Duration::from_millis)
+    }
+}
+
+/// The rolling health of a gateway
+#[derive(Clone, Debug)]
+struct GatewayHealth {
+    success_rate: f64,
+    latency_ms: f64,
+    consecutive_failures: u32,
+    open_until: Option<Instant>,
+}
+
+impl Default for GatewayHealth {
+    fn default() -> Self {
+        Self {
+            success_rate: 1.0,
+            latency_ms: INITIAL_LATENCY_MS,
+            consecutive_failures: 0,
+            open_until: None,
+        }
+    }
+}
+
+impl GatewayHealth {
+    /// The score used to order the gateways, higher is better. A gateway that
+    /// always answers in 100ms scores ten times better than one that answers
+    /// in a second.
+    fn score(&self) -> f64 {
+        self.success_rate / (1.0 + self.latency_ms / 100.0)
+    }
+
+    /// Returns true if the circuit of the gateway is open, i.e. it should be
+    /// skipped. Once the open duration elapsed the gateway is tried again.
+    fn is_open(&self, now: Instant) -> bool {
+        self.open_until.is_some_and(|open_until| now < open_until)
+    }
+
+    fn record_success(&mut self, latency: Duration) {
+        let latency_ms = latency.as_secs_f64() * 1000.0;
+        self.success_rate += HEALTH_SMOOTHING * (1.0 - self.success_rate);
+        self.latency_ms += HEALTH_SMOOTHING * (latency_ms - self.latency_ms);
+        self.consecutive_failures = 0;
+        self.open_until = None;
+    }
+
+    fn record_failure(&mut self, now: Instant) {
+        self.success_rate -= HEALTH_SMOOTHING * self.success_rate;
+        self.consecutive_failures += 1;
+        if self.consecutive_failures >= CIRCUIT_BREAKER_THRESHOLD {
+            let trips = self.consecutive_failures - CIRCUIT_BREAKER_THRESHOLD;
+            let open_duration = CIRCUIT_OPEN_DURATION
+                .saturating_mul(2_u32.saturating_pow(trips))
+                .min(MAX_CIRCUIT_OPEN_DURATION);
+            self.open_until = Some(now + open_duration);
+        }
+    }
+}
+
+/// A gateway of the pool, with its concurrency limit and health
+#[derive(Debug)]
+pub struct Gateway {
+    pub config: GatewayConfig,
+    permits: Option<Arc<Semaphore>>,
+    health: Mutex<GatewayHealth>,
+}
+
+impl Gateway {
+    fn new(config: GatewayConfig) -> Self {
+        Self {
+            permits: config
+                .max_concurrency
+                .map(|max_concurrency| Arc::new(Semaphore::new(max_concurrency.max(1)))),
+            config,
+            health: Mutex::new(GatewayHealth::default()),
+        }
+    }
+
+    fn health(&self) -> std::sync::MutexGuard<'_, GatewayHealth> {
+        self.health.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Waits for a request slot, if the gateway has a concurrency limit
+    pub async fn acquire(&self) -> Option<OwnedSemaphorePermit> {
+        match &self.permits {
+            Some(permits) => permits.clone().acquire_owned().await.ok(),
+            None => None,
+        }
+    }
+
+    /// Records a successful request and its latency
+    pub fn record_success(&self, latency: Duration) {
+        self.health().record_success(latency);
+    }
+
+    /// Records a failed request, opening the circuit after repeated failures
+    pub fn record_failure(&self) {
+        self.health().record_failure(Instant::now());
+    }
+}
+
+/// A pool of IPFS gateways. Gateways are tried in order of their rolling
+/// health score, gateways that keep failing are skipped for a while. The
+/// pool is cheap to clone and clones share the health of the gateways.
+#[derive(Clone, Debug)]
+pub struct GatewayPool {
+    gateways: Arc<Vec<Gateway>>,
+    /// Send the first request to the two best gateways at once and keep the
+    /// fastest response
+    pub race: bool,
+}
+
+impl GatewayPool {
+    /// Creates a pool from the configured gateways
+    pub fn new(configs: Vec<GatewayConfig>, race: bool) -> Result<Self, LibError> {
+        if configs.is_empty() {
+            return Err(LibError::GatewayConfigError(
+                "at least one IPFS gateway is required".into(),
+            ));
+        }
+        Ok(Self {
+            gateways: Arc::new(configs.into_iter().map(Gateway::new).collect()),
+            race,
+        })
+    }
+
+    /// Creates a pool from the JSON array of gateways, falling back to a
+    /// single gateway at `default_url` when no gateways are configured
+    pub fn from_config(
+        gateways: Option<&str>,
+        default_url: &str,
+        race: bool,
+    ) -> Result<Self, LibError> {
+        let configs = match gateways.filter(|gateways| !gateways.trim().is_empty()) {
+            Some(gateways) => serde_json::from_str(gateways)
+                .map_err(|e| LibError::GatewayConfigError(e.to_string()))?,
+            None => vec![GatewayConfig::new(default_url)],
+        };
+        Self::new(configs, race)
+    }
+
+    /// Returns the gateways to try, best first. Gateways with an open circuit
+    /// come last, so they are only used when every other gateway failed.
+    pub fn ranked(&self) -> Vec<&Gateway> {
+        let now = Instant::now();
+        let mut ranked = self
+            .gateways
+            .iter()
+            .map(|gateway| {
+                let health = gateway.health();
+                (gateway, health.is_open(now), health.score())
+            })
+            .collect::<Vec<_>>();
+        ranked.sort_by(|(_, a_open, a_score), (_, b_open, b_score)| {
+            a_open.cmp(b_open).then(b_score.total_cmp(a_score))
+        });
+        ranked.into_iter().map(|(gateway, _, _)| gateway).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_config() {
+        let pool = GatewayPool::from_config(
+            Some(
+                r#"[
+                    {"url": "http://ipfs:8080", "timeout_ms": 2000},
+                    {"url": "https://gateway.example", "max_concurrency": 4,
+                     "auth": {"type": "pinata_gateway_token", "token": "secret"}}
+                ]"#,
+            ),
+            "http://unused",
+            false,
+        )
+        .unwrap();
+
+        let ranked = pool.ranked();
+        assert_eq!(ranked.len(), 2);
+        assert_eq!(
+            ranked[0].config.timeout(),
+            Some(Duration::from_millis(2000))
+        );
+        assert_eq!(
+            ranked[1].config.fetch_url("Qm"),
+            "https://gateway.example/ipfs/Qm?pinataGatewayToken=secret"
+        );
+        assert_eq!(
+            ranked[1].config.car_url("Qm"),
+            "https://gateway.example/ipfs/Qm?pinataGatewayToken=secret&format=car"
+        );
+
+        let pool = GatewayPool::from_config(None, "http://ipfs:8080/", false).unwrap();
+        assert_eq!(
+            pool.ranked()[0].config.fetch_url("Qm"),
+            "http://ipfs:8080/ipfs/Qm"
+        );
+        assert_eq!(
+            pool.ranked()[0].config.ipns_url("example.eth"),
+            "http://ipfs:8080/ipns/example.eth"
+        );
+        assert!(GatewayPool::from_config(Some("[]"), "http://ipfs:8080", false).is_err());
+    }
+
+    #[test]
+    fn test_ranking_prefers_fast_and_healthy_gateways() {
+        let pool = GatewayPool::new(
+            vec![
+                GatewayConfig::new("http://slow"),
+                GatewayConfig::new("http://fast"),
+            ],
+            false,
+        )
+        .unwrap();
+
+        pool.gateways[0].record_success(Duration::from_secs(2));
+        pool.gateways[1].record_success(Duration::from_millis(50));
+        assert_eq!(pool.ranked()[0].config.url, "http://fast");
+
+        // Failing gateways go to the back once their circuit opens
+        for _ in 0..CIRCUIT_BREAKER_THRESHOLD {
+            pool.gateways[1].record_failure();
+        }
+        assert_eq!(pool.ranked()[0].config.url, "http://slow");
+    }
+
+    #[test]
+    fn test_circuit_breaker() {
+        let now = Instant::now();
+        let mut health = GatewayHealth::default();
+
+        for _ in 0..CIRCUIT_BREAKER_THRESHOLD - 1 {
+            health.record_failure(now);
+        }
+        assert!(!health.is_open(now));
+
+        health.record_failure(now);
+        assert!(health.is_open(now));
+        assert!(!health.is_open(now + CIRCUIT_OPEN_DURATION));
+
+        // Failing again after the cooldown doubles it
+        health.record_failure(now);
+        assert!(health.is_open(now + CIRCUIT_OPEN_DURATION));
+
+        health.record_success(Duration::from_millis(100));
+        assert!(!health.is_open(now));
+    }
+}
@@ -0,0 +1,504 @@
+use models::atom::AtomType;
+use serde_json::{Map, Value};
+use std::{collections::HashMap, str::FromStr, sync::LazyLock};
+use tracing::warn;
+
+/// The IRIs of the schema.org vocabulary, which is used with both schemes
+const SCHEMA_ORG_IRIS: [&str; 2] = ["http://schema.org/", "https://schema.org/"];
+/// The URLs of the schema.org context, without their scheme
+const SCHEMA_ORG_CONTEXT_URLS: [&str; 3] = [
+    "schema.org",
+    "schema.org/docs/jsonldcontext.jsonld",
+    "schema.org/docs/jsonldcontext.json",
+];
+/// How many terms a term or prefix can go through before it's expanded, so
+/// that cyclic definitions don't loop forever
+const MAX_EXPANSION_DEPTH: usize = 8;
+
+/// An offline copy of the schema.org context: its vocabulary, its prefixes
+/// and the keyword aliases it defines. Remote contexts are never fetched.
+static SCHEMA_ORG_CONTEXT: LazyLock<Context> = LazyLock::new(|| {
+    let document: Value = serde_json::from_str(include_str!("schema_org_context.jsonld"))
+        .expect("the schema.org context is valid JSON");
+    Context::default().merge(&document["@context"])
+});
+
+/// A node of a JSON-LD document, with its type and properties expanded to IRIs
+#[derive(Debug, Clone, PartialEq)]
+pub struct JsonLdNode {
+    pub id: Option<String>,
+    /// The expanded IRIs of the `@type`, or the type as written if it can't
+    /// be expanded
+    pub types: Vec<String>,
+    /// The properties, by their expanded IRI. Properties that can't be
+    /// expanded are dropped, as JSON-LD does.
+    pub properties: Map<String, Value>,
+}
+
+impl JsonLdNode {
+    /// Returns the local names of the schema.org types of the node, e.g.
+    /// `Person` for `http://schema.org/Person`
+    pub fn schema_org_types(&self) -> impl Iterator<Item = &str> {
+        self.types.iter().filter_map(|iri| schema_org_name(iri))
+    }
+
+    /// Returns the node as a schema.org object of the given type, the way the
+    /// `create_*_from_obj` functions of the resolver read it: schema.org
+    /// properties by their local name, with a single plain value each.
+    pub fn to_schema_org_object(&self, schema_org_type: &str) -> Value {
+        let mut object = Map::new();
+        object.insert("@type".to_string(), schema_org_type.into());
+        for (iri, value) in &self.properties {
+            let compacted = schema_org_name(iri).zip(compact_value(value));
+            if let Some((name, value)) = compacted {
+                object.insert(name.to_string(), value);
+            }
+        }
+        Value::Object(object)
+    }
+
+    /// Returns the node as an expanded JSON object
+    fn into_value(self) -> Value {
+        let mut object = Map::new();
+        if let Some(id) = self.id {
+            object.insert("@id".to_string(), id.into());
+        }
+        if !self.types.is_empty() {
+            object.insert("@type".to_string(), self.types.into());
+        }
+        object.extend(self.properties);
+        Value::Object(object)
+    }
+}
+
+/// The active context of a JSON-LD node
+#[derive(Debug, Clone, Default)]
+struct Context {
+    /// The `@vocab`, as written
+    vocab: Option<String>,
+    /// The terms and prefixes, with the IRI or keyword they stand for, as
+    /// written
+    terms: HashMap<String, String>,
+}
+
+impl Context {
+    /// Returns the context updated with a local `@context`, which can be a
+    /// context URL, an object of term definitions, or an array of both
+    fn merge(&self, local: &Value) -> Context {
+        match local {
+            Value::Null => Context::default(),
+            Value::String(url) if is_schema_org_context(url) => {
+                let mut context = self.clone();
+                context.vocab = SCHEMA_ORG_CONTEXT.vocab.clone();
+                context.terms.extend(SCHEMA_ORG_CONTEXT.terms.clone());
+                context
+            }
+            Value::String(url) => {
+                warn!("Ignoring the remote JSON-LD context {}", url);
+                self.clone()
+            }
+            Value::Array(contexts) => contexts
+                .iter()
+                .fold(self.clone(), |context, local| context.merge(local)),
+            Value::Object(definitions) => {
+                let mut context = self.clone();
+                for (term, definition) in definitions {
+                    match (term.as_str(), definition) {
+                        ("@vocab", Value::String(vocab)) => context.vocab = Some(vocab.clone()),
+                        ("@vocab", Value::Null) => context.vocab = None,
+                        // Other keywords, e.g. `@version` or `@language`, don't
+                        // change how IRIs are expanded
+                        (term, _) if term.starts_with('@') => {}
+                        (_, Value::String(iri)) => {
+                            context.terms.insert(term.clone(), iri.clone());
+                        }
+                        (_, Value::Object(definition)) => {
+                            match definition.get("@id").and_then(Value::as_str) {
+                                Some(iri) => {
+                                    context.terms.insert(term.clone(), iri.to_string());
+                                }
+                                // Without an `@id`, the term is relative to the vocabulary
+                                None => {
+                                    context.terms.remove(term);
+                                }
+                            }
+                        }
+                        _ => {
+                            context.terms.remove(term);
+                        }
+                    }
+                }
+                context
+            }
+            _ => self.clone(),
+        }
+    }
+
+    /// Expands a term, a compact IRI (`prefix:suffix`) or an absolute IRI.
+    /// Terms not defined by the context are only expanded against the
+    /// vocabulary if `vocab_relative` is set, as for properties and types.
+    fn expand_iri(&self, value: &str, vocab_relative: bool) -> Option<String> {
+        self.expand_iri_at(value, vocab_relative, 0)
+    }
+
+    fn expand_iri_at(&self, value: &str, vocab_relative: bool, depth: usize) -> Option<String> {
+        if value.starts_with('@') {
+            return Some(value.to_string());
+        }
+        if depth > MAX_EXPANSION_DEPTH {
+            return None;
+        }
+        let term = self.terms.get(value).filter(|_| vocab_relative);
+        if let Some(definition) = term {
+            return self.expand_iri_at(definition, true, depth + 1);
+        }
+        if let Some((prefix, suffix)) = value.split_once(':') {
+            if suffix.starts_with("//") {
+                return Some(value.to_string());
+            }
+            if let Some(definition) = self.terms.get(prefix) {
+                let prefix = self.expand_iri_at(definition, true, depth + 1)?;
+                return Some(format!("{}{}", prefix, suffix));
+            }
+            // An absolute IRI with another scheme, e.g. `urn:`, or a blank node
+            return Some(value.to_string());
+        }
+        let vocab = self.vocab.as_ref().filter(|_| vocab_relative);
+        if let Some(vocab) = vocab {
+            let vocab = self
+                .expand_iri_at(vocab, false, depth + 1)
+                .unwrap_or_else(|| vocab.clone());
+            return Some(format!("{}{}", vocab, value));
+        }
+        None
+    }
+
+    /// Expands a type, keeping it as written if it can't be expanded
+    fn expand_type(&self, value: &str) -> String {
+        self.expand_iri(value, true)
+            .unwrap_or_else(|| value.to_string())
+    }
+}
+
+/// Normalizes a JSON-LD document into its nodes: the top-level object if it
+/// has a type, followed by the nodes of its `@graph`. Returns `None` if the
+/// document isn't an object with a `@context`.
+pub fn normalize(json: &Value) -> Option<Vec<JsonLdNode>> {
+    let document = json.as_object()?;
+    let context = Context::default().merge(document.get("@context")?);
+
+    let mut nodes = Vec::new();
+    let top = expand_node(&Context::default(), document);
+    if !top.types.is_empty() {
+        nodes.push(top);
+    }
+    if let Some(graph) = document.get("@graph") {
+        nodes.extend(
+            as_slice(graph)
+                .iter()
+                .filter_map(Value::as_object)
+                .map(|node| expand_node(&context, node)),
+        );
+    }
+    Some(nodes)
+}
+
+/// Returns the node the resolver turns into an atom, with its schema.org
+/// type: the first node with a supported schema.org type, falling back to
+/// the first node with any schema.org type.
+pub fn schema_org_node(nodes: &[JsonLdNode]) -> Option<(&JsonLdNode, &str)> {
+    nodes
+        .iter()
+        .find_map(|node| {
+            node.schema_org_types()
+                .find(|t| AtomType::from_str(t).is_ok())
+                .map(|t| (node, t))
+        })
+        .or_else(|| {
+            nodes
+                .iter()
+                .find_map(|node| node.schema_org_types().next().map(|t| (node, t)))
+        })
+}
+
+/// Returns the document with the `@type` of every node expanded to IRIs, e.g.
+/// `ex:Badge` to `https://example.com/vocab#Badge`. Everything else is kept
+/// as written.
+pub fn with_expanded_types(json: &Value) -> Value {
+    expand_types(&Context::default(), json)
+}
+
+fn expand_types(context: &Context, value: &Value) -> Value {
+    match value {
+        Value::Array(values) => values.iter().map(|v| expand_types(context, v)).collect(),
+        Value::Object(node) => {
+            let context = match node.get("@context") {
+                Some(local) => context.merge(local),
+                None => context.clone(),
+            };
+            let node = node
+                .iter()
+                .map(|(key, value)| {
+                    let value = if key == "@context" {
+                        value.clone()
+                    } else if context.expand_iri(key, true).as_deref() == Some("@type") {
+                        match value {
+                            Value::String(t) => context.expand_type(t).into(),
+                            Value::Array(types) => types
+                                .iter()
+                                .map(|t| match t.as_str() {
+                                    Some(t) => context.expand_type(t).into(),
+                                    None => t.clone(),
+                                })
+                                .collect(),
+                            _ => value.clone(),
+                        }
+                    } else {
+                        expand_types(&context, value)
+                    };
+                    (key.clone(), value)
+                })
+                .collect();
+            Value::Object(node)
+        }
+        _ => value.clone(),
+    }
+}
+
+/// Expands a node object, after applying its own `@context` to the active one
+fn expand_node(context: &Context, node: &Map<String, Value>) -> JsonLdNode {
+    let context = match node.get("@context") {
+        Some(local) => context.merge(local),
+        None => context.clone(),
+    };
+
+    let mut expanded = JsonLdNode {
+        id: None,
+        types: Vec::new(),
+        properties: Map::new(),
+    };
+    for (key, value) in node {
+        match context.expand_iri(key, true).as_deref() {
+            Some("@id") => expanded.id = value.as_str().map(str::to_string),
+            Some("@type") => expanded.types.extend(
+                as_slice(value)
+                    .iter()
+                    .filter_map(Value::as_str)
+                    .map(|t| context.expand_type(t)),
+            ),
+            // Other keywords, e.g. `@context` or `@graph`
+            Some(keyword) if keyword.starts_with('@') => {}
+            Some(iri) => {
+                expanded
+                    .properties
+                    .insert(iri.to_string(), expand_value(&context, value));
+            }
+            None => {}
+        }
+    }
+    expanded
+}
+
+/// Expands a property value: nested nodes are expanded, and value objects
+/// (`{"@value": ...}`) are replaced by their value
+fn expand_value(context: &Context, value: &Value) -> Value {
+    match value {
+        Value::Array(values) => values.iter().map(|v| expand_value(context, v)).collect(),
+        Value::Object(object) => match object.get("@value") {
+            Some(literal) => literal.clone(),
+            None => expand_node(context, object).into_value(),
+        },
+        _ => value.clone(),
+    }
+}
+
+/// Compacts an expanded value to a single plain value: the first value of an
+/// array, the IRI of a link, or a nested object with schema.org names
+fn compact_value(value: &Value) -> Option<Value> {
+    match value {
+        Value::Null => None,
+        Value::Array(values) => values.iter().find_map(compact_value),
+        Value::Object(node) => {
+            // Links to other nodes, e.g. `"image": {"@id": "ipfs://..."}`
+            if let Some(id) = node.get("@id").filter(|_| node.len() == 1) {
+                return Some(id.clone());
+            }
+            let mut object = Map::new();
+            for (key, value) in node {
+                if key == "@type" {
+                    if let Some(name) = as_slice(value)
+                        .iter()
+                        .filter_map(Value::as_str)
+                        .find_map(schema_org_name)
+                    {
+                        object.insert(key.clone(), name.into());
+                    }
+                } else if let Some((name, value)) = schema_org_name(key).zip(compact_value(value)) {
+                    object.insert(name.to_string(), value);
+                }
+            }
+            Some(Value::Object(object))
+        }
+        _ => Some(value.clone()),
+    }
+}
+
+/// Returns the local name of a schema.org IRI
+fn schema_org_name(iri: &str) -> Option<&str> {
+    SCHEMA_ORG_IRIS
+        .iter()
+        .find_map(|vocab| iri.strip_prefix(vocab))
+        .filter(|name| !name.is_empty() && !name.contains('/'))
+}
+
+/// Returns whether a context URL is the schema.org context
+fn is_schema_org_context(url: &str) -> bool {
+    let url = url.trim().trim_end_matches('/');
+    url.strip_prefix("https://")
+        .or_else(|| url.strip_prefix("http://"))
+        .is_some_and(|rest| SCHEMA_ORG_CONTEXT_URLS.contains(&rest))
+}
+
+/// Returns the values of a JSON-LD property, which can be a single value or
+/// an array of them
+fn as_slice(value: &Value) -> &[Value] {
+    match value {
+        Value::Array(values) => values,
+        value => std::slice::from_ref(value),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    /// Returns the schema.org object of the first node of a document
+    fn schema_org_object(json: Value) -> Value {
+        let nodes = normalize(&json).unwrap();
+        let schema_org_type = nodes[0].schema_org_types().next().unwrap();
+        nodes[0].to_schema_org_object(schema_org_type)
+    }
+
+    #[test]
+    fn test_normalize_string_context() {
+        for context in [
+            "https://schema.org",
+            "https://schema.org/",
+            "http://schema.org",
+            "http://schema.org/",
+        ] {
+            let object = schema_org_object(json!({
+                "@context": context,
+                "@type": "Person",
+                "name": "Alice"
+            }));
+            assert_eq!(object, json!({"@type": "Person", "name": "Alice"}));
+        }
+    }
+
+    #[test]
+    fn test_normalize_array_and_object_contexts() {
+        let object = schema_org_object(json!({
+            "@context": ["https://schema.org", {"ex": "https://example.com/vocab#"}],
+            "type": "Organization",
+            "name": "Intuition",
+            "ex:founded": "2023"
+        }));
+        assert_eq!(
+            object,
+            json!({"@type": "Organization", "name": "Intuition"})
+        );
+
+        let object = schema_org_object(json!({
+            "@context": {"@vocab": "https://schema.org/"},
+            "@type": "Book",
+            "name": "Dune"
+        }));
+        assert_eq!(object, json!({"@type": "Book", "name": "Dune"}));
+    }
+
+    #[test]
+    fn test_normalize_prefixed_and_multiple_types() {
+        let nodes = normalize(&json!({
+            "@context": {"schema": "https://schema.org/", "ex": "https://example.com/vocab#"},
+            "@type": ["ex:Member", "schema:Person"],
+            "schema:name": {"@value": "Alice"},
+            "schema:image": {"@id": "ipfs://QmcqqAoEQLAP84ptTY1VjL7UoXMbGQ8sjyAPHXog8Ynbrt"},
+            "schema:url": ["https://alice.example.com", "https://example.com/alice"]
+        }))
+        .unwrap();
+
+        assert_eq!(
+            nodes[0].types,
+            vec![
+                "https://example.com/vocab#Member",
+                "https://schema.org/Person"
+            ]
+        );
+        assert_eq!(
+            nodes[0].schema_org_types().collect::<Vec<_>>(),
+            vec!["Person"]
+        );
+        assert_eq!(
+            nodes[0].to_schema_org_object("Person"),
+            json!({
+                "@type": "Person",
+                "name": "Alice",
+                "image": "ipfs://QmcqqAoEQLAP84ptTY1VjL7UoXMbGQ8sjyAPHXog8Ynbrt",
+                "url": "https://alice.example.com"
+            })
+        );
+    }
+
+    #[test]
+    fn test_normalize_graph() {
+        let nodes = normalize(&json!({
+            "@context": "https://schema.org",
+            "@graph": [
+                {"@type": "WebSite", "name": "Example"},
+                {
+                    "@type": "Place",
+                    "name": "Eiffel Tower",
+                    "geo": {"@type": "GeoCoordinates", "latitude": 48.8584, "longitude": 2.2945}
+                }
+            ]
+        }))
+        .unwrap();
+
+        assert_eq!(nodes.len(), 2);
+        assert_eq!(
+            nodes[1].to_schema_org_object("Place"),
+            json!({
+                "@type": "Place",
+                "name": "Eiffel Tower",
+                "geo": {"@type": "GeoCoordinates", "latitude": 48.8584, "longitude": 2.2945}
+            })
+        );
+    }
+
+    #[test]
+    fn test_normalize_unknown_vocabulary() {
+        let json = json!({
+            "@context": {"ex": "https://example.com/vocab#"},
+            "@type": "ex:Badge",
+            "name": "Early adopter"
+        });
+        let nodes = normalize(&json).unwrap();
+        assert_eq!(nodes[0].types, vec!["https://example.com/vocab#Badge"]);
+        assert_eq!(nodes[0].schema_org_types().count(), 0);
+        // Without a vocabulary, `name` can't be expanded
+        assert!(nodes[0].properties.is_empty());
+
+        assert_eq!(
+            with_expanded_types(&json),
+            json!({
+                "@context": {"ex": "https://example.com/vocab#"},
+                "@type": "https://example.com/vocab#Badge",
+                "name": "Early adopter"
+            })
+        );
+
+        assert!(normalize(&json!({"@type": "Person", "name": "Alice"})).is_none());
+    }
+}
@@ -1,5 +1,14 @@
+pub mod atom_metadata;
+pub mod auth;
+pub mod content_cache;
 pub mod error;
 pub mod image;
 pub mod ipfs;
+pub mod ipfs_gateway;
+pub mod json_ld;
+pub mod pinning;
 pub mod postgres;
+pub mod rate_limit;
+pub mod safe_fetch;
 pub mod types;
+pub mod unixfs;
@@ -0,0 +1,448 @@
+use crate::{
+    error::LibError,
+    ipfs::{IpfsResponse, BASE_DELAY, RETRY_ATTEMPTS},
+    unixfs::add_file,
+};
+use async_trait::async_trait;
+use bytes::Bytes;
+use reqwest::{
+    multipart::{Form, Part},
+    Client, StatusCode,
+};
+use serde::Deserialize;
+use std::{future::Future, io::ErrorKind, path::PathBuf, sync::Arc, time::Duration};
+use tokio::time::sleep;
+use tracing::warn;
+
+/// The timeout of the requests to the pinning services
+pub const PIN_TIMEOUT: Duration = Duration::from_secs(10);
+/// The default Pinata API
+pub const PINATA_API_URL: &str = "https://api.pinata.cloud";
+
+/// The configuration of a pinning backend. Backends are configured with a
+/// JSON array, e.g.
+/// `[{"type": "kubo", "url": "http://ipfs:5001"}, {"type": "pinata", "jwt": "..."}]`
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum PinningConfig {
+    /// A Kubo node, through its RPC API
+    Kubo { url: String },
+    /// The Pinata API, `url` defaults to [`PINATA_API_URL`]
+    Pinata { url: Option<String>, jwt: String },
+    /// A local directory, where files are stored under their CID
+    Filesystem { directory: String },
+}
+
+impl PinningConfig {
+    /// Creates the backend described by the configuration
+    fn into_service(self, http_client: &Client) -> Arc<dyn PinningService> {
+        match self {
+            PinningConfig::Kubo { url } => Arc::new(KuboPinning {
+                http_client: http_client.clone(),
+                url,
+            }),
+            PinningConfig::Pinata { url, jwt } => Arc::new(PinataPinning {
+                http_client: http_client.clone(),
+                url: url.unwrap_or_else(|| PINATA_API_URL.to_string()),
+                jwt,
+            }),
+            PinningConfig::Filesystem { directory } => Arc::new(FilesystemPinning {
+                directory: directory.into(),
+            }),
+        }
+    }
+}
+
+/// A service keeping uploaded content available on IPFS
+#[async_trait]
+pub trait PinningService: Send + Sync {
+    /// The name of the backend, used in logs
+    fn name(&self) -> String;
+
+    /// Stores and pins a file, and returns the CID the backend gave it
+    async fn pin(&self, name: &str, data: &Bytes) -> Result<String, LibError>;
+
+    /// Returns true if the backend still pins a CID
+    async fn is_pinned(&self, cid: &str) -> Result<bool, LibError>;
+}
+
+/// The response of Kubo to `pin/ls`
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct KuboPinList {
+    keys: serde_json::Map<String, serde_json::Value>,
+}
+
+/// Pins files to a Kubo node with `ipfs add`
+struct KuboPinning {
+    http_client: Client,
+    url: String,
+}
+
+#[async_trait]
+impl PinningService for KuboPinning {
+    fn name(&self) -> String {
+        format!("kubo ({})", self.url)
+    }
+
+    async fn pin(&self, name: &str, data: &Bytes) -> Result<String, LibError> {
+        let form = Form::new().part(
+            "file",
+            Part::bytes(data.to_vec()).file_name(name.to_string()),
+        );
+        let resp = self
+            .http_client
+            .post(format!("{}/api/v0/add?pin=true&cid-version=0", self.url))
+            .multipart(form)
+            .timeout(PIN_TIMEOUT)
+            .send()
+            .await?;
+        if !resp.status().is_success() {
+            return Err(resp.status().into());
+        }
+        Ok(resp.json::<IpfsResponse>().await?.hash)
+    }
+
+    async fn is_pinned(&self, cid: &str) -> Result<bool, LibError> {
+        let resp = self
+            .http_client
+            .post(format!(
+                "{}/api/v0/pin/ls?arg={}&type=recursive",
+                self.url, cid
+            ))
+            .timeout(PIN_TIMEOUT)
+            .send()
+            .await?;
+        match resp.status() {
+            status if status.is_success() => Ok(!resp.json::<KuboPinList>().await?.keys.is_empty()),
+            // Kubo answers with an error when the CID is not pinned
+            StatusCode::INTERNAL_SERVER_ERROR => {
+                let body = resp.text().await.unwrap_or_default();
+                if body.contains("not pinned") {
+                    Ok(false)
+                } else {
+                    Err(LibError::PinningError(body))
+                }
+            }
+            status => Err(status.into()),
+        }
+    }
+}
+
+/// The response of Pinata to a file upload
+#[derive(Deserialize)]
+struct PinataUpload {
+    #[serde(rename = "IpfsHash")]
+    ipfs_hash: String,
+}
+
+/// The response of Pinata to a pin list query
+#[derive(Deserialize)]
+struct PinataPinList {
+    count: u64,
+}
+
+/// Uploads files to the Pinata API
+struct PinataPinning {
+    http_client: Client,
+    url: String,
+    jwt: String,
+}
+
+#[async_trait]
+impl PinningService for PinataPinning {
+    fn name(&self) -> String {
+        format!("pinata ({})", self.url)
+    }
+
+    async fn pin(&self, name: &str, data: &Bytes) -> Result<String, LibError> {
+        let form = Form::new()
+            .part(
+                "file",
+                Part::bytes(data.to_vec()).file_name(name.to_string()),
+            )
+            .text(
+                "pinataMetadata",
+                serde_json::json!({ "name": name }).to_string(),
+            )
+            // The CIDv0 `ipfs add` gives, so every backend agrees on the CID
+            .text(
+                "pinataOptions",
+                serde_json::json!({ "cidVersion": 0 }).to_string(),
+            );
+        let resp = self
+            .http_client
+            .post(format!("{}/pinning/pinFileToIPFS", self.url))
+            .bearer_auth(&self.jwt)
+            .multipart(form)
+            .timeout(PIN_TIMEOUT)
+            .send()
+            .await?;
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let body = resp.text().await.unwrap_or_default();
+            return Err(LibError::PinningError(format!(
+                "Pinata upload failed: Status {}, Body: {}",
+                status, body
+            )));
+        }
+        Ok(resp.json::<PinataUpload>().await?.ipfs_hash)
+    }
+
+    async fn is_pinned(&self, cid: &str) -> Result<bool, LibError> {
+        let resp = self
+            .http_client
+            .get(format!(
+                "{}/data/pinList?hashContains={}&status=pinned&pageLimit=1",
+                self.url, cid
+            ))
+            .bearer_auth(&self.jwt)
+            .timeout(PIN_TIMEOUT)
+            .send()
+            .await?;
+        if !resp.status().is_success() {
+            return Err(resp.status().into());
+        }
+        Ok(resp.json::<PinataPinList>().await?.count > 0)
+    }
+}
+
+/// Stores files in a local directory under their CID, computed like
+/// `ipfs add` does. Useful for local development, and as a last resort copy
+/// of every upload.
+struct FilesystemPinning {
+    directory: PathBuf,
+}
+
+#[async_trait]
+impl PinningService for FilesystemPinning {
+    fn name(&self) -> String {
+        format!("filesystem ({})", self.directory.display())
+    }
+
+    async fn pin(&self, _name: &str, data: &Bytes) -> Result<String, LibError> {
+        let cid = add_file(data).0.to_string();
+        tokio::fs::create_dir_all(&self.directory).await?;
+        // Write to a temporary file first, so a crash never leaves a
+        // truncated file behind
+        let path = self.directory.join(&cid);
+        let tmp_path = path.with_extension("tmp");
+        tokio::fs::write(&tmp_path, data).await?;
+        tokio::fs::rename(&tmp_path, &path).await?;
+        Ok(cid)
+    }
+
+    async fn is_pinned(&self, cid: &str) -> Result<bool, LibError> {
+        // Only CIDs can be looked up, so a value can't escape the directory
+        if !cid.chars().all(|c| c.is_ascii_alphanumeric()) {
+            return Err(LibError::InvalidCid(cid.to_string()));
+        }
+        match tokio::fs::metadata(self.directory.join(cid)).await {
+            Ok(metadata) => Ok(metadata.is_file()),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(false),
+            Err(e) => Err(e.into()),
+        }
+    }
+}
+
+/// The pinning backends uploads are sent to. Every upload is pinned on all
+/// of them, and succeeds as long as one backend pinned it. Backends that
+/// failed are caught up by the reconciliation job.
+#[derive(Clone)]
+pub struct PinningPool {
+    backends: Arc<Vec<Arc<dyn PinningService>>>,
+    pub base_delay: Duration,
+    pub retry_attempts: i32,
+}
+
+impl PinningPool {
+    /// Creates a pool from the configured backends
+    pub fn new(configs: Vec<PinningConfig>, http_client: &Client) -> Result<Self, LibError> {
+        if configs.is_empty() {
+            return Err(LibError::PinningConfigError(
+                "at least one pinning backend is required".into(),
+            ));
+        }
+        Ok(Self {
+            backends: Arc::new(
+                configs
+                    .into_iter()
+                    .map(|config| config.into_service(http_client))
+                    .collect(),
+            ),
+            base_delay: BASE_DELAY,
+            retry_attempts: RETRY_ATTEMPTS,
+        })
+    }
+
+    /// Creates a pool from the JSON array of backends. When no backends are
+    /// configured, files are added to the Kubo node at `kubo_url`, and
+    /// uploaded to Pinata too if a `pinata_jwt` is set.
+    pub fn from_config(
+        backends: Option<&str>,
+        kubo_url: Option<&str>,
+        pinata_jwt: Option<&str>,
+        http_client: &Client,
+    ) -> Result<Self, LibError> {
+        let configs = match backends.filter(|backends| !backends.trim().is_empty()) {
+            Some(backends) => serde_json::from_str(backends)
+                .map_err(|e| LibError::PinningConfigError(e.to_string()))?,
+            None => {
+                let kubo = kubo_url.map(|url| PinningConfig::Kubo {
+                    url: url.to_string(),
+                });
+                let pinata = match pinata_jwt {
+                    Some(jwt) if !jwt.is_empty() => Some(PinningConfig::Pinata {
+                        url: None,
+                        jwt: jwt.to_string(),
+                    }),
+                    _ => None,
+                };
+                kubo.into_iter().chain(pinata).collect()
+            }
+        };
+        Self::new(configs, http_client)
+    }
+
+    /// Returns the configured backends
+    pub fn backends(&self) -> &[Arc<dyn PinningService>] {
+        &self.backends
+    }
+
+    /// Pins a file on every backend. Returns an [`IpfsResponse`] with the
+    /// `name`, `hash` and `size` of the file, as `ipfs add` would.
+    pub async fn pin(&self, name: &str, data: Bytes) -> Result<IpfsResponse, LibError> {
+        let (cid, size) = add_file(&data);
+        let cid = cid.to_string();
+
+        let mut errors = Vec::new();
+        for backend in self.backends.iter() {
+            if let Err(e) = self.pin_on(backend.as_ref(), &cid, name, &data).await {
+                warn!("Failed to pin {} on {}: {}", cid, backend.name(), e);
+                errors.push(format!("{}: {}", backend.name(), e));
+            }
+        }
+        if errors.len() == self.backends.len() {
+            return Err(LibError::PinningError(errors.join(", ")));
+        }
+
+        Ok(IpfsResponse {
+            name: name.to_string(),
+            hash: cid,
+            size: size.to_string(),
+        })
+    }
+
+    /// Pins a file with the given CID on a backend, with retries. A backend
+    /// giving the file another CID is an error, as it wouldn't be reachable
+    /// under the CID we store.
+    pub async fn pin_on(
+        &self,
+        backend: &dyn PinningService,
+        cid: &str,
+        name: &str,
+        data: &Bytes,
+    ) -> Result<(), LibError> {
+        let pinned_cid = self.with_retries(|| backend.pin(name, data)).await?;
+        if pinned_cid != cid {
+            return Err(LibError::IntegrityError(format!(
+                "{} pinned {} as {}",
+                backend.name(),
+                cid,
+                pinned_cid
+            )));
+        }
+        Ok(())
+    }
+
+    /// Checks if a backend pins a CID, with retries
+    pub async fn is_pinned_on(
+        &self,
+        backend: &dyn PinningService,
+        cid: &str,
+    ) -> Result<bool, LibError> {
+        self.with_retries(|| backend.is_pinned(cid)).await
+    }
+
+    /// Runs a request against a backend, retrying with exponential backoff
+    async fn with_retries<T, F, Fut>(&self, mut request: F) -> Result<T, LibError>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T, LibError>>,
+    {
+        let mut attempts = 0;
+        loop {
+            attempts += 1;
+            match request().await {
+                Ok(result) => return Ok(result),
+                Err(e) if attempts < self.retry_attempts => {
+                    warn!("Pinning error: {}, retrying... (attempt {})", e, attempts);
+                    sleep(self.base_delay.mul_f64(2_f64.powi(attempts - 1))).await;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_config() {
+        let client = Client::new();
+        let pool = PinningPool::from_config(
+            Some(r#"[{"type": "kubo", "url": "http://ipfs:5001"}, {"type": "filesystem", "directory": "/data/pins"}]"#),
+            None,
+            None,
+            &client,
+        )
+        .unwrap();
+        assert_eq!(pool.backends().len(), 2);
+
+        // Without a JWT, only the Kubo node is used
+        let pool =
+            PinningPool::from_config(None, Some("http://ipfs:5001"), Some(""), &client).unwrap();
+        assert_eq!(pool.backends().len(), 1);
+        let pool =
+            PinningPool::from_config(None, Some("http://ipfs:5001"), Some("jwt"), &client).unwrap();
+        assert_eq!(pool.backends().len(), 2);
+
+        assert!(PinningPool::from_config(None, None, None, &client).is_err());
+        assert!(
+            PinningPool::from_config(Some(r#"[{"type": "s3"}]"#), None, None, &client).is_err()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_filesystem_pinning() {
+        let directory = std::env::temp_dir().join(format!("pins-{}", rand::random::<u64>()));
+        let pool = PinningPool::new(
+            vec![PinningConfig::Filesystem {
+                directory: directory.display().to_string(),
+            }],
+            &Client::new(),
+        )
+        .unwrap();
+
+        let response = pool
+            .pin("hello.txt", Bytes::from_static(b"hello world\n"))
+            .await
+            .unwrap();
+        assert_eq!(
+            response.hash,
+            "QmT78zSuBmuS4z925WZfrqQ1qHaJ56DQaTfyMUF7F8ff5o"
+        );
+        assert_eq!(response.size, "20");
+
+        let backend = pool.backends()[0].as_ref();
+        assert!(pool.is_pinned_on(backend, &response.hash).await.unwrap());
+        assert!(!pool
+            .is_pinned_on(backend, "QmbFMke1KXqnYyBBWxB74N4c5SBnJMVAiMNRcGu6x1AwQH")
+            .await
+            .unwrap());
+
+        tokio::fs::remove_dir_all(directory).await.unwrap();
+    }
+}
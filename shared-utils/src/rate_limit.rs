@@ -0,0 +1,119 @@
+use std::{
+    collections::HashMap,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+/// The number of buckets above which idle buckets are dropped
+const MAX_IDLE_BUCKETS: usize = 10_000;
+
+/// A token bucket that holds up to `capacity` tokens and is refilled
+/// continuously at `capacity` tokens per minute.
+#[derive(Debug)]
+struct TokenBucket {
+    capacity: f64,
+    tokens: f64,
+    updated_at: Instant,
+}
+
+impl TokenBucket {
+    fn new(capacity: f64, now: Instant) -> Self {
+        Self {
+            capacity,
+            tokens: capacity,
+            updated_at: now,
+        }
+    }
+
+    /// Refills the bucket according to the elapsed time
+    fn refill(&mut self, now: Instant) {
+        let elapsed = now.duration_since(self.updated_at).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.capacity / 60.0).min(self.capacity);
+        self.updated_at = now;
+    }
+
+    /// Takes a token, or returns how long to wait until one is available
+    fn take(&mut self, now: Instant) -> Result<(), Duration> {
+        self.refill(now);
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            Ok(())
+        } else {
+            let missing = 1.0 - self.tokens;
+            Err(Duration::from_secs_f64(missing * 60.0 / self.capacity))
+        }
+    }
+
+    /// Returns true if the bucket is full, i.e. it can be dropped without
+    /// changing the outcome of the next request
+    fn is_full(&mut self, now: Instant) -> bool {
+        self.refill(now);
+        self.tokens >= self.capacity
+    }
+}
+
+/// An in-memory rate limiter keeping one token bucket per key, e.g. per API
+/// key or per IP address.
+#[derive(Debug, Default)]
+pub struct RateLimiter {
+    buckets: Mutex<HashMap<String, TokenBucket>>,
+}
+
+impl RateLimiter {
+    /// Creates an empty rate limiter
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Takes a token from the bucket of `key`, allowing `per_minute` requests
+    /// per minute with bursts of the same size. Returns the duration to wait
+    /// before retrying when the limit is reached.
+    pub fn check(&self, key: &str, per_minute: u32) -> Result<(), Duration> {
+        let now = Instant::now();
+        let capacity = f64::from(per_minute.max(1));
+        let mut buckets = self.buckets.lock().unwrap_or_else(|e| e.into_inner());
+
+        if buckets.len() > MAX_IDLE_BUCKETS {
+            buckets.retain(|_, bucket| !bucket.is_full(now));
+        }
+
+        let bucket = buckets
+            .entry(key.to_string())
+            .or_insert_with(|| TokenBucket::new(capacity, now));
+        // The limit of a key can change, e.g. when an API key is updated
+        if bucket.capacity != capacity {
+            bucket.capacity = capacity;
+            bucket.tokens = bucket.tokens.min(capacity);
+        }
+        bucket.take(now)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_token_bucket() {
+        let start = Instant::now();
+        let mut bucket = TokenBucket::new(2.0, start);
+
+        assert!(bucket.take(start).is_ok());
+        assert!(bucket.take(start).is_ok());
+        let retry_after = bucket.take(start).unwrap_err();
+        assert_eq!(retry_after, Duration::from_secs(30));
+
+        // Half a minute later a token is available again
+        assert!(bucket.take(start + Duration::from_secs(30)).is_ok());
+        assert!(bucket.take(start + Duration::from_secs(30)).is_err());
+    }
+
+    #[test]
+    fn test_rate_limiter_keys_are_independent() {
+        let limiter = RateLimiter::new();
+
+        assert!(limiter.check("a", 1).is_ok());
+        assert!(limiter.check("a", 1).is_err());
+        assert!(limiter.check("b", 1).is_ok());
+    }
+}
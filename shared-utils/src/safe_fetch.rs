@@ -0,0 +1,280 @@
+use crate::{
+    error::LibError,
+    ipfs::{IPFSResolver, BASE_DELAY, FETCH_TIMEOUT, RETRY_ATTEMPTS},
+    types::FetchedContent,
+};
+use reqwest::{
+    dns::{Addrs, Name, Resolve, Resolving},
+    header::CONTENT_TYPE,
+    redirect::{Attempt, Policy},
+    Client, StatusCode, Url,
+};
+use std::{
+    error::Error,
+    net::{IpAddr, Ipv4Addr, Ipv6Addr},
+    sync::Arc,
+    time::Duration,
+};
+use tokio::time::sleep;
+use tracing::warn;
+
+/// The maximum size of a fetched document, 5MiB
+pub const MAX_BODY_BYTES: usize = 5 << 20;
+/// The maximum number of redirects followed
+const MAX_REDIRECTS: usize = 5;
+
+/// Returns true if the address is publicly routable. Loopback, private,
+/// link-local, shared (CGNAT), documentation, benchmarking and reserved
+/// ranges are not.
+pub fn is_public_ip(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => is_public_ipv4(ip),
+        IpAddr::V6(ip) => match ip.to_ipv4_mapped() {
+            Some(ip) => is_public_ipv4(ip),
+            None => is_public_ipv6(ip),
+        },
+    }
+}
+
+fn is_public_ipv4(ip: Ipv4Addr) -> bool {
+    let [a, b, c, _] = ip.octets();
+    !(ip.is_unspecified()
+        || ip.is_loopback()
+        || ip.is_private()
+        || ip.is_link_local()
+        || ip.is_broadcast()
+        || ip.is_documentation()
+        || ip.is_multicast()
+        // 0.0.0.0/8
+        || a == 0
+        // 100.64.0.0/10, shared address space
+        || (a == 100 && (b & 0xc0) == 64)
+        // 192.0.0.0/24, IETF protocol assignments
+        || (a == 192 && b == 0 && c == 0)
+        // 198.18.0.0/15, benchmarking
+        || (a == 198 && (b & 0xfe) == 18)
+        // 240.0.0.0/4, reserved
+        || a >= 240)
+}
+
+fn is_public_ipv6(ip: Ipv6Addr) -> bool {
+    let segments = ip.segments();
+    !(ip.is_unspecified()
+        || ip.is_loopback()
+        || ip.is_multicast()
+        // fc00::/7, unique local
+        || (segments[0] & 0xfe00) == 0xfc00
+        // fe80::/10, link local
+        || (segments[0] & 0xffc0) == 0xfe80
+        // 2001:db8::/32, documentation
+        || (segments[0] == 0x2001 && segments[1] == 0xdb8)
+        // 64:ff9b::/96, NAT64 can reach private IPv4 addresses
+        || (segments[0] == 0x64 && segments[1] == 0xff9b))
+}
+
+/// Checks that a URL can be fetched: it must be `https` and, if its host is
+/// an IP address, the address must be public. Host names are checked when
+/// they are resolved.
+fn check_url(url: &Url) -> Result<(), LibError> {
+    if url.scheme() != "https" {
+        return Err(LibError::ForbiddenUrl(format!(
+            "{}: only https URLs can be fetched",
+            url
+        )));
+    }
+    let host = url
+        .host_str()
+        .ok_or_else(|| LibError::ForbiddenUrl(format!("{}: missing host", url)))?;
+    // IPv6 hosts are bracketed, host names don't parse as addresses and are
+    // checked when resolved
+    let Ok(ip) = host
+        .trim_start_matches('[')
+        .trim_end_matches(']')
+        .parse::<IpAddr>()
+    else {
+        return Ok(());
+    };
+    if !is_public_ip(ip) {
+        return Err(LibError::ForbiddenUrl(format!(
+            "{}: {} is not a public address",
+            url, ip
+        )));
+    }
+    Ok(())
+}
+
+/// A DNS resolver that only returns public addresses, so a host name can't
+/// be used to reach internal services, even if its records change between
+/// checks (DNS rebinding).
+struct PublicDnsResolver;
+
+impl Resolve for PublicDnsResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        Box::pin(resolve_public(name))
+    }
+}
+
+async fn resolve_public(name: Name) -> Result<Addrs, Box<dyn Error + Send + Sync>> {
+    let addrs = tokio::net::lookup_host((name.as_str(), 0))
+        .await?
+        .filter(|addr| is_public_ip(addr.ip()))
+        .collect::<Vec<_>>();
+    if addrs.is_empty() {
+        return Err(format!("{} does not resolve to a public address", name.as_str()).into());
+    }
+    Ok(Box::new(addrs.into_iter()))
+}
+
+/// Follows redirects to public `https` URLs only
+fn redirect_policy(attempt: Attempt) -> reqwest::redirect::Action {
+    if attempt.previous().len() >= MAX_REDIRECTS {
+        return attempt.error("too many redirects");
+    }
+    match check_url(attempt.url()) {
+        Ok(()) => attempt.follow(),
+        Err(e) => attempt.error(e),
+    }
+}
+
+/// Fetches documents from arbitrary `https` URLs, e.g. URLs found in atom
+/// data, without letting them reach internal services: only public
+/// addresses are connected to, redirects are checked the same way, no proxy
+/// is used and the size of the body is capped. Failed requests are retried
+/// with the same backoff and timeouts as the [`IPFSResolver`].
+#[derive(Clone)]
+pub struct SafeFetcher {
+    http_client: Client,
+    base_delay: Duration,
+    fetch_timeout: Duration,
+    max_body_bytes: usize,
+    retry_attempts: i32,
+}
+
+impl SafeFetcher {
+    /// Creates a fetcher with the retry and timeouts of the IPFS resolver
+    pub fn from_ipfs_resolver(resolver: &IPFSResolver) -> Result<Self, LibError> {
+        let http_client = Client::builder()
+            .dns_resolver(Arc::new(PublicDnsResolver))
+            .redirect(Policy::custom(redirect_policy))
+            .no_proxy()
+            .build()?;
+
+        Ok(Self {
+            http_client,
+            base_delay: resolver.base_delay.unwrap_or(BASE_DELAY),
+            fetch_timeout: resolver.fetch_timeout.unwrap_or(FETCH_TIMEOUT),
+            max_body_bytes: MAX_BODY_BYTES,
+            retry_attempts: resolver.retry_attempts.unwrap_or(RETRY_ATTEMPTS),
+        })
+    }
+
+    /// Fetches a document. Timeouts, connection errors, server errors and
+    /// rate limiting are retried, other failures are returned right away.
+    pub async fn fetch(&self, url: &str) -> Result<FetchedContent, LibError> {
+        let url = Url::parse(url).map_err(|e| LibError::ForbiddenUrl(format!("{}: {}", url, e)))?;
+        check_url(&url)?;
+
+        let mut attempts = 0;
+        loop {
+            attempts += 1;
+            match self.fetch_once(&url).await {
+                Ok(content) => return Ok(content),
+                Err(e) if attempts < self.retry_attempts && is_retryable(&e) => {
+                    warn!(
+                        "Fetching {} failed: {}, retrying... (attempt {})",
+                        url, e, attempts
+                    );
+                    sleep(self.base_delay.mul_f64(2_f64.powi(attempts - 1))).await;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    async fn fetch_once(&self, url: &Url) -> Result<FetchedContent, LibError> {
+        let mut resp = self
+            .http_client
+            .get(url.clone())
+            .timeout(self.fetch_timeout)
+            .send()
+            .await?;
+        let status = resp.status();
+        if !status.is_success() {
+            return Err(status.into());
+        }
+        if resp
+            .content_length()
+            .is_some_and(|length| length > self.max_body_bytes as u64)
+        {
+            return Err(LibError::ContentTooLarge(self.max_body_bytes));
+        }
+
+        let content_type = resp
+            .headers()
+            .get(CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_string);
+        let mut data = Vec::new();
+        while let Some(chunk) = resp.chunk().await? {
+            if data.len() + chunk.len() > self.max_body_bytes {
+                return Err(LibError::ContentTooLarge(self.max_body_bytes));
+            }
+            data.extend_from_slice(&chunk);
+        }
+
+        Ok(FetchedContent {
+            data: data.into(),
+            content_type,
+        })
+    }
+}
+
+/// Returns true if a failed request is worth retrying
+fn is_retryable(e: &LibError) -> bool {
+    match e {
+        LibError::Reqwest(e) => {
+            e.is_timeout()
+                || e.is_connect()
+                || e.status().is_some_and(|status| status.is_server_error())
+        }
+        LibError::NetworkError(status) => {
+            status.starts_with('5') || status.starts_with(StatusCode::TOO_MANY_REQUESTS.as_str())
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_public_ip() {
+        for ip in ["8.8.8.8", "1.1.1.1", "2606:4700:4700::1111"] {
+            assert!(is_public_ip(ip.parse().unwrap()), "{}", ip);
+        }
+        for ip in [
+            "127.0.0.1",
+            "10.0.0.1",
+            "172.16.0.1",
+            "192.168.1.1",
+            "169.254.169.254",
+            "100.64.0.1",
+            "0.0.0.0",
+            "::1",
+            "fd00::1",
+            "fe80::1",
+            "::ffff:127.0.0.1",
+        ] {
+            assert!(!is_public_ip(ip.parse().unwrap()), "{}", ip);
+        }
+    }
+
+    #[test]
+    fn test_check_url() {
+        assert!(check_url(&Url::parse("https://example.com/atom.json").unwrap()).is_ok());
+        assert!(check_url(&Url::parse("http://example.com/atom.json").unwrap()).is_err());
+        assert!(check_url(&Url::parse("https://169.254.169.254/latest").unwrap()).is_err());
+        assert!(check_url(&Url::parse("https://[::1]/").unwrap()).is_err());
+    }
+}
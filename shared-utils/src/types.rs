@@ -1,14 +1,32 @@
-use std::fmt::Display;
+use std::{fmt::Display, str::FromStr};
 
 use bytes::Bytes;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use utoipa::ToSchema;
 
+/// Supported schema.org contexts
+pub const SCHEMA_ORG_CONTEXTS: [&str; 4] = [
+    "https://schema.org",
+    "https://schema.org/",
+    "http://schema.org",
+    "http://schema.org/",
+];
+
+/// Content fetched from IPFS or over HTTP. Content fetched from IPFS is
+/// verified against its CID unless verification is disabled.
+#[derive(Clone, Debug)]
+pub struct FetchedContent {
+    pub data: Bytes,
+    pub content_type: Option<String>,
+}
+
 #[derive(Debug, Serialize, Deserialize, ToSchema, Default)]
 pub enum ClassificationModel {
     #[default]
     FalconsaiNsfwImageDetection,
+    /// The local `safe-content` classification API
+    SafeContentAi,
 }
 
 impl Display for ClassificationModel {
@@ -17,6 +35,21 @@ impl Display for ClassificationModel {
             ClassificationModel::FalconsaiNsfwImageDetection => {
                 write!(f, "Falconsai/nsfw_image_detection")
             }
+            ClassificationModel::SafeContentAi => write!(f, "steelcityamir/safe-content-ai"),
+        }
+    }
+}
+
+impl FromStr for ClassificationModel {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Falconsai/nsfw_image_detection" => {
+                Ok(ClassificationModel::FalconsaiNsfwImageDetection)
+            }
+            "steelcityamir/safe-content-ai" => Ok(ClassificationModel::SafeContentAi),
+            _ => Err(format!("Unknown classification model: {}", s)),
         }
     }
 }
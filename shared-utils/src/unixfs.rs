@@ -0,0 +1,490 @@
+use crate::error::LibError;
+use cid::{multihash::Multihash, Cid, Version};
+use sha2::{Digest, Sha256};
+use std::{collections::HashMap, io::Cursor};
+use unsigned_varint::{decode, encode};
+
+/// The multicodec of UnixFS nodes
+pub const DAG_PB: u64 = 0x70;
+/// The multicodec of raw blocks
+pub const RAW: u64 = 0x55;
+/// The multihash code of sha2-256
+const SHA2_256: u64 = 0x12;
+/// The multihash code of inlined content
+const IDENTITY: u64 = 0x00;
+
+/// The chunk size of the default `size-262144` chunker
+const CHUNK_SIZE: usize = 262_144;
+/// The maximum number of links of a node in the default balanced layout
+const MAX_LINKS: usize = 174;
+/// The UnixFS data types
+const UNIXFS_RAW: u64 = 0;
+const UNIXFS_DIRECTORY: u64 = 1;
+const UNIXFS_FILE: u64 = 2;
+
+/// A node of the file DAG being built: its CID, the size of its content and
+/// the cumulative size of its blocks
+struct DagNode {
+    cid: Cid,
+    filesize: u64,
+    tsize: u64,
+}
+
+/// Returns the sha2-256 CID of a block
+fn block_cid(version: Version, codec: u64, block: &[u8]) -> Cid {
+    let hash = Multihash::wrap(SHA2_256, &Sha256::digest(block))
+        .expect("a sha2-256 digest fits in a multihash");
+    Cid::new(version, codec, hash).expect("only dag-pb blocks get a CIDv0")
+}
+
+/// Returns true if the multihash of the block matches the CID. Fails if the
+/// hash function is not supported.
+fn matches(cid: &Cid, block: &[u8]) -> Result<bool, LibError> {
+    let hash = cid.hash();
+    match hash.code() {
+        SHA2_256 => Ok(Sha256::digest(block).as_slice() == hash.digest()),
+        IDENTITY => Ok(block == hash.digest()),
+        code => Err(LibError::IntegrityError(format!(
+            "unsupported multihash 0x{:x}",
+            code
+        ))),
+    }
+}
+
+/// Returns true if the content hashes to the CID when imported with the
+/// default UnixFS settings: 256KiB chunks in a balanced DAG, with either
+/// UnixFS leaves (CIDv0 default) or raw leaves (CIDv1 default).
+pub fn verify_file(cid: &Cid, content: &[u8]) -> Result<bool, LibError> {
+    match cid.codec() {
+        RAW => matches(cid, content),
+        DAG_PB => {
+            // Raw leaves can't be linked from a CIDv0
+            let layouts: &[bool] = if cid.version() == Version::V0 {
+                &[false]
+            } else {
+                &[true, false]
+            };
+            for raw_leaves in layouts.iter().copied() {
+                let root = import_file(content, cid.version(), raw_leaves);
+                if root.cid.codec() == DAG_PB && root.cid.hash() == cid.hash() {
+                    return Ok(true);
+                }
+            }
+            Ok(false)
+        }
+        _ => Ok(false),
+    }
+}
+
+/// Returns the CID `ipfs add` gives to a file with the default settings
+/// (CIDv0 with UnixFS leaves), and the cumulative size of its blocks, which
+/// Kubo reports as the `Size` of the file.
+pub fn add_file(content: &[u8]) -> (Cid, u64) {
+    let root = import_file(content, Version::V0, false);
+    (root.cid, root.tsize)
+}
+
+/// Builds the DAG of a file like `ipfs add` does with the default chunker
+/// and balanced layout, and returns its root
+fn import_file(content: &[u8], version: Version, raw_leaves: bool) -> DagNode {
+    let mut nodes = content
+        .chunks(CHUNK_SIZE)
+        .map(|chunk| {
+            if raw_leaves {
+                DagNode {
+                    cid: block_cid(Version::V1, RAW, chunk),
+                    filesize: chunk.len() as u64,
+                    tsize: chunk.len() as u64,
+                }
+            } else {
+                let block = encode_node(&[], &encode_unixfs(Some(chunk), chunk.len() as u64, &[]));
+                DagNode {
+                    cid: block_cid(version, DAG_PB, &block),
+                    filesize: chunk.len() as u64,
+                    tsize: block.len() as u64,
+                }
+            }
+        })
+        .collect::<Vec<_>>();
+
+    if nodes.is_empty() {
+        let block = encode_node(&[], &encode_unixfs(None, 0, &[]));
+        return DagNode {
+            cid: block_cid(version, DAG_PB, &block),
+            filesize: 0,
+            tsize: block.len() as u64,
+        };
+    }
+    // A file with a single chunk is its leaf
+    if nodes.len() == 1 {
+        return nodes.remove(0);
+    }
+
+    // Every level groups up to `MAX_LINKS` nodes of the level below. The
+    // first nodes of a level are full, so this matches the balanced layout.
+    loop {
+        nodes = nodes
+            .chunks(MAX_LINKS)
+            .map(|children| {
+                let filesize = children.iter().map(|child| child.filesize).sum();
+                let blocksizes = children
+                    .iter()
+                    .map(|child| child.filesize)
+                    .collect::<Vec<_>>();
+                let links = children
+                    .iter()
+                    .map(|child| (child.cid.to_bytes(), child.tsize))
+                    .collect::<Vec<_>>();
+                let block = encode_node(&links, &encode_unixfs(None, filesize, &blocksizes));
+                DagNode {
+                    cid: block_cid(version, DAG_PB, &block),
+                    filesize,
+                    tsize: block.len() as u64 + children.iter().map(|c| c.tsize).sum::<u64>(),
+                }
+            })
+            .collect();
+        if nodes.len() == 1 {
+            return nodes.remove(0);
+        }
+    }
+}
+
+/// Encodes the UnixFS data of a file node
+fn encode_unixfs(data: Option<&[u8]>, filesize: u64, blocksizes: &[u64]) -> Vec<u8> {
+    let mut out = Vec::new();
+    put_varint_field(1, UNIXFS_FILE, &mut out);
+    if let Some(data) = data.filter(|data| !data.is_empty()) {
+        put_bytes_field(2, data, &mut out);
+    }
+    put_varint_field(3, filesize, &mut out);
+    for blocksize in blocksizes {
+        put_varint_field(4, *blocksize, &mut out);
+    }
+    out
+}
+
+/// Encodes a dag-pb node. The links come first, and like go-ipfs every link
+/// has an empty name.
+fn encode_node(links: &[(Vec<u8>, u64)], data: &[u8]) -> Vec<u8> {
+    let mut out = Vec::new();
+    for (hash, tsize) in links {
+        let mut link = Vec::new();
+        put_bytes_field(1, hash, &mut link);
+        put_bytes_field(2, b"", &mut link);
+        put_varint_field(3, *tsize, &mut link);
+        put_bytes_field(2, &link, &mut out);
+    }
+    put_bytes_field(1, data, &mut out);
+    out
+}
+
+/// Appends an unsigned LEB128 varint
+fn write_varint(value: u64, out: &mut Vec<u8>) {
+    out.extend_from_slice(encode::u64(value, &mut encode::u64_buffer()));
+}
+
+/// Reads an unsigned LEB128 varint, returning it and the bytes after it
+fn read_varint(bytes: &[u8]) -> Option<(u64, &[u8])> {
+    decode::u64(bytes).ok()
+}
+
+fn put_varint_field(field: u64, value: u64, out: &mut Vec<u8>) {
+    write_varint(field << 3, out);
+    write_varint(value, out);
+}
+
+fn put_bytes_field(field: u64, value: &[u8], out: &mut Vec<u8>) {
+    write_varint((field << 3) | 2, out);
+    write_varint(value.len() as u64, out);
+    out.extend_from_slice(value);
+}
+
+/// A protobuf field, either a varint or length delimited
+enum Field<'a> {
+    Varint(u64),
+    Bytes(&'a [u8]),
+}
+
+/// Reads the fields of a protobuf message. Only the wire types used by
+/// dag-pb and UnixFS are supported.
+fn read_fields(mut bytes: &[u8]) -> Option<Vec<(u64, Field<'_>)>> {
+    let mut fields = Vec::new();
+    while !bytes.is_empty() {
+        let (key, rest) = read_varint(bytes)?;
+        let (value, rest) = read_varint(rest)?;
+        bytes = rest;
+        let field = match key & 0x07 {
+            0 => Field::Varint(value),
+            2 => {
+                let len = usize::try_from(value).ok()?;
+                let (value, rest) = bytes.split_at_checked(len)?;
+                bytes = rest;
+                Field::Bytes(value)
+            }
+            _ => return None,
+        };
+        fields.push((key >> 3, field));
+    }
+    Some(fields)
+}
+
+/// A decoded dag-pb node
+struct PbNode<'a> {
+    links: Vec<(Cid, String)>,
+    data: &'a [u8],
+}
+
+fn decode_node(block: &[u8]) -> Option<PbNode<'_>> {
+    let mut node = PbNode {
+        links: Vec::new(),
+        data: &[],
+    };
+    for (field, value) in read_fields(block)? {
+        match (field, value) {
+            (1, Field::Bytes(data)) => node.data = data,
+            (2, Field::Bytes(link)) => {
+                let mut hash = None;
+                let mut name = String::new();
+                for (field, value) in read_fields(link)? {
+                    match (field, value) {
+                        (1, Field::Bytes(bytes)) => hash = Some(Cid::try_from(bytes).ok()?),
+                        (2, Field::Bytes(bytes)) => {
+                            name = String::from_utf8(bytes.to_vec()).ok()?
+                        }
+                        _ => {}
+                    }
+                }
+                node.links.push((hash?, name));
+            }
+            _ => {}
+        }
+    }
+    Some(node)
+}
+
+/// Returns the UnixFS type and data of a dag-pb node
+fn decode_unixfs(data: &[u8]) -> Option<(u64, &[u8])> {
+    let mut unixfs_type = None;
+    let mut content: &[u8] = &[];
+    for (field, value) in read_fields(data)? {
+        match (field, value) {
+            (1, Field::Varint(value)) => unixfs_type = Some(value),
+            (2, Field::Bytes(bytes)) => content = bytes,
+            _ => {}
+        }
+    }
+    Some((unixfs_type?, content))
+}
+
+/// The verified blocks of a CAR file, keyed by multihash
+pub struct CarBlocks {
+    blocks: HashMap<Multihash<64>, Vec<u8>>,
+}
+
+impl CarBlocks {
+    /// Reads a CARv1 file and verifies that every block matches its CID.
+    /// Fails on the first block that doesn't.
+    pub fn read(car: &[u8]) -> Result<Self, LibError> {
+        let invalid = |reason: &str| LibError::IntegrityError(format!("invalid CAR: {}", reason));
+
+        let (header_len, header) = read_varint(car).ok_or_else(|| invalid("header"))?;
+        let mut rest = usize::try_from(header_len)
+            .ok()
+            .and_then(|len| header.get(len..))
+            .ok_or_else(|| invalid("header"))?;
+
+        let mut blocks = HashMap::new();
+        while !rest.is_empty() {
+            let (section_len, section) = read_varint(rest).ok_or_else(|| invalid("section"))?;
+            let (section, next) = usize::try_from(section_len)
+                .ok()
+                .and_then(|len| section.split_at_checked(len))
+                .ok_or_else(|| invalid("section"))?;
+            rest = next;
+
+            let mut reader = Cursor::new(section);
+            let cid = Cid::read_bytes(&mut reader).map_err(|_| invalid("CID"))?;
+            let block = &section[reader.position() as usize..];
+            if !matches(&cid, block)? {
+                return Err(LibError::IntegrityError(format!(
+                    "block {} doesn't match its CID",
+                    cid
+                )));
+            }
+            blocks.insert(*cid.hash(), block.to_vec());
+        }
+        Ok(Self { blocks })
+    }
+
+    fn get(&self, cid: &Cid) -> Result<&[u8], LibError> {
+        self.blocks
+            .get(cid.hash())
+            .map(Vec::as_slice)
+            .ok_or_else(|| LibError::IntegrityError(format!("block {} missing from CAR", cid)))
+    }
+
+    /// Returns the content of the file at `path` under the `root` directory,
+    /// or of the root itself if the path is empty
+    pub fn read_file(&self, root: &Cid, path: &[&str]) -> Result<Vec<u8>, LibError> {
+        let invalid = |cid: &Cid| LibError::IntegrityError(format!("invalid UnixFS node {}", cid));
+
+        let mut cid = *root;
+        for segment in path.iter().filter(|segment| !segment.is_empty()) {
+            let node = decode_node(self.get(&cid)?).ok_or_else(|| invalid(&cid))?;
+            match decode_unixfs(node.data) {
+                Some((UNIXFS_DIRECTORY, _)) => {}
+                _ => {
+                    return Err(LibError::IntegrityError(format!(
+                        "{} is not a directory, sharded directories are not supported",
+                        cid
+                    )))
+                }
+            }
+            cid = node
+                .links
+                .into_iter()
+                .find(|(_, name)| name == segment)
+                .map(|(cid, _)| cid)
+                .ok_or_else(|| LibError::ResourceNotFoundError(segment.to_string()))?;
+        }
+
+        let mut content = Vec::new();
+        self.append_file(&cid, &mut content)?;
+        Ok(content)
+    }
+
+    /// Appends the content of a file node and of its children
+    fn append_file(&self, cid: &Cid, content: &mut Vec<u8>) -> Result<(), LibError> {
+        if cid.codec() == RAW {
+            content.extend_from_slice(self.get(cid)?);
+            return Ok(());
+        }
+
+        let invalid = || LibError::IntegrityError(format!("invalid UnixFS file {}", cid));
+        let node = decode_node(self.get(cid)?).ok_or_else(invalid)?;
+        match decode_unixfs(node.data).ok_or_else(invalid)? {
+            (UNIXFS_FILE | UNIXFS_RAW, data) => content.extend_from_slice(data),
+            _ => return Err(invalid()),
+        }
+        for (child, _) in &node.links {
+            self.append_file(child, content)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_verify_file() {
+        // `ipfs add` of an empty file and of "hello world\n", and the raw
+        // CIDv1 of "hello world"
+        let empty = Cid::try_from("QmbFMke1KXqnYyBBWxB74N4c5SBnJMVAiMNRcGu6x1AwQH").unwrap();
+        assert!(verify_file(&empty, b"").unwrap());
+
+        let hello_v0 = Cid::try_from("QmT78zSuBmuS4z925WZfrqQ1qHaJ56DQaTfyMUF7F8ff5o").unwrap();
+        assert!(verify_file(&hello_v0, b"hello world\n").unwrap());
+        assert!(!verify_file(&hello_v0, b"hello world!\n").unwrap());
+
+        let hello_v1 =
+            Cid::try_from("bafkreifzjut3te2nhyekklss27nh3k72ysco7y32koao5eei66wof36n5e").unwrap();
+        // The raw CID of the empty block
+        let empty_v1 =
+            Cid::try_from("bafkreihdwdcefgh4dqkjv67uzcmw7ojee6xedzdetojuzjevtenxquvyku").unwrap();
+        assert!(verify_file(&empty_v1, b"").unwrap());
+        assert!(verify_file(&hello_v1, b"hello world").unwrap());
+    }
+
+    #[test]
+    fn test_add_file() {
+        let (cid, size) = add_file(b"hello world\n");
+        assert_eq!(
+            cid.to_string(),
+            "QmT78zSuBmuS4z925WZfrqQ1qHaJ56DQaTfyMUF7F8ff5o"
+        );
+        assert_eq!(size, 20);
+
+        let content = vec![7u8; CHUNK_SIZE * 2 + 10];
+        let (cid, _) = add_file(&content);
+        assert_eq!(cid.version(), Version::V0);
+        assert!(verify_file(&cid, &content).unwrap());
+    }
+
+    #[test]
+    fn test_add_file_balanced_layout() {
+        // One chunk more than a node can link, so the root links a full node
+        // of `MAX_LINKS` leaves and a node with the last leaf, like Kubo's
+        // balanced layout
+        let content = vec![7u8; CHUNK_SIZE * (MAX_LINKS + 1)];
+        let chunk = &content[..CHUNK_SIZE];
+        let leaf = encode_node(&[], &encode_unixfs(Some(chunk), CHUNK_SIZE as u64, &[]));
+        let leaf_cid = block_cid(Version::V0, DAG_PB, &leaf);
+
+        let parent = |leaves: usize| {
+            let links = vec![(leaf_cid.to_bytes(), leaf.len() as u64); leaves];
+            let block = encode_node(
+                &links,
+                &encode_unixfs(
+                    None,
+                    (CHUNK_SIZE * leaves) as u64,
+                    &vec![CHUNK_SIZE as u64; leaves],
+                ),
+            );
+            let tsize = block.len() + leaf.len() * leaves;
+            (block_cid(Version::V0, DAG_PB, &block), tsize as u64)
+        };
+        let (full, full_tsize) = parent(MAX_LINKS);
+        let (last, last_tsize) = parent(1);
+        let root = encode_node(
+            &[(full.to_bytes(), full_tsize), (last.to_bytes(), last_tsize)],
+            &encode_unixfs(
+                None,
+                content.len() as u64,
+                &[(CHUNK_SIZE * MAX_LINKS) as u64, CHUNK_SIZE as u64],
+            ),
+        );
+
+        let (cid, size) = add_file(&content);
+        assert_eq!(cid, block_cid(Version::V0, DAG_PB, &root));
+        assert_eq!(size, root.len() as u64 + full_tsize + last_tsize);
+        assert!(verify_file(&cid, &content).unwrap());
+    }
+
+    #[test]
+    fn test_read_car() {
+        let content = vec![7u8; CHUNK_SIZE + 10];
+        let leaves = content
+            .chunks(CHUNK_SIZE)
+            .map(|chunk| (block_cid(Version::V1, RAW, chunk), chunk.to_vec()))
+            .collect::<Vec<_>>();
+        let links = leaves
+            .iter()
+            .map(|(cid, block)| (cid.to_bytes(), block.len() as u64))
+            .collect::<Vec<_>>();
+        let root_block = encode_node(
+            &links,
+            &encode_unixfs(None, content.len() as u64, &[CHUNK_SIZE as u64, 10]),
+        );
+        let root = block_cid(Version::V1, DAG_PB, &root_block);
+        assert!(verify_file(&root, &content).unwrap());
+
+        let mut car = Vec::new();
+        write_varint(1, &mut car);
+        car.push(0xa0);
+        for (cid, block) in std::iter::once((root, root_block)).chain(leaves) {
+            let cid = cid.to_bytes();
+            write_varint((cid.len() + block.len()) as u64, &mut car);
+            car.extend(cid);
+            car.extend(block);
+        }
+        let blocks = CarBlocks::read(&car).unwrap();
+        assert_eq!(blocks.read_file(&root, &[]).unwrap(), content);
+
+        // A tampered block is rejected
+        let last = car.len() - 1;
+        car[last] = 8;
+        assert!(CarBlocks::read(&car).is_err());
+    }
+}